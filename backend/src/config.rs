@@ -0,0 +1,422 @@
+#[cfg(test)]
+mod tests;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use actix_identity::CookieIdentityPolicy;
+use actix_web::cookie::{Key, SameSite};
+use chrono::Duration;
+use shared::models::SessionExpiry;
+
+use crate::errors::MoolahBackendError;
+use crate::mailer::{FileMailer, LogMailer, Mailer, SmtpMailer};
+use crate::session_store::SessionIdentityPolicy;
+use crate::throttle::{LoginThrottle, ThrottlePolicy};
+use crate::Pool;
+
+/// Shortest signing key the cookie policy accepts.
+pub const MIN_SECRET_LENGTH: usize = 32;
+
+const AUTH_COOKIE_NAME: &str = "auth-cookie";
+
+/// Default longest a session may go unused, in seconds (a week)
+const DEFAULT_IDLE_TIMEOUT: i64 = 7 * 24 * 60 * 60;
+
+/// Default longest a session may last, in seconds (30 days)
+const DEFAULT_ABSOLUTE_TIMEOUT: i64 = 30 * 24 * 60 * 60;
+
+/// How the auth cookie is signed and scoped, read from the environment:
+///
+/// - `MOOLAH_ENV`: `production` to require a secret and secure cookies by default
+/// - `SESSION_SECRET`: signing key of at least 32 bytes
+/// - `SESSION_COOKIE_SECURE`: `true` or `false`
+/// - `SESSION_COOKIE_SAME_SITE`: `strict`, `lax` (default) or `none`
+/// - `SESSION_COOKIE_MAX_AGE`: lifetime in seconds, a browser session if unset
+/// - `SESSION_COOKIE_DOMAIN`: domain the cookie is sent to
+/// - `SESSION_IDLE_TIMEOUT`: seconds a session may go unused, a week by default
+/// - `SESSION_ABSOLUTE_TIMEOUT`: seconds a session may last, 30 days by default
+#[derive(Clone)]
+pub struct SessionConfig {
+    pub(crate) secret: Vec<u8>,
+    pub(crate) secure: bool,
+    pub(crate) same_site: SameSite,
+    pub(crate) max_age: Option<i64>,
+    pub(crate) domain: Option<String>,
+    pub(crate) idle_timeout: i64,
+    pub(crate) absolute_timeout: i64,
+}
+
+impl SessionConfig {
+    pub fn from_env() -> Result<Self, MoolahBackendError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Build the config from `var`, which looks up a variable by name.
+    pub fn from_vars<F>(var: F) -> Result<Self, MoolahBackendError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let production = var("MOOLAH_ENV").as_deref() == Some("production");
+
+        let secret = match var("SESSION_SECRET") {
+            Some(secret) if secret.len() >= MIN_SECRET_LENGTH => secret.into_bytes(),
+            Some(_) => {
+                return Err(MoolahBackendError::ConfigError(format!(
+                    "SESSION_SECRET must be at least {} bytes",
+                    MIN_SECRET_LENGTH
+                )))
+            }
+            None if production => {
+                return Err(MoolahBackendError::ConfigError(
+                    "SESSION_SECRET must be set in production".into(),
+                ))
+            }
+            None => {
+                log::warn!("SESSION_SECRET not set, sessions will not survive a restart");
+                Key::generate().master().to_vec()
+            }
+        };
+
+        let secure = match var("SESSION_COOKIE_SECURE") {
+            Some(secure) => parse_bool("SESSION_COOKIE_SECURE", &secure)?,
+            None => production,
+        };
+
+        let same_site = match var("SESSION_COOKIE_SAME_SITE").as_deref() {
+            None => SameSite::Lax,
+            Some(same_site) => match same_site.to_lowercase().as_str() {
+                "strict" => SameSite::Strict,
+                "lax" => SameSite::Lax,
+                "none" => SameSite::None,
+                _ => {
+                    return Err(MoolahBackendError::ConfigError(format!(
+                        "SESSION_COOKIE_SAME_SITE must be strict, lax or none, not {}",
+                        same_site
+                    )))
+                }
+            },
+        };
+
+        if same_site == SameSite::None && !secure {
+            return Err(MoolahBackendError::ConfigError(
+                "SESSION_COOKIE_SAME_SITE=none needs a secure cookie".into(),
+            ));
+        }
+
+        let max_age = match var("SESSION_COOKIE_MAX_AGE") {
+            Some(max_age) => Some(parse_seconds("SESSION_COOKIE_MAX_AGE", &max_age)?),
+            None => None,
+        };
+
+        let idle_timeout = match var("SESSION_IDLE_TIMEOUT") {
+            Some(timeout) => parse_seconds("SESSION_IDLE_TIMEOUT", &timeout)?,
+            None => DEFAULT_IDLE_TIMEOUT,
+        };
+
+        let absolute_timeout = match var("SESSION_ABSOLUTE_TIMEOUT") {
+            Some(timeout) => parse_seconds("SESSION_ABSOLUTE_TIMEOUT", &timeout)?,
+            None => DEFAULT_ABSOLUTE_TIMEOUT,
+        };
+
+        let domain = var("SESSION_COOKIE_DOMAIN").filter(|domain| !domain.is_empty());
+
+        Ok(SessionConfig {
+            secret,
+            secure,
+            same_site,
+            max_age,
+            domain,
+            idle_timeout,
+            absolute_timeout,
+        })
+    }
+
+    pub fn expiry(&self) -> SessionExpiry {
+        SessionExpiry {
+            idle: Duration::seconds(self.idle_timeout),
+            absolute: Duration::seconds(self.absolute_timeout),
+        }
+    }
+
+    /// The identity policy keeping sessions in `pool`'s database.
+    pub fn policy(&self, pool: Pool) -> SessionIdentityPolicy {
+        SessionIdentityPolicy::new(self.cookie_policy(), pool, self.expiry())
+    }
+
+    /// The policy issuing and checking the auth cookie carrying the session
+    /// token.
+    pub(crate) fn cookie_policy(&self) -> CookieIdentityPolicy {
+        let policy = CookieIdentityPolicy::new(&self.secret)
+            .name(AUTH_COOKIE_NAME)
+            .http_only(true)
+            .secure(self.secure)
+            .same_site(self.same_site);
+
+        let policy = match self.max_age {
+            Some(seconds) => policy.max_age_secs(seconds),
+            None => policy,
+        };
+
+        match &self.domain {
+            Some(domain) => policy.domain(domain.clone()),
+            None => policy,
+        }
+    }
+}
+
+const DEFAULT_APP_URL: &str = "http://localhost:3000";
+
+const DEFAULT_MAIL_DIR: &str = "mail";
+
+/// How outgoing email is delivered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MailTransport {
+    Log,
+    File(PathBuf),
+    Smtp {
+        host: String,
+        port: Option<u16>,
+        credentials: Option<(String, String)>,
+    },
+}
+
+/// How email is sent and where its links point, read from the environment:
+///
+/// - `MAILER`: `log`, `file` or `smtp`, required in production and `log` otherwise
+/// - `MAIL_FROM`: address email is sent from, required for `smtp`
+/// - `MAIL_DIR`: directory the `file` mailer writes to, `mail` by default
+/// - `SMTP_HOST`, `SMTP_PORT`: relay the `smtp` mailer sends through
+/// - `SMTP_USERNAME`, `SMTP_PASSWORD`: relay credentials, if it needs them
+/// - `APP_URL`: address of the frontend that emailed links open
+#[derive(Debug, Clone)]
+pub struct MailConfig {
+    pub(crate) transport: MailTransport,
+    pub(crate) from: String,
+    pub(crate) app_url: String,
+}
+
+impl MailConfig {
+    pub fn from_env() -> Result<Self, MoolahBackendError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Build the config from `var`, which looks up a variable by name.
+    pub fn from_vars<F>(var: F) -> Result<Self, MoolahBackendError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let production = var("MOOLAH_ENV").as_deref() == Some("production");
+
+        let transport = match var("MAILER").as_deref() {
+            None if production => {
+                return Err(MoolahBackendError::ConfigError(
+                    "MAILER must be set in production".into(),
+                ))
+            }
+            None | Some("log") => MailTransport::Log,
+            Some("file") => MailTransport::File(
+                var("MAIL_DIR")
+                    .unwrap_or_else(|| DEFAULT_MAIL_DIR.into())
+                    .into(),
+            ),
+            Some("smtp") => {
+                let host = var("SMTP_HOST").ok_or_else(|| {
+                    MoolahBackendError::ConfigError("MAILER=smtp needs SMTP_HOST".into())
+                })?;
+
+                let port = match var("SMTP_PORT") {
+                    Some(port) => Some(port.parse::<u16>().map_err(|_| {
+                        MoolahBackendError::ConfigError(format!(
+                            "SMTP_PORT must be a port number, not {}",
+                            port
+                        ))
+                    })?),
+                    None => None,
+                };
+
+                let credentials = match (var("SMTP_USERNAME"), var("SMTP_PASSWORD")) {
+                    (Some(username), Some(password)) => Some((username, password)),
+                    (None, None) => None,
+                    _ => {
+                        return Err(MoolahBackendError::ConfigError(
+                            "SMTP_USERNAME and SMTP_PASSWORD must be set together".into(),
+                        ))
+                    }
+                };
+
+                MailTransport::Smtp {
+                    host,
+                    port,
+                    credentials,
+                }
+            }
+            Some(mailer) => {
+                return Err(MoolahBackendError::ConfigError(format!(
+                    "MAILER must be log, file or smtp, not {}",
+                    mailer
+                )))
+            }
+        };
+
+        let from = match var("MAIL_FROM") {
+            Some(from) => from,
+            None if matches!(transport, MailTransport::Smtp { .. }) => {
+                return Err(MoolahBackendError::ConfigError(
+                    "MAILER=smtp needs MAIL_FROM".into(),
+                ))
+            }
+            None => "moolah@localhost".into(),
+        };
+
+        let app_url = var("APP_URL")
+            .unwrap_or_else(|| DEFAULT_APP_URL.into())
+            .trim_end_matches('/')
+            .to_string();
+
+        Ok(MailConfig {
+            transport,
+            from,
+            app_url,
+        })
+    }
+
+    /// The mailer delivering email by the configured transport.
+    pub fn mailer(&self) -> Result<Arc<dyn Mailer>, MoolahBackendError> {
+        Ok(match &self.transport {
+            MailTransport::Log => Arc::new(LogMailer),
+            MailTransport::File(dir) => Arc::new(FileMailer::new(dir.clone())),
+            MailTransport::Smtp {
+                host,
+                port,
+                credentials,
+            } => Arc::new(SmtpMailer::new(
+                &self.from,
+                host,
+                *port,
+                credentials.clone(),
+            )?),
+        })
+    }
+
+    /// A link to the frontend `page` carrying `token`.
+    pub fn link(&self, page: &str, token: &str) -> String {
+        format!("{}/{}?token={}", self.app_url, page, token)
+    }
+}
+
+/// Default failed logins after which a username is locked out
+const DEFAULT_LOCKOUT_ATTEMPTS: u32 = 10;
+
+/// Default length of a lockout, in seconds (15 minutes)
+const DEFAULT_LOCKOUT_DURATION: i64 = 15 * 60;
+
+/// How many times more failures an IP address is allowed than a username,
+/// since many users may share one behind a NAT.
+const IP_ATTEMPTS_FACTOR: u32 = 5;
+
+/// How failed logins are throttled, read from the environment:
+///
+/// - `LOGIN_LOCKOUT_ATTEMPTS`: failures before a username is locked out, 10 by
+///   default; an IP address is allowed five times as many
+/// - `LOGIN_LOCKOUT_DURATION`: seconds a lockout lasts, 15 minutes by default
+/// - `TRUST_PROXY_HEADERS`: `true` to take the client address from
+///   `Forwarded`/`X-Forwarded-For`, only safe behind a reverse proxy setting them
+#[derive(Debug, Clone)]
+pub struct ThrottleConfig {
+    pub(crate) lockout_attempts: u32,
+    pub(crate) lockout_duration: i64,
+    pub(crate) trust_proxy_headers: bool,
+}
+
+impl ThrottleConfig {
+    pub fn from_env() -> Result<Self, MoolahBackendError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Build the config from `var`, which looks up a variable by name.
+    pub fn from_vars<F>(var: F) -> Result<Self, MoolahBackendError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let lockout_attempts = match var("LOGIN_LOCKOUT_ATTEMPTS") {
+            Some(attempts) => match attempts.parse::<u32>() {
+                Ok(attempts) if attempts > 0 => attempts,
+                _ => {
+                    return Err(MoolahBackendError::ConfigError(format!(
+                        "LOGIN_LOCKOUT_ATTEMPTS must be a positive number, not {}",
+                        attempts
+                    )))
+                }
+            },
+            None => DEFAULT_LOCKOUT_ATTEMPTS,
+        };
+
+        let lockout_duration = match var("LOGIN_LOCKOUT_DURATION") {
+            Some(duration) => parse_seconds("LOGIN_LOCKOUT_DURATION", &duration)?,
+            None => DEFAULT_LOCKOUT_DURATION,
+        };
+
+        let trust_proxy_headers = match var("TRUST_PROXY_HEADERS") {
+            Some(trust) => parse_bool("TRUST_PROXY_HEADERS", &trust)?,
+            None => false,
+        };
+
+        Ok(ThrottleConfig {
+            lockout_attempts,
+            lockout_duration,
+            trust_proxy_headers,
+        })
+    }
+
+    /// Usernames get a few free attempts, then a wait doubling from a second
+    /// up to a minute, until they are locked out.
+    pub fn username_policy(&self) -> ThrottlePolicy {
+        ThrottlePolicy {
+            free_attempts: (self.lockout_attempts / 3).max(1),
+            base_delay: std::time::Duration::from_secs(1),
+            max_delay: std::time::Duration::from_secs(60),
+            lockout_attempts: self.lockout_attempts,
+            lockout: std::time::Duration::from_secs(self.lockout_duration as u64),
+        }
+    }
+
+    /// Like [`ThrottleConfig::username_policy`], with more attempts allowed.
+    pub fn ip_policy(&self) -> ThrottlePolicy {
+        let username_policy = self.username_policy();
+        ThrottlePolicy {
+            free_attempts: username_policy.free_attempts * IP_ATTEMPTS_FACTOR,
+            lockout_attempts: self.lockout_attempts.saturating_mul(IP_ATTEMPTS_FACTOR),
+            ..username_policy
+        }
+    }
+
+    pub fn throttle(&self) -> LoginThrottle {
+        LoginThrottle::new(
+            self.ip_policy(),
+            self.username_policy(),
+            self.trust_proxy_headers,
+        )
+    }
+}
+
+fn parse_seconds(name: &str, value: &str) -> Result<i64, MoolahBackendError> {
+    match value.parse::<i64>() {
+        Ok(seconds) if seconds > 0 => Ok(seconds),
+        _ => Err(MoolahBackendError::ConfigError(format!(
+            "{} must be a positive number of seconds, not {}",
+            name, value
+        ))),
+    }
+}
+
+fn parse_bool(name: &str, value: &str) -> Result<bool, MoolahBackendError> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(MoolahBackendError::ConfigError(format!(
+            "{} must be true or false, not {}",
+            name, value
+        ))),
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+
+use super::*;
+
+const SECRET: &str = "a session secret that is long enough";
+
+fn config(vars: &[(&str, &str)]) -> Result<SessionConfig, MoolahBackendError> {
+    let vars = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect::<HashMap<_, _>>();
+    SessionConfig::from_vars(|name| vars.get(name).cloned())
+}
+
+mod from_vars {
+    use super::*;
+
+    #[test]
+    fn test_production_requires_secret() {
+        assert!(config(&[("MOOLAH_ENV", "production")]).is_err());
+    }
+
+    #[test]
+    fn test_development_generates_secret() {
+        let config = config(&[]).unwrap();
+
+        assert!(config.secret.len() >= MIN_SECRET_LENGTH);
+        assert!(!config.secure);
+    }
+
+    #[test]
+    fn test_short_secret() {
+        assert!(config(&[("SESSION_SECRET", "too short")]).is_err());
+    }
+
+    #[test]
+    fn test_production_defaults() {
+        let config = config(&[("MOOLAH_ENV", "production"), ("SESSION_SECRET", SECRET)]).unwrap();
+
+        assert_eq!(SECRET.as_bytes(), config.secret);
+        assert!(config.secure);
+        assert_eq!(SameSite::Lax, config.same_site);
+        assert_eq!(None, config.max_age);
+        assert_eq!(None, config.domain);
+        assert_eq!(Duration::days(7), config.expiry().idle);
+        assert_eq!(Duration::days(30), config.expiry().absolute);
+    }
+
+    #[test]
+    fn test_session_timeouts() {
+        let config = config(&[
+            ("SESSION_IDLE_TIMEOUT", "1800"),
+            ("SESSION_ABSOLUTE_TIMEOUT", "86400"),
+        ])
+        .unwrap();
+
+        assert_eq!(Duration::minutes(30), config.expiry().idle);
+        assert_eq!(Duration::days(1), config.expiry().absolute);
+    }
+
+    #[test]
+    fn test_cookie_options() {
+        let config = config(&[
+            ("SESSION_SECRET", SECRET),
+            ("SESSION_COOKIE_SECURE", "true"),
+            ("SESSION_COOKIE_SAME_SITE", "Strict"),
+            ("SESSION_COOKIE_MAX_AGE", "3600"),
+            ("SESSION_COOKIE_DOMAIN", "moolah.example"),
+        ])
+        .unwrap();
+
+        assert!(config.secure);
+        assert_eq!(SameSite::Strict, config.same_site);
+        assert_eq!(Some(3600), config.max_age);
+        assert_eq!(Some("moolah.example".to_string()), config.domain);
+    }
+
+    #[test]
+    fn test_same_site_none_requires_secure() {
+        assert!(config(&[
+            ("SESSION_SECRET", SECRET),
+            ("SESSION_COOKIE_SAME_SITE", "none"),
+            ("SESSION_COOKIE_SECURE", "false"),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(config(&[("SESSION_COOKIE_SECURE", "maybe")]).is_err());
+        assert!(config(&[("SESSION_COOKIE_SAME_SITE", "sometimes")]).is_err());
+        assert!(config(&[("SESSION_COOKIE_MAX_AGE", "-5")]).is_err());
+        assert!(config(&[("SESSION_COOKIE_MAX_AGE", "a day")]).is_err());
+        assert!(config(&[("SESSION_IDLE_TIMEOUT", "0")]).is_err());
+    }
+}
+
+mod policy {
+    use actix_identity::{Identity, IdentityService};
+    use actix_web::cookie::Cookie;
+    use actix_web::{test, web, App, HttpResponse};
+
+    use super::*;
+
+    async fn login(id: Identity) -> HttpResponse {
+        id.remember("alice".into());
+        HttpResponse::Ok().finish()
+    }
+
+    async fn whoami(id: Identity) -> HttpResponse {
+        HttpResponse::Ok().body(id.identity().unwrap_or_default())
+    }
+
+    /// Log in through `issuer` and return the auth cookie it sets.
+    async fn issued_cookie(issuer: CookieIdentityPolicy) -> Cookie<'static> {
+        let app = test::init_service(
+            App::new()
+                .wrap(IdentityService::new(issuer))
+                .route("/login", web::post().to(login)),
+        )
+        .await;
+
+        let response =
+            test::call_service(&app, test::TestRequest::post().uri("/login").to_request()).await;
+        response
+            .response()
+            .cookies()
+            .next()
+            .expect("login should set a cookie")
+            .into_owned()
+    }
+
+    /// Who the app built from `config` thinks sent `cookie`.
+    async fn identity_for(config: &SessionConfig, cookie: Cookie<'static>) -> String {
+        let app = test::init_service(
+            App::new()
+                .wrap(IdentityService::new(config.cookie_policy()))
+                .route("/whoami", web::get().to(whoami)),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/whoami")
+            .cookie(cookie)
+            .to_request();
+        String::from_utf8(test::call_and_read_body(&app, request).await.to_vec()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_accepts_own_cookie() {
+        let config = config(&[("SESSION_SECRET", SECRET)]).unwrap();
+
+        let cookie = issued_cookie(config.cookie_policy()).await;
+
+        assert_eq!("alice", identity_for(&config, cookie).await);
+    }
+
+    #[actix_web::test]
+    async fn test_rejects_cookie_signed_with_another_key() {
+        let config = config(&[("SESSION_SECRET", SECRET)]).unwrap();
+
+        let cookie =
+            issued_cookie(CookieIdentityPolicy::new(&[0; 32]).name(AUTH_COOKIE_NAME)).await;
+
+        assert_eq!("", identity_for(&config, cookie).await);
+    }
+
+    #[actix_web::test]
+    async fn test_rejects_unsigned_cookie() {
+        let config = config(&[("SESSION_SECRET", SECRET)]).unwrap();
+
+        let cookie = Cookie::new(AUTH_COOKIE_NAME, "\"alice\"");
+
+        assert_eq!("", identity_for(&config, cookie).await);
+    }
+
+    #[actix_web::test]
+    async fn test_cookie_attributes() {
+        let config = config(&[
+            ("SESSION_SECRET", SECRET),
+            ("SESSION_COOKIE_SECURE", "true"),
+            ("SESSION_COOKIE_SAME_SITE", "strict"),
+            ("SESSION_COOKIE_MAX_AGE", "600"),
+            ("SESSION_COOKIE_DOMAIN", "moolah.example"),
+        ])
+        .unwrap();
+
+        let cookie = issued_cookie(config.cookie_policy()).await;
+
+        assert_eq!(AUTH_COOKIE_NAME, cookie.name());
+        assert_eq!(Some(true), cookie.secure());
+        assert_eq!(Some(true), cookie.http_only());
+        assert_eq!(Some(SameSite::Strict), cookie.same_site());
+        assert_eq!(Some(600), cookie.max_age().map(|age| age.whole_seconds()));
+        assert_eq!(Some("moolah.example"), cookie.domain());
+    }
+}
+
+mod mail {
+    use super::*;
+
+    fn mail_config(vars: &[(&str, &str)]) -> Result<MailConfig, MoolahBackendError> {
+        let vars = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+        MailConfig::from_vars(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_development_logs() {
+        let config = mail_config(&[]).unwrap();
+
+        assert_eq!(MailTransport::Log, config.transport);
+        assert_eq!(
+            "http://localhost:3000/reset-password?token=abc",
+            config.link("reset-password", "abc")
+        );
+    }
+
+    #[test]
+    fn test_production_requires_mailer() {
+        assert!(mail_config(&[("MOOLAH_ENV", "production")]).is_err());
+        assert!(mail_config(&[("MOOLAH_ENV", "production"), ("MAILER", "log")]).is_ok());
+    }
+
+    #[test]
+    fn test_file() {
+        let config = mail_config(&[("MAILER", "file"), ("MAIL_DIR", "/tmp/moolah")]).unwrap();
+
+        assert_eq!(MailTransport::File("/tmp/moolah".into()), config.transport);
+    }
+
+    #[test]
+    fn test_smtp() {
+        let config = mail_config(&[
+            ("MAILER", "smtp"),
+            ("MAIL_FROM", "Moolah <moolah@example.com>"),
+            ("SMTP_HOST", "smtp.example.com"),
+            ("SMTP_PORT", "465"),
+            ("SMTP_USERNAME", "moolah"),
+            ("SMTP_PASSWORD", "hunter2"),
+            ("APP_URL", "https://moolah.example.com/"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            MailTransport::Smtp {
+                host: "smtp.example.com".into(),
+                port: Some(465),
+                credentials: Some(("moolah".into(), "hunter2".into())),
+            },
+            config.transport
+        );
+        assert_eq!("https://moolah.example.com", config.app_url);
+    }
+
+    #[test]
+    fn test_invalid_smtp() {
+        assert!(mail_config(&[("MAILER", "smtp"), ("MAIL_FROM", "a@example.com")]).is_err());
+        assert!(mail_config(&[("MAILER", "smtp"), ("SMTP_HOST", "smtp.example.com")]).is_err());
+        assert!(mail_config(&[
+            ("MAILER", "smtp"),
+            ("MAIL_FROM", "a@example.com"),
+            ("SMTP_HOST", "smtp.example.com"),
+            ("SMTP_USERNAME", "moolah"),
+        ])
+        .is_err());
+        assert!(mail_config(&[("MAILER", "carrier pigeon")]).is_err());
+    }
+}
+
+mod throttle {
+    use super::*;
+
+    fn throttle_config(vars: &[(&str, &str)]) -> Result<ThrottleConfig, MoolahBackendError> {
+        let vars = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+        ThrottleConfig::from_vars(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = throttle_config(&[]).unwrap();
+        let username_policy = config.username_policy();
+        let ip_policy = config.ip_policy();
+
+        assert_eq!(3, username_policy.free_attempts);
+        assert_eq!(10, username_policy.lockout_attempts);
+        assert_eq!(std::time::Duration::from_secs(900), username_policy.lockout);
+        assert_eq!(15, ip_policy.free_attempts);
+        assert_eq!(50, ip_policy.lockout_attempts);
+        assert!(!config.trust_proxy_headers);
+    }
+
+    #[test]
+    fn test_options() {
+        let config = throttle_config(&[
+            ("LOGIN_LOCKOUT_ATTEMPTS", "5"),
+            ("LOGIN_LOCKOUT_DURATION", "3600"),
+            ("TRUST_PROXY_HEADERS", "true"),
+        ])
+        .unwrap();
+
+        assert_eq!(1, config.username_policy().free_attempts);
+        assert_eq!(5, config.username_policy().lockout_attempts);
+        assert_eq!(
+            std::time::Duration::from_secs(3600),
+            config.ip_policy().lockout
+        );
+        assert!(config.trust_proxy_headers);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(throttle_config(&[("LOGIN_LOCKOUT_ATTEMPTS", "0")]).is_err());
+        assert!(throttle_config(&[("LOGIN_LOCKOUT_DURATION", "soon")]).is_err());
+        assert!(throttle_config(&[("TRUST_PROXY_HEADERS", "maybe")]).is_err());
+    }
+}
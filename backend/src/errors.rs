@@ -6,11 +6,20 @@ pub enum MoolahBackendError {
     #[error("Environment error")]
     EnvironmentError(#[from] std::env::VarError),
 
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
     #[error("R2D2 error")]
     R2D2Error(#[from] r2d2::Error),
 
     #[error("Diesel error: {0}")]
     DieselError(#[from] diesel::result::Error),
+
+    #[error("Mail error: {0}")]
+    MailError(String),
+
+    #[error("Shared error: {0}")]
+    SharedError(#[from] shared::MoolahSharedError),
     // #[error("Argonautica error")]
     // ArgonauticaError(#[from] argonautica::Error),
 }
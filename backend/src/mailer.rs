@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests;
+
+mod smtp;
+
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Local;
+
+use crate::errors::MoolahBackendError;
+
+pub use smtp::SmtpMailer;
+
+/// A plain text email to a single recipient.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Somewhere to send email. Handlers take a `web::Data<dyn Mailer>` so the
+/// delivery method is chosen once by [`crate::config::MailConfig`].
+pub trait Mailer: Send + Sync {
+    fn send(&self, email: &Email) -> Result<(), MoolahBackendError>;
+}
+
+/// Writes each email to the log instead of sending it. Only fit for local
+/// development, since anyone reading the log can follow the links.
+pub struct LogMailer;
+
+impl Mailer for LogMailer {
+    fn send(&self, email: &Email) -> Result<(), MoolahBackendError> {
+        log::info!(
+            "email to {}\nSubject: {}\n\n{}",
+            email.to,
+            email.subject,
+            email.body
+        );
+        Ok(())
+    }
+}
+
+/// Writes each email to its own file in a directory, for local testing.
+pub struct FileMailer {
+    dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(dir: PathBuf) -> Self {
+        FileMailer { dir }
+    }
+}
+
+impl Mailer for FileMailer {
+    fn send(&self, email: &Email) -> Result<(), MoolahBackendError> {
+        let mail_error = |err: std::io::Error| MoolahBackendError::MailError(err.to_string());
+
+        fs::create_dir_all(&self.dir).map_err(mail_error)?;
+
+        let name = format!(
+            "{}-{}.eml",
+            Local::now().format("%Y%m%dT%H%M%S%.f"),
+            email.to.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        );
+        let contents = format!(
+            "To: {}\nSubject: {}\n\n{}\n",
+            email.to, email.subject, email.body
+        );
+
+        fs::write(self.dir.join(name), contents).map_err(mail_error)
+    }
+}
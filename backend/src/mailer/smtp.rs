@@ -0,0 +1,65 @@
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+use super::{Email, Mailer};
+use crate::errors::MoolahBackendError;
+
+/// Sends email through an SMTP relay over TLS.
+pub struct SmtpMailer {
+    from: Mailbox,
+    transport: SmtpTransport,
+}
+
+impl SmtpMailer {
+    pub fn new(
+        from: &str,
+        host: &str,
+        port: Option<u16>,
+        credentials: Option<(String, String)>,
+    ) -> Result<Self, MoolahBackendError> {
+        let from = from
+            .parse::<Mailbox>()
+            .map_err(|err| MoolahBackendError::ConfigError(format!("MAIL_FROM: {}", err)))?;
+
+        let builder = SmtpTransport::relay(host)
+            .map_err(|err| MoolahBackendError::ConfigError(format!("SMTP_HOST: {}", err)))?;
+
+        let builder = match port {
+            Some(port) => builder.port(port),
+            None => builder,
+        };
+
+        let builder = match credentials {
+            Some((username, password)) => builder.credentials(Credentials::new(username, password)),
+            None => builder,
+        };
+
+        Ok(SmtpMailer {
+            from,
+            transport: builder.build(),
+        })
+    }
+}
+
+impl Mailer for SmtpMailer {
+    fn send(&self, email: &Email) -> Result<(), MoolahBackendError> {
+        let to = email
+            .to
+            .parse::<Mailbox>()
+            .map_err(|err| MoolahBackendError::MailError(err.to_string()))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(email.subject.clone())
+            .body(email.body.clone())
+            .map_err(|err| MoolahBackendError::MailError(err.to_string()))?;
+
+        self.transport
+            .send(&message)
+            .map_err(|err| MoolahBackendError::MailError(err.to_string()))?;
+
+        Ok(())
+    }
+}
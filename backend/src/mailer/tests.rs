@@ -0,0 +1,26 @@
+use super::*;
+
+#[test]
+fn test_file_mailer_writes_email() {
+    let dir = std::env::temp_dir().join(format!("moolah-mail-{}", std::process::id()));
+    let mailer = FileMailer::new(dir.clone());
+
+    mailer
+        .send(&Email {
+            to: "alice@example.com".into(),
+            subject: "Hello".into(),
+            body: "a link".into(),
+        })
+        .unwrap();
+
+    let files = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(1, files.len());
+    assert!(files[0]
+        .to_string_lossy()
+        .ends_with("alice_example_com.eml"));
+}
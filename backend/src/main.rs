@@ -1,5 +1,4 @@
-use actix_identity::{CookieIdentityPolicy, IdentityService};
-use actix_web::cookie::SameSite;
+use actix_identity::IdentityService;
 use actix_web::middleware::Logger;
 use actix_web::{web, App, HttpResponse, HttpServer};
 use diesel::pg::PgConnection;
@@ -7,11 +6,20 @@ use diesel::r2d2::ConnectionManager;
 use dotenv::dotenv;
 use shared::routes;
 
+mod config;
 mod errors;
+mod mailer;
 mod services;
+mod session_store;
+mod throttle;
 
+use config::{MailConfig, SessionConfig, ThrottleConfig};
 use errors::MoolahBackendError;
-use services::{deltas, login, logout, predictions, user};
+use services::{
+    api_tokens, assets, deltas, email_verification, exports, generators, goals, imports,
+    investments, login, logout, net_worth, password_reset, predictions, retirement, rules,
+    sessions, taxes, totp, transactions, user,
+};
 
 type Pool = r2d2::Pool<ConnectionManager<PgConnection>>;
 type HttpResult = Result<HttpResponse, MoolahBackendError>;
@@ -27,27 +35,83 @@ async fn main() -> std::io::Result<()> {
         .build(manager)
         .expect("Failed to create Postgres pool.");
 
+    let session = SessionConfig::from_env().expect("invalid session configuration");
+    let mail = MailConfig::from_env().expect("invalid mail configuration");
+    let mailer = mail.mailer().expect("failed to set up mailer");
+    let throttle = web::Data::new(
+        ThrottleConfig::from_env()
+            .expect("invalid login throttle configuration")
+            .throttle(),
+    );
+
     HttpServer::new(move || {
         App::new()
             .wrap(Logger::default())
-            .wrap(IdentityService::new(
-                CookieIdentityPolicy::new(&[0; 32])
-                    .name("auth-cookie")
-                    .same_site(SameSite::None),
-            ))
+            .wrap(IdentityService::new(session.policy(pool.clone())))
             .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(session.expiry()))
+            .app_data(web::Data::new(mail.clone()))
+            .app_data(web::Data::from(mailer.clone()))
+            .app_data(throttle.clone())
             .route(routes::LOGOUT, web::put().to(logout::put_logout))
+            .route(routes::LOGIN, web::post().to(login::post_login))
             .route(
-                routes::LOGIN_REQUEST_PASSWORD,
-                web::put().to(login::put_request_login_password),
+                routes::LOGIN_TWO_FACTOR,
+                web::post().to(login::post_login_two_factor),
             )
-            .route(routes::LOGIN, web::patch().to(login::patch_login))
             .service(
                 web::resource(routes::USER)
                     .route(web::get().to(user::get_user_account))
                     .route(web::put().to(user::put_user))
                     .route(web::delete().to(user::delete_user)),
             )
+            .route(
+                routes::USER_PASSWORD,
+                web::patch().to(user::patch_user_password),
+            )
+            .route(routes::USER_EMAIL, web::patch().to(user::patch_user_email))
+            .route(
+                routes::USER_USERNAME,
+                web::patch().to(user::patch_user_username),
+            )
+            .service(
+                web::resource(routes::TOTP)
+                    .route(web::get().to(totp::get_totp))
+                    .route(web::post().to(totp::post_totp))
+                    .route(web::put().to(totp::put_totp))
+                    .route(web::delete().to(totp::delete_totp)),
+            )
+            .route(
+                routes::TOTP_RECOVERY_CODES,
+                web::post().to(totp::post_recovery_codes),
+            )
+            .route(
+                routes::PASSWORD_RESET_REQUEST,
+                web::post().to(password_reset::post_password_reset_request),
+            )
+            .route(
+                routes::PASSWORD_RESET,
+                web::post().to(password_reset::post_password_reset),
+            )
+            .route(
+                routes::EMAIL_VERIFICATION_REQUEST,
+                web::post().to(email_verification::post_verification_request),
+            )
+            .route(
+                routes::EMAIL_VERIFICATION,
+                web::post().to(email_verification::post_email_verification),
+            )
+            .service(
+                web::resource(routes::SESSIONS)
+                    .route(web::get().to(sessions::get_sessions))
+                    .route(web::delete().to(sessions::delete_session)),
+            )
+            .service(
+                web::resource(routes::API_TOKENS)
+                    .route(web::get().to(api_tokens::get_api_tokens))
+                    .route(web::post().to(api_tokens::post_api_token))
+                    .route(web::delete().to(api_tokens::delete_api_token)),
+            )
             .service(
                 web::resource(routes::PREDICTIONS)
                     .route(web::get().to(predictions::get_predictions))
@@ -55,7 +119,117 @@ async fn main() -> std::io::Result<()> {
                     .route(web::delete().to(predictions::delete_prediction))
                     .route(web::patch().to(predictions::patch_prediction)),
             )
-            .service(web::resource(routes::DELTAS).route(web::post().to(deltas::post_delta)))
+            .service(
+                web::resource(routes::DELTAS)
+                    .route(web::get().to(deltas::get_delta))
+                    .route(web::post().to(deltas::post_delta))
+                    .route(web::patch().to(deltas::patch_delta))
+                    .route(web::delete().to(deltas::delete_delta)),
+            )
+            .service(
+                web::resource(routes::GOALS)
+                    .route(web::get().to(goals::get_goals))
+                    .route(web::put().to(goals::put_goal))
+                    .route(web::delete().to(goals::delete_goal)),
+            )
+            .service(
+                web::resource(routes::GOALS_PROGRESS)
+                    .route(web::get().to(goals::get_goal_progress)),
+            )
+            .service(
+                web::resource(routes::INVESTMENTS)
+                    .route(web::get().to(investments::get_investment_accounts))
+                    .route(web::put().to(investments::put_investment_account))
+                    .route(web::delete().to(investments::delete_investment_account)),
+            )
+            .service(
+                web::resource(routes::INVESTMENTS_PROJECTION)
+                    .route(web::get().to(investments::get_investment_projections)),
+            )
+            .service(
+                web::resource(routes::ASSETS)
+                    .route(web::get().to(assets::get_assets))
+                    .route(web::put().to(assets::put_asset))
+                    .route(web::delete().to(assets::delete_asset)),
+            )
+            .service(
+                web::resource(routes::ASSET_VALUATIONS)
+                    .route(web::put().to(assets::put_asset_valuation))
+                    .route(web::delete().to(assets::delete_asset_valuation)),
+            )
+            .service(
+                web::resource(routes::NET_WORTH).route(web::get().to(net_worth::get_net_worth)),
+            )
+            .service(
+                web::resource(routes::GENERATE_LOAN)
+                    .route(web::post().to(generators::post_loan_preview))
+                    .route(web::put().to(generators::put_loan)),
+            )
+            .service(
+                web::resource(routes::GENERATE_DEBT_PAYOFF)
+                    .route(web::post().to(generators::post_debt_payoff_preview))
+                    .route(web::put().to(generators::put_debt_payoff)),
+            )
+            .service(
+                web::resource(routes::GENERATE_PAYCHECK)
+                    .route(web::post().to(generators::post_paycheck_preview))
+                    .route(web::put().to(generators::put_paycheck)),
+            )
+            .service(
+                web::resource(routes::TAX_ESTIMATE).route(web::post().to(taxes::post_tax_estimate)),
+            )
+            .service(
+                web::resource(routes::RETIREMENT_PROJECTION)
+                    .route(web::post().to(retirement::post_retirement_projection)),
+            )
+            .service(
+                web::resource(routes::TRANSACTIONS)
+                    .route(web::get().to(transactions::get_transactions)),
+            )
+            .service(
+                web::resource(routes::RECURRING).route(web::get().to(transactions::get_recurring)),
+            )
+            .service(
+                web::resource(routes::RULES)
+                    .route(web::get().to(rules::get_rules))
+                    .route(web::put().to(rules::put_rule))
+                    .route(web::delete().to(rules::delete_rule)),
+            )
+            .service(
+                web::resource(routes::RULES_APPLY).route(web::post().to(rules::post_apply_rules)),
+            )
+            .service(
+                web::resource(routes::EXPORT_DELTAS)
+                    .route(web::get().to(exports::get_deltas_export)),
+            )
+            .service(
+                web::resource(routes::EXPORT_SCHEDULE)
+                    .route(web::get().to(exports::get_schedule_export)),
+            )
+            .service(
+                web::resource(routes::EXPORT_JOURNAL)
+                    .route(web::get().to(exports::get_journal_export)),
+            )
+            .service(
+                web::resource(routes::IMPORT_CSV)
+                    .route(web::post().to(imports::post_csv_preview))
+                    .route(web::put().to(imports::put_csv_import)),
+            )
+            .service(
+                web::resource(routes::IMPORT_OFX)
+                    .route(web::post().to(imports::post_ofx_preview))
+                    .route(web::put().to(imports::put_ofx_import)),
+            )
+            .service(
+                web::resource(routes::IMPORT_QIF)
+                    .route(web::post().to(imports::post_qif_preview))
+                    .route(web::put().to(imports::put_qif_import)),
+            )
+            .service(
+                web::resource(routes::IMPORT_LEDGER)
+                    .route(web::post().to(imports::post_ledger_preview))
+                    .route(web::put().to(imports::put_ledger_import)),
+            )
     })
     .bind(("127.0.0.1", 8000))?
     .run()
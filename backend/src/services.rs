@@ -1,9 +1,31 @@
 use actix_identity::Identity;
+use chrono::{Duration, NaiveDateTime};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use shared::models::{user_tokens::hash_token, NewUserToken, TokenPurpose, UserToken};
 
+use crate::errors::MoolahBackendError;
+
+pub mod api_tokens;
+pub mod assets;
 pub mod deltas;
+pub mod email_verification;
+pub mod exports;
+pub mod generators;
+pub mod goals;
+pub mod imports;
+pub mod investments;
 pub mod login;
 pub mod logout;
+pub mod net_worth;
+pub mod password_reset;
 pub mod predictions;
+pub mod retirement;
+pub mod rules;
+pub mod sessions;
+pub mod taxes;
+pub mod totp;
+pub mod transactions;
 pub mod user;
 
 #[derive(PartialEq)]
@@ -13,6 +35,9 @@ enum AuthenticationStatus {
     Unauthorized,
 }
 
+/// How the identity making a request relates to `username`. The identity
+/// comes from either the session cookie or an API token sent as a bearer
+/// header; the identity policy has already checked a token's scopes.
 fn authentication_status(id: &Identity, username: &str) -> AuthenticationStatus {
     if let Some(auth_username) = id.identity() {
         if auth_username.to_lowercase() == username.to_lowercase() {
@@ -28,3 +53,89 @@ fn authentication_status(id: &Identity, username: &str) -> AuthenticationStatus
 fn is_authenticated(id: &Identity, username: &str) -> bool {
     authentication_status(id, username) == AuthenticationStatus::Matching
 }
+
+/// Whether the prediction exists and belongs to `username`.
+fn owns_prediction(
+    username: &str,
+    prediction_id: i32,
+    connection: &PgConnection,
+) -> Result<bool, MoolahBackendError> {
+    use shared::schema::predictions::dsl;
+
+    let prediction_user = dsl::predictions
+        .filter(dsl::id.eq(prediction_id))
+        .select(dsl::username)
+        .get_result::<String>(connection)
+        .optional()?;
+
+    Ok(prediction_user.as_deref() == Some(username))
+}
+
+/// Replace any unused `purpose` token of `username`'s with a new one lasting
+/// `lifetime`, returning the token to mail out. Only the latest link works.
+fn issue_token(
+    username: &str,
+    purpose: TokenPurpose,
+    lifetime: Duration,
+    connection: &PgConnection,
+) -> Result<String, MoolahBackendError> {
+    use shared::schema::user_tokens::dsl;
+
+    let now = chrono::Local::now().naive_utc();
+    let (new_token, token) = NewUserToken::new(username.to_string(), purpose, lifetime, now);
+
+    connection.transaction::<_, MoolahBackendError, _>(|| {
+        diesel::delete(
+            dsl::user_tokens
+                .filter(dsl::username.eq(username))
+                .filter(dsl::purpose.eq(purpose.as_str()))
+                .filter(dsl::used.is_null()),
+        )
+        .execute(connection)?;
+
+        diesel::insert_into(dsl::user_tokens)
+            .values(&new_token)
+            .execute(connection)?;
+
+        Ok(())
+    })?;
+
+    Ok(token)
+}
+
+/// The stored token matching `token`, if it can still be used for `purpose`.
+fn usable_token(
+    token: &str,
+    purpose: TokenPurpose,
+    now: NaiveDateTime,
+    connection: &PgConnection,
+) -> Result<Option<UserToken>, MoolahBackendError> {
+    use shared::schema::user_tokens::dsl;
+
+    let token = dsl::user_tokens
+        .filter(dsl::token_hash.eq(hash_token(token)))
+        .first::<UserToken>(connection)
+        .optional()?;
+
+    Ok(token.filter(|token| token.is_usable(purpose, now)))
+}
+
+/// Mark `token` used, unless it already was. Checking within the update stops
+/// two concurrent requests both spending it.
+fn spend_token(
+    token: &UserToken,
+    now: NaiveDateTime,
+    connection: &PgConnection,
+) -> Result<bool, MoolahBackendError> {
+    use shared::schema::user_tokens::dsl;
+
+    let spent = diesel::update(
+        dsl::user_tokens
+            .find(token.id())
+            .filter(dsl::used.is_null()),
+    )
+    .set(dsl::used.eq(now))
+    .execute(connection)?;
+
+    Ok(spent > 0)
+}
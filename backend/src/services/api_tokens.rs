@@ -0,0 +1,120 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{ApiToken, ApiTokenInfo, CreatedApiToken, NewApiToken, NewApiTokenRequest};
+use shared::schema::api_tokens;
+
+use super::is_authenticated;
+use crate::{HttpResult, Pool};
+
+/// Most API tokens a user can have at once.
+const MAX_API_TOKENS: i64 = 20;
+
+/// The user's API tokens, newest first. Expired tokens are listed until
+/// revoked, so the user can see why a script stopped working.
+pub async fn get_api_tokens(
+    path: web::Path<String>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner().to_lowercase();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to view these API tokens");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let connection = pool.get()?;
+
+    let tokens = api_tokens::table
+        .filter(api_tokens::username.eq(&username))
+        .order(api_tokens::created.desc())
+        .load::<ApiToken>(&connection)?
+        .iter()
+        .map(ApiToken::info)
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+/// Create an API token. The response is the only time the token itself is
+/// sent back.
+pub async fn post_api_token(
+    path: web::Path<String>,
+    web::Json(request): web::Json<NewApiTokenRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner().to_lowercase();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to create API tokens");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let now = chrono::Local::now().naive_utc();
+    let (new_token, token) = match NewApiToken::new(username.clone(), &request, now) {
+        Ok(new_token) => new_token,
+        Err(err) => {
+            log::debug!("invalid API token request: {}", err);
+            return Ok(HttpResponse::BadRequest().body(err.to_string()));
+        }
+    };
+
+    let connection = pool.get()?;
+
+    let count: i64 = api_tokens::table
+        .filter(api_tokens::username.eq(&username))
+        .count()
+        .get_result(&connection)?;
+    if count >= MAX_API_TOKENS {
+        return Ok(HttpResponse::Conflict().body(format!(
+            "an account can have at most {} API tokens; revoke one first",
+            MAX_API_TOKENS
+        )));
+    }
+
+    let created = diesel::insert_into(api_tokens::table)
+        .values(&new_token)
+        .get_result::<ApiToken>(&connection)?;
+
+    log::info!("created API token {} for {}", created.id(), username);
+
+    Ok(HttpResponse::Ok().json(CreatedApiToken {
+        info: created.info(),
+        token,
+    }))
+}
+
+/// Revoke one of the user's API tokens. It stops working immediately.
+pub async fn delete_api_token(
+    path: web::Path<String>,
+    web::Json(token): web::Json<ApiTokenInfo>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner().to_lowercase();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to revoke this API token");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let connection = pool.get()?;
+
+    let revoked = diesel::delete(
+        api_tokens::table
+            .filter(api_tokens::id.eq(token.id))
+            .filter(api_tokens::username.eq(&username)),
+    )
+    .get_result::<ApiToken>(&connection)
+    .optional()?;
+
+    match revoked {
+        Some(revoked) => {
+            log::info!("revoked API token {} of {}", revoked.id(), username);
+            Ok(HttpResponse::Ok().finish())
+        }
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
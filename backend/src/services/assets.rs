@@ -0,0 +1,217 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    Asset, AssetQuery, AssetValuation, AssetWithValuations, NewAssetRequest, NewAssetValuation,
+};
+use shared::schema::{asset_valuations, assets};
+
+use super::{is_authenticated, owns_prediction};
+use crate::errors::MoolahBackendError;
+use crate::{HttpResult, Pool};
+
+/// Every asset and liability of a prediction along with their valuations.
+pub(super) fn load_assets(
+    prediction_id: i32,
+    connection: &PgConnection,
+) -> Result<Vec<AssetWithValuations>, MoolahBackendError> {
+    let assets = assets::table
+        .filter(assets::prediction_id.eq(prediction_id))
+        .order(assets::id)
+        .load::<Asset>(connection)?;
+
+    let valuations = AssetValuation::belonging_to(&assets)
+        .order(asset_valuations::valued_on)
+        .load::<AssetValuation>(connection)?
+        .grouped_by(&assets);
+
+    Ok(assets
+        .into_iter()
+        .zip(valuations)
+        .map(|(asset, valuations)| AssetWithValuations { asset, valuations })
+        .collect())
+}
+
+/// Whether `username` owns the prediction an asset belongs to.
+fn owns_asset(
+    username: &str,
+    asset_id: i32,
+    connection: &PgConnection,
+) -> Result<bool, MoolahBackendError> {
+    let prediction_id = assets::table
+        .select(assets::prediction_id)
+        .filter(assets::id.eq(asset_id))
+        .first::<i32>(connection)
+        .optional()?;
+
+    match prediction_id {
+        Some(prediction_id) => owns_prediction(username, prediction_id, connection),
+        None => Ok(false),
+    }
+}
+
+pub async fn get_assets(
+    path: web::Path<String>,
+    query: web::Query<AssetQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, query.prediction_id, &connection)?
+    {
+        log::debug!("user is not authorized to get assets for this prediction");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let assets = load_assets(query.prediction_id, &connection)?;
+
+    Ok(HttpResponse::Ok().json(assets))
+}
+
+pub async fn put_asset(
+    path: web::Path<String>,
+    web::Json(request): web::Json<NewAssetRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, request.asset.prediction_id(), &connection)?
+    {
+        log::debug!("user is not authorized to post this asset");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    if let Err(err) = request.asset.validate() {
+        log::debug!("invalid asset: {}", err);
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let asset = connection.transaction::<_, MoolahBackendError, _>(|| {
+        let asset = diesel::insert_into(assets::table)
+            .values(&request.asset)
+            .get_result::<Asset>(&connection)?;
+
+        let valuation = NewAssetValuation::new(asset.id(), request.valued_on, request.value)?;
+        let valuation = diesel::insert_into(asset_valuations::table)
+            .values(&valuation)
+            .get_result::<AssetValuation>(&connection)?;
+
+        Ok(AssetWithValuations {
+            asset,
+            valuations: vec![valuation],
+        })
+    });
+
+    match asset {
+        Ok(asset) => {
+            log::debug!("completed insert of asset {}", asset.asset.id());
+            Ok(HttpResponse::Ok().json(asset))
+        }
+        Err(MoolahBackendError::SharedError(err)) => {
+            log::debug!("invalid asset valuation: {}", err);
+            Ok(HttpResponse::BadRequest().body(err.to_string()))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+pub async fn delete_asset(
+    path: web::Path<String>,
+    web::Json(asset): web::Json<Asset>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, asset.prediction_id(), &connection)?
+    {
+        log::debug!("user is not authorized to delete this asset");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let n_deleted_rows = diesel::delete(
+        assets::table
+            .filter(assets::id.eq(asset.id()))
+            .filter(assets::prediction_id.eq(asset.prediction_id())),
+    )
+    .execute(&connection)?;
+
+    log::info!("deleted {} asset", n_deleted_rows);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Record what an asset is worth on a day, replacing any earlier valuation
+/// for the same day.
+pub async fn put_asset_valuation(
+    path: web::Path<String>,
+    web::Json(valuation): web::Json<NewAssetValuation>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_asset(&username, valuation.asset_id(), &connection)?
+    {
+        log::debug!("user is not authorized to value this asset");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    if let Err(err) = valuation.validate() {
+        log::debug!("invalid asset valuation: {}", err);
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let valuation = diesel::insert_into(asset_valuations::table)
+        .values(&valuation)
+        .on_conflict((asset_valuations::asset_id, asset_valuations::valued_on))
+        .do_update()
+        .set(asset_valuations::value.eq(valuation.value()))
+        .get_result::<AssetValuation>(&connection)?;
+
+    log::debug!("completed upsert of 1 rows");
+    Ok(HttpResponse::Ok().json(valuation))
+}
+
+pub async fn delete_asset_valuation(
+    path: web::Path<String>,
+    web::Json(valuation): web::Json<AssetValuation>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_asset(&username, valuation.asset_id(), &connection)?
+    {
+        log::debug!("user is not authorized to delete this asset valuation");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let n_deleted_rows = diesel::delete(
+        asset_valuations::table
+            .filter(asset_valuations::id.eq(valuation.id()))
+            .filter(asset_valuations::asset_id.eq(valuation.asset_id())),
+    )
+    .execute(&connection)?;
+
+    log::info!("deleted {} asset valuation", n_deleted_rows);
+
+    Ok(HttpResponse::Ok().finish())
+}
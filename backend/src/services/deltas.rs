@@ -1,10 +1,11 @@
 use actix_identity::Identity;
 use actix_web::{web, HttpResponse};
 use diesel::prelude::*;
-use shared::models::NewDbDelta;
+use shared::models::{DbDelta, Delta, DeltaQuery, NewDbDelta, NewDelta};
 use shared::schema::{deltas, predictions};
 
-use crate::{services::is_authenticated, HttpResult, Pool};
+use super::{is_authenticated, owns_prediction};
+use crate::{HttpResult, Pool};
 
 pub async fn post_delta(
     path: web::Path<String>,
@@ -32,8 +33,110 @@ pub async fn post_delta(
     Ok(HttpResponse::Ok().finish())
 }
 
-// pub async fn get_delta() {}
+pub async fn get_delta(
+    path: web::Path<String>,
+    query: web::Query<DeltaQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to view this delta");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let connection = pool.get()?;
+
+    // Only the user's own deltas are looked up, so other users' delta ids
+    // cannot be told apart from ones that do not exist
+    let db_delta = deltas::table
+        .inner_join(predictions::table)
+        .filter(deltas::id.eq(query.delta_id))
+        .filter(predictions::username.eq(&username))
+        .select(deltas::all_columns)
+        .get_result::<DbDelta>(&connection)
+        .optional()?;
+
+    let db_delta = match db_delta {
+        Some(db_delta) => db_delta,
+        None => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let delta = Delta::try_from(db_delta)?;
+
+    Ok(HttpResponse::Ok().json(delta))
+}
+
+/// Replace every field of a delta. The delta stays with the prediction it was
+/// created in.
+pub async fn patch_delta(
+    path: web::Path<String>,
+    web::Json(delta): web::Json<Delta>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, delta.prediction_id(), &connection)?
+    {
+        log::debug!("user is not authorized to update this delta");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let delta_id = delta.id();
+    let prediction_id = delta.prediction_id();
+    let db_delta = NewDbDelta::from(NewDelta::from(delta));
 
-// pub async fn patch_delta() {}
+    let n_updated_rows = diesel::update(
+        deltas::table
+            .filter(deltas::id.eq(delta_id))
+            .filter(deltas::prediction_id.eq(prediction_id)),
+    )
+    .set(&db_delta)
+    .execute(&connection)?;
+
+    if n_updated_rows == 0 {
+        return Ok(HttpResponse::NotFound().finish());
+    }
 
-// pub async fn delete_delta() {}
+    log::info!("updated {} delta", n_updated_rows);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn delete_delta(
+    path: web::Path<String>,
+    web::Json(delta): web::Json<Delta>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, delta.prediction_id(), &connection)?
+    {
+        log::debug!("user is not authorized to delete this delta");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let n_deleted_rows = diesel::delete(
+        deltas::table
+            .filter(deltas::id.eq(delta.id()))
+            .filter(deltas::prediction_id.eq(delta.prediction_id())),
+    )
+    .execute(&connection)?;
+
+    if n_deleted_rows == 0 {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    log::info!("deleted {} delta", n_deleted_rows);
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,122 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use chrono::Duration;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use shared::{
+    models::{EmailVerificationForm, TokenPurpose, User},
+    schema::users,
+};
+
+use super::{is_authenticated, issue_token, spend_token, usable_token, user::UserQuery};
+use crate::config::MailConfig;
+use crate::errors::MoolahBackendError;
+use crate::mailer::{Email, Mailer};
+use crate::Pool;
+
+/// How long a verification link works for.
+const VERIFICATION_TOKEN_LIFETIME_HOURS: i64 = 48;
+
+/// Mail `username` a link proving they own `email`. Any earlier link stops
+/// working.
+pub(crate) fn send_verification(
+    username: &str,
+    email: &str,
+    connection: &PgConnection,
+    mail: &MailConfig,
+    mailer: &dyn Mailer,
+) -> Result<(), MoolahBackendError> {
+    let token = issue_token(
+        username,
+        TokenPurpose::EmailVerification,
+        Duration::hours(VERIFICATION_TOKEN_LIFETIME_HOURS),
+        connection,
+    )?;
+
+    mailer.send(&Email {
+        to: email.to_string(),
+        subject: "Verify your moolah email".into(),
+        body: format!(
+            "Follow this link within {} hours to confirm {} is the email for {}:\n\n{}\n\nIf you did not sign up for moolah, you can ignore this email.",
+            VERIFICATION_TOKEN_LIFETIME_HOURS,
+            email,
+            username,
+            mail.link("verify-email", &token)
+        ),
+    })?;
+
+    log::info!("sent verification link to {}", username);
+
+    Ok(())
+}
+
+/// Send the logged in user another verification link.
+pub async fn post_verification_request(
+    query: web::Query<UserQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+    mail: web::Data<MailConfig>,
+    mailer: web::Data<dyn Mailer>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let username = query.into_inner().username.to_ascii_lowercase();
+    if !is_authenticated(&id, &username) {
+        return Ok(HttpResponse::Unauthorized().body("not authorized to verify this email"));
+    }
+
+    let connection = pool.get()?;
+    let user: User = users::table
+        .filter(users::username.eq(&username))
+        .first(&connection)?;
+
+    if user.is_verified() {
+        return Ok(HttpResponse::BadRequest().body("email is already verified"));
+    }
+
+    send_verification(&username, &user.email, &connection, &mail, mailer.as_ref())?;
+
+    Ok(HttpResponse::Ok().body("verification link sent"))
+}
+
+/// Mark the user's email verified with a token from [`send_verification`].
+/// Changing the email deletes outstanding tokens, so a token only ever
+/// verifies the address it was sent to.
+pub async fn post_email_verification(
+    web::Json(form): web::Json<EmailVerificationForm>,
+    pool: web::Data<Pool>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let connection = pool.get()?;
+    let now = chrono::Local::now().naive_utc();
+
+    let token = usable_token(
+        form.token(),
+        TokenPurpose::EmailVerification,
+        now,
+        &connection,
+    )?;
+
+    let verified = match &token {
+        Some(token) => connection.transaction::<_, MoolahBackendError, _>(|| {
+            if !spend_token(token, now, &connection)? {
+                return Ok(false);
+            }
+
+            diesel::update(users::table.filter(users::username.eq(token.username())))
+                .set(users::verified_at.eq(now))
+                .execute(&connection)?;
+
+            Ok(true)
+        })?,
+        None => false,
+    };
+
+    match token {
+        Some(token) if verified => {
+            log::info!("verified email for {}", token.username());
+            Ok(HttpResponse::Ok().body("email verified"))
+        }
+        _ => {
+            log::debug!("rejected email verification with an invalid token");
+            Ok(HttpResponse::BadRequest().body("verification link is invalid or has expired"))
+        }
+    }
+}
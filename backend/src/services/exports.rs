@@ -0,0 +1,155 @@
+use actix_identity::Identity;
+use actix_web::{http::header, web, HttpResponse, HttpResponseBuilder};
+use diesel::prelude::*;
+use shared::exports::{self, DeltaRow, ExportFormat, ExportQuery, JournalQuery};
+use shared::models::{DbDelta, Delta, Prediction};
+use shared::projection;
+use shared::schema::predictions;
+
+use super::is_authenticated;
+use crate::{errors::MoolahBackendError, HttpResult, Pool};
+
+/// Load a prediction's deltas, if the prediction belongs to `username`.
+fn load_deltas(
+    username: &str,
+    prediction_id: i32,
+    pool: &Pool,
+) -> Result<Option<(Prediction, Vec<Delta>)>, MoolahBackendError> {
+    let connection = pool.get()?;
+
+    let prediction = predictions::table
+        .filter(predictions::id.eq(prediction_id))
+        .filter(predictions::username.eq(username))
+        .get_result::<Prediction>(&connection)
+        .optional()?;
+
+    match prediction {
+        Some(prediction) => {
+            let deltas = DbDelta::belonging_to(&prediction)
+                .load::<DbDelta>(&connection)?
+                .into_iter()
+                .map(Delta::try_from)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Some((prediction, deltas)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Start a response that browsers will save as a file rather than display.
+fn attachment(prediction: &Prediction, kind: &str, extension: &str) -> HttpResponseBuilder {
+    let mut response = HttpResponse::Ok();
+    response.insert_header((
+        header::CONTENT_DISPOSITION,
+        format!(
+            "attachment; filename=\"{}-{}.{}\"",
+            prediction.name().replace(['"', '/', '\\'], "_"),
+            kind,
+            extension
+        ),
+    ));
+    response
+}
+
+pub async fn get_deltas_export(
+    path: web::Path<String>,
+    query: web::Query<ExportQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to export deltas for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let (prediction, deltas) = match load_deltas(&username, query.prediction_id, &pool)? {
+        Some(found) => found,
+        None => {
+            log::debug!("user is not authorized to export this prediction");
+            return Ok(HttpResponse::Unauthorized().finish());
+        }
+    };
+
+    let mut response = attachment(&prediction, "deltas", query.format.extension());
+
+    match query.format {
+        ExportFormat::Csv => Ok(response
+            .content_type("text/csv; charset=utf-8")
+            .body(exports::deltas_csv(&deltas)?)),
+        ExportFormat::Json => {
+            Ok(response.json(deltas.iter().map(DeltaRow::from).collect::<Vec<_>>()))
+        }
+    }
+}
+
+pub async fn get_schedule_export(
+    path: web::Path<String>,
+    query: web::Query<ExportQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to export a schedule for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let (prediction, deltas) = match load_deltas(&username, query.prediction_id, &pool)? {
+        Some(found) => found,
+        None => {
+            log::debug!("user is not authorized to export this prediction");
+            return Ok(HttpResponse::Unauthorized().finish());
+        }
+    };
+
+    let occurrences = projection::schedule(&deltas, query.starting_balance);
+
+    let mut response = attachment(&prediction, "schedule", query.format.extension());
+
+    match query.format {
+        ExportFormat::Csv => Ok(response
+            .content_type("text/csv; charset=utf-8")
+            .body(exports::schedule_csv(&occurrences)?)),
+        ExportFormat::Json => Ok(response.json(occurrences)),
+    }
+}
+
+pub async fn get_journal_export(
+    path: web::Path<String>,
+    query: web::Query<JournalQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to export a journal for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let (prediction, deltas) = match load_deltas(&username, query.prediction_id, &pool)? {
+        Some(found) => found,
+        None => {
+            log::debug!("user is not authorized to export this prediction");
+            return Ok(HttpResponse::Unauthorized().finish());
+        }
+    };
+
+    let journal =
+        match exports::journal::journal(prediction.name(), &deltas, query.format, &query.account) {
+            Ok(journal) => journal,
+            Err(err) => {
+                log::debug!("could not export journal: {}", err);
+                return Ok(HttpResponse::BadRequest().body(err.to_string()));
+            }
+        };
+
+    Ok(
+        attachment(&prediction, "forecast", query.format.extension())
+            .content_type("text/plain; charset=utf-8")
+            .body(journal),
+    )
+}
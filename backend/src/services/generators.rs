@@ -0,0 +1,215 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
+use shared::generators::debt::{self, DebtPayoffRequest, DebtPayoffSummary};
+use shared::generators::loan::{self, LoanRequest};
+use shared::generators::paycheck::{self, PaycheckRequest};
+use shared::models::{NewDbDelta, NewPrediction, Prediction};
+use shared::schema::{deltas, predictions};
+
+use super::{is_authenticated, owns_prediction};
+use crate::errors::MoolahBackendError;
+use crate::{HttpResult, Pool};
+
+pub async fn post_loan_preview(
+    path: web::Path<String>,
+    web::Json(request): web::Json<LoanRequest>,
+    id: Identity,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to generate loans for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match loan::amortize(&request) {
+        Ok(schedule) => Ok(HttpResponse::Ok().json(schedule)),
+        Err(err) => {
+            log::debug!("invalid loan: {}", err);
+            Ok(HttpResponse::BadRequest().body(err.to_string()))
+        }
+    }
+}
+
+/// Add a loan's payments to a prediction, responding with the full schedule.
+pub async fn put_loan(
+    path: web::Path<String>,
+    web::Json(request): web::Json<LoanRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, request.prediction_id, &connection)?
+    {
+        log::debug!("user is not authorized to add a loan to this prediction");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let schedule = match loan::amortize(&request) {
+        Ok(schedule) => schedule,
+        Err(err) => {
+            log::debug!("invalid loan: {}", err);
+            return Ok(HttpResponse::BadRequest().body(err.to_string()));
+        }
+    };
+
+    let new_deltas = schedule
+        .clone()
+        .into_new_deltas(request.prediction_id)?
+        .into_iter()
+        .map(NewDbDelta::from)
+        .collect::<Vec<_>>();
+
+    let n_inserted_rows = diesel::insert_into(deltas::table)
+        .values(&new_deltas)
+        .execute(&connection)?;
+
+    log::debug!("completed insert of {} rows", n_inserted_rows);
+    Ok(HttpResponse::Ok().json(schedule))
+}
+
+pub async fn post_debt_payoff_preview(
+    path: web::Path<String>,
+    web::Json(request): web::Json<DebtPayoffRequest>,
+    id: Identity,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to plan debt payoff for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match debt::compare(&request) {
+        Ok(comparison) => Ok(HttpResponse::Ok().json(comparison)),
+        Err(err) => {
+            log::debug!("invalid debt payoff plan: {}", err);
+            Ok(HttpResponse::BadRequest().body(err.to_string()))
+        }
+    }
+}
+
+/// Create a scenario prediction holding the payments of the requested payoff
+/// strategy.
+pub async fn put_debt_payoff(
+    path: web::Path<String>,
+    web::Json(request): web::Json<DebtPayoffRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to plan debt payoff for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    if request.name.trim().is_empty() {
+        return Ok(HttpResponse::BadRequest().body("the scenario needs a name"));
+    }
+
+    let plan = match debt::plan(&request, request.strategy) {
+        Ok(plan) => plan,
+        Err(err) => {
+            log::debug!("invalid debt payoff plan: {}", err);
+            return Ok(HttpResponse::BadRequest().body(err.to_string()));
+        }
+    };
+
+    let connection = pool.get()?;
+
+    let summary = connection.transaction::<_, MoolahBackendError, _>(|| {
+        let prediction = diesel::insert_into(predictions::table)
+            .values(NewPrediction::new(username.clone(), request.name.clone()))
+            .get_result::<Prediction>(&connection)?;
+
+        let new_deltas = plan
+            .new_deltas(prediction.id(), request.first_payment_on)?
+            .into_iter()
+            .map(NewDbDelta::from)
+            .collect::<Vec<_>>();
+
+        let n_inserted_rows = diesel::insert_into(deltas::table)
+            .values(&new_deltas)
+            .execute(&connection)?;
+
+        log::debug!("completed insert of {} rows", n_inserted_rows);
+        Ok(DebtPayoffSummary {
+            prediction_id: prediction.id(),
+            plan,
+        })
+    })?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+pub async fn post_paycheck_preview(
+    path: web::Path<String>,
+    web::Json(request): web::Json<PaycheckRequest>,
+    id: Identity,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to generate paychecks for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match paycheck::paycheck(&request) {
+        Ok(paycheck) => Ok(HttpResponse::Ok().json(paycheck)),
+        Err(err) => {
+            log::debug!("invalid paycheck: {}", err);
+            Ok(HttpResponse::BadRequest().body(err.to_string()))
+        }
+    }
+}
+
+/// Add a paycheck's pay, deductions and withholding to a prediction,
+/// responding with the paycheck.
+pub async fn put_paycheck(
+    path: web::Path<String>,
+    web::Json(request): web::Json<PaycheckRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, request.prediction_id, &connection)?
+    {
+        log::debug!("user is not authorized to add a paycheck to this prediction");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let paycheck = match paycheck::paycheck(&request) {
+        Ok(paycheck) => paycheck,
+        Err(err) => {
+            log::debug!("invalid paycheck: {}", err);
+            return Ok(HttpResponse::BadRequest().body(err.to_string()));
+        }
+    };
+
+    let new_deltas = paycheck
+        .new_deltas(
+            request.prediction_id,
+            request.first_pay_on,
+            request.last_pay_on,
+        )?
+        .into_iter()
+        .map(NewDbDelta::from)
+        .collect::<Vec<_>>();
+
+    let n_inserted_rows = diesel::insert_into(deltas::table)
+        .values(&new_deltas)
+        .execute(&connection)?;
+
+    log::debug!("completed insert of {} rows", n_inserted_rows);
+    Ok(HttpResponse::Ok().json(paycheck))
+}
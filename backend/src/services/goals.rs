@@ -0,0 +1,128 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{DbDelta, Delta, Goal, GoalProgress, GoalQuery, NewGoal};
+use shared::schema::{deltas, goals};
+
+use super::{is_authenticated, owns_prediction};
+use crate::{HttpResult, Pool};
+
+pub async fn get_goals(
+    path: web::Path<String>,
+    query: web::Query<GoalQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, query.prediction_id, &connection)?
+    {
+        log::debug!("user is not authorized to get goals for this prediction");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let goals = goals::table
+        .filter(goals::prediction_id.eq(query.prediction_id))
+        .order(goals::id)
+        .load::<Goal>(&connection)?;
+
+    Ok(HttpResponse::Ok().json(goals))
+}
+
+pub async fn put_goal(
+    path: web::Path<String>,
+    web::Json(goal): web::Json<NewGoal>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, goal.prediction_id(), &connection)?
+    {
+        log::debug!("user is not authorized to post this goal");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    if let Err(err) = goal.validate() {
+        log::debug!("invalid goal: {}", err);
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let goal = diesel::insert_into(goals::table)
+        .values(&goal)
+        .get_result::<Goal>(&connection)?;
+
+    log::debug!("completed insert of 1 rows");
+    Ok(HttpResponse::Ok().json(goal))
+}
+
+pub async fn delete_goal(
+    path: web::Path<String>,
+    web::Json(goal): web::Json<Goal>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, goal.prediction_id(), &connection)?
+    {
+        log::debug!("user is not authorized to delete this goal");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let n_deleted_rows = diesel::delete(
+        goals::table
+            .filter(goals::id.eq(goal.id()))
+            .filter(goals::prediction_id.eq(goal.prediction_id())),
+    )
+    .execute(&connection)?;
+
+    log::info!("deleted {} goal", n_deleted_rows);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Solve every goal of a prediction against its current deltas.
+pub async fn get_goal_progress(
+    path: web::Path<String>,
+    query: web::Query<GoalQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, query.prediction_id, &connection)?
+    {
+        log::debug!("user is not authorized to get goal progress for this prediction");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let deltas = deltas::table
+        .filter(deltas::prediction_id.eq(query.prediction_id))
+        .load::<DbDelta>(&connection)?
+        .into_iter()
+        .map(Delta::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let progress = goals::table
+        .filter(goals::prediction_id.eq(query.prediction_id))
+        .order(goals::id)
+        .load::<Goal>(&connection)?
+        .iter()
+        .map(|goal| goal.progress(&deltas))
+        .collect::<Vec<GoalProgress>>();
+
+    Ok(HttpResponse::Ok().json(progress))
+}
@@ -0,0 +1,247 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
+use shared::imports::{
+    self,
+    csv::CsvImportRequest,
+    ledger::{LedgerImportRequest, LedgerImportSummary},
+    qif::QifImportRequest,
+    ImportPreview, ImportSummary, ParsedTransaction, StatementImportRequest,
+};
+use shared::models::{
+    CategorizationRule, NewDbDelta, NewPrediction, NewTransaction, Prediction, RuleSet, Transaction,
+};
+use shared::schema::{categorization_rules, deltas, predictions, transactions};
+
+use super::is_authenticated;
+use crate::{errors::MoolahBackendError, HttpResult, Pool};
+
+/// Store the new rows of an import preview for a user, leaving out the ones
+/// that were already imported from an earlier (possibly overlapping) statement
+/// and categorizing the rest with the user's rules.
+fn store(username: &str, preview: ImportPreview, pool: &Pool) -> HttpResult {
+    let connection = pool.get()?;
+
+    let existing = transactions::table
+        .filter(transactions::username.eq(username))
+        .load::<Transaction>(&connection)?
+        .into_iter()
+        .map(ParsedTransaction::from)
+        .collect::<Vec<_>>();
+
+    let (parsed, duplicates) = imports::deduplicate(preview.transactions, &existing);
+
+    let rules = RuleSet::new(
+        categorization_rules::table
+            .filter(categorization_rules::username.eq(username))
+            .order(categorization_rules::id)
+            .load::<CategorizationRule>(&connection)?,
+    )?;
+
+    let new_transactions = parsed
+        .into_iter()
+        .map(|parsed| {
+            let categorization = rules.categorize(&parsed);
+            NewTransaction::new(username.to_string(), parsed, categorization)
+        })
+        .collect::<Vec<_>>();
+
+    let n_inserted_rows = diesel::insert_into(transactions::table)
+        .values(&new_transactions)
+        .execute(&connection)?;
+
+    log::info!(
+        "imported {} transactions ({} duplicates, {} rows skipped)",
+        n_inserted_rows,
+        duplicates,
+        preview.skipped.len()
+    );
+
+    Ok(HttpResponse::Ok().json(ImportSummary {
+        imported: n_inserted_rows,
+        duplicates,
+        skipped: preview.skipped,
+    }))
+}
+
+pub async fn post_csv_preview(
+    path: web::Path<String>,
+    web::Json(request): web::Json<CsvImportRequest>,
+    id: Identity,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to preview imports for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    if let Err(err) = request.mapping.validate() {
+        log::debug!("invalid CSV mapping: {}", err);
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let preview = imports::csv::parse(&request.contents, &request.mapping);
+
+    Ok(HttpResponse::Ok().json(preview))
+}
+
+pub async fn put_csv_import(
+    path: web::Path<String>,
+    web::Json(request): web::Json<CsvImportRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to import transactions for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    if let Err(err) = request.mapping.validate() {
+        log::debug!("invalid CSV mapping: {}", err);
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let preview = imports::csv::parse(&request.contents, &request.mapping);
+
+    store(&username, preview, &pool)
+}
+
+pub async fn post_ofx_preview(
+    path: web::Path<String>,
+    web::Json(request): web::Json<StatementImportRequest>,
+    id: Identity,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to preview imports for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let preview = imports::ofx::parse(&request.contents);
+
+    Ok(HttpResponse::Ok().json(preview))
+}
+
+pub async fn put_ofx_import(
+    path: web::Path<String>,
+    web::Json(request): web::Json<StatementImportRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to import transactions for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let preview = imports::ofx::parse(&request.contents);
+
+    store(&username, preview, &pool)
+}
+
+pub async fn post_qif_preview(
+    path: web::Path<String>,
+    web::Json(request): web::Json<QifImportRequest>,
+    id: Identity,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to preview imports for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let preview = imports::qif::parse(&request.contents, request.date_order);
+
+    Ok(HttpResponse::Ok().json(preview))
+}
+
+pub async fn put_qif_import(
+    path: web::Path<String>,
+    web::Json(request): web::Json<QifImportRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to import transactions for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let preview = imports::qif::parse(&request.contents, request.date_order);
+
+    store(&username, preview, &pool)
+}
+
+pub async fn post_ledger_preview(
+    path: web::Path<String>,
+    web::Json(request): web::Json<LedgerImportRequest>,
+    id: Identity,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to preview imports for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let preview = imports::ledger::parse(&request);
+
+    Ok(HttpResponse::Ok().json(preview))
+}
+
+/// Create a new prediction holding a delta for every periodic transaction
+/// that could be represented.
+pub async fn put_ledger_import(
+    path: web::Path<String>,
+    web::Json(request): web::Json<LedgerImportRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to import predictions for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let preview = imports::ledger::parse(&request);
+
+    let connection = pool.get()?;
+
+    let summary = connection.transaction::<_, MoolahBackendError, _>(|| {
+        let prediction = diesel::insert_into(predictions::table)
+            .values(NewPrediction::new(username.clone(), request.name.clone()))
+            .get_result::<Prediction>(&connection)?;
+
+        let new_deltas = preview
+            .deltas
+            .into_iter()
+            .map(|delta| delta.into_new_delta(prediction.id()).into())
+            .collect::<Vec<NewDbDelta>>();
+
+        let n_inserted_rows = diesel::insert_into(deltas::table)
+            .values(&new_deltas)
+            .execute(&connection)?;
+
+        Ok(LedgerImportSummary {
+            prediction_id: prediction.id(),
+            imported: n_inserted_rows,
+            unrepresentable: preview.unrepresentable,
+        })
+    })?;
+
+    log::info!(
+        "imported {} deltas into a new prediction ({} rules could not be represented)",
+        summary.imported,
+        summary.unrepresentable.len()
+    );
+
+    Ok(HttpResponse::Ok().json(summary))
+}
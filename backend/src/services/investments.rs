@@ -0,0 +1,195 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    DbDelta, Delta, InvestmentAccount, InvestmentAccountWithContributions, InvestmentContribution,
+    InvestmentProjection, InvestmentProjectionQuery, InvestmentQuery, NewInvestmentAccountRequest,
+};
+use shared::schema::{deltas, investment_accounts, investment_contributions};
+
+use super::{is_authenticated, owns_prediction};
+use crate::errors::MoolahBackendError;
+use crate::{HttpResult, Pool};
+
+/// Every investment account of a prediction along with its contributing deltas.
+pub(super) fn load_accounts(
+    prediction_id: i32,
+    connection: &PgConnection,
+) -> Result<Vec<InvestmentAccountWithContributions>, MoolahBackendError> {
+    let accounts = investment_accounts::table
+        .filter(investment_accounts::prediction_id.eq(prediction_id))
+        .order(investment_accounts::id)
+        .load::<InvestmentAccount>(connection)?;
+
+    let contributions = InvestmentContribution::belonging_to(&accounts)
+        .load::<InvestmentContribution>(connection)?
+        .grouped_by(&accounts);
+
+    Ok(accounts
+        .into_iter()
+        .zip(contributions)
+        .map(
+            |(account, contributions)| InvestmentAccountWithContributions {
+                account,
+                contribution_delta_ids: contributions
+                    .into_iter()
+                    .map(|contribution| contribution.delta_id)
+                    .collect(),
+            },
+        )
+        .collect())
+}
+
+pub async fn get_investment_accounts(
+    path: web::Path<String>,
+    query: web::Query<InvestmentQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, query.prediction_id, &connection)?
+    {
+        log::debug!("user is not authorized to get investment accounts for this prediction");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let accounts = load_accounts(query.prediction_id, &connection)?;
+
+    Ok(HttpResponse::Ok().json(accounts))
+}
+
+pub async fn put_investment_account(
+    path: web::Path<String>,
+    web::Json(request): web::Json<NewInvestmentAccountRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+    let prediction_id = request.account.prediction_id();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username) || !owns_prediction(&username, prediction_id, &connection)?
+    {
+        log::debug!("user is not authorized to post this investment account");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    if let Err(err) = request.account.validate() {
+        log::debug!("invalid investment account: {}", err);
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let n_owned_deltas = deltas::table
+        .filter(deltas::prediction_id.eq(prediction_id))
+        .filter(deltas::id.eq_any(&request.contribution_delta_ids))
+        .count()
+        .get_result::<i64>(&connection)?;
+
+    if n_owned_deltas as usize != request.contribution_delta_ids.len() {
+        log::debug!("contributions must be deltas of the account's prediction");
+        return Ok(HttpResponse::BadRequest()
+            .body("contributions must be deltas of the account's prediction"));
+    }
+
+    let account = connection.transaction::<_, MoolahBackendError, _>(|| {
+        let account = diesel::insert_into(investment_accounts::table)
+            .values(&request.account)
+            .get_result::<InvestmentAccount>(&connection)?;
+
+        let contributions = request
+            .contribution_delta_ids
+            .iter()
+            .map(|&delta_id| InvestmentContribution {
+                investment_account_id: account.id(),
+                delta_id,
+            })
+            .collect::<Vec<_>>();
+
+        let n_inserted_rows = diesel::insert_into(investment_contributions::table)
+            .values(&contributions)
+            .execute(&connection)?;
+        log::debug!("completed insert of {} contributions", n_inserted_rows);
+
+        Ok(InvestmentAccountWithContributions {
+            account,
+            contribution_delta_ids: request.contribution_delta_ids.clone(),
+        })
+    })?;
+
+    Ok(HttpResponse::Ok().json(account))
+}
+
+pub async fn delete_investment_account(
+    path: web::Path<String>,
+    web::Json(account): web::Json<InvestmentAccount>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, account.prediction_id(), &connection)?
+    {
+        log::debug!("user is not authorized to delete this investment account");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let n_deleted_rows = diesel::delete(
+        investment_accounts::table
+            .filter(investment_accounts::id.eq(account.id()))
+            .filter(investment_accounts::prediction_id.eq(account.prediction_id())),
+    )
+    .execute(&connection)?;
+
+    log::info!("deleted {} investment account", n_deleted_rows);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Project every investment account of a prediction until the given date.
+pub async fn get_investment_projections(
+    path: web::Path<String>,
+    query: web::Query<InvestmentProjectionQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, query.prediction_id, &connection)?
+    {
+        log::debug!("user is not authorized to get investment projections for this prediction");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let accounts = load_accounts(query.prediction_id, &connection)?;
+    for account in &accounts {
+        if let Err(err) = query.validate(account.account.start_on()) {
+            log::debug!("invalid investment projection request: {}", err);
+            return Ok(HttpResponse::BadRequest().body(err.to_string()));
+        }
+    }
+
+    let deltas = deltas::table
+        .filter(deltas::prediction_id.eq(query.prediction_id))
+        .load::<DbDelta>(&connection)?
+        .into_iter()
+        .map(Delta::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let projections = accounts
+        .iter()
+        .map(|account| account.projection(&deltas, query.until, query.runs))
+        .collect::<Vec<InvestmentProjection>>();
+
+    Ok(HttpResponse::Ok().json(projections))
+}
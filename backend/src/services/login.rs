@@ -1,51 +1,168 @@
+use std::time::Instant;
+
 use actix_identity::Identity;
-use actix_web::{web, HttpResponse};
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::Duration;
 use diesel::prelude::*;
-use shared::models::{User, UserLoginRequestForm};
-use shared::schema::users::dsl::{email, last_login, password, username, users};
+use diesel::PgConnection;
+use shared::models::users::verify_missing_user;
+use shared::models::{LoginResponse, TokenPurpose, TwoFactorLoginForm, User, UserLoginForm};
+use shared::schema::users::dsl::{last_login, username, users};
 
+use super::totp::{check_second_factor, enabled_totp};
+use super::{issue_token, spend_token, usable_token};
 use crate::errors::MoolahBackendError;
+use crate::throttle::LoginThrottle;
 use crate::Pool;
 
-pub async fn put_request_login_password(
-    web::Json(user_form): web::Json<UserLoginRequestForm>,
+/// How long the user has to give a second factor after their password.
+const TWO_FACTOR_TOKEN_LIFETIME_MINUTES: i64 = 5;
+
+/// Check the username and password against the stored hash and, if they
+/// match, remember the user. The hash never leaves the server.
+///
+/// Failures are throttled per username and per client address, so after a
+/// few wrong guesses each attempt has to wait longer, and after more the
+/// username or address is locked out for a while.
+///
+/// With two factor authentication on, the user is not remembered yet; they get
+/// a token to send along with a code to [`post_login_two_factor`]. The attempt
+/// stays counted against them until that succeeds.
+pub async fn post_login(
+    web::Json(login_form): web::Json<UserLoginForm>,
+    req: HttpRequest,
+    id: Identity,
     pool: web::Data<Pool>,
+    throttle: web::Data<LoginThrottle>,
 ) -> Result<HttpResponse, MoolahBackendError> {
+    let login_username = login_form.username.to_lowercase();
+    let client = throttle
+        .client_ip(&req)
+        .map_or_else(|| "unknown address".to_string(), |ip| ip.to_string());
+
+    let keys = throttle.keys(&req, &login_username);
+    if let Err(wait) = throttle.attempt(&keys, Instant::now()) {
+        return Ok(throttled(&login_username, &client, wait));
+    }
+
     let connection = pool.get()?;
 
     let user = users
-        .filter(username.eq(user_form.username))
-        .first::<User>(&connection);
+        .filter(username.eq(&login_username))
+        .first::<User>(&connection)
+        .optional()?;
+
+    let verified = match &user {
+        Some(user) => user.verify_user(&login_username, &login_form.password)?,
+        None => verify_missing_user(&login_form.password)?,
+    };
 
-    if let Ok(user) = user {
-        Ok(HttpResponse::Ok().json(user))
-    } else {
-        Ok(HttpResponse::InternalServerError().body("could not retreive user"))
+    if !verified {
+        log::warn!("failed login for {} from {}", login_username, client);
+        return Ok(HttpResponse::Unauthorized().body("incorrect username/password combo"));
     }
+
+    if enabled_totp(&login_username, &connection)?.is_some() {
+        let token = issue_token(
+            &login_username,
+            TokenPurpose::TwoFactorLogin,
+            Duration::minutes(TWO_FACTOR_TOKEN_LIFETIME_MINUTES),
+            &connection,
+        )?;
+
+        log::debug!("asking {} for a second factor", login_username);
+        return Ok(HttpResponse::Ok().json(LoginResponse::TwoFactorRequired { token }));
+    }
+
+    throttle.succeeded(&keys);
+    log_in(login_username, &id, &connection)?;
+
+    Ok(HttpResponse::Ok().json(LoginResponse::LoggedIn))
 }
 
-pub async fn patch_login(
-    web::Json(user_form): web::Json<User>,
+/// Finish logging in to an account with two factor authentication, with the
+/// token from [`post_login`] and an authenticator or recovery code. Wrong codes
+/// are throttled like wrong passwords.
+pub async fn post_login_two_factor(
+    web::Json(form): web::Json<TwoFactorLoginForm>,
+    req: HttpRequest,
     id: Identity,
     pool: web::Data<Pool>,
+    throttle: web::Data<LoginThrottle>,
 ) -> Result<HttpResponse, MoolahBackendError> {
     let connection = pool.get()?;
+    let now = chrono::Local::now().naive_utc();
+
+    let token = match usable_token(form.token(), TokenPurpose::TwoFactorLogin, now, &connection)? {
+        Some(token) => token,
+        None => {
+            log::debug!("rejected second factor with an invalid login token");
+            return Ok(HttpResponse::Unauthorized().body("login has expired, please start again"));
+        }
+    };
 
-    let target_user = users
-        .filter(username.eq(user_form.username))
-        .filter(email.eq(user_form.email))
-        .filter(password.eq(user_form.password));
+    let login_username = token.username().to_string();
+    let client = throttle
+        .client_ip(&req)
+        .map_or_else(|| "unknown address".to_string(), |ip| ip.to_string());
 
+    let keys = throttle.keys(&req, &login_username);
+    if let Err(wait) = throttle.attempt(&keys, Instant::now()) {
+        return Ok(throttled(&login_username, &client, wait));
+    }
+
+    if !check_second_factor(&login_username, form.code(), now, &connection)? {
+        log::warn!(
+            "failed second factor for {} from {}",
+            login_username,
+            client
+        );
+        return Ok(HttpResponse::Unauthorized().body("incorrect code"));
+    }
+
+    if !spend_token(&token, now, &connection)? {
+        return Ok(HttpResponse::Unauthorized().body("login has expired, please start again"));
+    }
+
+    throttle.succeeded(&keys);
+    log_in(login_username, &id, &connection)?;
+
+    Ok(HttpResponse::Ok().json(LoginResponse::LoggedIn))
+}
+
+fn log_in(
+    login_username: String,
+    id: &Identity,
+    connection: &PgConnection,
+) -> Result<(), MoolahBackendError> {
     let now = chrono::Local::now().naive_utc();
 
-    let user = diesel::update(target_user)
+    diesel::update(users.filter(username.eq(&login_username)))
         .set(last_login.eq(now))
-        .get_result::<User>(&connection);
+        .execute(connection)?;
 
-    if let Ok(user) = user {
-        id.remember(user.username.to_lowercase());
-        Ok(HttpResponse::Ok().finish())
-    } else {
-        Ok(HttpResponse::InternalServerError().body("incorrect username/email/password combo"))
-    }
+    id.remember(login_username);
+    Ok(())
+}
+
+pub(crate) fn throttled(
+    login_username: &str,
+    client: &str,
+    wait: std::time::Duration,
+) -> HttpResponse {
+    // Round up so a client waiting the advertised time is let through
+    let seconds = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    log::warn!(
+        "throttled login for {} from {}, {}s to wait",
+        login_username,
+        client,
+        seconds
+    );
+
+    HttpResponse::TooManyRequests()
+        .insert_header((header::RETRY_AFTER, seconds.to_string()))
+        .body(format!(
+            "too many failed login attempts, try again in {} seconds",
+            seconds
+        ))
 }
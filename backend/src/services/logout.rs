@@ -5,7 +5,7 @@ use crate::MoolahBackendError;
 
 pub async fn put_logout(id: Identity) -> Result<HttpResponse, MoolahBackendError> {
     id.forget();
-    log::debug!("forgot user id cookie session");
+    log::debug!("ended session");
 
     Ok(HttpResponse::Ok().finish())
 }
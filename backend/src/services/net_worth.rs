@@ -0,0 +1,51 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use shared::models::{DbDelta, Delta};
+use shared::projection::net_worth::{self, NetWorthQuery};
+use shared::schema::deltas;
+
+use super::{assets::load_assets, investments::load_accounts};
+use super::{is_authenticated, owns_prediction};
+use crate::{HttpResult, Pool};
+
+/// Cash, investments, assets and liabilities of a prediction combined into a
+/// monthly net worth.
+pub async fn get_net_worth(
+    path: web::Path<String>,
+    query: web::Query<NetWorthQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, query.prediction_id, &connection)?
+    {
+        log::debug!("user is not authorized to get net worth for this prediction");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let investments = load_accounts(query.prediction_id, &connection)?;
+    let start_on = investments
+        .iter()
+        .map(|account| account.account.start_on())
+        .fold(query.from, NaiveDate::min);
+    if let Err(err) = query.validate(start_on) {
+        log::debug!("invalid net worth query: {}", err);
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let deltas = deltas::table
+        .filter(deltas::prediction_id.eq(query.prediction_id))
+        .load::<DbDelta>(&connection)?
+        .into_iter()
+        .map(Delta::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    let assets = load_assets(query.prediction_id, &connection)?;
+
+    Ok(HttpResponse::Ok().json(net_worth::series(&query, &deltas, &investments, &assets)))
+}
@@ -0,0 +1,124 @@
+use actix_web::{web, HttpResponse};
+use chrono::Duration;
+use diesel::prelude::*;
+use shared::{
+    models::{PasswordResetForm, PasswordResetRequest, TokenPurpose, User},
+    schema::{api_tokens, sessions, users},
+};
+
+use super::{issue_token, spend_token, usable_token};
+use crate::config::MailConfig;
+use crate::errors::MoolahBackendError;
+use crate::mailer::{Email, Mailer};
+use crate::Pool;
+
+/// How long a reset link works for.
+const RESET_TOKEN_LIFETIME_MINUTES: i64 = 60;
+
+const REQUEST_RESPONSE: &str = "if that email belongs to an account, a reset link is on its way";
+
+/// Email a password reset link to the account with the requested address.
+/// The response is the same whether or not there is such an account, so this
+/// cannot be used to find out who has one. Only verified addresses are sent
+/// links, so a mistyped address cannot be used to take the account over.
+pub async fn post_password_reset_request(
+    web::Json(request): web::Json<PasswordResetRequest>,
+    pool: web::Data<Pool>,
+    mail: web::Data<MailConfig>,
+    mailer: web::Data<dyn Mailer>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let connection = pool.get()?;
+
+    let user = users::table
+        .filter(users::email.eq(request.email()))
+        .filter(users::verified_at.is_not_null())
+        .first::<User>(&connection)
+        .optional()?;
+
+    let user = match user {
+        Some(user) => user,
+        None => {
+            log::debug!("password reset requested for unknown or unverified email");
+            return Ok(HttpResponse::Ok().body(REQUEST_RESPONSE));
+        }
+    };
+
+    let token = issue_token(
+        &user.username,
+        TokenPurpose::PasswordReset,
+        Duration::minutes(RESET_TOKEN_LIFETIME_MINUTES),
+        &connection,
+    )?;
+
+    // a failed send has to look the same as a missing account, or the error
+    // would give away that the address has one
+    let sent = mailer.send(&Email {
+        to: user.email.clone(),
+        subject: "Reset your moolah password".into(),
+        body: format!(
+            "Someone asked to reset the password for {}. If that was you, follow this link within {} minutes:\n\n{}\n\nIf not, you can ignore this email.",
+            user.username,
+            RESET_TOKEN_LIFETIME_MINUTES,
+            mail.link("reset-password", &token)
+        ),
+    });
+
+    match sent {
+        Ok(()) => log::info!("sent password reset link to {}", user.username),
+        Err(err) => log::error!(
+            "could not send password reset link to {}: {}",
+            user.username,
+            err
+        ),
+    }
+
+    Ok(HttpResponse::Ok().body(REQUEST_RESPONSE))
+}
+
+/// Set a new password with a token from [`post_password_reset_request`]. The
+/// token is spent, and every session and API token for the account is ended.
+pub async fn post_password_reset(
+    web::Json(form): web::Json<PasswordResetForm>,
+    pool: web::Data<Pool>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let connection = pool.get()?;
+    let now = chrono::Local::now().naive_utc();
+
+    let token = match usable_token(form.token(), TokenPurpose::PasswordReset, now, &connection)? {
+        Some(token) => token,
+        None => {
+            log::debug!("rejected password reset with an invalid token");
+            return Ok(HttpResponse::BadRequest().body("reset link is invalid or has expired"));
+        }
+    };
+
+    let password_hash = match form.new_password_hash() {
+        Ok(password_hash) => password_hash,
+        Err(err) => return Ok(HttpResponse::BadRequest().body(err.to_string())),
+    };
+
+    let reset = connection.transaction::<_, MoolahBackendError, _>(|| {
+        if !spend_token(&token, now, &connection)? {
+            return Ok(false);
+        }
+
+        diesel::update(users::table.filter(users::username.eq(token.username())))
+            .set(users::password.eq(password_hash))
+            .execute(&connection)?;
+
+        diesel::delete(sessions::table.filter(sessions::username.eq(token.username())))
+            .execute(&connection)?;
+        diesel::delete(api_tokens::table.filter(api_tokens::username.eq(token.username())))
+            .execute(&connection)?;
+
+        Ok(true)
+    })?;
+
+    if !reset {
+        return Ok(HttpResponse::BadRequest().body("reset link is invalid or has expired"));
+    }
+
+    log::info!("reset password for {}", token.username());
+
+    Ok(HttpResponse::Ok().body("password reset"))
+}
@@ -0,0 +1,41 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{DbDelta, Delta};
+use shared::projection::retirement::{self, RetirementRequest};
+use shared::schema::deltas;
+
+use super::{is_authenticated, owns_prediction};
+use crate::{HttpResult, Pool};
+
+pub async fn post_retirement_projection(
+    path: web::Path<String>,
+    web::Json(request): web::Json<RetirementRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, request.prediction_id, &connection)?
+    {
+        log::debug!("user is not authorized to project retirement for this prediction");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    if let Err(err) = request.validate() {
+        log::debug!("invalid retirement request: {}", err);
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let deltas = deltas::table
+        .filter(deltas::prediction_id.eq(request.prediction_id))
+        .load::<DbDelta>(&connection)?
+        .into_iter()
+        .map(Delta::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(HttpResponse::Ok().json(retirement::project(&request, &deltas)))
+}
@@ -0,0 +1,172 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
+use shared::imports::ParsedTransaction;
+use shared::models::{
+    CategorizationRule, CategorizationSummary, NewCategorizationRule, RuleSet, Transaction,
+};
+use shared::schema::{categorization_rules, deltas, predictions, transactions};
+
+use super::is_authenticated;
+use crate::{errors::MoolahBackendError, HttpResult, Pool};
+
+pub async fn get_rules(path: web::Path<String>, id: Identity, pool: web::Data<Pool>) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to get categorization rules for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let connection = pool.get()?;
+
+    let rules = categorization_rules::table
+        .filter(categorization_rules::username.eq(username))
+        .order(categorization_rules::id)
+        .load::<CategorizationRule>(&connection)?;
+
+    Ok(HttpResponse::Ok().json(rules))
+}
+
+pub async fn put_rule(
+    path: web::Path<String>,
+    web::Json(rule): web::Json<NewCategorizationRule>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) || username != rule.username() {
+        log::debug!("user is not authorized to post this categorization rule");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    if let Err(err) = rule.validate() {
+        log::debug!("invalid categorization rule: {}", err);
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let connection = pool.get()?;
+
+    if let Some(delta_id) = rule.delta_id() {
+        let delta_user = deltas::table
+            .inner_join(predictions::table)
+            .filter(deltas::id.eq(delta_id))
+            .select(predictions::username)
+            .get_result::<String>(&connection)
+            .optional()?;
+
+        if delta_user.as_deref() != Some(username.as_str()) {
+            log::debug!("user is not authorized to link a rule to this delta");
+            return Ok(HttpResponse::Unauthorized().finish());
+        }
+    }
+
+    let rule = diesel::insert_into(categorization_rules::table)
+        .values(&rule)
+        .get_result::<CategorizationRule>(&connection)?;
+
+    log::debug!("completed insert of 1 rows");
+    Ok(HttpResponse::Ok().json(rule))
+}
+
+pub async fn delete_rule(
+    path: web::Path<String>,
+    web::Json(rule): web::Json<CategorizationRule>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) || username != rule.username() {
+        log::debug!("user is not authorized to delete this categorization rule");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let connection = pool.get()?;
+
+    let n_deleted_rows = diesel::delete(
+        categorization_rules::table
+            .filter(categorization_rules::id.eq(rule.id()))
+            .filter(categorization_rules::username.eq(rule.username())),
+    )
+    .execute(&connection)?;
+
+    log::info!("deleted {} categorization rule", n_deleted_rows);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Re-run all of a user's rules over every one of their transactions, so that
+/// new, changed or deleted rules are reflected in already imported history.
+pub async fn post_apply_rules(
+    path: web::Path<String>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to apply categorization rules for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let connection = pool.get()?;
+
+    let rules = RuleSet::new(
+        categorization_rules::table
+            .filter(categorization_rules::username.eq(&username))
+            .order(categorization_rules::id)
+            .load::<CategorizationRule>(&connection)?,
+    )?;
+
+    let summary = connection.transaction::<_, MoolahBackendError, _>(|| {
+        let existing = transactions::table
+            .filter(transactions::username.eq(&username))
+            .load::<Transaction>(&connection)?;
+
+        let mut summary = CategorizationSummary {
+            categorized: 0,
+            uncategorized: 0,
+        };
+
+        for transaction in existing {
+            let id = transaction.id();
+            let current = (
+                transaction.category().map(String::from),
+                transaction.delta_id(),
+            );
+
+            let (category, delta_id) = match rules.categorize(&ParsedTransaction::from(transaction))
+            {
+                Some(categorization) => {
+                    summary.categorized += 1;
+                    (Some(categorization.category), categorization.delta_id)
+                }
+                None => {
+                    summary.uncategorized += 1;
+                    (None, None)
+                }
+            };
+
+            if (category.clone(), delta_id) != current {
+                diesel::update(transactions::table.find(id))
+                    .set((
+                        transactions::category.eq(category),
+                        transactions::delta_id.eq(delta_id),
+                    ))
+                    .execute(&connection)?;
+            }
+        }
+
+        Ok(summary)
+    })?;
+
+    log::info!(
+        "applied categorization rules ({} categorized, {} uncategorized)",
+        summary.categorized,
+        summary.uncategorized
+    );
+
+    Ok(HttpResponse::Ok().json(summary))
+}
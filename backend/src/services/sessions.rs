@@ -0,0 +1,92 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{Session, SessionExpiry, SessionInfo};
+use shared::schema::sessions;
+
+use super::is_authenticated;
+use crate::session_store::CurrentSession;
+use crate::{HttpResult, Pool};
+
+/// The user's sessions that have not expired, most recently used first.
+pub async fn get_sessions(
+    path: web::Path<String>,
+    req: HttpRequest,
+    id: Identity,
+    pool: web::Data<Pool>,
+    expiry: web::Data<SessionExpiry>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to view these sessions");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let connection = pool.get()?;
+
+    let current = req.extensions().get::<CurrentSession>().cloned();
+    let now = chrono::Local::now().naive_utc();
+
+    let sessions = sessions::table
+        .filter(sessions::username.eq(username.to_lowercase()))
+        .order(sessions::last_seen.desc())
+        .load::<Session>(&connection)?
+        .into_iter()
+        .filter(|session| !session.is_expired(now, &expiry))
+        .map(|session| {
+            let is_current = current
+                .as_ref()
+                .map(|CurrentSession(token_hash)| token_hash.as_str())
+                == Some(session.token_hash());
+            session.info(is_current)
+        })
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(sessions))
+}
+
+/// Revoke one of the user's sessions. Revoking the session making the request
+/// logs it out too.
+pub async fn delete_session(
+    path: web::Path<String>,
+    web::Json(session): web::Json<SessionInfo>,
+    req: HttpRequest,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to revoke this session");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let connection = pool.get()?;
+
+    let revoked = diesel::delete(
+        sessions::table
+            .filter(sessions::id.eq(session.id))
+            .filter(sessions::username.eq(username.to_lowercase())),
+    )
+    .get_result::<Session>(&connection)
+    .optional()?;
+
+    let revoked = match revoked {
+        Some(revoked) => revoked,
+        None => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let current = req.extensions().get::<CurrentSession>().cloned();
+    if current
+        .as_ref()
+        .map(|CurrentSession(token_hash)| token_hash.as_str())
+        == Some(revoked.token_hash())
+    {
+        id.forget();
+    }
+
+    log::info!("revoked session {}", revoked.id());
+
+    Ok(HttpResponse::Ok().finish())
+}
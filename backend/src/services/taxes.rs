@@ -0,0 +1,47 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{DbDelta, Delta};
+use shared::schema::deltas;
+use shared::taxes::{self, TaxEstimateRequest};
+
+use super::{is_authenticated, owns_prediction};
+use crate::{HttpResult, Pool};
+
+pub async fn post_tax_estimate(
+    path: web::Path<String>,
+    web::Json(request): web::Json<TaxEstimateRequest>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    let connection = pool.get()?;
+
+    if !is_authenticated(&id, &username)
+        || !owns_prediction(&username, request.prediction_id, &connection)?
+    {
+        log::debug!("user is not authorized to estimate taxes for this prediction");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    if let Err(err) = request.table.validate() {
+        log::debug!("invalid tax table: {}", err);
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let deltas = deltas::table
+        .filter(deltas::prediction_id.eq(request.prediction_id))
+        .load::<DbDelta>(&connection)?
+        .into_iter()
+        .map(Delta::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match taxes::estimate(&deltas, request.year, &request.table) {
+        Ok(estimate) => Ok(HttpResponse::Ok().json(estimate)),
+        Err(err) => {
+            log::debug!("invalid tax estimate request: {}", err);
+            Ok(HttpResponse::BadRequest().body(err.to_string()))
+        }
+    }
+}
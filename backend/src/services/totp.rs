@@ -0,0 +1,298 @@
+use std::time::Instant;
+
+use actix_identity::Identity;
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use shared::{
+    models::{
+        totp::hash_recovery_code, NewRecoveryCode, NewUserTotp, TotpCodeForm, TotpEnrollment,
+        TotpStatus, UserTotp,
+    },
+    schema::{recovery_codes, user_totp},
+};
+
+use super::{is_authenticated, login::throttled, user::UserQuery};
+use crate::errors::MoolahBackendError;
+use crate::throttle::LoginThrottle;
+use crate::Pool;
+
+/// Whether two factor authentication is on, and how many recovery codes are
+/// left.
+pub async fn get_totp(
+    query: web::Query<UserQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let username = query.into_inner().username.to_ascii_lowercase();
+    if !is_authenticated(&id, &username) {
+        log::debug!("unauthorized request for two factor status of {}", username);
+        return Ok(HttpResponse::Unauthorized().body("not authorized for this account"));
+    }
+
+    let connection = pool.get()?;
+    let status = TotpStatus {
+        enabled: enabled_totp(&username, &connection)?.is_some(),
+        recovery_codes_left: recovery_codes_left(&username, &connection)?,
+    };
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// Start enrolling an authenticator with a new secret. Nothing changes for
+/// logging in until [`put_totp`] confirms it.
+pub async fn post_totp(
+    query: web::Query<UserQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let username = query.into_inner().username.to_ascii_lowercase();
+    if !is_authenticated(&id, &username) {
+        log::debug!("unauthorized two factor enrollment for {}", username);
+        return Ok(HttpResponse::Unauthorized().body("not authorized for this account"));
+    }
+
+    let connection = pool.get()?;
+    if enabled_totp(&username, &connection)?.is_some() {
+        return Ok(HttpResponse::Conflict().body("two factor authentication is already on"));
+    }
+
+    let now = chrono::Local::now().naive_utc();
+    let new_totp = NewUserTotp::new(username.clone(), now);
+
+    let totp: UserTotp = diesel::insert_into(user_totp::table)
+        .values(&new_totp)
+        .on_conflict(user_totp::username)
+        .do_update()
+        .set(&new_totp)
+        .get_result(&connection)?;
+
+    let enrollment = TotpEnrollment {
+        qr_code: qr_code(&totp.provisioning_uri()),
+        ..totp.enrollment()
+    };
+
+    Ok(HttpResponse::Ok().json(enrollment))
+}
+
+/// Turn two factor authentication on with a code from the newly enrolled
+/// authenticator, returning a fresh set of recovery codes. They are only ever
+/// shown this once.
+pub async fn put_totp(
+    query: web::Query<UserQuery>,
+    web::Json(form): web::Json<TotpCodeForm>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let username = query.into_inner().username.to_ascii_lowercase();
+    if !is_authenticated(&id, &username) {
+        log::debug!("unauthorized two factor confirmation for {}", username);
+        return Ok(HttpResponse::Unauthorized().body("not authorized for this account"));
+    }
+
+    let connection = pool.get()?;
+    let totp = user_totp::table
+        .find(&username)
+        .first::<UserTotp>(&connection)
+        .optional()?;
+
+    let totp = match totp {
+        Some(totp) if !totp.is_enabled() => totp,
+        Some(_) => {
+            return Ok(HttpResponse::Conflict().body("two factor authentication is already on"))
+        }
+        None => return Ok(HttpResponse::BadRequest().body("no authenticator being enrolled")),
+    };
+
+    let now = chrono::Local::now().naive_utc();
+    let step = match totp.verify(form.code(), now) {
+        Some(step) => step,
+        None => return Ok(HttpResponse::BadRequest().body("incorrect code")),
+    };
+
+    let codes = connection.transaction::<_, MoolahBackendError, _>(|| {
+        diesel::update(user_totp::table.find(&username))
+            .set((user_totp::enabled_at.eq(now), user_totp::last_step.eq(step)))
+            .execute(&connection)?;
+
+        replace_recovery_codes(&username, &connection)
+    })?;
+
+    log::info!("enabled two factor authentication for {}", username);
+
+    Ok(HttpResponse::Ok().json(codes))
+}
+
+/// Turn two factor authentication off, given a current code.
+pub async fn delete_totp(
+    query: web::Query<UserQuery>,
+    web::Json(form): web::Json<TotpCodeForm>,
+    req: HttpRequest,
+    id: Identity,
+    pool: web::Data<Pool>,
+    throttle: web::Data<LoginThrottle>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let username = query.into_inner().username.to_ascii_lowercase();
+    if !is_authenticated(&id, &username) {
+        log::debug!("unauthorized two factor removal for {}", username);
+        return Ok(HttpResponse::Unauthorized().body("not authorized for this account"));
+    }
+
+    // Guessing codes from a stolen session is throttled like logging in
+    let keys = throttle.keys(&req, &username);
+    if let Err(wait) = throttle.attempt(&keys, Instant::now()) {
+        return Ok(throttled(&username, "an existing session", wait));
+    }
+
+    let connection = pool.get()?;
+    let now = chrono::Local::now().naive_utc();
+    if !check_second_factor(&username, form.code(), now, &connection)? {
+        log::warn!("incorrect two factor code from a session for {}", username);
+        return Ok(HttpResponse::BadRequest().body("incorrect code"));
+    }
+    throttle.succeeded(&keys);
+
+    connection.transaction::<_, MoolahBackendError, _>(|| {
+        diesel::delete(recovery_codes::table.filter(recovery_codes::username.eq(&username)))
+            .execute(&connection)?;
+        diesel::delete(user_totp::table.find(&username)).execute(&connection)?;
+        Ok(())
+    })?;
+
+    log::info!("disabled two factor authentication for {}", username);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Replace the recovery codes, given a current code.
+pub async fn post_recovery_codes(
+    query: web::Query<UserQuery>,
+    web::Json(form): web::Json<TotpCodeForm>,
+    req: HttpRequest,
+    id: Identity,
+    pool: web::Data<Pool>,
+    throttle: web::Data<LoginThrottle>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let username = query.into_inner().username.to_ascii_lowercase();
+    if !is_authenticated(&id, &username) {
+        log::debug!("unauthorized recovery code request for {}", username);
+        return Ok(HttpResponse::Unauthorized().body("not authorized for this account"));
+    }
+
+    // Guessing codes from a stolen session is throttled like logging in
+    let keys = throttle.keys(&req, &username);
+    if let Err(wait) = throttle.attempt(&keys, Instant::now()) {
+        return Ok(throttled(&username, "an existing session", wait));
+    }
+
+    let connection = pool.get()?;
+    let now = chrono::Local::now().naive_utc();
+    if !check_second_factor(&username, form.code(), now, &connection)? {
+        log::warn!("incorrect two factor code from a session for {}", username);
+        return Ok(HttpResponse::BadRequest().body("incorrect code"));
+    }
+    throttle.succeeded(&keys);
+
+    let codes = connection.transaction(|| replace_recovery_codes(&username, &connection))?;
+
+    log::info!("replaced recovery codes for {}", username);
+
+    Ok(HttpResponse::Ok().json(codes))
+}
+
+/// The user's authenticator, if two factor authentication is on.
+pub(crate) fn enabled_totp(
+    username: &str,
+    connection: &PgConnection,
+) -> Result<Option<UserTotp>, MoolahBackendError> {
+    let totp = user_totp::table
+        .find(username)
+        .first::<UserTotp>(connection)
+        .optional()?;
+
+    Ok(totp.filter(UserTotp::is_enabled))
+}
+
+/// Whether `code` is a current authenticator code or an unused recovery code
+/// for `username`, spending it if so. Both are spent with a conditional update
+/// so the same code cannot be used twice, even concurrently.
+pub(crate) fn check_second_factor(
+    username: &str,
+    code: &str,
+    now: NaiveDateTime,
+    connection: &PgConnection,
+) -> Result<bool, MoolahBackendError> {
+    let totp = match enabled_totp(username, connection)? {
+        Some(totp) => totp,
+        None => return Ok(false),
+    };
+
+    if let Some(step) = totp.verify(code, now) {
+        let spent = diesel::update(
+            user_totp::table.find(username).filter(
+                user_totp::last_step
+                    .is_null()
+                    .or(user_totp::last_step.lt(step)),
+            ),
+        )
+        .set(user_totp::last_step.eq(step))
+        .execute(connection)?;
+
+        return Ok(spent > 0);
+    }
+
+    let spent = diesel::update(
+        recovery_codes::table
+            .filter(recovery_codes::username.eq(username))
+            .filter(recovery_codes::code_hash.eq(hash_recovery_code(code)))
+            .filter(recovery_codes::used.is_null()),
+    )
+    .set(recovery_codes::used.eq(now))
+    .execute(connection)?;
+
+    if spent > 0 {
+        log::info!("{} used a recovery code", username);
+    }
+
+    Ok(spent > 0)
+}
+
+fn recovery_codes_left(
+    username: &str,
+    connection: &PgConnection,
+) -> Result<i64, MoolahBackendError> {
+    Ok(recovery_codes::table
+        .filter(recovery_codes::username.eq(username))
+        .filter(recovery_codes::used.is_null())
+        .count()
+        .get_result(connection)?)
+}
+
+fn replace_recovery_codes(
+    username: &str,
+    connection: &PgConnection,
+) -> Result<Vec<String>, MoolahBackendError> {
+    let (new_codes, codes) = NewRecoveryCode::generate(username);
+
+    diesel::delete(recovery_codes::table.filter(recovery_codes::username.eq(username)))
+        .execute(connection)?;
+    diesel::insert_into(recovery_codes::table)
+        .values(&new_codes)
+        .execute(connection)?;
+
+    Ok(codes)
+}
+
+/// `uri` as an SVG QR code in a `data:` URI, ready for an `img` tag.
+fn qr_code(uri: &str) -> Option<String> {
+    let code = QrCode::new(uri.as_bytes()).ok()?;
+    let image = code.render::<svg::Color>().min_dimensions(200, 200).build();
+
+    Some(format!(
+        "data:image/svg+xml;base64,{}",
+        base64::encode(image)
+    ))
+}
@@ -0,0 +1,65 @@
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
+use shared::imports::ParsedTransaction;
+use shared::models::Transaction;
+use shared::recurring::{self, RecurringQuery};
+use shared::schema::transactions::dsl;
+
+use super::is_authenticated;
+use crate::{HttpResult, Pool};
+
+pub async fn get_transactions(
+    path: web::Path<String>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to get transactions for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let connection = pool.get()?;
+
+    let transactions = dsl::transactions
+        .filter(dsl::username.eq(username))
+        .order((dsl::posted_on.desc(), dsl::id.desc()))
+        .load::<Transaction>(&connection)?;
+
+    Ok(HttpResponse::Ok().json(transactions))
+}
+
+pub async fn get_recurring(
+    path: web::Path<String>,
+    query: web::Query<RecurringQuery>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> HttpResult {
+    let username = path.into_inner();
+
+    if !is_authenticated(&id, &username) {
+        log::debug!("user is not authorized to get recurring transactions for this user");
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let connection = pool.get()?;
+
+    let transactions = dsl::transactions
+        .filter(dsl::username.eq(username))
+        .load::<Transaction>(&connection)?
+        .into_iter()
+        .map(ParsedTransaction::from)
+        .collect::<Vec<_>>();
+
+    let proposals = recurring::detect(&transactions, &query);
+
+    log::debug!(
+        "proposed {} recurring deltas from {} transactions",
+        proposals.len(),
+        transactions.len()
+    );
+
+    Ok(HttpResponse::Ok().json(proposals))
+}
@@ -1,19 +1,33 @@
 use actix_identity::Identity;
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use chrono::NaiveDateTime;
 use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use serde::Deserialize;
 use shared::{
-    models::{users::UserAccount, NewUser, User},
-    schema::users::dsl,
+    models::{
+        users::UserAccount, NewUser, TokenPurpose, User, UserEmailChangeForm,
+        UserPasswordChangeForm, UserUsernameChangeForm,
+    },
+    schema::{sessions, user_tokens, users::dsl},
 };
+use validator::Validate;
 
-use super::{authentication_status, AuthenticationStatus};
-use crate::{errors::MoolahBackendError, Pool};
+use super::email_verification::send_verification;
+use super::{authentication_status, is_authenticated, AuthenticationStatus};
+use crate::config::MailConfig;
+use crate::mailer::Mailer;
+use crate::{errors::MoolahBackendError, session_store::CurrentSession, Pool};
 
+/// Register a user and mail them a link to verify their email. Failing to
+/// send it does not fail the registration; they can ask for another from the
+/// account page.
 pub async fn put_user(
     web::Json(new_user): web::Json<NewUser>,
     id: Identity,
     pool: web::Data<Pool>,
+    mail: web::Data<MailConfig>,
+    mailer: web::Data<dyn Mailer>,
 ) -> Result<HttpResponse, MoolahBackendError> {
     log::debug!("posting registration user form");
 
@@ -25,6 +39,20 @@ pub async fn put_user(
 
     log::info!("process registration for {}", new_user.username);
 
+    if let Err(err) = send_verification(
+        &new_user.username,
+        &new_user.email,
+        &connection,
+        &mail,
+        mailer.as_ref(),
+    ) {
+        log::warn!(
+            "could not send verification link to {}: {}",
+            new_user.username,
+            err
+        );
+    }
+
     if let Some(username) = id.identity() {
         log::debug!("already logged in for user {} - forgetting", username);
         id.forget();
@@ -38,7 +66,7 @@ pub async fn put_user(
 
 #[derive(Deserialize)]
 pub struct UserQuery {
-    username: String,
+    pub(crate) username: String,
 }
 
 pub async fn get_user_account(
@@ -85,3 +113,152 @@ pub async fn delete_user(
         }
     }
 }
+
+/// Change the user's password, given their current one. Every other session
+/// is ended so a stolen session does not outlive the old password.
+pub async fn patch_user_password(
+    query: web::Query<UserQuery>,
+    web::Json(form): web::Json<UserPasswordChangeForm>,
+    req: HttpRequest,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let username = query.into_inner().username.to_ascii_lowercase();
+    if !is_authenticated(&id, &username) {
+        return Ok(HttpResponse::Unauthorized().body("not authorized to change this password"));
+    }
+
+    let connection = pool.get()?;
+    let user: User = dsl::users
+        .filter(dsl::username.eq(&username))
+        .first(&connection)?;
+
+    if !user.verify_user(&username, form.current_password())? {
+        return Ok(HttpResponse::Unauthorized().body("current password is incorrect"));
+    }
+
+    let password_hash = match form.new_password_hash() {
+        Ok(password_hash) => password_hash,
+        Err(err) => return Ok(HttpResponse::BadRequest().body(err.to_string())),
+    };
+
+    let current = req.extensions().get::<CurrentSession>().cloned();
+    connection.transaction::<_, MoolahBackendError, _>(|| {
+        diesel::update(dsl::users.filter(dsl::username.eq(&username)))
+            .set(dsl::password.eq(password_hash))
+            .execute(&connection)?;
+
+        let other_sessions = sessions::table.filter(sessions::username.eq(&username));
+        match current {
+            Some(CurrentSession(token_hash)) => {
+                diesel::delete(other_sessions.filter(sessions::token_hash.ne(token_hash)))
+                    .execute(&connection)?
+            }
+            None => diesel::delete(other_sessions).execute(&connection)?,
+        };
+
+        Ok(())
+    })?;
+
+    log::info!("changed password for {}", username);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Change the user's email. The new address is unverified until they follow
+/// the link mailed to it, and links sent to the old one stop working.
+pub async fn patch_user_email(
+    query: web::Query<UserQuery>,
+    web::Json(form): web::Json<UserEmailChangeForm>,
+    id: Identity,
+    pool: web::Data<Pool>,
+    mail: web::Data<MailConfig>,
+    mailer: web::Data<dyn Mailer>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let username = query.into_inner().username.to_ascii_lowercase();
+    if !is_authenticated(&id, &username) {
+        return Ok(HttpResponse::Unauthorized().body("not authorized to change this email"));
+    }
+
+    if let Err(err) = form.validate() {
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let connection = pool.get()?;
+    let email = form.email().to_lowercase();
+    let updated = connection.transaction(|| {
+        diesel::update(dsl::users.filter(dsl::username.eq(&username)))
+            .set((
+                dsl::email.eq(&email),
+                dsl::verified_at.eq(None::<NaiveDateTime>),
+            ))
+            .execute(&connection)?;
+
+        diesel::delete(
+            user_tokens::table
+                .filter(user_tokens::username.eq(&username))
+                .filter(user_tokens::purpose.eq_any([
+                    TokenPurpose::PasswordReset.as_str(),
+                    TokenPurpose::EmailVerification.as_str(),
+                ]))
+                .filter(user_tokens::used.is_null()),
+        )
+        .execute(&connection)
+    });
+
+    match updated {
+        Ok(_) => {
+            if let Err(err) =
+                send_verification(&username, &email, &connection, &mail, mailer.as_ref())
+            {
+                log::warn!("could not send verification link to {}: {}", username, err);
+            }
+            Ok(HttpResponse::Ok().finish())
+        }
+        Err(err) if is_unique_violation(&err) => {
+            Ok(HttpResponse::Conflict().body("email is already in use"))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Rename the user. Their predictions, transactions, rules and sessions
+/// follow through the `ON UPDATE CASCADE` foreign keys, so they stay logged in.
+pub async fn patch_user_username(
+    query: web::Query<UserQuery>,
+    web::Json(form): web::Json<UserUsernameChangeForm>,
+    id: Identity,
+    pool: web::Data<Pool>,
+) -> Result<HttpResponse, MoolahBackendError> {
+    let username = query.into_inner().username.to_ascii_lowercase();
+    if !is_authenticated(&id, &username) {
+        return Ok(HttpResponse::Unauthorized().body("not authorized to change this username"));
+    }
+
+    if let Err(err) = form.validate() {
+        return Ok(HttpResponse::BadRequest().body(err.to_string()));
+    }
+
+    let connection = pool.get()?;
+    let updated = diesel::update(dsl::users.filter(dsl::username.eq(&username)))
+        .set(dsl::username.eq(form.username().to_lowercase()))
+        .execute(&connection);
+
+    match updated {
+        Ok(_) => {
+            log::info!("renamed {} to {}", username, form.username());
+            Ok(HttpResponse::Ok().finish())
+        }
+        Err(err) if is_unique_violation(&err) => {
+            Ok(HttpResponse::Conflict().body("username is already taken"))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn is_unique_violation(err: &DieselError) -> bool {
+    matches!(
+        err,
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)
+    )
+}
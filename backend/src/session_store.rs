@@ -0,0 +1,288 @@
+#[cfg(test)]
+mod tests;
+
+use std::future::{ready, Future};
+use std::pin::Pin;
+
+use actix_identity::{CookieIdentityPolicy, IdentityPolicy};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::error::{ErrorForbidden, ErrorUnauthorized};
+use actix_web::http::header::{AUTHORIZATION, USER_AGENT};
+use actix_web::{Error, HttpMessage, HttpRequest};
+use chrono::{Duration, Local, NaiveDateTime};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use shared::models::{
+    user_tokens::hash_token, ApiScope, ApiToken, NewSession, Session, SessionExpiry,
+};
+use shared::schema::{api_tokens, sessions};
+
+use crate::errors::MoolahBackendError;
+use crate::Pool;
+
+/// How stale `last_seen` and `last_used` may get before a request writes it back, so that not
+/// every request costs an update.
+const LAST_SEEN_RESOLUTION_SECS: i64 = 60;
+
+/// The token hash of the session making a request, kept in the request's
+/// extensions for handlers that need to tell it apart from the user's others.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentSession(pub String);
+
+/// The id of the API token a request was made with, in place of a session.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurrentApiToken(pub i32);
+
+/// Identity policy keeping sessions in Postgres. The signed cookie only carries
+/// a random session token, which is stored hashed; the identity handlers see
+/// is the username of the session it points to, as long as that session exists
+/// and has not expired.
+///
+/// Scripts can instead send an API token as an `Authorization: Bearer` header,
+/// in which case the identity is the token's user. The request is refused
+/// outright if the token is unknown, expired or lacks the scope the request
+/// needs, and no session is ever started or ended for it.
+pub struct SessionIdentityPolicy {
+    cookie: CookieIdentityPolicy,
+    pool: Pool,
+    expiry: SessionExpiry,
+}
+
+impl SessionIdentityPolicy {
+    pub fn new(cookie: CookieIdentityPolicy, pool: Pool, expiry: SessionExpiry) -> Self {
+        SessionIdentityPolicy {
+            cookie,
+            pool,
+            expiry,
+        }
+    }
+}
+
+impl IdentityPolicy for SessionIdentityPolicy {
+    type Future = Pin<Box<dyn Future<Output = Result<Option<String>, Error>>>>;
+    type ResponseFuture = Pin<Box<dyn Future<Output = Result<(), Error>>>>;
+
+    fn from_request(&self, req: &mut ServiceRequest) -> Self::Future {
+        let request = req.request().clone();
+        let pool = self.pool.clone();
+
+        if let Some(bearer) = bearer_token(&request) {
+            return Box::pin(async move {
+                let connection = pool.get().map_err(MoolahBackendError::from)?;
+                let token = resolve_api_token(&connection, &bearer, now())?
+                    .ok_or_else(|| ErrorUnauthorized("invalid or expired API token"))?;
+
+                check_scope(&request, |scope| token.allows(scope))?;
+
+                request.extensions_mut().insert(CurrentApiToken(token.id()));
+                Ok(Some(token.username().to_string()))
+            });
+        }
+
+        let token = self.cookie.from_request(req);
+        let expiry = self.expiry;
+
+        Box::pin(async move {
+            let token = match token.await? {
+                Some(token) => token,
+                None => return Ok(None),
+            };
+
+            let connection = pool.get().map_err(MoolahBackendError::from)?;
+            let token_hash = hash_token(&token);
+            let username = resolve(&connection, &token_hash, &expiry, now())?;
+
+            if username.is_some() {
+                request.extensions_mut().insert(CurrentSession(token_hash));
+            }
+            Ok(username)
+        })
+    }
+
+    fn to_response<B>(
+        &self,
+        identity: Option<String>,
+        changed: bool,
+        res: &mut ServiceResponse<B>,
+    ) -> Self::ResponseFuture {
+        let api_token = res
+            .request()
+            .extensions()
+            .get::<CurrentApiToken>()
+            .is_some();
+        if !changed || api_token {
+            return Box::pin(ready(Ok(())));
+        }
+
+        let current = res.request().extensions().get::<CurrentSession>().cloned();
+        let user_agent = res
+            .request()
+            .headers()
+            .get(USER_AGENT)
+            .and_then(|agent| agent.to_str().ok())
+            .map(str::to_string);
+
+        let token =
+            self.pool.get().map_err(MoolahBackendError::from).and_then(
+                |connection| match identity {
+                    Some(username) => start(
+                        &connection,
+                        username,
+                        user_agent,
+                        current.as_ref(),
+                        &self.expiry,
+                        now(),
+                    )
+                    .map(Some),
+                    None => end(&connection, current.as_ref()).map(|_| None),
+                },
+            );
+
+        match token {
+            Ok(token) => Box::pin(self.cookie.to_response(token, true, res)),
+            Err(err) => Box::pin(ready(Err(err.into()))),
+        }
+    }
+}
+
+fn now() -> NaiveDateTime {
+    Local::now().naive_utc()
+}
+
+/// The username the session with `token_hash` belongs to, or `None` if there
+/// is no such session or it has expired.
+fn resolve(
+    connection: &PgConnection,
+    token_hash: &str,
+    expiry: &SessionExpiry,
+    now: NaiveDateTime,
+) -> Result<Option<String>, MoolahBackendError> {
+    let session = sessions::table
+        .filter(sessions::token_hash.eq(token_hash))
+        .first::<Session>(connection)
+        .optional()?;
+
+    let session = match session {
+        Some(session) => session,
+        None => return Ok(None),
+    };
+
+    if session.is_expired(now, expiry) {
+        diesel::delete(&session).execute(connection)?;
+        log::debug!("session {} expired", session.id());
+        return Ok(None);
+    }
+
+    if now - session.last_seen() > Duration::seconds(LAST_SEEN_RESOLUTION_SECS) {
+        diesel::update(&session)
+            .set(sessions::last_seen.eq(now))
+            .execute(connection)?;
+    }
+
+    Ok(Some(session.username().to_string()))
+}
+
+/// The token in a request's `Authorization: Bearer` header, if it has one.
+fn bearer_token(request: &HttpRequest) -> Option<String> {
+    let header = request.headers().get(AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = header.trim().split_once(' ')?;
+
+    if scheme.eq_ignore_ascii_case("bearer") {
+        Some(token.trim().to_string())
+    } else {
+        None
+    }
+}
+
+/// Refuse `request` unless a token that `allows` the scope its route needs
+/// may make it. The route is found from the path the router will match, with
+/// its percent-encoding normalized, so spelling a path differently cannot
+/// change the scope it needs. Requests to no route at all are refused.
+fn check_scope(request: &HttpRequest, allows: impl Fn(ApiScope) -> bool) -> Result<(), Error> {
+    let pattern = request
+        .resource_map()
+        .match_pattern(request.match_info().as_str())
+        .ok_or_else(|| ErrorForbidden("API tokens can only be used on API routes"))?;
+
+    match ApiScope::required(request.method().as_str(), &pattern) {
+        Some(scope) if allows(scope) => Ok(()),
+        Some(scope) => Err(ErrorForbidden(format!(
+            "API token does not have the {} scope",
+            scope.as_str()
+        ))),
+        None => Err(ErrorForbidden("API tokens cannot manage the account")),
+    }
+}
+
+/// The API token `token` is, if it exists and has not expired.
+fn resolve_api_token(
+    connection: &PgConnection,
+    token: &str,
+    now: NaiveDateTime,
+) -> Result<Option<ApiToken>, MoolahBackendError> {
+    let api_token = api_tokens::table
+        .filter(api_tokens::token_hash.eq(hash_token(token)))
+        .first::<ApiToken>(connection)
+        .optional()?;
+
+    let api_token = match api_token {
+        Some(api_token) if !api_token.is_expired(now) => api_token,
+        _ => return Ok(None),
+    };
+
+    let stale = api_token
+        .last_used()
+        .map(|last_used| now - last_used > Duration::seconds(LAST_SEEN_RESOLUTION_SECS))
+        .unwrap_or(true);
+    if stale {
+        diesel::update(&api_token)
+            .set(api_tokens::last_used.eq(now))
+            .execute(connection)?;
+    }
+
+    Ok(Some(api_token))
+}
+
+/// Start a session for `username` and return its token. Any session the
+/// request came in with is ended, along with the user's expired sessions.
+fn start(
+    connection: &PgConnection,
+    username: String,
+    user_agent: Option<String>,
+    replacing: Option<&CurrentSession>,
+    expiry: &SessionExpiry,
+    now: NaiveDateTime,
+) -> Result<String, MoolahBackendError> {
+    end(connection, replacing)?;
+
+    diesel::delete(
+        sessions::table
+            .filter(sessions::username.eq(&username))
+            .filter(
+                sessions::last_seen
+                    .lt(now - expiry.idle)
+                    .or(sessions::created.lt(now - expiry.absolute)),
+            ),
+    )
+    .execute(connection)?;
+
+    let (session, token) = NewSession::new(username, user_agent, now);
+    diesel::insert_into(sessions::table)
+        .values(&session)
+        .execute(connection)?;
+
+    log::info!("started session for {}", session.username());
+    Ok(token)
+}
+
+fn end(
+    connection: &PgConnection,
+    current: Option<&CurrentSession>,
+) -> Result<(), MoolahBackendError> {
+    if let Some(CurrentSession(token_hash)) = current {
+        diesel::delete(sessions::table.filter(sessions::token_hash.eq(token_hash)))
+            .execute(connection)?;
+        log::debug!("ended session");
+    }
+    Ok(())
+}
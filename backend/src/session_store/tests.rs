@@ -0,0 +1,111 @@
+use actix_web::dev::Service;
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App, HttpResponse};
+use shared::routes;
+
+use super::*;
+
+async fn ok() -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+/// The status of a `method` request to `uri` made with a token holding only
+/// `scopes`, against an app with a few of the real routes.
+async fn status(method: &str, uri: &str, scopes: &[ApiScope]) -> StatusCode {
+    let scopes = scopes.to_vec();
+    let app = test::init_service(
+        App::new()
+            .wrap_fn(move |req, srv| {
+                let checked = check_scope(req.request(), |scope| scopes.contains(&scope));
+                let response = srv.call(req);
+                async move {
+                    checked?;
+                    response.await
+                }
+            })
+            .route(routes::LOGIN, web::post().to(ok))
+            .service(
+                web::resource(routes::API_TOKENS)
+                    .route(web::get().to(ok))
+                    .route(web::post().to(ok)),
+            )
+            .service(
+                web::resource(routes::PREDICTIONS)
+                    .route(web::get().to(ok))
+                    .route(web::put().to(ok)),
+            )
+            .service(web::resource(routes::IMPORT_CSV).route(web::post().to(ok))),
+    )
+    .await;
+
+    let request = test::TestRequest::default()
+        .method(method.parse().unwrap())
+        .uri(uri)
+        .to_request();
+    match app.call(request).await {
+        Ok(response) => response.status(),
+        Err(err) => err.as_response_error().status_code(),
+    }
+}
+
+#[actix_web::test]
+async fn test_scopes_follow_the_route() {
+    let write = [ApiScope::Write];
+
+    assert_eq!(
+        StatusCode::OK,
+        status("PUT", "/api/predictions/alice", &write).await
+    );
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        status("GET", "/api/predictions/alice", &write).await
+    );
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        status("POST", "/api/import/csv/alice", &write).await
+    );
+    assert_eq!(
+        StatusCode::OK,
+        status("POST", "/api/import/csv/alice", &[ApiScope::Import]).await
+    );
+}
+
+#[actix_web::test]
+async fn test_account_routes_refused() {
+    let all = ApiScope::ALL;
+
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        status("POST", "/api/tokens/alice", &all).await
+    );
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        status("POST", "/api/login", &all).await
+    );
+}
+
+#[actix_web::test]
+async fn test_encoded_paths_need_the_same_scope() {
+    let write = [ApiScope::Write];
+
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        status("POST", "/api/%74okens/alice", &write).await
+    );
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        status("GET", "/api/%74okens/alice", &[ApiScope::Read]).await
+    );
+    assert_eq!(
+        StatusCode::OK,
+        status("PUT", "/api/%70redictions/alice", &write).await
+    );
+}
+
+#[actix_web::test]
+async fn test_unknown_routes_refused() {
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        status("GET", "/api/nowhere/alice", &ApiScope::ALL).await
+    );
+}
@@ -0,0 +1,200 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use actix_web::HttpRequest;
+
+/// Most keys tracked before stale ones are swept out, so a flood of made up
+/// usernames cannot grow the table without bound.
+const MAX_TRACKED: usize = 10_000;
+
+/// What a login attempt is counted against.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ThrottleKey {
+    Ip(IpAddr),
+    Username(String),
+}
+
+/// How many failures a key is allowed before it has to wait between attempts,
+/// and before it is locked out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThrottlePolicy {
+    /// Failures allowed before each further attempt has to wait.
+    pub free_attempts: u32,
+    /// Wait after the first failure past the free ones, doubling each time.
+    pub base_delay: Duration,
+    /// Longest wait backoff alone imposes.
+    pub max_delay: Duration,
+    /// Failures after which the key is locked out.
+    pub lockout_attempts: u32,
+    /// How long a lockout lasts. Failures older than this are forgotten.
+    pub lockout: Duration,
+}
+
+impl ThrottlePolicy {
+    /// How long after the `failures`th failure the next attempt has to wait.
+    fn delay(&self, failures: u32) -> Duration {
+        if failures <= self.free_attempts {
+            return Duration::ZERO;
+        }
+
+        let doublings = (failures - self.free_attempts - 1).min(31);
+        self.base_delay
+            .saturating_mul(1 << doublings)
+            .min(self.max_delay)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Failures {
+    count: u32,
+    last: Instant,
+    locked_until: Option<Instant>,
+}
+
+/// Counts failed logins per IP address and per username, turning repeated
+/// failures into exponentially growing waits and then a temporary lockout.
+///
+/// An attempt is counted as failed as soon as it is let through, so parallel
+/// guesses cannot all slip in before the first is recorded; a successful
+/// login is then [`LoginThrottle::succeeded`] back off.
+pub struct LoginThrottle {
+    ip_policy: ThrottlePolicy,
+    username_policy: ThrottlePolicy,
+    trust_proxy_headers: bool,
+    failures: Mutex<HashMap<ThrottleKey, Failures>>,
+}
+
+impl LoginThrottle {
+    pub fn new(
+        ip_policy: ThrottlePolicy,
+        username_policy: ThrottlePolicy,
+        trust_proxy_headers: bool,
+    ) -> Self {
+        LoginThrottle {
+            ip_policy,
+            username_policy,
+            trust_proxy_headers,
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The address `req` came from: the peer, or the client a reverse proxy
+    /// forwarded it for if its headers are trusted.
+    pub fn client_ip(&self, req: &HttpRequest) -> Option<IpAddr> {
+        if self.trust_proxy_headers {
+            let info = req.connection_info();
+            let forwarded = info
+                .realip_remote_addr()
+                .and_then(|addr| addr.parse::<IpAddr>().ok());
+            if forwarded.is_some() {
+                return forwarded;
+            }
+        }
+
+        req.peer_addr().map(|addr| addr.ip())
+    }
+
+    /// The keys a login for `username` through `req` is counted against.
+    pub fn keys(&self, req: &HttpRequest, username: &str) -> Vec<ThrottleKey> {
+        let mut keys = vec![ThrottleKey::Username(username.to_string())];
+        if let Some(ip) = self.client_ip(req) {
+            keys.push(ThrottleKey::Ip(ip));
+        }
+        keys
+    }
+
+    fn policy(&self, key: &ThrottleKey) -> &ThrottlePolicy {
+        match key {
+            ThrottleKey::Ip(_) => &self.ip_policy,
+            ThrottleKey::Username(_) => &self.username_policy,
+        }
+    }
+
+    /// How long `key` has to wait at `now` before another attempt.
+    fn wait(&self, key: &ThrottleKey, failures: &Failures, now: Instant) -> Duration {
+        let policy = self.policy(key);
+
+        let locked = failures
+            .locked_until
+            .map(|until| until.saturating_duration_since(now))
+            .unwrap_or_default();
+        let backoff = (failures.last + policy.delay(failures.count)).saturating_duration_since(now);
+
+        locked.max(backoff)
+    }
+
+    /// Let an attempt for all of `keys` through at `now`, counting it as a
+    /// failure against each, or say how long to wait if any of them has to.
+    pub fn attempt(&self, keys: &[ThrottleKey], now: Instant) -> Result<(), Duration> {
+        let mut failures = self.failures.lock().expect("login throttle poisoned");
+
+        let wait = keys
+            .iter()
+            .filter_map(|key| failures.get(key).map(|f| self.wait(key, f, now)))
+            .max()
+            .unwrap_or_default();
+
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+
+        if failures.len() >= MAX_TRACKED {
+            failures
+                .retain(|key, f| now.saturating_duration_since(f.last) < self.policy(key).lockout);
+        }
+
+        for key in keys {
+            let policy = self.policy(key);
+            let entry = failures.entry(key.clone()).or_insert(Failures {
+                count: 0,
+                last: now,
+                locked_until: None,
+            });
+
+            // Start over once the last failure is older than a lockout
+            if now.saturating_duration_since(entry.last) >= policy.lockout {
+                entry.count = 0;
+                entry.locked_until = None;
+            }
+
+            entry.count += 1;
+            entry.last = now;
+
+            if entry.count >= policy.lockout_attempts {
+                log::warn!("locking out {:?} after {} failed logins", key, entry.count);
+                entry.locked_until = Some(now + policy.lockout);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Take back the failure [`LoginThrottle::attempt`] counted for a login
+    /// that succeeded. The username starts over; the IP address only has the
+    /// one attempt forgiven, so logging into an account of one's own does not
+    /// clear the way for guessing at others.
+    pub fn succeeded(&self, keys: &[ThrottleKey]) {
+        let mut failures = self.failures.lock().expect("login throttle poisoned");
+
+        for key in keys {
+            match key {
+                ThrottleKey::Username(_) => {
+                    failures.remove(key);
+                }
+                ThrottleKey::Ip(_) => {
+                    if let Some(entry) = failures.get_mut(key) {
+                        entry.count = entry.count.saturating_sub(1);
+                        if entry.count < self.ip_policy.lockout_attempts {
+                            entry.locked_until = None;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use super::*;
+
+const USERNAME_POLICY: ThrottlePolicy = ThrottlePolicy {
+    free_attempts: 3,
+    base_delay: Duration::from_secs(1),
+    max_delay: Duration::from_secs(60),
+    lockout_attempts: 10,
+    lockout: Duration::from_secs(900),
+};
+
+const IP_POLICY: ThrottlePolicy = ThrottlePolicy {
+    free_attempts: 10,
+    base_delay: Duration::from_secs(1),
+    max_delay: Duration::from_secs(60),
+    lockout_attempts: 50,
+    lockout: Duration::from_secs(900),
+};
+
+fn throttle() -> LoginThrottle {
+    LoginThrottle::new(IP_POLICY, USERNAME_POLICY, false)
+}
+
+fn alice() -> ThrottleKey {
+    ThrottleKey::Username("alice".into())
+}
+
+fn ip() -> ThrottleKey {
+    ThrottleKey::Ip([192, 0, 2, 1].into())
+}
+
+fn secs(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+mod delay {
+    use super::*;
+
+    #[test]
+    fn test_free_attempts() {
+        assert_eq!(Duration::ZERO, USERNAME_POLICY.delay(3));
+    }
+
+    #[test]
+    fn test_doubles() {
+        assert_eq!(secs(1), USERNAME_POLICY.delay(4));
+        assert_eq!(secs(2), USERNAME_POLICY.delay(5));
+        assert_eq!(secs(8), USERNAME_POLICY.delay(7));
+    }
+
+    #[test]
+    fn test_capped() {
+        assert_eq!(secs(32), USERNAME_POLICY.delay(9));
+        assert_eq!(secs(60), USERNAME_POLICY.delay(10));
+        assert_eq!(secs(60), USERNAME_POLICY.delay(u32::MAX));
+    }
+}
+
+mod attempt {
+    use super::*;
+
+    #[test]
+    fn test_free_attempts() {
+        let throttle = throttle();
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(Ok(()), throttle.attempt(&[alice(), ip()], now));
+        }
+    }
+
+    #[test]
+    fn test_backoff() {
+        let throttle = throttle();
+        let now = Instant::now();
+
+        for _ in 0..4 {
+            throttle.attempt(&[alice()], now).unwrap();
+        }
+
+        assert_eq!(Err(secs(1)), throttle.attempt(&[alice()], now));
+        assert_eq!(Ok(()), throttle.attempt(&[alice()], now + secs(1)));
+        assert_eq!(Err(secs(2)), throttle.attempt(&[alice()], now + secs(1)));
+    }
+
+    #[test]
+    fn test_keys_throttled_separately() {
+        let throttle = throttle();
+        let now = Instant::now();
+
+        for _ in 0..4 {
+            throttle.attempt(&[alice()], now).unwrap();
+        }
+
+        assert!(throttle.attempt(&[alice(), ip()], now).is_err());
+        assert_eq!(
+            Ok(()),
+            throttle.attempt(&[ThrottleKey::Username("bob".into()), ip()], now)
+        );
+    }
+
+    #[test]
+    fn test_lockout() {
+        let throttle = throttle();
+        let mut now = Instant::now();
+
+        for _ in 0..10 {
+            now += secs(60);
+            throttle.attempt(&[alice()], now).unwrap();
+        }
+
+        assert_eq!(Err(secs(900)), throttle.attempt(&[alice()], now));
+        assert_eq!(
+            Err(secs(600)),
+            throttle.attempt(&[alice()], now + secs(300))
+        );
+        assert_eq!(Ok(()), throttle.attempt(&[alice()], now + secs(900)));
+        assert_eq!(Ok(()), throttle.attempt(&[alice()], now + secs(900)));
+    }
+
+    #[test]
+    fn test_failures_forgotten() {
+        let throttle = throttle();
+        let now = Instant::now();
+
+        for _ in 0..4 {
+            throttle.attempt(&[alice()], now).unwrap();
+        }
+
+        let later = now + secs(900);
+        for _ in 0..3 {
+            assert_eq!(Ok(()), throttle.attempt(&[alice()], later));
+        }
+    }
+}
+
+mod succeeded {
+    use super::*;
+
+    #[test]
+    fn test_clears_username() {
+        let throttle = throttle();
+        let now = Instant::now();
+
+        for _ in 0..4 {
+            throttle.attempt(&[alice()], now).unwrap();
+        }
+        throttle.succeeded(&[alice()]);
+
+        assert_eq!(Ok(()), throttle.attempt(&[alice()], now));
+    }
+
+    #[test]
+    fn test_forgives_one_ip_attempt() {
+        let throttle = throttle();
+        let now = Instant::now();
+
+        for _ in 0..11 {
+            throttle.attempt(&[ip()], now).unwrap();
+        }
+        assert!(throttle.attempt(&[ip()], now).is_err());
+
+        throttle.succeeded(&[ip()]);
+
+        assert_eq!(Ok(()), throttle.attempt(&[ip()], now));
+        assert!(throttle.attempt(&[ip()], now).is_err());
+    }
+}
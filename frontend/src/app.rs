@@ -6,7 +6,10 @@ use yew_router::prelude::*;
 extern crate console_error_panic_hook;
 
 use crate::components::{AppContext, ContextData, Footer};
-use crate::pages::{Account, Home, Login, Logout, Register};
+use crate::pages::{
+    Account, ForgotPassword, Home, Import, Login, Logout, Register, ResetPassword, Rules,
+    VerifyEmail,
+};
 
 #[derive(Routable, PartialEq, Clone, Copy, Debug)]
 pub enum Route {
@@ -25,6 +28,21 @@ pub enum Route {
     #[at("/register")]
     Register,
 
+    #[at("/forgot-password")]
+    ForgotPassword,
+
+    #[at("/reset-password")]
+    ResetPassword,
+
+    #[at("/verify-email")]
+    VerifyEmail,
+
+    #[at("/import")]
+    Import,
+
+    #[at("/rules")]
+    Rules,
+
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -38,6 +56,11 @@ fn switch(route: &Route) -> Html {
         Route::Logout => html! { <Logout /> },
         Route::Account => html! { <Account /> },
         Route::Register => html! { <Register /> },
+        Route::ForgotPassword => html! { <ForgotPassword /> },
+        Route::ResetPassword => html! { <ResetPassword /> },
+        Route::VerifyEmail => html! { <VerifyEmail /> },
+        Route::Import => html! { <Import /> },
+        Route::Rules => html! { <Rules /> },
     }
 }
 
@@ -1,3 +1,4 @@
+mod account;
 mod app_context;
 mod footer;
 mod header;
@@ -5,9 +6,14 @@ mod home;
 mod loading;
 mod unauthorized;
 
+pub use account::{ApiTokens, TwoFactor};
 pub use app_context::{AppContext, ContextData};
 pub use footer::Footer;
 pub use header::Header;
-pub use home::{NewDelta, NewPrediction, PredictionPanel};
+pub use home::{
+    DebtPlanner, Goals, InvestmentAccounts, LedgerImport, LoanGenerator, NetWorth, NewDelta,
+    NewPrediction, PaycheckGenerator, PredictionPanel, RecurringDeltas, RetirementPlanner,
+    TaxEstimator,
+};
 pub use loading::Loading;
 pub use unauthorized::Unauthorized;
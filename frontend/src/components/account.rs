@@ -0,0 +1,5 @@
+mod api_tokens;
+mod two_factor;
+
+pub use api_tokens::ApiTokens;
+pub use two_factor::TwoFactor;
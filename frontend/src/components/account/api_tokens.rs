@@ -0,0 +1,332 @@
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use reqwest::Client;
+use shared::models::{ApiScope, ApiTokenInfo, CreatedApiToken, NewApiTokenRequest};
+use shared::{path_patterns, routes};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::components::Loading;
+use crate::requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction};
+use crate::{InternalResponseError, ResponseResult};
+
+const DATETIME_FORMAT: &str = "%a %h %d %Y %r %Z";
+
+/// Lifetimes offered for new tokens, in days, with `None` for no expiry.
+const LIFETIMES: [(Option<i64>, &str); 4] = [
+    (Some(30), "30 days"),
+    (Some(90), "90 days"),
+    (Some(365), "a year"),
+    (None, "never"),
+];
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct ApiTokensProps {
+    pub username: String,
+}
+
+pub enum ApiTokensMsg {
+    ReceivedTokens(ResponseResult<Vec<ApiTokenInfo>>),
+    NameChanged(String),
+    ScopeToggled(ApiScope),
+    LifetimeChanged(Option<i64>),
+    CreateSubmitted,
+    ReceivedCreated(ResponseResult<CreatedApiToken>),
+    Revoke(ApiTokenInfo),
+    ReceivedRevokeResponse(ResponseResult<i32>),
+}
+
+/// Creating and revoking the API tokens scripts use in place of logging in.
+/// A new token is shown once, right after it is created.
+pub struct ApiTokens {
+    client: Client,
+    tokens: Option<ResponseResult<Vec<ApiTokenInfo>>>,
+    name: String,
+    scopes: Vec<ApiScope>,
+    expires_in_days: Option<i64>,
+    created: Option<String>,
+    error: Option<String>,
+}
+
+impl Component for ApiTokens {
+    type Message = ApiTokensMsg;
+    type Properties = ApiTokensProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let api_tokens = ApiTokens {
+            client: Client::new(),
+            tokens: None,
+            name: String::new(),
+            scopes: vec![ApiScope::Read],
+            expires_in_days: LIFETIMES[1].0,
+            created: None,
+            error: None,
+        };
+        api_tokens.get_tokens(ctx);
+        api_tokens
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ApiTokensMsg::ReceivedTokens(tokens) => self.tokens = Some(tokens),
+            ApiTokensMsg::NameChanged(name) => self.name = name,
+            ApiTokensMsg::ScopeToggled(scope) => {
+                if self.scopes.contains(&scope) {
+                    self.scopes.retain(|granted| *granted != scope);
+                } else {
+                    self.scopes.push(scope);
+                }
+            }
+            ApiTokensMsg::LifetimeChanged(expires_in_days) => {
+                self.expires_in_days = expires_in_days
+            }
+            ApiTokensMsg::CreateSubmitted => {
+                self.created = None;
+                match NewApiTokenRequest::new(
+                    self.name.clone(),
+                    self.scopes.clone(),
+                    self.expires_in_days,
+                ) {
+                    Ok(request) => {
+                        self.error = None;
+                        self.create_token(ctx, request);
+                    }
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            ApiTokensMsg::ReceivedCreated(response) => match response {
+                Ok(created) => {
+                    self.name.clear();
+                    self.error = None;
+                    self.created = Some(created.token);
+                    if let Some(Ok(tokens)) = &mut self.tokens {
+                        tokens.insert(0, created.info);
+                    }
+                }
+                Err(err) => self.error = Some(err.to_string()),
+            },
+            ApiTokensMsg::Revoke(token) => self.revoke_token(ctx, token),
+            ApiTokensMsg::ReceivedRevokeResponse(response) => match response {
+                Ok(id) => {
+                    self.error = None;
+                    if let Some(Ok(tokens)) = &mut self.tokens {
+                        tokens.retain(|token| token.id != id);
+                    }
+                }
+                Err(err) => self.error = Some(err.to_string()),
+            },
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div>
+                <h2>{ "API tokens" }</h2>
+                <p>{ "scripts can send a token as an \"Authorization: Bearer\" header in place of logging in" }</p>
+                {
+                    if let Some(err) = &self.error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                {
+                    if let Some(token) = &self.created {
+                        html! {
+                            <div>
+                                <p>{ "copy this token now, it will not be shown again" }</p>
+                                <p><code>{ token }</code></p>
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                { self.view_tokens(ctx) }
+                { self.view_create_form(ctx) }
+            </div>
+        }
+    }
+}
+
+impl ApiTokens {
+    fn view_tokens(&self, ctx: &Context<Self>) -> Html {
+        match &self.tokens {
+            Some(Ok(tokens)) if tokens.is_empty() => html! { <p>{ "no tokens" }</p> },
+            Some(Ok(tokens)) => html! {
+                <table>
+                    <tr>
+                        <th>{ "name" }</th>
+                        <th>{ "scopes" }</th>
+                        <th>{ "created" }</th>
+                        <th>{ "last used" }</th>
+                        <th>{ "expires" }</th>
+                        <th></th>
+                    </tr>
+                    {
+                        tokens.iter().map(|token| {
+                            let onclick = {
+                                let token = token.clone();
+                                ctx.link().callback(move |_| ApiTokensMsg::Revoke(token.clone()))
+                            };
+                            let scopes = token
+                                .scopes
+                                .iter()
+                                .map(ApiScope::as_str)
+                                .collect::<Vec<_>>()
+                                .join(", ");
+
+                            html! {
+                                <tr key={ token.id }>
+                                    <td>{ &token.name }</td>
+                                    <td>{ scopes }</td>
+                                    <td>{ format_time(token.created) }</td>
+                                    <td>{ token.last_used.map(format_time).unwrap_or_else(|| "never".into()) }</td>
+                                    <td>{ token.expires.map(format_time).unwrap_or_else(|| "never".into()) }</td>
+                                    <td><button {onclick}>{ "revoke" }</button></td>
+                                </tr>
+                            }
+                        }).collect::<Html>()
+                    }
+                </table>
+            },
+            Some(Err(err)) => html! { <p>{ err }</p> },
+            None => html! { <Loading /> },
+        }
+    }
+
+    fn view_create_form(&self, ctx: &Context<Self>) -> Html {
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            ApiTokensMsg::CreateSubmitted
+        });
+        let onchange_name = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| ApiTokensMsg::NameChanged(input.value()))
+        });
+        let onchange_lifetime = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let select = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok());
+            select.map(|select| ApiTokensMsg::LifetimeChanged(select.value().parse().ok()))
+        });
+
+        html! {
+            <form {onsubmit}>
+                <div>
+                    <label for="api-token-name">{ "name:" }</label>
+                    <input id="api-token-name" type="text" placeholder="nightly import"
+                        value={self.name.clone()} onchange={onchange_name} required=true/>
+                </div>
+                <fieldset>
+                    <legend>{ "scopes:" }</legend>
+                    {
+                        ApiScope::ALL.iter().map(|scope| {
+                            let scope = *scope;
+                            let onclick = ctx.link().callback(move |_| ApiTokensMsg::ScopeToggled(scope));
+                            let id = format!("api-token-scope-{}", scope.as_str());
+
+                            html! {
+                                <div key={ scope.as_str() }>
+                                    <input type="checkbox" id={id.clone()} checked={self.scopes.contains(&scope)} {onclick}/>
+                                    <label for={id}>{ scope.as_str() }</label>
+                                </div>
+                            }
+                        }).collect::<Html>()
+                    }
+                </fieldset>
+                <div>
+                    <label for="api-token-lifetime">{ "expires after:" }</label>
+                    <select id="api-token-lifetime" onchange={onchange_lifetime}>
+                        {
+                            LIFETIMES.iter().map(|(days, label)| {
+                                let value = days.map(|days| days.to_string()).unwrap_or_default();
+                                html! {
+                                    <option {value} selected={*days == self.expires_in_days}>{ label }</option>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </select>
+                </div>
+                <input type="submit" value="create token"/>
+            </form>
+        }
+    }
+
+    fn path(&self, ctx: &Context<Self>) -> String {
+        fully_qualified_path(
+            &replace_pattern(
+                routes::API_TOKENS,
+                path_patterns::API_TOKENS,
+                &ctx.props().username,
+            )
+            .expect("could not replace pattern in route"),
+        )
+        .expect("could not create path")
+    }
+
+    fn get_tokens(&self, ctx: &Context<Self>) {
+        let request = self.client.get(self.path(ctx));
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<Vec<ApiTokenInfo>>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError(
+                            "API tokens response",
+                            err.to_string(),
+                        )
+                    })
+                })
+            }));
+            let response = Requester::default().make(request, on_ok).await;
+
+            scope.send_message(ApiTokensMsg::ReceivedTokens(response));
+        });
+    }
+
+    fn create_token(&self, ctx: &Context<Self>, form: NewApiTokenRequest) {
+        let request = self.client.post(self.path(ctx)).json(&form);
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<CreatedApiToken>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError(
+                            "API token response",
+                            err.to_string(),
+                        )
+                    })
+                })
+            }));
+            let response = Requester::default().make(request, on_ok).await;
+
+            scope.send_message(ApiTokensMsg::ReceivedCreated(response));
+        });
+    }
+
+    fn revoke_token(&self, ctx: &Context<Self>, token: ApiTokenInfo) {
+        let request = self.client.delete(self.path(ctx)).json(&token);
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let on_ok = ResponseAction::from(|_| Ok(()));
+            let response = Requester::default()
+                .make(request, on_ok)
+                .await
+                .map(|_| token.id);
+
+            scope.send_message(ApiTokensMsg::ReceivedRevokeResponse(response));
+        });
+    }
+}
+
+fn format_time(time: NaiveDateTime) -> String {
+    DateTime::<Utc>::from_utc(time, Utc)
+        .with_timezone(&Local)
+        .format(DATETIME_FORMAT)
+        .to_string()
+}
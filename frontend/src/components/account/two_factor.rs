@@ -0,0 +1,306 @@
+use reqwest::{Client, Method};
+use serde::de::DeserializeOwned;
+use shared::models::{TotpCodeForm, TotpEnrollment, TotpStatus};
+use shared::routes;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::requests::{fully_qualified_path, Requester, ResponseAction};
+use crate::{InternalResponseError, ResponseResult};
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct TwoFactorProps {
+    pub username: String,
+}
+
+pub enum TwoFactorMsg {
+    ReceivedStatus(ResponseResult<TotpStatus>),
+    EnrollRequested,
+    ReceivedEnrollment(ResponseResult<TotpEnrollment>),
+    CodeChanged(String),
+    ConfirmSubmitted,
+    RecoveryCodesRequested,
+    ReceivedRecoveryCodes(ResponseResult<Vec<String>>),
+    DisableSubmitted,
+    ReceivedDisableResponse(ResponseResult<()>),
+}
+
+/// Turning two factor authentication on and off from the account page:
+/// enrolling an authenticator app from a QR code, and showing recovery codes
+/// the one time they are available.
+pub struct TwoFactor {
+    client: Client,
+    status: Option<ResponseResult<TotpStatus>>,
+    enrollment: Option<TotpEnrollment>,
+    code: String,
+    recovery_codes: Option<Vec<String>>,
+    error: Option<InternalResponseError>,
+}
+
+impl Component for TwoFactor {
+    type Message = TwoFactorMsg;
+    type Properties = TwoFactorProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let two_factor = TwoFactor {
+            client: Client::new(),
+            status: None,
+            enrollment: None,
+            code: String::new(),
+            recovery_codes: None,
+            error: None,
+        };
+        two_factor.get_status(ctx);
+        two_factor
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            TwoFactorMsg::ReceivedStatus(status) => self.status = Some(status),
+            TwoFactorMsg::EnrollRequested => {
+                self.error = None;
+                self.recovery_codes = None;
+                self.send_json(
+                    ctx,
+                    routes::TOTP,
+                    Method::POST,
+                    None,
+                    TwoFactorMsg::ReceivedEnrollment,
+                );
+            }
+            TwoFactorMsg::ReceivedEnrollment(response) => match response {
+                Ok(enrollment) => self.enrollment = Some(enrollment),
+                Err(err) => self.error = Some(err),
+            },
+            TwoFactorMsg::CodeChanged(code) => self.code = code,
+            TwoFactorMsg::ConfirmSubmitted => {
+                self.send_json(
+                    ctx,
+                    routes::TOTP,
+                    Method::PUT,
+                    Some(self.code_form()),
+                    TwoFactorMsg::ReceivedRecoveryCodes,
+                );
+            }
+            TwoFactorMsg::RecoveryCodesRequested => {
+                self.send_json(
+                    ctx,
+                    routes::TOTP_RECOVERY_CODES,
+                    Method::POST,
+                    Some(self.code_form()),
+                    TwoFactorMsg::ReceivedRecoveryCodes,
+                );
+            }
+            TwoFactorMsg::ReceivedRecoveryCodes(response) => {
+                self.code.clear();
+                match response {
+                    Ok(codes) => {
+                        self.enrollment = None;
+                        self.error = None;
+                        self.recovery_codes = Some(codes);
+                        self.get_status(ctx);
+                    }
+                    Err(err) => self.error = Some(err),
+                }
+            }
+            TwoFactorMsg::DisableSubmitted => {
+                let request = self
+                    .client
+                    .delete(self.path(routes::TOTP))
+                    .query(&[("username", &ctx.props().username)])
+                    .json(&self.code_form());
+                let scope = ctx.link().clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let on_ok = ResponseAction::from(|_| Ok(()));
+                    let response = Requester::default().make(request, on_ok).await;
+
+                    scope.send_message(TwoFactorMsg::ReceivedDisableResponse(response));
+                });
+            }
+            TwoFactorMsg::ReceivedDisableResponse(response) => {
+                self.code.clear();
+                match response {
+                    Ok(()) => {
+                        self.error = None;
+                        self.recovery_codes = None;
+                        self.get_status(ctx);
+                    }
+                    Err(err) => self.error = Some(err),
+                }
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div>
+                <h2>{ "two factor authentication" }</h2>
+                {
+                    if let Some(err) = &self.error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                { self.view_recovery_codes() }
+                {
+                    match (&self.status, &self.enrollment) {
+                        (_, Some(enrollment)) => self.view_enrollment(ctx, enrollment),
+                        (Some(Ok(status)), None) if status.enabled => self.view_enabled(ctx, status),
+                        (Some(Ok(_)), None) => {
+                            let onclick = ctx.link().callback(|_| TwoFactorMsg::EnrollRequested);
+                            html! {
+                                <>
+                                    <p>{ "off - logging in only takes a password" }</p>
+                                    <button {onclick}>{ "set up an authenticator app" }</button>
+                                </>
+                            }
+                        }
+                        (Some(Err(err)), None) => html! { <p>{ err }</p> },
+                        (None, None) => html! {},
+                    }
+                }
+            </div>
+        }
+    }
+}
+
+impl TwoFactor {
+    fn view_enrollment(&self, ctx: &Context<Self>, enrollment: &TotpEnrollment) -> Html {
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            TwoFactorMsg::ConfirmSubmitted
+        });
+
+        html! {
+            <>
+                <p>{ "scan this code with your authenticator app, or enter the key by hand" }</p>
+                {
+                    match &enrollment.qr_code {
+                        Some(qr_code) => html! {
+                            <img src={qr_code.clone()} alt="authenticator setup QR code" />
+                        },
+                        None => html! {},
+                    }
+                }
+                <p><code>{ &enrollment.secret }</code></p>
+                <form {onsubmit}>
+                    { self.view_code_input(ctx) }
+                    <input type="submit" value="turn on"/>
+                </form>
+            </>
+        }
+    }
+
+    fn view_enabled(&self, ctx: &Context<Self>, status: &TotpStatus) -> Html {
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            TwoFactorMsg::DisableSubmitted
+        });
+        let onclick_recovery_codes = ctx.link().callback(|ev: MouseEvent| {
+            ev.prevent_default();
+            TwoFactorMsg::RecoveryCodesRequested
+        });
+
+        html! {
+            <>
+                <p>{ format!("on - {} recovery codes left", status.recovery_codes_left) }</p>
+                <form {onsubmit}>
+                    { self.view_code_input(ctx) }
+                    <button onclick={onclick_recovery_codes}>{ "new recovery codes" }</button>
+                    <input type="submit" value="turn off"/>
+                </form>
+            </>
+        }
+    }
+
+    fn view_code_input(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| TwoFactorMsg::CodeChanged(input.value()))
+        });
+
+        html! {
+            <div>
+                <label for="totp-code">{ "code:" }</label>
+                <input id="totp-code" type="text" autocomplete="one-time-code" placeholder="123456"
+                    value={self.code.clone()} {onchange} required=true/>
+            </div>
+        }
+    }
+
+    fn view_recovery_codes(&self) -> Html {
+        match &self.recovery_codes {
+            Some(codes) => html! {
+                <div>
+                    <p>{ "save these recovery codes somewhere safe. each logs you in once without your authenticator, and they will not be shown again" }</p>
+                    <ul>
+                        { for codes.iter().map(|code| html! { <li><code>{ code }</code></li> }) }
+                    </ul>
+                </div>
+            },
+            None => html! {},
+        }
+    }
+
+    fn code_form(&self) -> TotpCodeForm {
+        TotpCodeForm::new(self.code.clone())
+    }
+
+    fn path(&self, route: &str) -> String {
+        fully_qualified_path(route).expect("could not create path")
+    }
+
+    fn get_status(&self, ctx: &Context<Self>) {
+        self.send_json(
+            ctx,
+            routes::TOTP,
+            Method::GET,
+            None,
+            TwoFactorMsg::ReceivedStatus,
+        );
+    }
+
+    /// Send a request for `ctx`'s user to `route`, parsing the JSON response
+    /// into a message with `msg`.
+    fn send_json<T, M>(
+        &self,
+        ctx: &Context<Self>,
+        route: &str,
+        method: Method,
+        form: Option<TotpCodeForm>,
+        msg: M,
+    ) where
+        T: DeserializeOwned + 'static,
+        M: Fn(ResponseResult<T>) -> TwoFactorMsg + 'static,
+    {
+        let request = self
+            .client
+            .request(method, self.path(route))
+            .query(&[("username", &ctx.props().username)]);
+        let request = match form {
+            Some(form) => request.json(&form),
+            None => request,
+        };
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<T>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError(
+                            "two factor response",
+                            err.to_string(),
+                        )
+                    })
+                })
+            }));
+            let response = Requester::default().make(request, on_ok).await;
+
+            scope.send_message(msg(response));
+        });
+    }
+}
@@ -52,6 +52,8 @@ impl Component for Header {
                             if self.app_context.borrow_mut().is_logged_in() {
                                 html! {
                                     <>
+                                        <Link<Route> to={Route::Import}>{ "import" }</Link<Route>>
+                                        <Link<Route> to={Route::Rules}>{ "rules" }</Link<Route>>
                                         <Link<Route> to={Route::Account}>{ "account" }</Link<Route>>
                                         <Link<Route> to={Route::Logout}>{ "logout" }</Link<Route>>
                                     </>
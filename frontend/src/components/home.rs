@@ -1,7 +1,27 @@
+pub mod debt_planner;
+pub mod goals;
+pub mod investment_accounts;
+pub mod ledger_import;
+pub mod loan_generator;
+pub mod net_worth;
 pub mod new_delta;
 pub mod new_prediction;
+pub mod paycheck_generator;
 pub mod prediction_panel;
+pub mod recurring_deltas;
+pub mod retirement_planner;
+pub mod tax_estimator;
 
+pub use debt_planner::DebtPlanner;
+pub use goals::Goals;
+pub use investment_accounts::InvestmentAccounts;
+pub use ledger_import::LedgerImport;
+pub use loan_generator::LoanGenerator;
+pub use net_worth::NetWorth;
 pub use new_delta::NewDelta;
 pub use new_prediction::NewPrediction;
+pub use paycheck_generator::PaycheckGenerator;
 pub use prediction_panel::PredictionPanel;
+pub use recurring_deltas::RecurringDeltas;
+pub use retirement_planner::RetirementPlanner;
+pub use tax_estimator::TaxEstimator;
@@ -0,0 +1,412 @@
+use chrono::{Local, NaiveDate};
+use reqwest::Client;
+use shared::{
+    generators::debt::{
+        Debt, DebtPayoffComparison, DebtPayoffPlan, DebtPayoffRequest, DebtPayoffSummary,
+        PayoffStrategy,
+    },
+    path_patterns, routes,
+};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::AppContext,
+    requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction},
+    InternalResponseError, ResponseResult,
+};
+
+const DATE_FMT: &str = "%x";
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct DebtPlannerProps {
+    pub oncreate: Callback<()>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum DebtField {
+    Name,
+    Balance,
+    Rate,
+    MinimumPayment,
+}
+
+pub enum DebtPlannerMsg {
+    Open(bool),
+    NameChanged(String),
+    BudgetChanged(String),
+    FirstPaymentChanged(String),
+    DebtAdded,
+    DebtRemoved(usize),
+    DebtChanged(usize, DebtField, String),
+    PreviewRequested,
+    ReceivedPreview(ResponseResult<DebtPayoffComparison>),
+    CreateRequested(PayoffStrategy),
+    ReceivedCreateResponse(ResponseResult<DebtPayoffSummary>),
+}
+
+/// Compares paying several debts off smallest-balance first (snowball) and
+/// highest-rate first (avalanche), and creates a scenario prediction from
+/// either.
+pub struct DebtPlanner {
+    app_context: AppContext,
+    open: bool,
+    request: DebtPayoffRequest,
+    form_error: Option<String>,
+    preview: Option<ResponseResult<DebtPayoffComparison>>,
+    summary: Option<ResponseResult<DebtPayoffSummary>>,
+}
+
+fn empty_debt() -> Debt {
+    Debt {
+        name: String::new(),
+        balance: 0.,
+        annual_rate: 0.,
+        minimum_payment: 0.,
+    }
+}
+
+impl Component for DebtPlanner {
+    type Message = DebtPlannerMsg;
+    type Properties = DebtPlannerProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (app_context, _) = ctx
+            .link()
+            .context(Callback::noop())
+            .expect("no AppContext provided");
+
+        DebtPlanner {
+            app_context,
+            open: false,
+            request: DebtPayoffRequest {
+                name: String::new(),
+                debts: vec![empty_debt()],
+                monthly_budget: 0.,
+                first_payment_on: Local::now().naive_utc().date(),
+                strategy: PayoffStrategy::Avalanche,
+            },
+            form_error: None,
+            preview: None,
+            summary: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            DebtPlannerMsg::Open(open) => {
+                self.open = open;
+                self.form_error = None;
+                self.preview = None;
+                self.summary = None;
+            }
+            DebtPlannerMsg::NameChanged(name) => self.request.name = name,
+            DebtPlannerMsg::BudgetChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.request.monthly_budget = value;
+                    self.form_error = None;
+                    self.preview = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid monthly budget ({})", value)),
+            },
+            DebtPlannerMsg::FirstPaymentChanged(date) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(date) => {
+                        self.request.first_payment_on = date;
+                        self.form_error = None;
+                        self.preview = None;
+                    }
+                    Err(_) => {
+                        self.form_error = Some(format!("invalid first payment date ({})", date))
+                    }
+                }
+            }
+            DebtPlannerMsg::DebtAdded => self.request.debts.push(empty_debt()),
+            DebtPlannerMsg::DebtRemoved(index) => {
+                if index < self.request.debts.len() {
+                    self.request.debts.remove(index);
+                    self.preview = None;
+                }
+            }
+            DebtPlannerMsg::DebtChanged(index, field, value) => {
+                if let Some(debt) = self.request.debts.get_mut(index) {
+                    let parsed = match field {
+                        DebtField::Name => {
+                            debt.name = value.clone();
+                            Ok(())
+                        }
+                        DebtField::Balance => value.parse().map(|v| debt.balance = v),
+                        DebtField::Rate => value.parse().map(|v| debt.annual_rate = v),
+                        DebtField::MinimumPayment => {
+                            value.parse().map(|v| debt.minimum_payment = v)
+                        }
+                    };
+
+                    match parsed {
+                        Ok(_) => {
+                            self.form_error = None;
+                            self.preview = None;
+                        }
+                        Err(_) => self.form_error = Some(format!("invalid amount ({})", value)),
+                    }
+                }
+            }
+            DebtPlannerMsg::PreviewRequested => {
+                if let Err(err) = self.request.validate() {
+                    self.form_error = Some(err.to_string());
+                } else {
+                    self.post_preview_if_logged_in(ctx);
+                }
+            }
+            DebtPlannerMsg::ReceivedPreview(response) => self.preview = Some(response),
+            DebtPlannerMsg::CreateRequested(strategy) => {
+                if self.request.name.trim().is_empty() {
+                    self.form_error = Some("the scenario needs a name".into());
+                } else {
+                    self.request.strategy = strategy;
+                    self.put_plan_if_logged_in(ctx);
+                }
+            }
+            DebtPlannerMsg::ReceivedCreateResponse(response) => {
+                if response.is_ok() {
+                    self.preview = None;
+                    ctx.props().oncreate.emit(());
+                }
+                self.summary = Some(response);
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if self.open {
+            self.view_open(ctx)
+        } else {
+            let onclick = ctx.link().callback(|_| DebtPlannerMsg::Open(true));
+
+            html! {
+                <div>
+                    <button {onclick}>{ "plan debt payoff" }</button>
+                </div>
+            }
+        }
+    }
+}
+
+fn input_callback(
+    ctx: &Context<DebtPlanner>,
+    msg: fn(String) -> DebtPlannerMsg,
+) -> Callback<Event> {
+    ctx.link().batch_callback(move |ev: Event| {
+        let target = ev.target();
+        let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+        input.map(|input| msg(input.value()))
+    })
+}
+
+fn debt_callback(ctx: &Context<DebtPlanner>, index: usize, field: DebtField) -> Callback<Event> {
+    ctx.link().batch_callback(move |ev: Event| {
+        let target = ev.target();
+        let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+        input.map(|input| DebtPlannerMsg::DebtChanged(index, field, input.value()))
+    })
+}
+
+// Sub-views
+impl DebtPlanner {
+    fn view_open(&self, ctx: &Context<Self>) -> Html {
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            DebtPlannerMsg::PreviewRequested
+        });
+        let onadd = ctx.link().callback(|_| DebtPlannerMsg::DebtAdded);
+        let oncancel = ctx.link().callback(|_| DebtPlannerMsg::Open(false));
+
+        html! {
+            <div>
+                <h3>{ "plan debt payoff" }</h3>
+                {
+                    if let Some(err) = &self.form_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <form {onsubmit}>
+                    <table>
+                        <tr>
+                            <th>{ "debt" }</th>
+                            <th>{ "balance" }</th>
+                            <th>{ "annual rate (%)" }</th>
+                            <th>{ "minimum payment" }</th>
+                            <th></th>
+                        </tr>
+                        {
+                            self.request.debts.iter().enumerate().map(|(i, debt)| {
+                                let onremove = ctx.link().callback(move |_| DebtPlannerMsg::DebtRemoved(i));
+
+                                html! {
+                                    <tr>
+                                        <td><input type="text" required=true value={debt.name.clone()} onchange={debt_callback(ctx, i, DebtField::Name)}/></td>
+                                        <td><input type="number" step="0.01" value={debt.balance.to_string()} onchange={debt_callback(ctx, i, DebtField::Balance)}/></td>
+                                        <td><input type="number" step="0.001" value={debt.annual_rate.to_string()} onchange={debt_callback(ctx, i, DebtField::Rate)}/></td>
+                                        <td><input type="number" step="0.01" value={debt.minimum_payment.to_string()} onchange={debt_callback(ctx, i, DebtField::MinimumPayment)}/></td>
+                                        <td><i class="fa fa-trash" aria-hidden="true" onclick={onremove}></i></td>
+                                    </tr>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </table>
+                    <input type="button" value="add debt" onclick={onadd}/>
+                    <div>
+                        <label for="debt-budget">{ "monthly budget for debts:" }</label>
+                        <input type="number" id="debt-budget" step="0.01" value={self.request.monthly_budget.to_string()} onchange={input_callback(ctx, DebtPlannerMsg::BudgetChanged)}/>
+                    </div>
+                    <div>
+                        <label for="debt-first">{ "first payment on:" }</label>
+                        <input type="date" id="debt-first" value={self.request.first_payment_on.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, DebtPlannerMsg::FirstPaymentChanged)}/>
+                    </div>
+                    <div>
+                        <label for="debt-name">{ "scenario name:" }</label>
+                        <input type="text" id="debt-name" value={self.request.name.clone()} onchange={input_callback(ctx, DebtPlannerMsg::NameChanged)}/>
+                    </div>
+                    <input type="submit" value="compare strategies"/>
+                    <input type="button" value="close" onclick={oncancel}/>
+                </form>
+                { self.view_preview(ctx) }
+                { self.view_summary() }
+            </div>
+        }
+    }
+
+    fn view_preview(&self, ctx: &Context<Self>) -> Html {
+        match &self.preview {
+            Some(Ok(comparison)) => html! {
+                <div>
+                    { view_plan(ctx, &comparison.snowball) }
+                    { view_plan(ctx, &comparison.avalanche) }
+                </div>
+            },
+            Some(Err(err)) => html! { <p>{ format!("error planning debt payoff: {}", err) }</p> },
+            None => html! {},
+        }
+    }
+
+    fn view_summary(&self) -> Html {
+        match &self.summary {
+            Some(Ok(summary)) => html! {
+                <p>{ format!("created a {} scenario prediction", summary.plan.strategy.name()) }</p>
+            },
+            Some(Err(err)) => html! { <p>{ format!("error creating scenario: {}", err) }</p> },
+            None => html! {},
+        }
+    }
+}
+
+fn view_plan(ctx: &Context<DebtPlanner>, plan: &DebtPayoffPlan) -> Html {
+    let strategy = plan.strategy;
+    let onclick = ctx
+        .link()
+        .callback(move |_| DebtPlannerMsg::CreateRequested(strategy));
+
+    html! {
+        <div>
+            <h4>{ strategy.name() }</h4>
+            <p>
+                { format!(
+                    "debt free on {} after ${:.2} of interest",
+                    plan.payoff_on.format(DATE_FMT),
+                    plan.total_interest,
+                ) }
+            </p>
+            <table>
+                <tr>
+                    <th>{ "debt" }</th>
+                    <th>{ "paid off on" }</th>
+                    <th>{ "interest" }</th>
+                </tr>
+                {
+                    plan.debts.iter().map(|payoff| html! {
+                        <tr>
+                            <td>{ payoff.name.clone() }</td>
+                            <td>{ payoff.payoff_on.format(DATE_FMT).to_string() }</td>
+                            <td>{ format!("{:.2}", payoff.total_interest) }</td>
+                        </tr>
+                    }).collect::<Html>()
+                }
+            </table>
+            <button {onclick}>{ format!("create {} scenario", strategy.name()) }</button>
+        </div>
+    }
+}
+
+// Request functions
+impl DebtPlanner {
+    fn path(username: &str) -> String {
+        fully_qualified_path(
+            &replace_pattern(
+                routes::GENERATE_DEBT_PAYOFF,
+                path_patterns::GENERATE_DEBT_PAYOFF,
+                username,
+            )
+            .expect("could not replace pattern"),
+        )
+        .expect("could not create fully qualified path")
+    }
+
+    fn post_preview_if_logged_in(&self, ctx: &Context<Self>) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            let path = Self::path(username);
+            let request = self.request.clone();
+
+            let scope = ctx.link().clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let request = Client::new().post(path).json(&request);
+                let on_ok = ResponseAction::new(Box::new(|response| {
+                    Box::pin(async {
+                        response
+                            .json::<DebtPayoffComparison>()
+                            .await
+                            .map_err(|err| {
+                                InternalResponseError::ResponseAwaitError(
+                                    "debt payoff comparison",
+                                    err.to_string(),
+                                )
+                            })
+                    })
+                }));
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(DebtPlannerMsg::ReceivedPreview(response));
+            })
+        }
+    }
+
+    fn put_plan_if_logged_in(&self, ctx: &Context<Self>) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            let path = Self::path(username);
+            let request = self.request.clone();
+
+            let scope = ctx.link().clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let request = Client::new().put(path).json(&request);
+                let on_ok = ResponseAction::new(Box::new(|response| {
+                    Box::pin(async {
+                        response.json::<DebtPayoffSummary>().await.map_err(|err| {
+                            InternalResponseError::ResponseAwaitError(
+                                "debt payoff summary",
+                                err.to_string(),
+                            )
+                        })
+                    })
+                }));
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(DebtPlannerMsg::ReceivedCreateResponse(response));
+            })
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use chrono::{Local, NaiveDate};
+use reqwest::Client;
+use shared::{
+    models::{Goal, GoalQuery, NewGoal, PredictionWithDeltas},
+    path_patterns, routes,
+};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::AppContext,
+    requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction},
+    InternalResponseError, ResponseResult,
+};
+
+const DATE_FMT: &str = "%x";
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct GoalsProps {
+    pub prediction: PredictionWithDeltas,
+}
+
+pub enum GoalsMsg {
+    ReceivedGoals(ResponseResult<Vec<Goal>>),
+    Open(bool),
+    NameChanged(String),
+    TargetBalanceChanged(String),
+    TargetDateChanged(String),
+    StartingBalanceChanged(String),
+    StartOnChanged(String),
+    Submitted,
+    ReceivedCreateResponse(ResponseResult<()>),
+    DeleteRequested(usize),
+    ReceivedDeleteResponse(ResponseResult<()>),
+}
+
+/// A prediction's savings goals, with when each is reached and what it would
+/// take to reach it on time.
+pub struct Goals {
+    app_context: AppContext,
+    goals: Option<ResponseResult<Vec<Goal>>>,
+    open: bool,
+    name: String,
+    target_balance: Option<f32>,
+    target_date: Option<NaiveDate>,
+    starting_balance: f32,
+    start_on: NaiveDate,
+    form_error: Option<String>,
+    response_error: Option<InternalResponseError>,
+}
+
+impl Component for Goals {
+    type Message = GoalsMsg;
+    type Properties = GoalsProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (app_context, _) = ctx
+            .link()
+            .context(Callback::noop())
+            .expect("no AppContext provided");
+
+        let goals = Goals {
+            app_context,
+            goals: None,
+            open: false,
+            name: String::new(),
+            target_balance: None,
+            target_date: None,
+            starting_balance: 0.,
+            start_on: Local::now().naive_utc().date(),
+            form_error: None,
+            response_error: None,
+        };
+        goals.get_goals_if_logged_in(ctx);
+        goals
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            GoalsMsg::ReceivedGoals(response) => self.goals = Some(response),
+            GoalsMsg::Open(open) => {
+                self.open = open;
+                self.form_error = None;
+            }
+            GoalsMsg::NameChanged(name) => self.name = name,
+            GoalsMsg::TargetBalanceChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.target_balance = Some(value);
+                    self.form_error = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid target balance ({})", value)),
+            },
+            GoalsMsg::TargetDateChanged(date) => {
+                if date.is_empty() {
+                    self.target_date = None;
+                } else {
+                    match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                        Ok(date) => {
+                            self.target_date = Some(date);
+                            self.form_error = None;
+                        }
+                        Err(_) => self.form_error = Some(format!("invalid target date ({})", date)),
+                    }
+                }
+            }
+            GoalsMsg::StartingBalanceChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.starting_balance = value;
+                    self.form_error = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid starting balance ({})", value)),
+            },
+            GoalsMsg::StartOnChanged(date) => match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                Ok(date) => {
+                    self.start_on = date;
+                    self.form_error = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid start date ({})", date)),
+            },
+            GoalsMsg::Submitted => self.put_goal_if_logged_in(ctx),
+            GoalsMsg::ReceivedCreateResponse(response) => match response {
+                Ok(_) => {
+                    self.open = false;
+                    self.response_error = None;
+                    self.get_goals_if_logged_in(ctx);
+                }
+                Err(err) => self.response_error = Some(err),
+            },
+            GoalsMsg::DeleteRequested(index) => self.delete_goal_if_logged_in(ctx, index),
+            GoalsMsg::ReceivedDeleteResponse(response) => match response {
+                Ok(_) => {
+                    self.response_error = None;
+                    self.get_goals_if_logged_in(ctx);
+                }
+                Err(err) => self.response_error = Some(err),
+            },
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div>
+                <h3>{ "goals" }</h3>
+                {
+                    if let Some(err) = &self.response_error {
+                        html! { <p>{ format!("error updating goals: {}", err) }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                { self.view_goals(ctx) }
+                {
+                    if self.open {
+                        self.view_form(ctx)
+                    } else {
+                        let onclick = ctx.link().callback(|_| GoalsMsg::Open(true));
+                        html! { <button {onclick}>{ "new goal" }</button> }
+                    }
+                }
+            </div>
+        }
+    }
+}
+
+fn input_callback(ctx: &Context<Goals>, msg: fn(String) -> GoalsMsg) -> Callback<Event> {
+    ctx.link().batch_callback(move |ev: Event| {
+        let target = ev.target();
+        let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+        input.map(|input| msg(input.value()))
+    })
+}
+
+fn format_date(date: Option<NaiveDate>, none: &str) -> String {
+    match date {
+        Some(date) => date.format(DATE_FMT).to_string(),
+        None => none.to_string(),
+    }
+}
+
+fn format_amount(amount: Option<f32>) -> String {
+    match amount {
+        Some(amount) => format!("${:.2}", amount),
+        None => "-".to_string(),
+    }
+}
+
+// Sub-views
+impl Goals {
+    fn view_goals(&self, ctx: &Context<Self>) -> Html {
+        match &self.goals {
+            Some(Ok(goals)) if goals.is_empty() => html! {},
+            Some(Ok(goals)) => html! {
+                <table>
+                    <tr>
+                        <th>{ "name" }</th>
+                        <th>{ "target" }</th>
+                        <th>{ "by" }</th>
+                        <th>{ "reached on" }</th>
+                        <th>{ "shortfall" }</th>
+                        <th>{ "extra per month" }</th>
+                        <th></th>
+                    </tr>
+                    {
+                        goals.iter().enumerate().map(|(i, goal)| {
+                            let progress = goal.progress(ctx.props().prediction.deltas());
+                            let onclick = ctx.link().callback(move |_| GoalsMsg::DeleteRequested(i));
+
+                            html! {
+                                <tr key={ goal.id() }>
+                                    <td>{ goal.name() }</td>
+                                    <td>{ format!("${:.2}", goal.target_balance()) }</td>
+                                    <td>{ format_date(goal.target_date(), "-") }</td>
+                                    <td>{ format_date(progress.reached_on, "not reached") }</td>
+                                    <td>{ format_amount(progress.shortfall) }</td>
+                                    <td>{ format_amount(progress.extra_monthly_contribution) }</td>
+                                    <td><i class="fa fa-trash" aria-hidden="true" {onclick}></i></td>
+                                </tr>
+                            }
+                        }).collect::<Html>()
+                    }
+                </table>
+            },
+            Some(Err(err)) => html! { <p>{ format!("error getting goals: {}", err) }</p> },
+            None => html! {},
+        }
+    }
+
+    fn view_form(&self, ctx: &Context<Self>) -> Html {
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            GoalsMsg::Submitted
+        });
+        let oncancel = ctx.link().callback(|_| GoalsMsg::Open(false));
+
+        html! {
+            <>
+                {
+                    if let Some(err) = &self.form_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <form {onsubmit}>
+                    <div>
+                        <label for="goal-name">{ "name:" }</label>
+                        <input type="text" id="goal-name" required=true onchange={input_callback(ctx, GoalsMsg::NameChanged)}/>
+                    </div>
+                    <div>
+                        <label for="goal-target">{ "target balance:" }</label>
+                        <input type="number" id="goal-target" required=true onchange={input_callback(ctx, GoalsMsg::TargetBalanceChanged)}/>
+                    </div>
+                    <div>
+                        <label for="goal-date">{ "by (optional):" }</label>
+                        <input type="date" id="goal-date" onchange={input_callback(ctx, GoalsMsg::TargetDateChanged)}/>
+                    </div>
+                    <div>
+                        <label for="goal-balance">{ "current balance:" }</label>
+                        <input type="number" id="goal-balance" value={self.starting_balance.to_string()} onchange={input_callback(ctx, GoalsMsg::StartingBalanceChanged)}/>
+                    </div>
+                    <div>
+                        <label for="goal-start">{ "as of:" }</label>
+                        <input type="date" id="goal-start" value={self.start_on.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, GoalsMsg::StartOnChanged)}/>
+                    </div>
+                    <input type="submit" value="create"/>
+                    <input type="button" value="cancel" onclick={oncancel}/>
+                </form>
+            </>
+        }
+    }
+}
+
+// Request functions
+impl Goals {
+    fn path(username: &str) -> String {
+        fully_qualified_path(
+            &replace_pattern(routes::GOALS, path_patterns::GOALS, username)
+                .expect("could not replace pattern"),
+        )
+        .expect("could not create fully qualified path")
+    }
+
+    fn get_goals_if_logged_in(&self, ctx: &Context<Self>) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.get_goals(ctx, username)
+        }
+    }
+
+    fn get_goals(&self, ctx: &Context<Self>, username: &str) {
+        let path = Self::path(username);
+        let query = GoalQuery {
+            prediction_id: ctx.props().prediction.id(),
+        };
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = Client::new().get(path).query(&query);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<Vec<Goal>>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError("goals", err.to_string())
+                    })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(GoalsMsg::ReceivedGoals(response));
+        })
+    }
+
+    fn put_goal_if_logged_in(&mut self, ctx: &Context<Self>) {
+        let target_balance = match self.target_balance {
+            Some(target_balance) => target_balance,
+            None => {
+                self.form_error = Some("goal needs a target balance".into());
+                return;
+            }
+        };
+
+        let goal = match NewGoal::new(
+            ctx.props().prediction.id(),
+            self.name.clone(),
+            target_balance,
+            self.target_date,
+            self.starting_balance,
+            self.start_on,
+        ) {
+            Ok(goal) => goal,
+            Err(err) => {
+                self.form_error = Some(err.to_string());
+                return;
+            }
+        };
+
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            let path = Self::path(username);
+
+            let scope = ctx.link().clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let request = Client::new().put(path).json(&goal);
+                let on_ok = ResponseAction::from(|_| Ok(()));
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(GoalsMsg::ReceivedCreateResponse(response));
+            })
+        }
+    }
+
+    fn delete_goal_if_logged_in(&self, ctx: &Context<Self>, index: usize) {
+        let goal = match &self.goals {
+            Some(Ok(goals)) => match goals.get(index) {
+                Some(goal) => goal.clone(),
+                None => return,
+            },
+            _ => return,
+        };
+
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            let path = Self::path(username);
+
+            let scope = ctx.link().clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let request = Client::new().delete(path).json(&goal);
+                let on_ok = ResponseAction::from(|_| Ok(()));
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(GoalsMsg::ReceivedDeleteResponse(response));
+            })
+        }
+    }
+}
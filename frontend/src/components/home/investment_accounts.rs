@@ -0,0 +1,438 @@
+use chrono::{Local, NaiveDate};
+use reqwest::Client;
+use shared::{
+    models::{
+        InvestmentAccount, InvestmentAccountWithContributions, InvestmentQuery,
+        NewInvestmentAccount, NewInvestmentAccountRequest, PredictionWithDeltas,
+    },
+    path_patterns,
+    projection::growth::DEFAULT_RUNS,
+    routes,
+};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::AppContext,
+    requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction},
+    InternalResponseError, ResponseResult,
+};
+
+const DEFAULT_HORIZON_YEARS: i64 = 10;
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct InvestmentAccountsProps {
+    pub prediction: PredictionWithDeltas,
+}
+
+pub enum InvestmentAccountsMsg {
+    ReceivedAccounts(ResponseResult<Vec<InvestmentAccountWithContributions>>),
+    HorizonChanged(String),
+    Open(bool),
+    NameChanged(String),
+    StartingBalanceChanged(String),
+    StartOnChanged(String),
+    ExpectedReturnChanged(String),
+    VolatilityChanged(String),
+    ContributionToggled(i32),
+    Submitted,
+    ReceivedCreateResponse(ResponseResult<()>),
+    DeleteRequested(usize),
+    ReceivedDeleteResponse(ResponseResult<()>),
+}
+
+/// A prediction's investment accounts, each grown from the deltas paying into
+/// it, with the range of balances it could reach by the horizon.
+pub struct InvestmentAccounts {
+    app_context: AppContext,
+    accounts: Option<ResponseResult<Vec<InvestmentAccountWithContributions>>>,
+    horizon: NaiveDate,
+    open: bool,
+    name: String,
+    starting_balance: f32,
+    start_on: NaiveDate,
+    expected_return: f32,
+    volatility: f32,
+    contribution_delta_ids: Vec<i32>,
+    form_error: Option<String>,
+    response_error: Option<InternalResponseError>,
+}
+
+impl Component for InvestmentAccounts {
+    type Message = InvestmentAccountsMsg;
+    type Properties = InvestmentAccountsProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (app_context, _) = ctx
+            .link()
+            .context(Callback::noop())
+            .expect("no AppContext provided");
+        let today = Local::now().naive_utc().date();
+
+        let accounts = InvestmentAccounts {
+            app_context,
+            accounts: None,
+            horizon: today + chrono::Duration::days(365 * DEFAULT_HORIZON_YEARS),
+            open: false,
+            name: String::new(),
+            starting_balance: 0.,
+            start_on: today,
+            expected_return: 7.,
+            volatility: 15.,
+            contribution_delta_ids: Vec::new(),
+            form_error: None,
+            response_error: None,
+        };
+        accounts.get_accounts_if_logged_in(ctx);
+        accounts
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            InvestmentAccountsMsg::ReceivedAccounts(response) => self.accounts = Some(response),
+            InvestmentAccountsMsg::HorizonChanged(date) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(date) => {
+                        self.horizon = date;
+                        self.form_error = None;
+                    }
+                    Err(_) => self.form_error = Some(format!("invalid horizon ({})", date)),
+                }
+            }
+            InvestmentAccountsMsg::Open(open) => {
+                self.open = open;
+                self.form_error = None;
+                self.contribution_delta_ids.clear();
+            }
+            InvestmentAccountsMsg::NameChanged(name) => self.name = name,
+            InvestmentAccountsMsg::StartingBalanceChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.starting_balance = value;
+                    self.form_error = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid starting balance ({})", value)),
+            },
+            InvestmentAccountsMsg::StartOnChanged(date) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(date) => {
+                        self.start_on = date;
+                        self.form_error = None;
+                    }
+                    Err(_) => self.form_error = Some(format!("invalid start date ({})", date)),
+                }
+            }
+            InvestmentAccountsMsg::ExpectedReturnChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.expected_return = value;
+                    self.form_error = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid expected return ({})", value)),
+            },
+            InvestmentAccountsMsg::VolatilityChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.volatility = value;
+                    self.form_error = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid volatility ({})", value)),
+            },
+            InvestmentAccountsMsg::ContributionToggled(delta_id) => {
+                if let Some(i) = self
+                    .contribution_delta_ids
+                    .iter()
+                    .position(|id| *id == delta_id)
+                {
+                    self.contribution_delta_ids.remove(i);
+                } else {
+                    self.contribution_delta_ids.push(delta_id);
+                }
+            }
+            InvestmentAccountsMsg::Submitted => self.put_account_if_logged_in(ctx),
+            InvestmentAccountsMsg::ReceivedCreateResponse(response) => match response {
+                Ok(_) => {
+                    self.open = false;
+                    self.contribution_delta_ids.clear();
+                    self.response_error = None;
+                    self.get_accounts_if_logged_in(ctx);
+                }
+                Err(err) => self.response_error = Some(err),
+            },
+            InvestmentAccountsMsg::DeleteRequested(index) => {
+                self.delete_account_if_logged_in(ctx, index)
+            }
+            InvestmentAccountsMsg::ReceivedDeleteResponse(response) => match response {
+                Ok(_) => {
+                    self.response_error = None;
+                    self.get_accounts_if_logged_in(ctx);
+                }
+                Err(err) => self.response_error = Some(err),
+            },
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div>
+                <h3>{ "investments" }</h3>
+                {
+                    if let Some(err) = &self.response_error {
+                        html! { <p>{ format!("error updating investment accounts: {}", err) }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                { self.view_accounts(ctx) }
+                {
+                    if self.open {
+                        self.view_form(ctx)
+                    } else {
+                        let onclick = ctx.link().callback(|_| InvestmentAccountsMsg::Open(true));
+                        html! { <button {onclick}>{ "new investment account" }</button> }
+                    }
+                }
+            </div>
+        }
+    }
+}
+
+fn input_callback(
+    ctx: &Context<InvestmentAccounts>,
+    msg: fn(String) -> InvestmentAccountsMsg,
+) -> Callback<Event> {
+    ctx.link().batch_callback(move |ev: Event| {
+        let target = ev.target();
+        let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+        input.map(|input| msg(input.value()))
+    })
+}
+
+// Sub-views
+impl InvestmentAccounts {
+    fn view_accounts(&self, ctx: &Context<Self>) -> Html {
+        match &self.accounts {
+            Some(Ok(accounts)) if accounts.is_empty() => html! {},
+            Some(Ok(accounts)) => html! {
+                <>
+                    <div>
+                        <label for="investment-horizon">{ "projected to:" }</label>
+                        <input type="date" id="investment-horizon" value={self.horizon.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, InvestmentAccountsMsg::HorizonChanged)}/>
+                    </div>
+                    <table>
+                        <tr>
+                            <th>{ "name" }</th>
+                            <th>{ "return" }</th>
+                            <th>{ "volatility" }</th>
+                            <th>{ "contributed" }</th>
+                            <th>{ "expected" }</th>
+                            <th>{ "low (10%)" }</th>
+                            <th>{ "median" }</th>
+                            <th>{ "high (90%)" }</th>
+                            <th></th>
+                        </tr>
+                        {
+                            accounts.iter().enumerate().map(|(i, account)| {
+                                let projection = account.projection(ctx.props().prediction.deltas(), self.horizon, DEFAULT_RUNS);
+                                let onclick = ctx.link().callback(move |_| InvestmentAccountsMsg::DeleteRequested(i));
+                                let account = &account.account;
+
+                                let cells = match projection.points.last() {
+                                    Some(point) => html! {
+                                        <>
+                                            <td>{ format!("${:.2}", point.contributed) }</td>
+                                            <td>{ format!("${:.2}", point.expected) }</td>
+                                            <td>{ format!("${:.2}", point.low) }</td>
+                                            <td>{ format!("${:.2}", point.median) }</td>
+                                            <td>{ format!("${:.2}", point.high) }</td>
+                                        </>
+                                    },
+                                    None => html! { <td colspan="5">{ "starts after the horizon" }</td> },
+                                };
+
+                                html! {
+                                    <tr key={ account.id() }>
+                                        <td>{ account.name() }</td>
+                                        <td>{ format!("{}%", account.expected_return()) }</td>
+                                        <td>{ format!("{}%", account.volatility()) }</td>
+                                        { cells }
+                                        <td><i class="fa fa-trash" aria-hidden="true" {onclick}></i></td>
+                                    </tr>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </table>
+                </>
+            },
+            Some(Err(err)) => {
+                html! { <p>{ format!("error getting investment accounts: {}", err) }</p> }
+            }
+            None => html! {},
+        }
+    }
+
+    fn view_form(&self, ctx: &Context<Self>) -> Html {
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            InvestmentAccountsMsg::Submitted
+        });
+        let oncancel = ctx.link().callback(|_| InvestmentAccountsMsg::Open(false));
+
+        html! {
+            <>
+                {
+                    if let Some(err) = &self.form_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <form {onsubmit}>
+                    <div>
+                        <label for="investment-name">{ "name:" }</label>
+                        <input type="text" id="investment-name" required=true onchange={input_callback(ctx, InvestmentAccountsMsg::NameChanged)}/>
+                    </div>
+                    <div>
+                        <label for="investment-balance">{ "current balance:" }</label>
+                        <input type="number" id="investment-balance" step="0.01" value={self.starting_balance.to_string()} onchange={input_callback(ctx, InvestmentAccountsMsg::StartingBalanceChanged)}/>
+                    </div>
+                    <div>
+                        <label for="investment-start">{ "as of:" }</label>
+                        <input type="date" id="investment-start" value={self.start_on.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, InvestmentAccountsMsg::StartOnChanged)}/>
+                    </div>
+                    <div>
+                        <label for="investment-return">{ "expected annual return (%):" }</label>
+                        <input type="number" id="investment-return" step="0.1" value={self.expected_return.to_string()} onchange={input_callback(ctx, InvestmentAccountsMsg::ExpectedReturnChanged)}/>
+                    </div>
+                    <div>
+                        <label for="investment-volatility">{ "volatility (%):" }</label>
+                        <input type="number" id="investment-volatility" step="0.1" min="0" value={self.volatility.to_string()} onchange={input_callback(ctx, InvestmentAccountsMsg::VolatilityChanged)}/>
+                    </div>
+                    <fieldset>
+                        <legend>{ "paid into by:" }</legend>
+                        {
+                            ctx.props().prediction.deltas().iter().map(|delta| {
+                                let delta_id = delta.id();
+                                let onclick = ctx.link().callback(move |_| InvestmentAccountsMsg::ContributionToggled(delta_id));
+                                let id = format!("investment-delta-{}", delta_id);
+
+                                html! {
+                                    <div key={ delta_id }>
+                                        <input type="checkbox" id={id.clone()} checked={self.contribution_delta_ids.contains(&delta_id)} {onclick}/>
+                                        <label for={id}>{ delta.name() }</label>
+                                    </div>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </fieldset>
+                    <input type="submit" value="create"/>
+                    <input type="button" value="cancel" onclick={oncancel}/>
+                </form>
+            </>
+        }
+    }
+}
+
+// Request functions
+impl InvestmentAccounts {
+    fn path(username: &str) -> String {
+        fully_qualified_path(
+            &replace_pattern(routes::INVESTMENTS, path_patterns::INVESTMENTS, username)
+                .expect("could not replace pattern"),
+        )
+        .expect("could not create fully qualified path")
+    }
+
+    fn get_accounts_if_logged_in(&self, ctx: &Context<Self>) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.get_accounts(ctx, username)
+        }
+    }
+
+    fn get_accounts(&self, ctx: &Context<Self>, username: &str) {
+        let path = Self::path(username);
+        let query = InvestmentQuery {
+            prediction_id: ctx.props().prediction.id(),
+        };
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = Client::new().get(path).query(&query);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response
+                        .json::<Vec<InvestmentAccountWithContributions>>()
+                        .await
+                        .map_err(|err| {
+                            InternalResponseError::ResponseAwaitError(
+                                "investment accounts",
+                                err.to_string(),
+                            )
+                        })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(InvestmentAccountsMsg::ReceivedAccounts(response));
+        })
+    }
+
+    fn put_account_if_logged_in(&mut self, ctx: &Context<Self>) {
+        let account = match NewInvestmentAccount::new(
+            ctx.props().prediction.id(),
+            self.name.clone(),
+            self.starting_balance,
+            self.start_on,
+            self.expected_return,
+            self.volatility,
+        ) {
+            Ok(account) => account,
+            Err(err) => {
+                self.form_error = Some(err.to_string());
+                return;
+            }
+        };
+        let request = NewInvestmentAccountRequest {
+            account,
+            contribution_delta_ids: self.contribution_delta_ids.clone(),
+        };
+
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            let path = Self::path(username);
+
+            let scope = ctx.link().clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let request = Client::new().put(path).json(&request);
+                let on_ok = ResponseAction::from(|_| Ok(()));
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(InvestmentAccountsMsg::ReceivedCreateResponse(response));
+            })
+        }
+    }
+
+    fn delete_account_if_logged_in(&self, ctx: &Context<Self>, index: usize) {
+        let account: InvestmentAccount = match &self.accounts {
+            Some(Ok(accounts)) => match accounts.get(index) {
+                Some(account) => account.account.clone(),
+                None => return,
+            },
+            _ => return,
+        };
+
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            let path = Self::path(username);
+
+            let scope = ctx.link().clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let request = Client::new().delete(path).json(&account);
+                let on_ok = ResponseAction::from(|_| Ok(()));
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(InvestmentAccountsMsg::ReceivedDeleteResponse(response));
+            })
+        }
+    }
+}
@@ -0,0 +1,380 @@
+use chrono::{Duration, Local, NaiveDate};
+use reqwest::Client;
+use shared::{
+    imports::ledger::{LedgerImportPreview, LedgerImportRequest, LedgerImportSummary},
+    path_patterns, routes,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{File, HtmlInputElement};
+use yew::prelude::*;
+
+use crate::{
+    components::AppContext,
+    requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction},
+    InternalResponseError, ResponseResult,
+};
+
+const DATE_FMT: &str = "%x";
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct LedgerImportProps {
+    pub oncreate: Callback<()>,
+}
+
+pub enum LedgerImportMsg {
+    Open(bool),
+    FileSelected(File),
+    FileRead(Option<String>),
+    NameChanged(String),
+    AccountChanged(String),
+    FromChanged(String),
+    ToChanged(String),
+    PreviewRequested,
+    ReceivedPreview(ResponseResult<LedgerImportPreview>),
+    ImportRequested,
+    ReceivedImport(ResponseResult<LedgerImportSummary>),
+}
+
+/// Creates a new prediction from the periodic transactions of a ledger or
+/// hledger journal.
+pub struct LedgerImport {
+    app_context: AppContext,
+    open: bool,
+    request: LedgerImportRequest,
+    form_error: Option<String>,
+    preview: Option<ResponseResult<LedgerImportPreview>>,
+    summary: Option<ResponseResult<LedgerImportSummary>>,
+}
+
+impl Component for LedgerImport {
+    type Message = LedgerImportMsg;
+    type Properties = LedgerImportProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let now = Local::now().naive_utc().date();
+
+        let (app_context, _) = ctx
+            .link()
+            .context(Callback::noop())
+            .expect("no AppContext provided");
+
+        LedgerImport {
+            app_context,
+            open: false,
+            request: LedgerImportRequest {
+                name: String::new(),
+                contents: String::new(),
+                account: None,
+                from: now,
+                to: now + Duration::days(365),
+            },
+            form_error: None,
+            preview: None,
+            summary: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            LedgerImportMsg::Open(open) => {
+                self.open = open;
+                self.preview = None;
+                self.summary = None;
+            }
+            LedgerImportMsg::FileSelected(file) => {
+                let scope = ctx.link().clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let contents = JsFuture::from(file.text())
+                        .await
+                        .ok()
+                        .and_then(|text| text.as_string());
+
+                    scope.send_message(LedgerImportMsg::FileRead(contents));
+                });
+            }
+            LedgerImportMsg::FileRead(contents) => {
+                match contents {
+                    Some(contents) => {
+                        self.request.contents = contents;
+                        self.form_error = None;
+                    }
+                    None => self.form_error = Some("could not read selected file".into()),
+                }
+                self.preview = None;
+                self.summary = None;
+            }
+            LedgerImportMsg::NameChanged(name) => self.request.name = name,
+            LedgerImportMsg::AccountChanged(account) => {
+                self.request.account = Some(account.trim().to_string()).filter(|a| !a.is_empty());
+                self.preview = None;
+            }
+            LedgerImportMsg::FromChanged(date) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(from) => {
+                        self.request.from = from;
+                        self.form_error = None;
+                    }
+                    Err(_) => self.form_error = Some(format!("invalid start date ({})", date)),
+                }
+                self.preview = None;
+            }
+            LedgerImportMsg::ToChanged(date) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(to) => {
+                        self.request.to = to;
+                        self.form_error = None;
+                    }
+                    Err(_) => self.form_error = Some(format!("invalid end date ({})", date)),
+                }
+                self.preview = None;
+            }
+            LedgerImportMsg::PreviewRequested => self.send_if_logged_in(ctx, false),
+            LedgerImportMsg::ReceivedPreview(response) => self.preview = Some(response),
+            LedgerImportMsg::ImportRequested => {
+                if self.request.name.trim().is_empty() {
+                    self.form_error = Some("the new prediction needs a name".into());
+                } else {
+                    self.send_if_logged_in(ctx, true);
+                }
+            }
+            LedgerImportMsg::ReceivedImport(response) => {
+                if response.is_ok() {
+                    self.preview = None;
+                    ctx.props().oncreate.emit(());
+                }
+                self.summary = Some(response);
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if self.open {
+            self.view_open(ctx)
+        } else {
+            let onclick = ctx.link().callback(|_| LedgerImportMsg::Open(true));
+
+            html! {
+                <div>
+                    <button {onclick}>{ "import from ledger" }</button>
+                </div>
+            }
+        }
+    }
+}
+
+fn input_callback(
+    ctx: &Context<LedgerImport>,
+    msg: fn(String) -> LedgerImportMsg,
+) -> Callback<Event> {
+    ctx.link().batch_callback(move |ev: Event| {
+        let target = ev.target();
+        let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+        input.map(|input| msg(input.value()))
+    })
+}
+
+// Sub-views
+impl LedgerImport {
+    fn view_open(&self, ctx: &Context<Self>) -> Html {
+        let onchange_file = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input
+                .and_then(|input| input.files())
+                .and_then(|files| files.get(0))
+                .map(LedgerImportMsg::FileSelected)
+        });
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            LedgerImportMsg::PreviewRequested
+        });
+        let oncancel = ctx.link().callback(|_| LedgerImportMsg::Open(false));
+
+        html! {
+            <div>
+                <h3>{ "import prediction from ledger" }</h3>
+                {
+                    if let Some(err) = &self.form_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <form {onsubmit}>
+                    <div>
+                        <label for="ledger-file">{ "journal:" }</label>
+                        <input type="file" id="ledger-file" accept=".ledger,.journal,.dat,.txt" onchange={onchange_file}/>
+                    </div>
+                    <div>
+                        <label for="ledger-name">{ "prediction name:" }</label>
+                        <input type="text" id="ledger-name" value={self.request.name.clone()} onchange={input_callback(ctx, LedgerImportMsg::NameChanged)}/>
+                    </div>
+                    <div>
+                        <label for="ledger-account">{ "tracked account:" }</label>
+                        <input type="text" id="ledger-account" placeholder="the posting without an amount" onchange={input_callback(ctx, LedgerImportMsg::AccountChanged)}/>
+                    </div>
+                    <div>
+                        <label for="ledger-from">{ "rules without a start begin on:" }</label>
+                        <input type="date" id="ledger-from" value={self.request.from.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, LedgerImportMsg::FromChanged)}/>
+                    </div>
+                    <div>
+                        <label for="ledger-to">{ "rules without an end stop on:" }</label>
+                        <input type="date" id="ledger-to" value={self.request.to.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, LedgerImportMsg::ToChanged)}/>
+                    </div>
+                    <input type="submit" value="preview" disabled={self.request.contents.is_empty()}/>
+                    <input type="button" value="close" onclick={oncancel}/>
+                </form>
+                { self.view_preview(ctx) }
+                { self.view_summary() }
+            </div>
+        }
+    }
+
+    fn view_preview(&self, ctx: &Context<Self>) -> Html {
+        match &self.preview {
+            Some(Ok(preview)) => {
+                let onclick = ctx.link().callback(|_| LedgerImportMsg::ImportRequested);
+
+                html! {
+                    <div>
+                        <table>
+                            <tr>
+                                <th>{ "name" }</th>
+                                <th>{ "value" }</th>
+                                <th>{ "first" }</th>
+                                <th>{ "occurrences" }</th>
+                            </tr>
+                            {
+                                preview.deltas.iter().map(|delta| {
+                                    let dates = delta.repetition.dates();
+
+                                    html! {
+                                        <tr>
+                                            <td>{ delta.name.clone() }</td>
+                                            <td>{ format!("{:.2}", delta.value) }</td>
+                                            <td>{ dates.first().map(|date| date.format(DATE_FMT).to_string()).unwrap_or_default() }</td>
+                                            <td>{ dates.len() }</td>
+                                        </tr>
+                                    }
+                                }).collect::<Html>()
+                            }
+                        </table>
+                        { view_unrepresentable(&preview.unrepresentable) }
+                        <button {onclick} disabled={preview.deltas.is_empty()}>
+                            { format!("create prediction with {} deltas", preview.deltas.len()) }
+                        </button>
+                    </div>
+                }
+            }
+            Some(Err(err)) => html! { <p>{ format!("error reading journal: {}", err) }</p> },
+            None => html! {},
+        }
+    }
+
+    fn view_summary(&self) -> Html {
+        match &self.summary {
+            Some(Ok(summary)) => html! {
+                <div>
+                    <p>{ format!("created a prediction with {} deltas", summary.imported) }</p>
+                    { view_unrepresentable(&summary.unrepresentable) }
+                </div>
+            },
+            Some(Err(err)) => html! { <p>{ format!("error creating prediction: {}", err) }</p> },
+            None => html! {},
+        }
+    }
+}
+
+fn view_unrepresentable(rules: &[shared::imports::ledger::UnrepresentableRule]) -> Html {
+    if rules.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <>
+            <p>{ format!("{} rules could not be represented:", rules.len()) }</p>
+            <ul>
+                {
+                    rules.iter().map(|rule| html! {
+                        <li>{ format!("line {}: {} ({})", rule.line, rule.rule, rule.reason) }</li>
+                    }).collect::<Html>()
+                }
+            </ul>
+        </>
+    }
+}
+
+// Request functions
+impl LedgerImport {
+    fn send_if_logged_in(&self, ctx: &Context<Self>, import: bool) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            if import {
+                self.put_import(ctx, username)
+            } else {
+                self.post_preview(ctx, username)
+            }
+        }
+    }
+
+    fn path(username: &str) -> String {
+        fully_qualified_path(
+            &replace_pattern(
+                routes::IMPORT_LEDGER,
+                path_patterns::IMPORT_LEDGER,
+                username,
+            )
+            .expect("could not replace pattern"),
+        )
+        .expect("could not create fully qualified path")
+    }
+
+    fn post_preview(&self, ctx: &Context<Self>, username: &str) {
+        let path = Self::path(username);
+        let request = self.request.clone();
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = Client::new().post(path).json(&request);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<LedgerImportPreview>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError(
+                            "ledger import preview",
+                            err.to_string(),
+                        )
+                    })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(LedgerImportMsg::ReceivedPreview(response));
+        })
+    }
+
+    fn put_import(&self, ctx: &Context<Self>, username: &str) {
+        let path = Self::path(username);
+        let request = self.request.clone();
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = Client::new().put(path).json(&request);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<LedgerImportSummary>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError(
+                            "ledger import summary",
+                            err.to_string(),
+                        )
+                    })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(LedgerImportMsg::ReceivedImport(response));
+        })
+    }
+}
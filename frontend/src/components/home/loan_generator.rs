@@ -0,0 +1,346 @@
+use chrono::{Local, NaiveDate};
+use reqwest::Client;
+use shared::{
+    generators::loan::{LoanRequest, LoanSchedule},
+    path_patterns, routes,
+};
+use stylist::{css, YieldStyle};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::AppContext,
+    requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction},
+    InternalResponseError, ResponseResult,
+};
+
+const DATE_FMT: &str = "%x";
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct LoanGeneratorProps {
+    pub prediction_id: i32,
+    pub oncreate: Callback<()>,
+}
+
+pub enum LoanGeneratorMsg {
+    Open(bool),
+    NameChanged(String),
+    PrincipalChanged(String),
+    RateChanged(String),
+    TermChanged(String),
+    FirstPaymentChanged(String),
+    ExtraPaymentChanged(String),
+    PreviewRequested,
+    ReceivedPreview(ResponseResult<LoanSchedule>),
+    CreateRequested,
+    ReceivedCreateResponse(ResponseResult<LoanSchedule>),
+}
+
+/// Adds the monthly payments of an amortizing loan to a prediction.
+pub struct LoanGenerator {
+    app_context: AppContext,
+    open: bool,
+    request: LoanRequest,
+    form_error: Option<String>,
+    preview: Option<ResponseResult<LoanSchedule>>,
+    create_error: Option<InternalResponseError>,
+}
+
+impl Component for LoanGenerator {
+    type Message = LoanGeneratorMsg;
+    type Properties = LoanGeneratorProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (app_context, _) = ctx
+            .link()
+            .context(Callback::noop())
+            .expect("no AppContext provided");
+
+        LoanGenerator {
+            app_context,
+            open: false,
+            request: LoanRequest {
+                prediction_id: ctx.props().prediction_id,
+                name: String::new(),
+                principal: 0.,
+                annual_rate: 0.,
+                term_months: 360,
+                first_payment_on: Local::now().naive_utc().date(),
+                extra_monthly_payment: 0.,
+            },
+            form_error: None,
+            preview: None,
+            create_error: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            LoanGeneratorMsg::Open(open) => {
+                self.open = open;
+                self.form_error = None;
+                self.preview = None;
+                self.create_error = None;
+            }
+            LoanGeneratorMsg::NameChanged(name) => self.request.name = name,
+            LoanGeneratorMsg::PrincipalChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.request.principal = value;
+                    self.form_error = None;
+                    self.preview = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid principal ({})", value)),
+            },
+            LoanGeneratorMsg::RateChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.request.annual_rate = value;
+                    self.form_error = None;
+                    self.preview = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid interest rate ({})", value)),
+            },
+            LoanGeneratorMsg::TermChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.request.term_months = value;
+                    self.form_error = None;
+                    self.preview = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid term ({})", value)),
+            },
+            LoanGeneratorMsg::FirstPaymentChanged(date) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(date) => {
+                        self.request.first_payment_on = date;
+                        self.form_error = None;
+                        self.preview = None;
+                    }
+                    Err(_) => {
+                        self.form_error = Some(format!("invalid first payment date ({})", date))
+                    }
+                }
+            }
+            LoanGeneratorMsg::ExtraPaymentChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.request.extra_monthly_payment = value;
+                    self.form_error = None;
+                    self.preview = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid extra payment ({})", value)),
+            },
+            LoanGeneratorMsg::PreviewRequested => {
+                if let Err(err) = self.request.validate() {
+                    self.form_error = Some(err.to_string());
+                } else {
+                    self.send_if_logged_in(ctx, false);
+                }
+            }
+            LoanGeneratorMsg::ReceivedPreview(response) => self.preview = Some(response),
+            LoanGeneratorMsg::CreateRequested => self.send_if_logged_in(ctx, true),
+            LoanGeneratorMsg::ReceivedCreateResponse(response) => match response {
+                Ok(_) => {
+                    self.open = false;
+                    self.preview = None;
+                    self.create_error = None;
+                    ctx.props().oncreate.emit(());
+                }
+                Err(err) => self.create_error = Some(err),
+            },
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if self.open {
+            self.view_open(ctx)
+        } else {
+            let onclick = ctx.link().callback(|_| LoanGeneratorMsg::Open(true));
+
+            html! {
+                <div>
+                    <button {onclick}>{ "add a loan" }</button>
+                </div>
+            }
+        }
+    }
+}
+
+fn input_callback(
+    ctx: &Context<LoanGenerator>,
+    msg: fn(String) -> LoanGeneratorMsg,
+) -> Callback<Event> {
+    ctx.link().batch_callback(move |ev: Event| {
+        let target = ev.target();
+        let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+        input.map(|input| msg(input.value()))
+    })
+}
+
+// Sub-views
+impl LoanGenerator {
+    fn view_open(&self, ctx: &Context<Self>) -> Html {
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            LoanGeneratorMsg::PreviewRequested
+        });
+        let oncancel = ctx.link().callback(|_| LoanGeneratorMsg::Open(false));
+
+        html! {
+            <div>
+                <h3>{ "add a loan" }</h3>
+                {
+                    if let Some(err) = &self.form_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <form {onsubmit}>
+                    <div>
+                        <label for="loan-name">{ "name:" }</label>
+                        <input type="text" id="loan-name" required=true value={self.request.name.clone()} onchange={input_callback(ctx, LoanGeneratorMsg::NameChanged)}/>
+                    </div>
+                    <div>
+                        <label for="loan-principal">{ "amount borrowed:" }</label>
+                        <input type="number" id="loan-principal" step="0.01" required=true onchange={input_callback(ctx, LoanGeneratorMsg::PrincipalChanged)}/>
+                    </div>
+                    <div>
+                        <label for="loan-rate">{ "annual interest rate (%):" }</label>
+                        <input type="number" id="loan-rate" step="0.001" required=true onchange={input_callback(ctx, LoanGeneratorMsg::RateChanged)}/>
+                    </div>
+                    <div>
+                        <label for="loan-term">{ "term (months):" }</label>
+                        <input type="number" id="loan-term" min="1" value={self.request.term_months.to_string()} onchange={input_callback(ctx, LoanGeneratorMsg::TermChanged)}/>
+                    </div>
+                    <div>
+                        <label for="loan-first">{ "first payment on:" }</label>
+                        <input type="date" id="loan-first" value={self.request.first_payment_on.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, LoanGeneratorMsg::FirstPaymentChanged)}/>
+                    </div>
+                    <div>
+                        <label for="loan-extra">{ "extra monthly payment:" }</label>
+                        <input type="number" id="loan-extra" step="0.01" value={self.request.extra_monthly_payment.to_string()} onchange={input_callback(ctx, LoanGeneratorMsg::ExtraPaymentChanged)}/>
+                    </div>
+                    <input type="submit" value="preview"/>
+                    <input type="button" value="cancel" onclick={oncancel}/>
+                </form>
+                { self.view_preview(ctx) }
+            </div>
+        }
+    }
+
+    fn view_preview(&self, ctx: &Context<Self>) -> Html {
+        match &self.preview {
+            Some(Ok(schedule)) => {
+                let onclick = ctx.link().callback(|_| LoanGeneratorMsg::CreateRequested);
+
+                html! {
+                    <div>
+                        <p>
+                            { format!(
+                                "${:.2} per month, paid off on {} after ${:.2} of interest",
+                                schedule.monthly_payment,
+                                schedule.payoff_on.format(DATE_FMT),
+                                schedule.total_interest,
+                            ) }
+                        </p>
+                        <div class={ self.style() }>
+                            <table>
+                                <tr>
+                                    <th>{ "#" }</th>
+                                    <th>{ "date" }</th>
+                                    <th>{ "payment" }</th>
+                                    <th>{ "principal" }</th>
+                                    <th>{ "interest" }</th>
+                                    <th>{ "remaining" }</th>
+                                </tr>
+                                {
+                                    schedule.payments.iter().map(|payment| html! {
+                                        <tr key={ payment.number }>
+                                            <td>{ payment.number }</td>
+                                            <td>{ payment.date.format(DATE_FMT).to_string() }</td>
+                                            <td>{ format!("{:.2}", payment.payment) }</td>
+                                            <td>{ format!("{:.2}", payment.principal) }</td>
+                                            <td>{ format!("{:.2}", payment.interest) }</td>
+                                            <td>{ format!("{:.2}", payment.remaining) }</td>
+                                        </tr>
+                                    }).collect::<Html>()
+                                }
+                            </table>
+                        </div>
+                        {
+                            if let Some(err) = &self.create_error {
+                                html! { <p>{ format!("error adding loan: {}", err) }</p> }
+                            } else {
+                                html! {}
+                            }
+                        }
+                        <button {onclick}>{ "add payments to prediction" }</button>
+                    </div>
+                }
+            }
+            Some(Err(err)) => html! { <p>{ format!("error previewing loan: {}", err) }</p> },
+            None => html! {},
+        }
+    }
+}
+
+impl YieldStyle for LoanGenerator {
+    fn style_from(&self) -> stylist::StyleSource<'static> {
+        css!(
+            "max-height: 300px;
+            overflow-y: auto;"
+        )
+    }
+}
+
+// Request functions
+impl LoanGenerator {
+    fn send_if_logged_in(&self, ctx: &Context<Self>, create: bool) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.send(ctx, username, create)
+        }
+    }
+
+    fn path(username: &str) -> String {
+        fully_qualified_path(
+            &replace_pattern(
+                routes::GENERATE_LOAN,
+                path_patterns::GENERATE_LOAN,
+                username,
+            )
+            .expect("could not replace pattern"),
+        )
+        .expect("could not create fully qualified path")
+    }
+
+    fn send(&self, ctx: &Context<Self>, username: &str, create: bool) {
+        let path = Self::path(username);
+        let request = self.request.clone();
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let client = Client::new();
+            let request = if create {
+                client.put(path)
+            } else {
+                client.post(path)
+            }
+            .json(&request);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<LoanSchedule>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError("loan schedule", err.to_string())
+                    })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            if create {
+                scope.send_message(LoanGeneratorMsg::ReceivedCreateResponse(response));
+            } else {
+                scope.send_message(LoanGeneratorMsg::ReceivedPreview(response));
+            }
+        })
+    }
+}
@@ -0,0 +1,554 @@
+use chrono::{Local, NaiveDate};
+use reqwest::Client;
+use shared::{
+    models::{
+        Asset, AssetQuery, AssetWithValuations, NewAsset, NewAssetRequest, NewAssetValuation,
+        PredictionWithDeltas,
+    },
+    path_patterns,
+    projection::net_worth::{NetWorthPoint, NetWorthQuery},
+    routes,
+};
+use stylist::{css, YieldStyle};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::{
+    components::AppContext,
+    requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction},
+    InternalResponseError, ResponseResult,
+};
+
+const DATE_FMT: &str = "%x";
+const DEFAULT_HORIZON_YEARS: i64 = 5;
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct NetWorthProps {
+    pub prediction: PredictionWithDeltas,
+}
+
+pub enum NetWorthMsg {
+    ReceivedAssets(ResponseResult<Vec<AssetWithValuations>>),
+    ReceivedSeries(ResponseResult<Vec<NetWorthPoint>>),
+    StartingBalanceChanged(String),
+    UntilChanged(String),
+    Open(bool),
+    NameChanged(String),
+    KindSelected(String),
+    RateChanged(String),
+    ValuedOnChanged(String),
+    ValueChanged(String),
+    Submitted,
+    RevalueRequested(usize),
+    ReceivedUpdateResponse(ResponseResult<()>),
+    DeleteRequested(usize),
+}
+
+/// A prediction's assets and liabilities, and its net worth over time once
+/// they are combined with cash and investments.
+pub struct NetWorth {
+    app_context: AppContext,
+    assets: Option<ResponseResult<Vec<AssetWithValuations>>>,
+    series: Option<ResponseResult<Vec<NetWorthPoint>>>,
+    starting_balance: f32,
+    from: NaiveDate,
+    until: NaiveDate,
+    open: bool,
+    name: String,
+    is_liability: bool,
+    annual_rate: Option<f32>,
+    valued_on: NaiveDate,
+    value: Option<f32>,
+    form_error: Option<String>,
+    response_error: Option<InternalResponseError>,
+}
+
+impl Component for NetWorth {
+    type Message = NetWorthMsg;
+    type Properties = NetWorthProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (app_context, _) = ctx
+            .link()
+            .context(Callback::noop())
+            .expect("no AppContext provided");
+        let today = Local::now().naive_utc().date();
+
+        let net_worth = NetWorth {
+            app_context,
+            assets: None,
+            series: None,
+            starting_balance: 0.,
+            from: today,
+            until: today + chrono::Duration::days(365 * DEFAULT_HORIZON_YEARS),
+            open: false,
+            name: String::new(),
+            is_liability: false,
+            annual_rate: None,
+            valued_on: today,
+            value: None,
+            form_error: None,
+            response_error: None,
+        };
+        net_worth.refresh_if_logged_in(ctx);
+        net_worth
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            NetWorthMsg::ReceivedAssets(response) => self.assets = Some(response),
+            NetWorthMsg::ReceivedSeries(response) => self.series = Some(response),
+            NetWorthMsg::StartingBalanceChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.starting_balance = value;
+                    self.form_error = None;
+                    self.get_series_if_logged_in(ctx);
+                }
+                Err(_) => self.form_error = Some(format!("invalid cash balance ({})", value)),
+            },
+            NetWorthMsg::UntilChanged(date) => match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                Ok(date) => {
+                    self.until = date;
+                    self.form_error = None;
+                    self.get_series_if_logged_in(ctx);
+                }
+                Err(_) => self.form_error = Some(format!("invalid end date ({})", date)),
+            },
+            NetWorthMsg::Open(open) => {
+                self.open = open;
+                self.form_error = None;
+            }
+            NetWorthMsg::NameChanged(name) => self.name = name,
+            NetWorthMsg::KindSelected(kind) => self.is_liability = kind == "liability",
+            NetWorthMsg::RateChanged(rate) => {
+                if rate.is_empty() {
+                    self.annual_rate = None;
+                } else {
+                    match rate.parse() {
+                        Ok(rate) => {
+                            self.annual_rate = Some(rate);
+                            self.form_error = None;
+                        }
+                        Err(_) => self.form_error = Some(format!("invalid annual rate ({})", rate)),
+                    }
+                }
+            }
+            NetWorthMsg::ValuedOnChanged(date) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(date) => {
+                        self.valued_on = date;
+                        self.form_error = None;
+                    }
+                    Err(_) => self.form_error = Some(format!("invalid valuation date ({})", date)),
+                }
+            }
+            NetWorthMsg::ValueChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.value = Some(value);
+                    self.form_error = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid value ({})", value)),
+            },
+            NetWorthMsg::Submitted => self.put_asset_if_logged_in(ctx),
+            NetWorthMsg::RevalueRequested(index) => self.put_valuation_if_logged_in(ctx, index),
+            NetWorthMsg::ReceivedUpdateResponse(response) => match response {
+                Ok(_) => {
+                    self.open = false;
+                    self.response_error = None;
+                    self.refresh_if_logged_in(ctx);
+                }
+                Err(err) => self.response_error = Some(err),
+            },
+            NetWorthMsg::DeleteRequested(index) => self.delete_asset_if_logged_in(ctx, index),
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div>
+                <h3>{ "net worth" }</h3>
+                {
+                    if let Some(err) = &self.response_error {
+                        html! { <p>{ format!("error updating assets: {}", err) }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                {
+                    if let Some(err) = &self.form_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                { self.view_assets(ctx) }
+                {
+                    if self.open {
+                        self.view_form(ctx)
+                    } else {
+                        let onclick = ctx.link().callback(|_| NetWorthMsg::Open(true));
+                        html! { <button {onclick}>{ "new asset or liability" }</button> }
+                    }
+                }
+                { self.view_series(ctx) }
+            </div>
+        }
+    }
+}
+
+impl YieldStyle for NetWorth {
+    fn style_from(&self) -> stylist::StyleSource<'static> {
+        css!(
+            r#"
+            max-height: 400px;
+            overflow-y: auto;
+            "#
+        )
+    }
+}
+
+fn input_callback(ctx: &Context<NetWorth>, msg: fn(String) -> NetWorthMsg) -> Callback<Event> {
+    ctx.link().batch_callback(move |ev: Event| {
+        let target = ev.target();
+        let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+        input.map(|input| msg(input.value()))
+    })
+}
+
+// Sub-views
+impl NetWorth {
+    fn view_assets(&self, ctx: &Context<Self>) -> Html {
+        match &self.assets {
+            Some(Ok(assets)) if assets.is_empty() => html! {},
+            Some(Ok(assets)) => html! {
+                <>
+                    <table>
+                        <tr>
+                            <th>{ "name" }</th>
+                            <th>{ "kind" }</th>
+                            <th>{ "yearly change" }</th>
+                            <th>{ "last valued" }</th>
+                            <th>{ "value today" }</th>
+                            <th></th>
+                            <th></th>
+                        </tr>
+                        {
+                            assets.iter().enumerate().map(|(i, asset)| {
+                                let onrevalue = ctx.link().callback(move |_| NetWorthMsg::RevalueRequested(i));
+                                let ondelete = ctx.link().callback(move |_| NetWorthMsg::DeleteRequested(i));
+                                let last_valued = asset.valuations.last().map_or(String::new(), |valuation| {
+                                    format!("{} on {}", valuation.value(), valuation.valued_on().format(DATE_FMT))
+                                });
+
+                                html! {
+                                    <tr key={ asset.asset.id() }>
+                                        <td>{ asset.asset.name() }</td>
+                                        <td>{ if asset.asset.is_liability() { "liability" } else { "asset" } }</td>
+                                        <td>{ asset.asset.annual_rate().map_or("-".to_string(), |rate| format!("{}%", rate)) }</td>
+                                        <td>{ last_valued }</td>
+                                        <td>{ asset.value_on(self.from).map_or("-".to_string(), |value| format!("${:.2}", value)) }</td>
+                                        <td><button onclick={onrevalue}>{ "record value" }</button></td>
+                                        <td><i class="fa fa-trash" aria-hidden="true" onclick={ondelete}></i></td>
+                                    </tr>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </table>
+                    {
+                        // shared with the new asset form, so only one set is shown
+                        if self.open {
+                            html! {}
+                        } else {
+                            self.view_valuation_inputs(ctx)
+                        }
+                    }
+                </>
+            },
+            Some(Err(err)) => html! { <p>{ format!("error getting assets: {}", err) }</p> },
+            None => html! {},
+        }
+    }
+
+    fn view_form(&self, ctx: &Context<Self>) -> Html {
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            NetWorthMsg::Submitted
+        });
+        let oninput_kind = ctx.link().callback(|ev: InputEvent| {
+            let event = ev.dyn_into::<Event>().unwrap_throw();
+            let event_target = event.target().unwrap_throw();
+            let target = event_target.dyn_into::<HtmlSelectElement>().unwrap_throw();
+            NetWorthMsg::KindSelected(target.value())
+        });
+        let oncancel = ctx.link().callback(|_| NetWorthMsg::Open(false));
+
+        html! {
+            <form {onsubmit}>
+                <div>
+                    <label for="asset-name">{ "name:" }</label>
+                    <input type="text" id="asset-name" required=true onchange={input_callback(ctx, NetWorthMsg::NameChanged)}/>
+                </div>
+                <div>
+                    <label for="asset-kind">{ "kind:" }</label>
+                    <select id="asset-kind" oninput={oninput_kind}>
+                        <option value="asset" selected={!self.is_liability}>{ "asset" }</option>
+                        <option value="liability" selected={self.is_liability}>{ "liability" }</option>
+                    </select>
+                </div>
+                <div>
+                    <label for="asset-rate">{ "yearly change (%, optional):" }</label>
+                    <input type="number" id="asset-rate" step="0.1" onchange={input_callback(ctx, NetWorthMsg::RateChanged)}/>
+                </div>
+                { self.view_valuation_inputs(ctx) }
+                <input type="submit" value="create"/>
+                <input type="button" value="cancel" onclick={oncancel}/>
+            </form>
+        }
+    }
+
+    fn view_valuation_inputs(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <>
+                <div>
+                    <label for="asset-value">{ "value:" }</label>
+                    <input type="number" id="asset-value" step="0.01" min="0" onchange={input_callback(ctx, NetWorthMsg::ValueChanged)}/>
+                </div>
+                <div>
+                    <label for="asset-valued-on">{ "as of:" }</label>
+                    <input type="date" id="asset-valued-on" value={self.valued_on.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, NetWorthMsg::ValuedOnChanged)}/>
+                </div>
+            </>
+        }
+    }
+
+    fn view_series(&self, ctx: &Context<Self>) -> Html {
+        let points = match &self.series {
+            Some(Ok(points)) => points,
+            Some(Err(err)) => {
+                return html! { <p>{ format!("error getting net worth: {}", err) }</p> }
+            }
+            None => return html! {},
+        };
+
+        html! {
+            <>
+                <div>
+                    <label for="net-worth-cash">{ "cash today:" }</label>
+                    <input type="number" id="net-worth-cash" step="0.01" value={self.starting_balance.to_string()} onchange={input_callback(ctx, NetWorthMsg::StartingBalanceChanged)}/>
+                </div>
+                <div>
+                    <label for="net-worth-until">{ "until:" }</label>
+                    <input type="date" id="net-worth-until" value={self.until.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, NetWorthMsg::UntilChanged)}/>
+                </div>
+                <div class={self.style()}>
+                    <table>
+                        <tr>
+                            <th>{ "date" }</th>
+                            <th>{ "cash" }</th>
+                            <th>{ "investments" }</th>
+                            <th>{ "assets" }</th>
+                            <th>{ "liabilities" }</th>
+                            <th>{ "net worth" }</th>
+                        </tr>
+                        {
+                            points.iter().map(|point| html! {
+                                <tr key={ point.date.to_string() }>
+                                    <td>{ point.date.format(DATE_FMT).to_string() }</td>
+                                    <td>{ format!("{:.2}", point.cash) }</td>
+                                    <td>{ format!("{:.2}", point.investments) }</td>
+                                    <td>{ format!("{:.2}", point.assets) }</td>
+                                    <td>{ format!("-{:.2}", point.liabilities) }</td>
+                                    <td>{ format!("{:.2}", point.net_worth) }</td>
+                                </tr>
+                            }).collect::<Html>()
+                        }
+                    </table>
+                </div>
+            </>
+        }
+    }
+}
+
+// Request functions
+impl NetWorth {
+    fn path(route: &str, pattern: &str, username: &str) -> String {
+        fully_qualified_path(
+            &replace_pattern(route, pattern, username).expect("could not replace pattern"),
+        )
+        .expect("could not create fully qualified path")
+    }
+
+    fn refresh_if_logged_in(&self, ctx: &Context<Self>) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.get_assets(ctx, username);
+            self.get_series(ctx, username);
+        }
+    }
+
+    fn get_series_if_logged_in(&self, ctx: &Context<Self>) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.get_series(ctx, username)
+        }
+    }
+
+    fn get_assets(&self, ctx: &Context<Self>, username: &str) {
+        let path = Self::path(routes::ASSETS, path_patterns::ASSETS, username);
+        let query = AssetQuery {
+            prediction_id: ctx.props().prediction.id(),
+        };
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = Client::new().get(path).query(&query);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response
+                        .json::<Vec<AssetWithValuations>>()
+                        .await
+                        .map_err(|err| {
+                            InternalResponseError::ResponseAwaitError("assets", err.to_string())
+                        })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(NetWorthMsg::ReceivedAssets(response));
+        })
+    }
+
+    fn get_series(&self, ctx: &Context<Self>, username: &str) {
+        let path = Self::path(routes::NET_WORTH, path_patterns::NET_WORTH, username);
+        let query = NetWorthQuery {
+            prediction_id: ctx.props().prediction.id(),
+            starting_balance: self.starting_balance,
+            from: self.from,
+            until: self.until,
+        };
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = Client::new().get(path).query(&query);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<Vec<NetWorthPoint>>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError("net worth", err.to_string())
+                    })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(NetWorthMsg::ReceivedSeries(response));
+        })
+    }
+
+    fn put_asset_if_logged_in(&mut self, ctx: &Context<Self>) {
+        let value = match self.value {
+            Some(value) => value,
+            None => {
+                self.form_error = Some("asset needs a value".into());
+                return;
+            }
+        };
+        let asset = match NewAsset::new(
+            ctx.props().prediction.id(),
+            self.name.clone(),
+            self.is_liability,
+            self.annual_rate,
+        ) {
+            Ok(asset) => asset,
+            Err(err) => {
+                self.form_error = Some(err.to_string());
+                return;
+            }
+        };
+        let request = NewAssetRequest {
+            asset,
+            valued_on: self.valued_on,
+            value,
+        };
+
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            let path = Self::path(routes::ASSETS, path_patterns::ASSETS, username);
+
+            let scope = ctx.link().clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let request = Client::new().put(path).json(&request);
+                let on_ok = ResponseAction::from(|_| Ok(()));
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(NetWorthMsg::ReceivedUpdateResponse(response));
+            })
+        }
+    }
+
+    fn put_valuation_if_logged_in(&mut self, ctx: &Context<Self>, index: usize) {
+        let asset_id = match &self.assets {
+            Some(Ok(assets)) => match assets.get(index) {
+                Some(asset) => asset.asset.id(),
+                None => return,
+            },
+            _ => return,
+        };
+        let value = match self.value {
+            Some(value) => value,
+            None => {
+                self.form_error = Some("fill in a value to record".into());
+                return;
+            }
+        };
+        let valuation = match NewAssetValuation::new(asset_id, self.valued_on, value) {
+            Ok(valuation) => valuation,
+            Err(err) => {
+                self.form_error = Some(err.to_string());
+                return;
+            }
+        };
+
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            let path = Self::path(
+                routes::ASSET_VALUATIONS,
+                path_patterns::ASSET_VALUATIONS,
+                username,
+            );
+
+            let scope = ctx.link().clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let request = Client::new().put(path).json(&valuation);
+                let on_ok = ResponseAction::from(|_| Ok(()));
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(NetWorthMsg::ReceivedUpdateResponse(response));
+            })
+        }
+    }
+
+    fn delete_asset_if_logged_in(&self, ctx: &Context<Self>, index: usize) {
+        let asset: Asset = match &self.assets {
+            Some(Ok(assets)) => match assets.get(index) {
+                Some(asset) => asset.asset.clone(),
+                None => return,
+            },
+            _ => return,
+        };
+
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            let path = Self::path(routes::ASSETS, path_patterns::ASSETS, username);
+
+            let scope = ctx.link().clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let request = Client::new().delete(path).json(&asset);
+                let on_ok = ResponseAction::from(|_| Ok(()));
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(NetWorthMsg::ReceivedUpdateResponse(response));
+            })
+        }
+    }
+}
@@ -6,7 +6,7 @@ use shared::{
     models::{
         self,
         deltas::{app::repetition::MonthDay, db::DbDateRepetition},
-        NewDbDelta, Repetition,
+        Delta, DeltaKind, NewDbDelta, Repetition,
     },
     path_patterns, routes,
 };
@@ -46,6 +46,9 @@ where
 #[derive(Debug, PartialEq, Properties)]
 pub struct NewDeltaProps {
     pub prediction_id: i32,
+    /// An existing delta to edit in place of creating a new one
+    #[prop_or_default]
+    pub delta: Option<Delta>,
     pub oncreate: Callback<()>,
 }
 
@@ -60,6 +63,9 @@ pub enum ParseError {
     #[error("unable to parse negative uncertainty ({0}) to f32")]
     NegativeUncertainty(String),
 
+    #[error("unable to parse kind ({0}) to a delta kind")]
+    Kind(String),
+
     #[error("unable to parse repetition ({0}) to a repetition frequency")]
     Repetition(String),
 
@@ -88,6 +94,7 @@ pub enum NewDeltaMsg {
     ValueChanged(String),
     PosUncertaintyChanged(String),
     NegUncertaintyChanged(String),
+    KindChanged(String),
     RepetitionChanged(String),
     StartDateChanged(String),
     EndDateChanged(String),
@@ -108,6 +115,7 @@ pub struct NewDelta {
     value: f32,
     positive_uncertainty: f32,
     negative_uncertainty: f32,
+    kind: DeltaKind,
     db_repetition: DbDateRepetition,
     start_on: NaiveDate,
     end_on: Option<NaiveDate>,
@@ -127,7 +135,7 @@ impl Component for NewDelta {
             .context(Callback::noop())
             .expect("no AppContext provided");
 
-        NewDelta {
+        let new_delta = NewDelta {
             app_context,
             open: false,
             parse_error: None,
@@ -135,6 +143,7 @@ impl Component for NewDelta {
             value: 0.,
             positive_uncertainty: 0.,
             negative_uncertainty: 0.,
+            kind: DeltaKind::Other,
             db_repetition: DbDateRepetition::Monthly,
             start_on: now,
             end_on: Some(now + Duration::days(31)),
@@ -142,6 +151,11 @@ impl Component for NewDelta {
             repeat_weekday: None,
             repetition_error: None,
             response: None,
+        };
+
+        match &ctx.props().delta {
+            Some(delta) => new_delta.editing(delta),
+            None => new_delta,
         }
     }
 
@@ -170,6 +184,13 @@ impl Component for NewDelta {
                     self.parse_error = Some(ParseError::NegativeUncertainty(neg_unc));
                 }
             }
+            NewDeltaMsg::KindChanged(kind) => {
+                if let Ok(value) = kind.parse() {
+                    self.kind = value;
+                } else {
+                    self.parse_error = Some(ParseError::Kind(kind));
+                }
+            }
             NewDeltaMsg::RepetitionChanged(repetition) => {
                 log::debug!("repetition changed");
                 if let Ok(repetition) = repetition.clone().try_into() {
@@ -211,7 +232,7 @@ impl Component for NewDelta {
                     self.parse_error = Some(ParseError::RepeatWeekday(weekday))
                 }
             }
-            NewDeltaMsg::Submitted => self.post_delta_if_logged_in(ctx),
+            NewDeltaMsg::Submitted => self.submit_delta_if_logged_in(ctx),
             NewDeltaMsg::ErrorBuildingRepetition(error) => self.repetition_error = Some(error),
             NewDeltaMsg::ReceivedResponse(response) => {
                 if response.is_ok() {
@@ -244,6 +265,12 @@ impl NewDelta {
         let onchange_neg_unc = input_callback(ctx, |input| {
             NewDeltaMsg::NegUncertaintyChanged(input.value())
         });
+        let oninput_kind = ctx.link().callback(|ev: InputEvent| {
+            let event = ev.dyn_into::<Event>().unwrap_throw();
+            let event_target = event.target().unwrap_throw();
+            let target = event_target.dyn_into::<HtmlSelectElement>().unwrap_throw();
+            NewDeltaMsg::KindChanged(target.value())
+        });
         let oninput_repetition = ctx.link().callback(|ev: InputEvent| {
             let event = ev.dyn_into::<Event>().unwrap_throw();
             let event_target = event.target().unwrap_throw();
@@ -260,40 +287,51 @@ impl NewDelta {
                 <form {onsubmit}>
                     <div>
                         <label for="name">{ "name:" }</label>
-                        <input type="text" id="name" name="name" required=true placehold="name" onchange={onchange_name}/>
+                        <input type="text" id="name" name="name" required=true placehold="name" value={self.name.clone()} onchange={onchange_name}/>
                     </div>
                     <div>
                         <label for="value">{ "value:" }</label>
-                        <input type="number" id="value" name="value" required=true onchange={onchange_value}/>
+                        <input type="number" id="value" name="value" required=true value={self.value.to_string()} onchange={onchange_value}/>
                     </div>
                     <div>
                         <label for="pos-unc">{ "positive uncertainty:" }</label>
-                        <input type="number" id="pos-unc" name="pos-unc" required=false onchange={onchange_pos_unc}/>
+                        <input type="number" id="pos-unc" name="pos-unc" required=false value={self.positive_uncertainty.to_string()} onchange={onchange_pos_unc}/>
                     </div>
                     <div>
                         <label for="neg-unc">{ "negative uncertainty:" }</label>
-                        <input type="number" id="neg-unc" name="neg-unc" required=false onchange={onchange_neg_unc}/>
+                        <input type="number" id="neg-unc" name="neg-unc" required=false value={self.negative_uncertainty.to_string()} onchange={onchange_neg_unc}/>
+                    </div>
+                    <div>
+                        <label for="kind">{ "kind:" }</label>
+                        <select name="kind" id="kind" oninput={oninput_kind}>
+                            {
+                                for DeltaKind::ALL.iter().map(|kind| html! {
+                                    <option value={kind.as_str()} selected={*kind == self.kind}>{ kind.label() }</option>
+                                })
+                            }
+                        </select>
                     </div>
                     <div>
                         <label for="repetition">{ "repetition:" }</label>
                         <select name="repetition" id="repetition" oninput={oninput_repetition}>
                             // <option value="none" selected=true disabled=true hidden=true required=true>{ "select an option" }</option>
-                            <option value="monthly" selected=true>{ "monthly" }</option>
-                            <option value="weekly">{ "weekly" }</option>
-                            <option value="daily">{ "daily" }</option>
-                            <option value="once">{ "once" }</option>
+                            <option value="monthly" selected={self.db_repetition == DbDateRepetition::Monthly}>{ "monthly" }</option>
+                            <option value="weekly" selected={self.db_repetition == DbDateRepetition::Weekly}>{ "weekly" }</option>
+                            <option value="biweekly" selected={self.db_repetition == DbDateRepetition::Biweekly}>{ "every other week" }</option>
+                            <option value="daily" selected={self.db_repetition == DbDateRepetition::Daily}>{ "daily" }</option>
+                            <option value="once" selected={self.db_repetition == DbDateRepetition::Once}>{ "once" }</option>
                         </select>
                     </div>
                     {
                         match self.db_repetition {
                             DbDateRepetition::Monthly => self.view_monthly(ctx),
-                            DbDateRepetition::Weekly => self.view_weekly(ctx),
+                            DbDateRepetition::Weekly | DbDateRepetition::Biweekly => self.view_weekly(ctx),
                             DbDateRepetition::Daily => self.view_daily(ctx),
                             DbDateRepetition::Once => self.view_once(ctx),
                         }
                     }
                     <div>
-                        <input type="submit" value="create"/>
+                        <input type="submit" value={ if ctx.props().delta.is_some() { "update" } else { "create" } }/>
                         <input type="button" value="cancel" onclick={oncancel}/>
                     </div>
                 </form>
@@ -312,15 +350,15 @@ impl NewDelta {
             <>
                 <div>
                     <label for="monthly-start">{ "starting on:" }</label>
-                    <input type="date" id="monthly-start" name="monthly-start" onchange={onchange_start}/>
+                    <input type="date" id="monthly-start" name="monthly-start" value={self.start_on.to_string()} onchange={onchange_start}/>
                 </div>
                 <div>
                     <label for="monthly-end">{ "ending on:" }</label>
-                    <input type="date" id="monthly-end" name="monthly-end" onchange={onchange_end}/>
+                    <input type="date" id="monthly-end" name="monthly-end" value={self.end_on.map(|date| date.to_string()).unwrap_or_default()} onchange={onchange_end}/>
                 </div>
                 <div>
                     <label for="monthly-repeat">{ "repeating on:" }</label>
-                    <input type="number" id="monthly-repeat" name="monthly-repeat" min=1 max=31 step=1 onchange={onchange_monthday}/>
+                    <input type="number" id="monthly-repeat" name="monthly-repeat" min=1 max=31 step=1 value={self.repeat_day.map(|day| Into::<i16>::into(day).to_string()).unwrap_or_default()} onchange={onchange_monthday}/>
                 </div>
             </>
         }
@@ -336,27 +374,28 @@ impl NewDelta {
             let target = event_target.dyn_into::<HtmlSelectElement>().unwrap_throw();
             NewDeltaMsg::RepeatWeekdayChanged(target.value())
         });
+        let weekday = self.repeat_weekday.unwrap_or(Weekday::Mon);
 
         html! {
             <>
                 <div>
                     <label for="weekly-start">{ "starting on:" }</label>
-                    <input type="date" id="weekly-start" name="weekly-start" onchange={onchange_start}/>
+                    <input type="date" id="weekly-start" name="weekly-start" value={self.start_on.to_string()} onchange={onchange_start}/>
                 </div>
                 <div>
                     <label for="weekly-end">{ "ending on:" }</label>
-                    <input type="date" id="weekly-end" name="weekly-end" onchange={onchange_end}/>
+                    <input type="date" id="weekly-end" name="weekly-end" value={self.end_on.map(|date| date.to_string()).unwrap_or_default()} onchange={onchange_end}/>
                 </div>
                 <div>
                     <label for="weekly-weekday">{ "repeating on:" }</label>
                     <select name="weekly-weekday" id="weekly-weekday" oninput={oninput_weekday}>
-                        <option value="Mon" selected=true>{ "monday" }</option>
-                        <option value="Tue">{ "tuesday" }</option>
-                        <option value="Wed">{ "wednesday" }</option>
-                        <option value="Thu">{ "thursday" }</option>
-                        <option value="Fri">{ "friday" }</option>
-                        <option value="Sat">{ "saturday" }</option>
-                        <option value="Sun">{ "sunday" }</option>
+                        <option value="Mon" selected={weekday == Weekday::Mon}>{ "monday" }</option>
+                        <option value="Tue" selected={weekday == Weekday::Tue}>{ "tuesday" }</option>
+                        <option value="Wed" selected={weekday == Weekday::Wed}>{ "wednesday" }</option>
+                        <option value="Thu" selected={weekday == Weekday::Thu}>{ "thursday" }</option>
+                        <option value="Fri" selected={weekday == Weekday::Fri}>{ "friday" }</option>
+                        <option value="Sat" selected={weekday == Weekday::Sat}>{ "saturday" }</option>
+                        <option value="Sun" selected={weekday == Weekday::Sun}>{ "sunday" }</option>
                     </select>
                 </div>
             </>
@@ -372,11 +411,11 @@ impl NewDelta {
             <>
                 <div>
                     <label for="daily-start">{ "starting on:" }</label>
-                    <input type="date" id="daily-start" name="daily-start" onchange={onchange_start}/>
+                    <input type="date" id="daily-start" name="daily-start" value={self.start_on.to_string()} onchange={onchange_start}/>
                 </div>
                 <div>
                     <label for="daily-end">{ "ending on:" }</label>
-                    <input type="date" id="daily-end" name="daily-end" onchange={onchange_end}/>
+                    <input type="date" id="daily-end" name="daily-end" value={self.end_on.map(|date| date.to_string()).unwrap_or_default()} onchange={onchange_end}/>
                 </div>
             </>
         }
@@ -388,7 +427,7 @@ impl NewDelta {
         html! {
             <div>
                 <label for="once-on">{ "occurs on:" }</label>
-                <input type="date" id="once-on" name="once-on" {onchange}/>
+                <input type="date" id="once-on" name="once-on" value={self.start_on.to_string()} {onchange}/>
             </div>
         }
     }
@@ -409,22 +448,66 @@ impl NewDelta {
 
     fn view_closed(&self, ctx: &Context<Self>) -> Html {
         let onclick = ctx.link().callback(|_| NewDeltaMsg::Open(true));
+        let icon = if ctx.props().delta.is_some() {
+            "fa fa-pencil"
+        } else {
+            "fa fa-plus"
+        };
 
         html! {
-            <i class="fa fa-plus" aria-hidden="true" {onclick}></i>
+            <i class={icon} aria-hidden="true" {onclick}></i>
+        }
+    }
+}
+
+impl NewDelta {
+    /// Fill the form in from an existing delta.
+    fn editing(self, delta: &Delta) -> Self {
+        let (start_on, end_on, repeat_day, repeat_weekday) = match delta.repetition() {
+            Repetition::Monthly {
+                from,
+                to,
+                repeat_on_day,
+            } => (from, Some(to), Some(repeat_on_day), None),
+            Repetition::Weekly {
+                from,
+                to,
+                repeat_on_weekday,
+            }
+            | Repetition::Biweekly {
+                from,
+                to,
+                repeat_on_weekday,
+            } => (from, Some(to), None, Some(repeat_on_weekday)),
+            Repetition::Daily { from, to } => (from, Some(to), None, None),
+            Repetition::Once { on } => (on, None, None, None),
+        };
+
+        NewDelta {
+            name: delta.name().to_string(),
+            value: delta.value(),
+            positive_uncertainty: delta.positive_uncertainty(),
+            negative_uncertainty: delta.negative_uncertainty(),
+            kind: delta.kind(),
+            db_repetition: delta.repetition().into(),
+            start_on,
+            end_on,
+            repeat_day,
+            repeat_weekday,
+            ..self
         }
     }
 }
 
 // Request functions
 impl NewDelta {
-    fn post_delta_if_logged_in(&self, ctx: &Context<Self>) {
+    fn submit_delta_if_logged_in(&self, ctx: &Context<Self>) {
         if let Some(username) = self.app_context.borrow_mut().username() {
-            self.post_delta(ctx, username)
+            self.submit_delta(ctx, username)
         }
     }
 
-    fn post_delta(&self, ctx: &Context<Self>, username: &str) {
+    fn submit_delta(&self, ctx: &Context<Self>, username: &str) {
         let repetition = match self.db_repetition {
             DbDateRepetition::Monthly => {
                 if self.end_on.is_none() {
@@ -452,6 +535,19 @@ impl NewDelta {
                     })
                 }
             }
+            DbDateRepetition::Biweekly => {
+                if self.end_on.is_none() {
+                    Err(RepetitionError::MissingFields("biweekly", "end date"))
+                } else if self.repeat_weekday.is_none() {
+                    Err(RepetitionError::MissingFields("biweekly", "repeat weekday"))
+                } else {
+                    Ok(Repetition::Biweekly {
+                        from: self.start_on,
+                        to: self.end_on.unwrap(),
+                        repeat_on_weekday: self.repeat_weekday.unwrap(),
+                    })
+                }
+            }
             DbDateRepetition::Daily => {
                 if self.end_on.is_none() {
                     Err(RepetitionError::MissingFields("daily", "end date"))
@@ -471,15 +567,7 @@ impl NewDelta {
             return;
         }
 
-        let new_db_delta: NewDbDelta = models::NewDelta::new(
-            ctx.props().prediction_id,
-            self.name.clone(),
-            self.value,
-            self.positive_uncertainty,
-            self.negative_uncertainty,
-            repetition.unwrap(),
-        )
-        .into();
+        let repetition = repetition.unwrap();
 
         let path = fully_qualified_path(
             &replace_pattern(routes::DELTAS, path_patterns::DELTAS, username)
@@ -487,9 +575,36 @@ impl NewDelta {
         )
         .expect("could not create fully qualified path");
 
+        let request = match &ctx.props().delta {
+            Some(delta) => Client::new().patch(path).json(
+                &Delta::new(
+                    delta.id(),
+                    ctx.props().prediction_id,
+                    self.name.clone(),
+                    self.value,
+                    self.positive_uncertainty,
+                    self.negative_uncertainty,
+                    repetition,
+                )
+                .with_kind(self.kind),
+            ),
+            None => {
+                let new_db_delta: NewDbDelta = models::NewDelta::new(
+                    ctx.props().prediction_id,
+                    self.name.clone(),
+                    self.value,
+                    self.positive_uncertainty,
+                    self.negative_uncertainty,
+                    repetition,
+                )
+                .with_kind(self.kind)
+                .into();
+                Client::new().post(path).json(&new_db_delta)
+            }
+        };
+
         let scope = ctx.link().clone();
         wasm_bindgen_futures::spawn_local(async move {
-            let request = Client::new().post(path).json(&new_db_delta);
             let on_ok = ResponseAction::from(|_| Ok(()));
             let requester = Requester::default();
             let response = requester.make(request, on_ok).await;
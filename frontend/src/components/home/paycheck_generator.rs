@@ -0,0 +1,522 @@
+use chrono::{Datelike, Local, NaiveDate};
+use reqwest::Client;
+use shared::{
+    generators::paycheck::{
+        Deduction, PayFrequency, Paycheck, PaycheckRequest, Withholding, WithholdingMethod,
+    },
+    path_patterns, routes, taxes,
+};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::{
+    components::AppContext,
+    requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction},
+    InternalResponseError, ResponseResult,
+};
+
+const DATE_FMT: &str = "%x";
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct PaycheckGeneratorProps {
+    pub prediction_id: i32,
+    pub oncreate: Callback<()>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum DeductionField {
+    Name,
+    Amount,
+    Percent,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum WithholdingField {
+    Name,
+    Brackets,
+    Value,
+}
+
+pub enum PaycheckGeneratorMsg {
+    Open(bool),
+    NameChanged(String),
+    GrossChanged(String),
+    FrequencyChanged(String),
+    FirstPayChanged(String),
+    LastPayChanged(String),
+    DeductionAdded,
+    DeductionRemoved(usize),
+    DeductionChanged(usize, DeductionField, String),
+    WithholdingAdded,
+    WithholdingRemoved(usize),
+    WithholdingChanged(usize, WithholdingField, String),
+    PreviewRequested,
+    ReceivedPreview(ResponseResult<Paycheck>),
+    CreateRequested,
+    ReceivedCreateResponse(ResponseResult<Paycheck>),
+}
+
+/// A withholding as typed into the form: a flat rate, or a bracket table in
+/// the `over:rate, ...` form.
+struct WithholdingInput {
+    name: String,
+    brackets: bool,
+    value: String,
+}
+
+impl WithholdingInput {
+    fn to_withholding(&self) -> Result<Withholding, String> {
+        let method = if self.brackets {
+            WithholdingMethod::Brackets {
+                brackets: taxes::parse_brackets(&self.value).map_err(|err| err.to_string())?,
+            }
+        } else {
+            WithholdingMethod::Flat {
+                rate: self
+                    .value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid rate for {} ({})", self.name, self.value))?,
+            }
+        };
+
+        Ok(Withholding {
+            name: self.name.clone(),
+            method,
+        })
+    }
+}
+
+/// Adds a paycheck to a prediction: net pay, and a delta for each pre-tax
+/// deduction and withholding taken out of gross pay to leave it.
+pub struct PaycheckGenerator {
+    app_context: AppContext,
+    open: bool,
+    request: PaycheckRequest,
+    withholdings: Vec<WithholdingInput>,
+    form_error: Option<String>,
+    preview: Option<ResponseResult<Paycheck>>,
+    create_error: Option<InternalResponseError>,
+}
+
+impl Component for PaycheckGenerator {
+    type Message = PaycheckGeneratorMsg;
+    type Properties = PaycheckGeneratorProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (app_context, _) = ctx
+            .link()
+            .context(Callback::noop())
+            .expect("no AppContext provided");
+
+        let today = Local::now().naive_utc().date();
+
+        PaycheckGenerator {
+            app_context,
+            open: false,
+            request: PaycheckRequest {
+                prediction_id: ctx.props().prediction_id,
+                name: String::new(),
+                gross_annual: 0.,
+                frequency: PayFrequency::Biweekly,
+                first_pay_on: today,
+                last_pay_on: NaiveDate::from_ymd(today.year(), 12, 31),
+                pre_tax_deductions: Vec::new(),
+                withholdings: Vec::new(),
+            },
+            withholdings: Vec::new(),
+            form_error: None,
+            preview: None,
+            create_error: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            PaycheckGeneratorMsg::Open(open) => {
+                self.open = open;
+                self.form_error = None;
+                self.preview = None;
+                self.create_error = None;
+            }
+            PaycheckGeneratorMsg::NameChanged(name) => self.request.name = name,
+            PaycheckGeneratorMsg::GrossChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.request.gross_annual = value;
+                    self.form_error = None;
+                    self.preview = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid gross salary ({})", value)),
+            },
+            PaycheckGeneratorMsg::FrequencyChanged(frequency) => {
+                self.request.frequency = match frequency.as_str() {
+                    "weekly" => PayFrequency::Weekly,
+                    "semi-monthly" => PayFrequency::SemiMonthly,
+                    "monthly" => PayFrequency::Monthly,
+                    _ => PayFrequency::Biweekly,
+                };
+                self.preview = None;
+            }
+            PaycheckGeneratorMsg::FirstPayChanged(date) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(date) => {
+                        self.request.first_pay_on = date;
+                        self.form_error = None;
+                        self.preview = None;
+                    }
+                    Err(_) => self.form_error = Some(format!("invalid first pay date ({})", date)),
+                }
+            }
+            PaycheckGeneratorMsg::LastPayChanged(date) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(date) => {
+                        self.request.last_pay_on = date;
+                        self.form_error = None;
+                        self.preview = None;
+                    }
+                    Err(_) => self.form_error = Some(format!("invalid last pay date ({})", date)),
+                }
+            }
+            PaycheckGeneratorMsg::DeductionAdded => {
+                self.request.pre_tax_deductions.push(Deduction {
+                    name: String::new(),
+                    amount: 0.,
+                    percent: false,
+                })
+            }
+            PaycheckGeneratorMsg::DeductionRemoved(index) => {
+                if index < self.request.pre_tax_deductions.len() {
+                    self.request.pre_tax_deductions.remove(index);
+                    self.preview = None;
+                }
+            }
+            PaycheckGeneratorMsg::DeductionChanged(index, field, value) => {
+                if let Some(deduction) = self.request.pre_tax_deductions.get_mut(index) {
+                    match field {
+                        DeductionField::Name => deduction.name = value,
+                        DeductionField::Percent => deduction.percent = value == "percent",
+                        DeductionField::Amount => match value.parse() {
+                            Ok(amount) => {
+                                deduction.amount = amount;
+                                self.form_error = None;
+                            }
+                            Err(_) => {
+                                self.form_error = Some(format!("invalid deduction ({})", value))
+                            }
+                        },
+                    }
+                    self.preview = None;
+                }
+            }
+            PaycheckGeneratorMsg::WithholdingAdded => self.withholdings.push(WithholdingInput {
+                name: String::new(),
+                brackets: false,
+                value: String::new(),
+            }),
+            PaycheckGeneratorMsg::WithholdingRemoved(index) => {
+                if index < self.withholdings.len() {
+                    self.withholdings.remove(index);
+                    self.preview = None;
+                }
+            }
+            PaycheckGeneratorMsg::WithholdingChanged(index, field, value) => {
+                if let Some(withholding) = self.withholdings.get_mut(index) {
+                    match field {
+                        WithholdingField::Name => withholding.name = value,
+                        WithholdingField::Brackets => withholding.brackets = value == "brackets",
+                        WithholdingField::Value => withholding.value = value,
+                    }
+                    self.preview = None;
+                }
+            }
+            PaycheckGeneratorMsg::PreviewRequested => {
+                match self
+                    .withholdings
+                    .iter()
+                    .map(WithholdingInput::to_withholding)
+                    .collect::<Result<Vec<_>, _>>()
+                {
+                    Ok(withholdings) => {
+                        self.request.withholdings = withholdings;
+                        match self.request.validate() {
+                            Ok(_) => self.send_if_logged_in(ctx, false),
+                            Err(err) => self.form_error = Some(err.to_string()),
+                        }
+                    }
+                    Err(err) => self.form_error = Some(err),
+                }
+            }
+            PaycheckGeneratorMsg::ReceivedPreview(response) => self.preview = Some(response),
+            PaycheckGeneratorMsg::CreateRequested => self.send_if_logged_in(ctx, true),
+            PaycheckGeneratorMsg::ReceivedCreateResponse(response) => match response {
+                Ok(_) => {
+                    self.open = false;
+                    self.preview = None;
+                    self.create_error = None;
+                    ctx.props().oncreate.emit(());
+                }
+                Err(err) => self.create_error = Some(err),
+            },
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if self.open {
+            self.view_open(ctx)
+        } else {
+            let onclick = ctx.link().callback(|_| PaycheckGeneratorMsg::Open(true));
+
+            html! {
+                <div>
+                    <button {onclick}>{ "add a paycheck" }</button>
+                </div>
+            }
+        }
+    }
+}
+
+fn select_callback(
+    ctx: &Context<PaycheckGenerator>,
+    msg: impl Fn(String) -> PaycheckGeneratorMsg + 'static,
+) -> Callback<InputEvent> {
+    ctx.link().callback(move |ev: InputEvent| {
+        let event = ev.dyn_into::<Event>().unwrap_throw();
+        let event_target = event.target().unwrap_throw();
+        let target = event_target.dyn_into::<HtmlSelectElement>().unwrap_throw();
+        msg(target.value())
+    })
+}
+
+fn input_callback(
+    ctx: &Context<PaycheckGenerator>,
+    msg: impl Fn(String) -> PaycheckGeneratorMsg + 'static,
+) -> Callback<Event> {
+    ctx.link().batch_callback(move |ev: Event| {
+        let target = ev.target();
+        let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+        input.map(|input| msg(input.value()))
+    })
+}
+
+// Sub-views
+impl PaycheckGenerator {
+    fn view_open(&self, ctx: &Context<Self>) -> Html {
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            PaycheckGeneratorMsg::PreviewRequested
+        });
+        let oncancel = ctx.link().callback(|_| PaycheckGeneratorMsg::Open(false));
+
+        html! {
+            <div>
+                <h3>{ "add a paycheck" }</h3>
+                {
+                    if let Some(err) = &self.form_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <form {onsubmit}>
+                    <div>
+                        <label for="paycheck-name">{ "name:" }</label>
+                        <input type="text" id="paycheck-name" required=true value={self.request.name.clone()} onchange={input_callback(ctx, PaycheckGeneratorMsg::NameChanged)}/>
+                    </div>
+                    <div>
+                        <label for="paycheck-gross">{ "gross annual salary:" }</label>
+                        <input type="number" id="paycheck-gross" step="0.01" required=true onchange={input_callback(ctx, PaycheckGeneratorMsg::GrossChanged)}/>
+                    </div>
+                    <div>
+                        <label for="paycheck-frequency">{ "paid:" }</label>
+                        <select id="paycheck-frequency" oninput={select_callback(ctx, PaycheckGeneratorMsg::FrequencyChanged)}>
+                            <option value="weekly">{ "weekly" }</option>
+                            <option value="biweekly" selected=true>{ "every other week" }</option>
+                            <option value="semi-monthly">{ "on the 15th and last day of the month" }</option>
+                            <option value="monthly">{ "monthly" }</option>
+                        </select>
+                    </div>
+                    <div>
+                        <label for="paycheck-first">{ "first paycheck:" }</label>
+                        <input type="date" id="paycheck-first" value={self.request.first_pay_on.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, PaycheckGeneratorMsg::FirstPayChanged)}/>
+                    </div>
+                    <div>
+                        <label for="paycheck-last">{ "paid until:" }</label>
+                        <input type="date" id="paycheck-last" value={self.request.last_pay_on.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, PaycheckGeneratorMsg::LastPayChanged)}/>
+                    </div>
+                    { self.view_deductions(ctx) }
+                    { self.view_withholdings(ctx) }
+                    <input type="submit" value="preview"/>
+                    <input type="button" value="cancel" onclick={oncancel}/>
+                </form>
+                { self.view_preview(ctx) }
+            </div>
+        }
+    }
+
+    fn view_deductions(&self, ctx: &Context<Self>) -> Html {
+        let onadd = ctx
+            .link()
+            .callback(|_| PaycheckGeneratorMsg::DeductionAdded);
+
+        html! {
+            <div>
+                <h4>{ "pre-tax deductions" }</h4>
+                {
+                    self.request.pre_tax_deductions.iter().enumerate().map(|(i, deduction)| {
+                        let onremove = ctx.link().callback(move |_| PaycheckGeneratorMsg::DeductionRemoved(i));
+
+                        html! {
+                            <div>
+                                <input type="text" placeholder="401k" value={deduction.name.clone()} onchange={input_callback(ctx, move |value| PaycheckGeneratorMsg::DeductionChanged(i, DeductionField::Name, value))}/>
+                                <input type="number" step="0.01" value={deduction.amount.to_string()} onchange={input_callback(ctx, move |value| PaycheckGeneratorMsg::DeductionChanged(i, DeductionField::Amount, value))}/>
+                                <select oninput={select_callback(ctx, move |value| PaycheckGeneratorMsg::DeductionChanged(i, DeductionField::Percent, value))}>
+                                    <option value="dollars" selected={!deduction.percent}>{ "dollars per paycheck" }</option>
+                                    <option value="percent" selected={deduction.percent}>{ "% of gross pay" }</option>
+                                </select>
+                                <i class="fa fa-trash" aria-hidden="true" onclick={onremove}></i>
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+                <input type="button" value="add deduction" onclick={onadd}/>
+            </div>
+        }
+    }
+
+    fn view_withholdings(&self, ctx: &Context<Self>) -> Html {
+        let onadd = ctx
+            .link()
+            .callback(|_| PaycheckGeneratorMsg::WithholdingAdded);
+
+        html! {
+            <div>
+                <h4>{ "withholding" }</h4>
+                {
+                    self.withholdings.iter().enumerate().map(|(i, withholding)| {
+                        let onremove = ctx.link().callback(move |_| PaycheckGeneratorMsg::WithholdingRemoved(i));
+                        let placeholder = if withholding.brackets { "0:10, 11600:12, 47150:22" } else { "rate (%)" };
+
+                        html! {
+                            <div>
+                                <input type="text" placeholder="federal" value={withholding.name.clone()} onchange={input_callback(ctx, move |value| PaycheckGeneratorMsg::WithholdingChanged(i, WithholdingField::Name, value))}/>
+                                <select oninput={select_callback(ctx, move |value| PaycheckGeneratorMsg::WithholdingChanged(i, WithholdingField::Brackets, value))}>
+                                    <option value="flat" selected={!withholding.brackets}>{ "flat rate" }</option>
+                                    <option value="brackets" selected={withholding.brackets}>{ "annual brackets" }</option>
+                                </select>
+                                <input type="text" {placeholder} value={withholding.value.clone()} onchange={input_callback(ctx, move |value| PaycheckGeneratorMsg::WithholdingChanged(i, WithholdingField::Value, value))}/>
+                                <i class="fa fa-trash" aria-hidden="true" onclick={onremove}></i>
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+                <input type="button" value="add withholding" onclick={onadd}/>
+            </div>
+        }
+    }
+
+    fn view_preview(&self, ctx: &Context<Self>) -> Html {
+        match &self.preview {
+            Some(Ok(paycheck)) => {
+                let onclick = ctx
+                    .link()
+                    .callback(|_| PaycheckGeneratorMsg::CreateRequested);
+
+                html! {
+                    <div>
+                        <table>
+                            <tr>
+                                <td>{ "gross pay" }</td>
+                                <td>{ format!("{:.2}", paycheck.gross) }</td>
+                            </tr>
+                            {
+                                paycheck.deductions.iter().chain(&paycheck.withholdings).map(|line| html! {
+                                    <tr>
+                                        <td>{ line.name.clone() }</td>
+                                        <td>{ format!("-{:.2}", line.amount) }</td>
+                                    </tr>
+                                }).collect::<Html>()
+                            }
+                            <tr>
+                                <th>{ "net pay" }</th>
+                                <th>{ format!("{:.2}", paycheck.net) }</th>
+                            </tr>
+                        </table>
+                        <p>
+                            { format!(
+                                "paid {}, {} paychecks from {} to {}",
+                                paycheck.frequency.name(),
+                                paycheck.pay_dates.len(),
+                                paycheck.pay_dates.first().map(|date| date.format(DATE_FMT).to_string()).unwrap_or_default(),
+                                paycheck.pay_dates.last().map(|date| date.format(DATE_FMT).to_string()).unwrap_or_default(),
+                            ) }
+                        </p>
+                        {
+                            if let Some(err) = &self.create_error {
+                                html! { <p>{ format!("error adding paycheck: {}", err) }</p> }
+                            } else {
+                                html! {}
+                            }
+                        }
+                        <button {onclick}>{ "add paycheck to prediction" }</button>
+                    </div>
+                }
+            }
+            Some(Err(err)) => html! { <p>{ format!("error previewing paycheck: {}", err) }</p> },
+            None => html! {},
+        }
+    }
+}
+
+// Request functions
+impl PaycheckGenerator {
+    fn send_if_logged_in(&self, ctx: &Context<Self>, create: bool) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.send(ctx, username, create)
+        }
+    }
+
+    fn path(username: &str) -> String {
+        fully_qualified_path(
+            &replace_pattern(
+                routes::GENERATE_PAYCHECK,
+                path_patterns::GENERATE_PAYCHECK,
+                username,
+            )
+            .expect("could not replace pattern"),
+        )
+        .expect("could not create fully qualified path")
+    }
+
+    fn send(&self, ctx: &Context<Self>, username: &str, create: bool) {
+        let path = Self::path(username);
+        let request = self.request.clone();
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let client = Client::new();
+            let request = if create {
+                client.put(path)
+            } else {
+                client.post(path)
+            }
+            .json(&request);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<Paycheck>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError("paycheck", err.to_string())
+                    })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            if create {
+                scope.send_message(PaycheckGeneratorMsg::ReceivedCreateResponse(response));
+            } else {
+                scope.send_message(PaycheckGeneratorMsg::ReceivedPreview(response));
+            }
+        })
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use reqwest::Client;
 use shared::{
     models::{
-        deltas::app::repetition::MonthDay, predictions::PredictionWithDeltas, Prediction,
+        deltas::app::repetition::MonthDay, predictions::PredictionWithDeltas, Delta, Prediction,
         Repetition,
     },
     path_patterns, routes,
@@ -15,7 +15,10 @@ use yew::prelude::*;
 
 use crate::requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction};
 use crate::{
-    components::{AppContext, NewDelta},
+    components::{
+        AppContext, Goals, InvestmentAccounts, LoanGenerator, NetWorth, NewDelta,
+        PaycheckGenerator, RecurringDeltas, RetirementPlanner, TaxEstimator,
+    },
     ResponseResult,
 };
 
@@ -31,6 +34,7 @@ pub struct PredictionPanelProps {
 pub enum PredictionPanelMsg {
     AppContextUpdated(AppContext),
     DeletePrediction,
+    DeleteDelta(Delta),
     UpdatePredictionNameRequested,
     PredictionNameChanged(String),
     PredictionNameChangeSubmitted,
@@ -72,6 +76,15 @@ impl Component for PredictionPanel {
         let oncreate_delta = ctx
             .link()
             .callback(|_| PredictionPanelMsg::ReceivedUpdateResponse(Ok(())));
+        let oncreate_recurring = ctx
+            .link()
+            .callback(|_| PredictionPanelMsg::ReceivedUpdateResponse(Ok(())));
+        let oncreate_loan = ctx
+            .link()
+            .callback(|_| PredictionPanelMsg::ReceivedUpdateResponse(Ok(())));
+        let oncreate_paycheck = ctx
+            .link()
+            .callback(|_| PredictionPanelMsg::ReceivedUpdateResponse(Ok(())));
 
         html! {
             <>
@@ -99,6 +112,17 @@ impl Component for PredictionPanel {
                     { self.view_delta_table(ctx) }
 
                     <NewDelta prediction_id={ctx.props().prediction.id()} oncreate={oncreate_delta}/>
+                    <RecurringDeltas prediction_id={ctx.props().prediction.id()} oncreate={oncreate_recurring}/>
+                    <LoanGenerator prediction_id={ctx.props().prediction.id()} oncreate={oncreate_loan}/>
+                    <PaycheckGenerator prediction_id={ctx.props().prediction.id()} oncreate={oncreate_paycheck}/>
+
+                    <Goals prediction={ctx.props().prediction.clone()}/>
+                    <InvestmentAccounts prediction={ctx.props().prediction.clone()}/>
+                    <NetWorth prediction={ctx.props().prediction.clone()}/>
+                    <RetirementPlanner prediction={ctx.props().prediction.clone()}/>
+                    <TaxEstimator prediction={ctx.props().prediction.clone()}/>
+
+                    { self.view_exports(ctx) }
                 </div>
             </>
         }
@@ -110,6 +134,10 @@ impl Component for PredictionPanel {
                 self.delete_prediction_if_logged_in(ctx);
                 log::trace!("delete prediction requested");
             }
+            PredictionPanelMsg::DeleteDelta(delta) => {
+                self.delete_delta_if_logged_in(ctx, delta);
+                log::trace!("delete delta requested");
+            }
             PredictionPanelMsg::AppContextUpdated(_) => todo!(),
             PredictionPanelMsg::ReceivedDeleteResponse(response) => {
                 if response.is_ok() {
@@ -147,6 +175,47 @@ impl Component for PredictionPanel {
 }
 
 impl PredictionPanel {
+    fn view_exports(&self, ctx: &Context<Self>) -> Html {
+        let username = match self.app_context.borrow_mut().username() {
+            Some(username) => username.to_string(),
+            None => return html! {},
+        };
+
+        let link = |route: &str, pattern: &str, format: &str| {
+            let path = fully_qualified_path(
+                &replace_pattern(route, pattern, &username)
+                    .expect("could not replace pattern in route"),
+            )
+            .expect("could not create path");
+
+            format!(
+                "{}?prediction_id={}&format={}",
+                path,
+                ctx.props().prediction.id(),
+                format
+            )
+        };
+
+        html! {
+            <p>
+                { "export deltas: " }
+                <a href={link(routes::EXPORT_DELTAS, path_patterns::EXPORT_DELTAS, "csv")}>{ "csv" }</a>
+                { " " }
+                <a href={link(routes::EXPORT_DELTAS, path_patterns::EXPORT_DELTAS, "json")}>{ "json" }</a>
+                { " | export schedule: " }
+                <a href={link(routes::EXPORT_SCHEDULE, path_patterns::EXPORT_SCHEDULE, "csv")}>{ "csv" }</a>
+                { " " }
+                <a href={link(routes::EXPORT_SCHEDULE, path_patterns::EXPORT_SCHEDULE, "json")}>{ "json" }</a>
+                { " | export journal: " }
+                <a href={link(routes::EXPORT_JOURNAL, path_patterns::EXPORT_JOURNAL, "ledger")}>{ "ledger" }</a>
+                { " " }
+                <a href={link(routes::EXPORT_JOURNAL, path_patterns::EXPORT_JOURNAL, "hledger")}>{ "hledger" }</a>
+                { " " }
+                <a href={link(routes::EXPORT_JOURNAL, path_patterns::EXPORT_JOURNAL, "beancount")}>{ "beancount" }</a>
+            </p>
+        }
+    }
+
     fn view_delta_table(&self, ctx: &Context<Self>) -> Html {
         html! {
             <table>
@@ -155,6 +224,7 @@ impl PredictionPanel {
                     <th>{ "value" }</th>
                     <th>{ "uncertainty" }</th>
                     <th>{ "occurs" }</th>
+                    <th></th>
                 </tr>
                 {
                     ctx.props().prediction.deltas().into_iter().map(|delta| {
@@ -217,18 +287,38 @@ impl PredictionPanel {
                                     to.format(DATE_FMT)
                                 )
                             }
+                            Repetition::Biweekly { from, to, repeat_on_weekday } => {
+                                format!(
+                                    "every other {} from {} to {}",
+                                    repeat_on_weekday.to_string(),
+                                    from.format(DATE_FMT),
+                                    to.format(DATE_FMT)
+                                )
+                            }
                             Repetition::Daily { from, to } => {
                                 format!("every day from {} to {}", from.format(DATE_FMT), to.format(DATE_FMT))
                             }
                             Repetition::Once { on } => format!("one time on {}", on.format(DATE_FMT)),
                         };
 
+                        let onupdate = ctx
+                            .link()
+                            .callback(|_| PredictionPanelMsg::ReceivedUpdateResponse(Ok(())));
+                        let onclick_delete = {
+                            let delta = delta.clone();
+                            ctx.link().callback(move |_| PredictionPanelMsg::DeleteDelta(delta.clone()))
+                        };
+
                         html! {
                             <tr key={ delta.id() }>
                                 <td>{ delta.name() }</td>
                                 <td>{ value }</td>
                                 <td>{ unc_fmt }</td>
                                 <td>{ dates_fmt }</td>
+                                <td>
+                                    <NewDelta prediction_id={ctx.props().prediction.id()} delta={Some(delta.clone())} oncreate={onupdate}/>
+                                    <i class="fa fa-trash" aria-hidden="true" onclick={onclick_delete}></i>
+                                </td>
                             </tr>
                         }
                     }).collect::<Html>()
@@ -327,6 +417,32 @@ impl PredictionPanel {
         });
     }
 
+    fn delete_delta_if_logged_in(&self, ctx: &Context<Self>, delta: Delta) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.delete_delta(ctx, &username, delta);
+        }
+    }
+
+    fn delete_delta(&self, ctx: &Context<Self>, username: &str, delta: Delta) {
+        let path = fully_qualified_path(
+            &replace_pattern(routes::DELTAS, path_patterns::DELTAS, username)
+                .expect("could not replace pattern in route"),
+        )
+        .expect("could not create path");
+
+        let client = Arc::new(self.client.clone());
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            log::debug!("deleting delta: {:?}", delta);
+            let request = client.delete(path).json(&delta);
+            let on_ok = ResponseAction::from(|_| Ok(()));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(PredictionPanelMsg::ReceivedUpdateResponse(response));
+        });
+    }
+
     fn update_prediction_if_logged_in(&self, ctx: &Context<Self>) {
         if let Some(username) = self.app_context.borrow_mut().username() {
             self.update_prediction(ctx, &username);
@@ -0,0 +1,305 @@
+use chrono::{Duration, Local, NaiveDate};
+use reqwest::Client;
+use shared::{
+    models::{NewDbDelta, Repetition},
+    path_patterns,
+    recurring::{RecurringDelta, RecurringQuery},
+    routes,
+};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::AppContext,
+    requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction},
+    InternalResponseError, ResponseResult,
+};
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct RecurringDeltasProps {
+    pub prediction_id: i32,
+    pub oncreate: Callback<()>,
+}
+
+pub enum RecurringDeltasMsg {
+    Open(bool),
+    FromChanged(String),
+    ToChanged(String),
+    SuggestRequested,
+    ReceivedSuggestions(ResponseResult<Vec<RecurringDelta>>),
+    AddRequested(usize),
+    ReceivedAddResponse(usize, ResponseResult<()>),
+}
+
+/// Suggests deltas for a prediction from the recurring transactions in the
+/// user's imported history.
+pub struct RecurringDeltas {
+    app_context: AppContext,
+    open: bool,
+    query: RecurringQuery,
+    date_error: Option<String>,
+    suggestions: Option<ResponseResult<Vec<RecurringDelta>>>,
+    add_response: Option<ResponseResult<()>>,
+}
+
+impl Component for RecurringDeltas {
+    type Message = RecurringDeltasMsg;
+    type Properties = RecurringDeltasProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let now = Local::now().naive_utc().date();
+
+        let (app_context, _) = ctx
+            .link()
+            .context(Callback::noop())
+            .expect("no AppContext provided");
+
+        RecurringDeltas {
+            app_context,
+            open: false,
+            query: RecurringQuery {
+                from: now,
+                to: now + Duration::days(365),
+            },
+            date_error: None,
+            suggestions: None,
+            add_response: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            RecurringDeltasMsg::Open(open) => {
+                self.open = open;
+                if !open {
+                    self.suggestions = None;
+                    self.add_response = None;
+                }
+            }
+            RecurringDeltasMsg::FromChanged(date) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(from) => {
+                        self.query.from = from;
+                        self.date_error = None;
+                    }
+                    Err(_) => self.date_error = Some(format!("invalid start date ({})", date)),
+                }
+            }
+            RecurringDeltasMsg::ToChanged(date) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(to) => {
+                        self.query.to = to;
+                        self.date_error = None;
+                    }
+                    Err(_) => self.date_error = Some(format!("invalid end date ({})", date)),
+                }
+            }
+            RecurringDeltasMsg::SuggestRequested => self.get_suggestions_if_logged_in(ctx),
+            RecurringDeltasMsg::ReceivedSuggestions(response) => self.suggestions = Some(response),
+            RecurringDeltasMsg::AddRequested(index) => self.post_delta_if_logged_in(ctx, index),
+            RecurringDeltasMsg::ReceivedAddResponse(index, response) => {
+                if response.is_ok() {
+                    if let Some(Ok(suggestions)) = &mut self.suggestions {
+                        suggestions.remove(index);
+                    }
+                    ctx.props().oncreate.emit(());
+                }
+                self.add_response = Some(response);
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if self.open {
+            self.view_open(ctx)
+        } else {
+            let onclick = ctx.link().callback(|_| RecurringDeltasMsg::Open(true));
+
+            html! {
+                <button {onclick}>{ "suggest from history" }</button>
+            }
+        }
+    }
+}
+
+fn describe(repetition: &Repetition) -> String {
+    match repetition {
+        Repetition::Monthly { repeat_on_day, .. } => format!("monthly on the {}", repeat_on_day),
+        Repetition::Weekly {
+            repeat_on_weekday, ..
+        } => format!("every {}", repeat_on_weekday),
+        Repetition::Biweekly {
+            repeat_on_weekday, ..
+        } => format!("every other {}", repeat_on_weekday),
+        Repetition::Daily { .. } => "every day".to_string(),
+        Repetition::Once { on } => format!("once on {}", on.format("%x")),
+    }
+}
+
+// Sub-views
+impl RecurringDeltas {
+    fn view_open(&self, ctx: &Context<Self>) -> Html {
+        let onchange_from = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| RecurringDeltasMsg::FromChanged(input.value()))
+        });
+        let onchange_to = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| RecurringDeltasMsg::ToChanged(input.value()))
+        });
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            RecurringDeltasMsg::SuggestRequested
+        });
+        let oncancel = ctx.link().callback(|_| RecurringDeltasMsg::Open(false));
+
+        html! {
+            <div>
+                <h4>{ "suggested from imported transactions" }</h4>
+                {
+                    if let Some(err) = &self.date_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <form {onsubmit}>
+                    <div>
+                        <label for="recurring-from">{ "repeat from:" }</label>
+                        <input type="date" id="recurring-from" value={self.query.from.format("%Y-%m-%d").to_string()} onchange={onchange_from}/>
+                    </div>
+                    <div>
+                        <label for="recurring-to">{ "repeat to:" }</label>
+                        <input type="date" id="recurring-to" value={self.query.to.format("%Y-%m-%d").to_string()} onchange={onchange_to}/>
+                    </div>
+                    <input type="submit" value="suggest"/>
+                    <input type="button" value="close" onclick={oncancel}/>
+                </form>
+                {
+                    if let Some(Err(err)) = &self.add_response {
+                        html! { <p>{ format!("error adding delta: {}", err) }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                { self.view_suggestions(ctx) }
+            </div>
+        }
+    }
+
+    fn view_suggestions(&self, ctx: &Context<Self>) -> Html {
+        match &self.suggestions {
+            Some(Ok(suggestions)) if suggestions.is_empty() => {
+                html! { <p>{ "no recurring transactions found" }</p> }
+            }
+            Some(Ok(suggestions)) => html! {
+                <table>
+                    <tr>
+                        <th>{ "name" }</th>
+                        <th>{ "value" }</th>
+                        <th>{ "uncertainty" }</th>
+                        <th>{ "occurs" }</th>
+                        <th>{ "seen" }</th>
+                        <th></th>
+                    </tr>
+                    {
+                        suggestions.iter().enumerate().map(|(i, suggestion)| {
+                            let onclick = ctx.link().callback(move |_| RecurringDeltasMsg::AddRequested(i));
+
+                            html! {
+                                <tr>
+                                    <td>{ suggestion.name.clone() }</td>
+                                    <td>{ format!("{:.2}", suggestion.value) }</td>
+                                    <td>{ format!("+${:.2} / -${:.2}", suggestion.positive_uncertainty, suggestion.negative_uncertainty) }</td>
+                                    <td>{ describe(&suggestion.repetition) }</td>
+                                    <td>{ format!("{} times", suggestion.occurrences) }</td>
+                                    <td><i class="fa fa-plus" aria-hidden="true" {onclick}></i></td>
+                                </tr>
+                            }
+                        }).collect::<Html>()
+                    }
+                </table>
+            },
+            Some(Err(err)) => {
+                html! { <p>{ format!("error finding recurring transactions: {}", err) }</p> }
+            }
+            None => html! {},
+        }
+    }
+}
+
+// Request functions
+impl RecurringDeltas {
+    fn get_suggestions_if_logged_in(&self, ctx: &Context<Self>) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.get_suggestions(ctx, username)
+        }
+    }
+
+    fn get_suggestions(&self, ctx: &Context<Self>, username: &str) {
+        let path = fully_qualified_path(
+            &replace_pattern(routes::RECURRING, path_patterns::RECURRING, username)
+                .expect("could not replace pattern"),
+        )
+        .expect("could not create fully qualified path");
+
+        let query = self.query;
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = Client::new().get(path).query(&query);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<Vec<RecurringDelta>>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError(
+                            "recurring deltas",
+                            err.to_string(),
+                        )
+                    })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(RecurringDeltasMsg::ReceivedSuggestions(response));
+        })
+    }
+
+    fn post_delta_if_logged_in(&self, ctx: &Context<Self>, index: usize) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.post_delta(ctx, username, index)
+        }
+    }
+
+    fn post_delta(&self, ctx: &Context<Self>, username: &str, index: usize) {
+        let suggestion = match &self.suggestions {
+            Some(Ok(suggestions)) => match suggestions.get(index) {
+                Some(suggestion) => suggestion.clone(),
+                None => return,
+            },
+            _ => return,
+        };
+
+        let new_db_delta: NewDbDelta = suggestion.into_new_delta(ctx.props().prediction_id).into();
+
+        let path = fully_qualified_path(
+            &replace_pattern(routes::DELTAS, path_patterns::DELTAS, username)
+                .expect("could not replace pattern"),
+        )
+        .expect("could not create fully qualified path");
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = Client::new().post(path).json(&new_db_delta);
+            let on_ok = ResponseAction::from(|_| Ok(()));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(RecurringDeltasMsg::ReceivedAddResponse(index, response));
+        })
+    }
+}
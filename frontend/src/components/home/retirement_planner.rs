@@ -0,0 +1,303 @@
+use chrono::{Datelike, Local, NaiveDate};
+use shared::{
+    models::PredictionWithDeltas,
+    projection::retirement::{self, RetirementRequest, WithdrawalRule},
+};
+use stylist::{css, YieldStyle};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+const DATE_FMT: &str = "%x";
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct RetirementPlannerProps {
+    pub prediction: PredictionWithDeltas,
+}
+
+pub enum RetirementPlannerMsg {
+    Open(bool),
+    BornOnChanged(String),
+    RetireOnChanged(String),
+    UntilAgeChanged(String),
+    StartingBalanceChanged(String),
+    ExpectedReturnChanged(String),
+    InflationChanged(String),
+    ContributionToggled(i32),
+    WithdrawalRuleSelected(String),
+    WithdrawalChanged(String),
+}
+
+/// Projects retirement savings decades ahead, a year at a time, and shows
+/// when they would run out.
+pub struct RetirementPlanner {
+    open: bool,
+    request: RetirementRequest,
+    form_error: Option<String>,
+}
+
+impl Component for RetirementPlanner {
+    type Message = RetirementPlannerMsg;
+    type Properties = RetirementPlannerProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let today = Local::now().naive_utc().date();
+
+        RetirementPlanner {
+            open: false,
+            request: RetirementRequest {
+                prediction_id: ctx.props().prediction.id(),
+                born_on: NaiveDate::from_ymd(today.year() - 30, 1, 1),
+                start_on: today,
+                retire_on: NaiveDate::from_ymd(today.year() + 35, 1, 1),
+                until_age: 95,
+                starting_balance: 0.,
+                expected_return: 6.,
+                inflation: 2.5,
+                contribution_delta_ids: Vec::new(),
+                withdrawal: WithdrawalRule::Percentage { rate: 4. },
+            },
+            form_error: None,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let request = &mut self.request;
+        let parsed = match msg {
+            RetirementPlannerMsg::Open(open) => {
+                self.open = open;
+                Ok(())
+            }
+            RetirementPlannerMsg::BornOnChanged(date) => {
+                parse_date(&date, "birth date").map(|date| request.born_on = date)
+            }
+            RetirementPlannerMsg::RetireOnChanged(date) => {
+                parse_date(&date, "retirement date").map(|date| request.retire_on = date)
+            }
+            RetirementPlannerMsg::UntilAgeChanged(value) => {
+                parse(&value, "age").map(|value| request.until_age = value)
+            }
+            RetirementPlannerMsg::StartingBalanceChanged(value) => {
+                parse(&value, "current savings").map(|value| request.starting_balance = value)
+            }
+            RetirementPlannerMsg::ExpectedReturnChanged(value) => {
+                parse(&value, "expected return").map(|value| request.expected_return = value)
+            }
+            RetirementPlannerMsg::InflationChanged(value) => {
+                parse(&value, "inflation").map(|value| request.inflation = value)
+            }
+            RetirementPlannerMsg::ContributionToggled(delta_id) => {
+                let ids = &mut request.contribution_delta_ids;
+                match ids.iter().position(|id| *id == delta_id) {
+                    Some(i) => {
+                        ids.remove(i);
+                    }
+                    None => ids.push(delta_id),
+                }
+                Ok(())
+            }
+            RetirementPlannerMsg::WithdrawalRuleSelected(rule) => {
+                request.withdrawal = match rule.as_str() {
+                    "fixed" => WithdrawalRule::Fixed { annual: 40000. },
+                    _ => WithdrawalRule::Percentage { rate: 4. },
+                };
+                Ok(())
+            }
+            RetirementPlannerMsg::WithdrawalChanged(value) => match &mut request.withdrawal {
+                WithdrawalRule::Fixed { annual } => {
+                    parse(&value, "withdrawal").map(|value| *annual = value)
+                }
+                WithdrawalRule::Percentage { rate } => {
+                    parse(&value, "withdrawal rate").map(|value| *rate = value)
+                }
+            },
+        };
+
+        self.form_error = parsed
+            .and_then(|_| self.request.validate().map_err(|err| err.to_string()))
+            .err();
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if self.open {
+            self.view_open(ctx)
+        } else {
+            let onclick = ctx.link().callback(|_| RetirementPlannerMsg::Open(true));
+
+            html! {
+                <div>
+                    <button {onclick}>{ "plan retirement" }</button>
+                </div>
+            }
+        }
+    }
+}
+
+impl YieldStyle for RetirementPlanner {
+    fn style_from(&self) -> stylist::StyleSource<'static> {
+        css!(
+            r#"
+            max-height: 400px;
+            overflow-y: auto;
+            "#
+        )
+    }
+}
+
+fn input_callback(
+    ctx: &Context<RetirementPlanner>,
+    msg: fn(String) -> RetirementPlannerMsg,
+) -> Callback<Event> {
+    ctx.link().batch_callback(move |ev: Event| {
+        let target = ev.target();
+        let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+        input.map(|input| msg(input.value()))
+    })
+}
+
+fn parse<T: std::str::FromStr>(value: &str, label: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid {} ({})", label, value))
+}
+
+fn parse_date(value: &str, label: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("invalid {} ({})", label, value))
+}
+
+// Sub-views
+impl RetirementPlanner {
+    fn view_open(&self, ctx: &Context<Self>) -> Html {
+        let oninput_rule = ctx.link().callback(|ev: InputEvent| {
+            let event = ev.dyn_into::<Event>().unwrap_throw();
+            let event_target = event.target().unwrap_throw();
+            let target = event_target.dyn_into::<HtmlSelectElement>().unwrap_throw();
+            RetirementPlannerMsg::WithdrawalRuleSelected(target.value())
+        });
+        let onclose = ctx.link().callback(|_| RetirementPlannerMsg::Open(false));
+        let request = &self.request;
+        let (withdrawal_label, withdrawal_value) = match request.withdrawal {
+            WithdrawalRule::Fixed { annual } => ("withdraw per year (today's money):", annual),
+            WithdrawalRule::Percentage { rate } => ("withdraw in first year (% of savings):", rate),
+        };
+
+        html! {
+            <div>
+                <h3>{ "retirement" }</h3>
+                {
+                    if let Some(err) = &self.form_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <div>
+                    <label for="retirement-born">{ "born on:" }</label>
+                    <input type="date" id="retirement-born" value={request.born_on.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, RetirementPlannerMsg::BornOnChanged)}/>
+                </div>
+                <div>
+                    <label for="retirement-on">{ "retire on:" }</label>
+                    <input type="date" id="retirement-on" value={request.retire_on.format("%Y-%m-%d").to_string()} onchange={input_callback(ctx, RetirementPlannerMsg::RetireOnChanged)}/>
+                </div>
+                <div>
+                    <label for="retirement-until">{ "plan until age:" }</label>
+                    <input type="number" id="retirement-until" min="1" max={retirement::MAX_AGE.to_string()} value={request.until_age.to_string()} onchange={input_callback(ctx, RetirementPlannerMsg::UntilAgeChanged)}/>
+                </div>
+                <div>
+                    <label for="retirement-balance">{ "current savings:" }</label>
+                    <input type="number" id="retirement-balance" step="0.01" value={request.starting_balance.to_string()} onchange={input_callback(ctx, RetirementPlannerMsg::StartingBalanceChanged)}/>
+                </div>
+                <div>
+                    <label for="retirement-return">{ "expected annual return (%):" }</label>
+                    <input type="number" id="retirement-return" step="0.1" value={request.expected_return.to_string()} onchange={input_callback(ctx, RetirementPlannerMsg::ExpectedReturnChanged)}/>
+                </div>
+                <div>
+                    <label for="retirement-inflation">{ "inflation (%):" }</label>
+                    <input type="number" id="retirement-inflation" step="0.1" value={request.inflation.to_string()} onchange={input_callback(ctx, RetirementPlannerMsg::InflationChanged)}/>
+                </div>
+                <fieldset>
+                    <legend>{ "saving through:" }</legend>
+                    {
+                        ctx.props().prediction.deltas().iter().map(|delta| {
+                            let delta_id = delta.id();
+                            let onclick = ctx.link().callback(move |_| RetirementPlannerMsg::ContributionToggled(delta_id));
+                            let id = format!("retirement-delta-{}", delta_id);
+
+                            html! {
+                                <div key={ delta_id }>
+                                    <input type="checkbox" id={id.clone()} checked={request.contribution_delta_ids.contains(&delta_id)} {onclick}/>
+                                    <label for={id}>{ delta.name() }</label>
+                                </div>
+                            }
+                        }).collect::<Html>()
+                    }
+                </fieldset>
+                <div>
+                    <label for="retirement-rule">{ "withdrawals:" }</label>
+                    <select id="retirement-rule" oninput={oninput_rule}>
+                        <option value="percentage" selected={matches!(request.withdrawal, WithdrawalRule::Percentage { .. })}>{ WithdrawalRule::Percentage { rate: 0. }.name() }</option>
+                        <option value="fixed" selected={matches!(request.withdrawal, WithdrawalRule::Fixed { .. })}>{ WithdrawalRule::Fixed { annual: 0. }.name() }</option>
+                    </select>
+                </div>
+                <div>
+                    <label for="retirement-withdrawal">{ withdrawal_label }</label>
+                    <input type="number" id="retirement-withdrawal" step="0.01" value={withdrawal_value.to_string()} onchange={input_callback(ctx, RetirementPlannerMsg::WithdrawalChanged)}/>
+                </div>
+                {
+                    if self.form_error.is_none() {
+                        self.view_projection(ctx)
+                    } else {
+                        html! {}
+                    }
+                }
+                <input type="button" value="close" onclick={onclose}/>
+            </div>
+        }
+    }
+
+    fn view_projection(&self, ctx: &Context<Self>) -> Html {
+        let projection = retirement::project(&self.request, ctx.props().prediction.deltas());
+
+        let outcome = match projection.depletion_age {
+            Some(age) => format!("savings run out at age {}", age),
+            None => format!("savings last past age {}", self.request.until_age),
+        };
+
+        html! {
+            <>
+                <p>{ format!("savings at retirement: ${:.2}", projection.balance_at_retirement) }</p>
+                <p>{ outcome }</p>
+                <div class={self.style()}>
+                    <table>
+                        <tr>
+                            <th>{ "year from" }</th>
+                            <th>{ "age" }</th>
+                            <th>{ "contributions" }</th>
+                            <th>{ "growth" }</th>
+                            <th>{ "withdrawals" }</th>
+                            <th>{ "shortfall" }</th>
+                            <th>{ "balance" }</th>
+                            <th>{ "in today's money" }</th>
+                        </tr>
+                        {
+                            projection.years.iter().map(|year| html! {
+                                <tr key={ year.starts_on.to_string() }>
+                                    <td>{ year.starts_on.format(DATE_FMT).to_string() }</td>
+                                    <td>{ year.age }</td>
+                                    <td>{ format!("{:.2}", year.contributions) }</td>
+                                    <td>{ format!("{:.2}", year.growth) }</td>
+                                    <td>{ format!("{:.2}", year.withdrawals) }</td>
+                                    <td>{ format!("{:.2}", year.shortfall) }</td>
+                                    <td>{ format!("{:.2}", year.balance) }</td>
+                                    <td>{ format!("{:.2}", year.real_balance) }</td>
+                                </tr>
+                            }).collect::<Html>()
+                        }
+                    </table>
+                </div>
+            </>
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use chrono::{Datelike, Local};
+use shared::{
+    models::PredictionWithDeltas,
+    taxes::{self, TaxTable, DEFAULT_TABLES},
+};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct TaxEstimatorProps {
+    pub prediction: PredictionWithDeltas,
+}
+
+pub enum TaxEstimatorMsg {
+    Open(bool),
+    YearChanged(String),
+    TableSelected(String),
+    StandardDeductionChanged(String),
+    BracketsChanged(String),
+}
+
+/// Estimates a year's income tax from a prediction's income and compares it
+/// with the withholding already in the prediction.
+pub struct TaxEstimator {
+    open: bool,
+    year: i32,
+    table: TaxTable,
+    brackets: String,
+    form_error: Option<String>,
+}
+
+impl Component for TaxEstimator {
+    type Message = TaxEstimatorMsg;
+    type Properties = TaxEstimatorProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        let table = DEFAULT_TABLES[0].clone();
+
+        TaxEstimator {
+            open: false,
+            year: Local::now().naive_utc().date().year(),
+            brackets: taxes::format_brackets(&table.brackets),
+            table,
+            form_error: None,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            TaxEstimatorMsg::Open(open) => self.open = open,
+            TaxEstimatorMsg::YearChanged(year) => match year.parse() {
+                Ok(year) => {
+                    self.year = year;
+                    self.form_error = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid year ({})", year)),
+            },
+            TaxEstimatorMsg::TableSelected(name) => {
+                if let Some(table) = DEFAULT_TABLES.iter().find(|table| table.name == name) {
+                    self.table = table.clone();
+                    self.brackets = taxes::format_brackets(&table.brackets);
+                    self.form_error = None;
+                }
+            }
+            TaxEstimatorMsg::StandardDeductionChanged(value) => match value.parse() {
+                Ok(value) => {
+                    self.table.standard_deduction = value;
+                    self.form_error = None;
+                }
+                Err(_) => self.form_error = Some(format!("invalid standard deduction ({})", value)),
+            },
+            TaxEstimatorMsg::BracketsChanged(brackets) => {
+                match taxes::parse_brackets(&brackets) {
+                    Ok(parsed) => {
+                        self.table.brackets = parsed;
+                        self.form_error = None;
+                    }
+                    Err(err) => self.form_error = Some(err.to_string()),
+                }
+                self.brackets = brackets;
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if self.open {
+            self.view_open(ctx)
+        } else {
+            let onclick = ctx.link().callback(|_| TaxEstimatorMsg::Open(true));
+
+            html! {
+                <div>
+                    <button {onclick}>{ "estimate taxes" }</button>
+                </div>
+            }
+        }
+    }
+}
+
+fn input_callback(
+    ctx: &Context<TaxEstimator>,
+    msg: fn(String) -> TaxEstimatorMsg,
+) -> Callback<Event> {
+    ctx.link().batch_callback(move |ev: Event| {
+        let target = ev.target();
+        let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+        input.map(|input| msg(input.value()))
+    })
+}
+
+// Sub-views
+impl TaxEstimator {
+    fn view_open(&self, ctx: &Context<Self>) -> Html {
+        let oninput_table = ctx.link().callback(|ev: InputEvent| {
+            let event = ev.dyn_into::<Event>().unwrap_throw();
+            let event_target = event.target().unwrap_throw();
+            let target = event_target.dyn_into::<HtmlSelectElement>().unwrap_throw();
+            TaxEstimatorMsg::TableSelected(target.value())
+        });
+        let onclose = ctx.link().callback(|_| TaxEstimatorMsg::Open(false));
+
+        html! {
+            <div>
+                <h3>{ "tax estimate" }</h3>
+                {
+                    if let Some(err) = &self.form_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <div>
+                    <label for="tax-year">{ "year:" }</label>
+                    <input type="number" id="tax-year" value={self.year.to_string()} onchange={input_callback(ctx, TaxEstimatorMsg::YearChanged)}/>
+                </div>
+                <div>
+                    <label for="tax-table">{ "starting from:" }</label>
+                    <select id="tax-table" oninput={oninput_table}>
+                        {
+                            DEFAULT_TABLES.iter().map(|table| html! {
+                                <option value={table.name.clone()} selected={table.name == self.table.name}>{ table.name.clone() }</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                </div>
+                <div>
+                    <label for="tax-deduction">{ "standard deduction:" }</label>
+                    <input type="number" id="tax-deduction" step="0.01" value={self.table.standard_deduction.to_string()} onchange={input_callback(ctx, TaxEstimatorMsg::StandardDeductionChanged)}/>
+                </div>
+                <div>
+                    <label for="tax-brackets">{ "brackets (over:rate):" }</label>
+                    <input type="text" id="tax-brackets" size="80" value={self.brackets.clone()} onchange={input_callback(ctx, TaxEstimatorMsg::BracketsChanged)}/>
+                </div>
+                { self.view_estimate(ctx) }
+                <input type="button" value="close" onclick={onclose}/>
+            </div>
+        }
+    }
+
+    fn view_estimate(&self, ctx: &Context<Self>) -> Html {
+        let estimate =
+            match taxes::estimate(ctx.props().prediction.deltas(), self.year, &self.table) {
+                Ok(estimate) => estimate,
+                Err(err) => return html! { <p>{ err.to_string() }</p> },
+            };
+
+        let outcome = if estimate.refund >= 0. {
+            format!("expected refund: ${:.2}", estimate.refund)
+        } else {
+            format!("expected balance due: ${:.2}", -estimate.refund)
+        };
+
+        html! {
+            <>
+                <table>
+                    <tr>
+                        <td>{ "income" }</td>
+                        <td>{ format!("{:.2}", estimate.income) }</td>
+                    </tr>
+                    <tr>
+                        <td>{ "pre-tax deductions" }</td>
+                        <td>{ format!("-{:.2}", estimate.pre_tax_deductions) }</td>
+                    </tr>
+                    <tr>
+                        <td>{ "standard deduction" }</td>
+                        <td>{ format!("-{:.2}", estimate.standard_deduction) }</td>
+                    </tr>
+                    <tr>
+                        <td>{ "taxable income" }</td>
+                        <td>{ format!("{:.2}", estimate.taxable_income) }</td>
+                    </tr>
+                    <tr>
+                        <td>{ "estimated tax" }</td>
+                        <td>{ format!("{:.2}", estimate.tax) }</td>
+                    </tr>
+                    <tr>
+                        <td>{ "withheld" }</td>
+                        <td>{ format!("{:.2}", estimate.withheld) }</td>
+                    </tr>
+                </table>
+                <p>{ outcome }</p>
+            </>
+        }
+    }
+}
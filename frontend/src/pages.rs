@@ -1,11 +1,21 @@
 mod account;
+mod forgot_password;
 pub mod home;
+mod import;
 mod login;
 mod logout;
 mod register;
+mod reset_password;
+mod rules;
+mod verify_email;
 
 pub use account::Account;
+pub use forgot_password::ForgotPassword;
 pub use home::Home;
+pub use import::Import;
 pub use login::Login;
 pub use logout::Logout;
 pub use register::Register;
+pub use reset_password::ResetPassword;
+pub use rules::Rules;
+pub use verify_email::VerifyEmail;
@@ -1,28 +1,68 @@
 use chrono::{DateTime, Local, Utc};
 use gloo_dialogs::confirm;
 use reqwest::Client;
-use shared::models::UserAccount;
-use shared::routes;
+use shared::models::{
+    SessionInfo, UserAccount, UserEmailChangeForm, UserPasswordChangeForm, UserUsernameChangeForm,
+};
+use shared::{path_patterns, routes};
 use std::sync::Arc;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew_router::components::Redirect;
 
 use crate::app::Route;
 use crate::components::AppContext;
-use crate::components::{Header, Loading, Unauthorized};
-use crate::requests::{fully_qualified_path, Requester, ResponseAction};
+use crate::components::{ApiTokens, Header, Loading, TwoFactor, Unauthorized};
+use crate::requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction};
 use crate::InternalResponseError;
 use crate::ResponseResult;
 
 const DATETIME_FORMAT: &str = "%a %h %d %Y %r %Z";
 
+fn input_callback<C>(ctx: &Context<Account>, msg: C) -> Callback<Event>
+where
+    C: Fn(String) -> AccountMsg + Copy + 'static,
+{
+    ctx.link().batch_callback(move |ev: Event| {
+        let target = ev.target();
+        let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+        input.map(|input| msg(input.value()))
+    })
+}
+
+fn submit_callback<C>(ctx: &Context<Account>, msg: C) -> Callback<FocusEvent>
+where
+    C: Fn() -> AccountMsg + Copy + 'static,
+{
+    ctx.link().callback(move |ev: FocusEvent| {
+        ev.prevent_default();
+        msg()
+    })
+}
+
 pub enum AccountMsg {
     AppContextUpdated(AppContext),
     ReceivedResponse(ResponseResult<UserAccount>),
-    ResetPassword,
+    CurrentPasswordChanged(String),
+    NewPasswordChanged(String),
+    ConfirmPasswordChanged(String),
+    PasswordChangeSubmitted,
+    ReceivedPasswordChangeResponse(ResponseResult<()>),
+    EmailChanged(String),
+    EmailChangeSubmitted,
+    ReceivedEmailChangeResponse(ResponseResult<String>),
+    VerificationRequested,
+    ReceivedVerificationResponse(ResponseResult<()>),
+    UsernameChanged(String),
+    UsernameChangeSubmitted,
+    ReceivedUsernameChangeResponse(ResponseResult<String>),
     DeleteAccountInitiated,
     DeleteAccountConfirmed,
     ReceivedDeleteAccountResponse(ResponseResult<Route>),
+    ReceivedSessionsResponse(ResponseResult<Vec<SessionInfo>>),
+    RevokeSession(SessionInfo),
+    ReceivedRevokeSessionResponse(ResponseResult<SessionInfo>),
 }
 
 pub struct Account {
@@ -30,6 +70,17 @@ pub struct Account {
     account: Option<ResponseResult<UserAccount>>,
     client: Client,
     delete_response: Option<ResponseResult<Route>>,
+    sessions: Option<ResponseResult<Vec<SessionInfo>>>,
+    revoke_response: Option<ResponseResult<SessionInfo>>,
+    current_password: String,
+    new_password: String,
+    confirm_password: String,
+    password_response: Option<ResponseResult<()>>,
+    new_email: String,
+    email_response: Option<ResponseResult<String>>,
+    verification_response: Option<ResponseResult<()>>,
+    new_username: String,
+    username_response: Option<ResponseResult<String>>,
 }
 
 impl Component for Account {
@@ -47,13 +98,25 @@ impl Component for Account {
             account: None,
             client: Client::new(),
             delete_response: None,
+            sessions: None,
+            revoke_response: None,
+            current_password: String::new(),
+            new_password: String::new(),
+            confirm_password: String::new(),
+            password_response: None,
+            new_email: String::new(),
+            email_response: None,
+            verification_response: None,
+            new_username: String::new(),
+            username_response: None,
         }
     }
 
     fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
         if first_render {
             if let Some(username) = self.app_context.borrow_mut().username() {
-                self.get_account(ctx, &username)
+                self.get_account(ctx, &username);
+                self.get_sessions(ctx, &username);
             } else {
                 ctx.link()
                     .callback(|_| {
@@ -69,6 +132,10 @@ impl Component for Account {
             html! {
                 <Redirect<Route> to={redirect_route.clone()} />
             }
+        } else if let Some(Ok(SessionInfo { current: true, .. })) = self.revoke_response {
+            html! {
+                <Redirect<Route> to={Route::Login} />
+            }
         } else {
             match &self.account {
                 Some(Ok(_account)) => self.view_logged_in(ctx),
@@ -81,7 +148,68 @@ impl Component for Account {
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             AccountMsg::ReceivedResponse(resp) => self.account = Some(resp),
-            AccountMsg::ResetPassword => todo!(),
+            AccountMsg::CurrentPasswordChanged(password) => self.current_password = password,
+            AccountMsg::NewPasswordChanged(password) => self.new_password = password,
+            AccountMsg::ConfirmPasswordChanged(password) => self.confirm_password = password,
+            AccountMsg::PasswordChangeSubmitted => {
+                let form = UserPasswordChangeForm::new(
+                    self.current_password.clone(),
+                    self.new_password.clone(),
+                    self.confirm_password.clone(),
+                );
+                self.patch_user(
+                    ctx,
+                    routes::USER_PASSWORD,
+                    &form,
+                    AccountMsg::ReceivedPasswordChangeResponse,
+                );
+            }
+            AccountMsg::ReceivedPasswordChangeResponse(response) => {
+                if response.is_ok() {
+                    self.current_password.clear();
+                    self.new_password.clear();
+                    self.confirm_password.clear();
+                }
+                self.password_response = Some(response);
+            }
+            AccountMsg::EmailChanged(email) => self.new_email = email,
+            AccountMsg::EmailChangeSubmitted => {
+                let form = UserEmailChangeForm::new(self.new_email.clone());
+                let email = form.email().to_string();
+                self.patch_user(ctx, routes::USER_EMAIL, &form, move |response| {
+                    AccountMsg::ReceivedEmailChangeResponse(response.map(|_| email.clone()))
+                });
+            }
+            AccountMsg::ReceivedEmailChangeResponse(response) => {
+                if let (Ok(email), Some(Ok(account))) = (&response, &mut self.account) {
+                    account.email = email.clone();
+                    account.verified_at = None;
+                }
+                self.email_response = Some(response);
+                self.verification_response = None;
+            }
+            AccountMsg::VerificationRequested => self.request_verification(ctx),
+            AccountMsg::ReceivedVerificationResponse(response) => {
+                self.verification_response = Some(response);
+            }
+            AccountMsg::UsernameChanged(username) => self.new_username = username,
+            AccountMsg::UsernameChangeSubmitted => {
+                let form = UserUsernameChangeForm::new(self.new_username.clone());
+                let username = form.username().to_string();
+                self.patch_user(ctx, routes::USER_USERNAME, &form, move |response| {
+                    AccountMsg::ReceivedUsernameChangeResponse(response.map(|_| username.clone()))
+                });
+            }
+            AccountMsg::ReceivedUsernameChangeResponse(response) => {
+                if let (Ok(username), Some(Ok(account))) = (&response, &mut self.account) {
+                    account.username = username.clone();
+                    self.app_context
+                        .borrow_mut()
+                        .login(username.clone())
+                        .expect("could not log in");
+                }
+                self.username_response = Some(response);
+            }
             AccountMsg::DeleteAccountInitiated => {
                 let delete_confirmed = confirm(
                     "Are you sure you want to delete your moolah account? This action cannot be undone.",
@@ -123,6 +251,25 @@ impl Component for Account {
                 }
                 self.delete_response = Some(response);
             }
+            AccountMsg::ReceivedSessionsResponse(response) => self.sessions = Some(response),
+            AccountMsg::RevokeSession(session) => {
+                if let Some(username) = self.app_context.borrow_mut().username() {
+                    self.revoke_session(ctx, &username, session);
+                }
+            }
+            AccountMsg::ReceivedRevokeSessionResponse(response) => {
+                match &response {
+                    Ok(session) if session.current => self.app_context.borrow_mut().logout(),
+                    Ok(session) => {
+                        let id = session.id;
+                        if let Some(Ok(sessions)) = &mut self.sessions {
+                            sessions.retain(|session| session.id != id);
+                        }
+                    }
+                    Err(_) => log::error!("error revoking session"),
+                }
+                self.revoke_response = Some(response);
+            }
             AccountMsg::AppContextUpdated(_context) => {}
         }
         true
@@ -138,7 +285,6 @@ impl Account {
             .as_ref()
             .expect("this should never panic - should have account");
 
-        let onclick_reset_password = ctx.link().callback(|_| AccountMsg::ResetPassword);
         let onclick_delete_account = ctx.link().callback(|_| AccountMsg::DeleteAccountInitiated);
 
         let created = DateTime::<Utc>::from_utc(account.created, Utc)
@@ -154,16 +300,29 @@ impl Account {
             <>
                 <Header title="account" heading={account.username.clone()}/>
 
+                <div>
+                    <h2>{ "username" }</h2>
+                    { self.view_username_form(ctx) }
+                </div>
+
                 <div>
                     <h2>{ "email" }</h2>
                     <p>{ account.email.clone() }</p>
+                    { self.view_verification(ctx, account) }
+                    { self.view_email_form(ctx) }
                 </div>
 
                 <div>
                     <h2>{ "password" }</h2>
-                    <button onclick={onclick_reset_password}>{ "reset password" }</button>
+                    { self.view_password_form(ctx) }
                 </div>
 
+                <TwoFactor username={account.username.clone()} />
+
+                { self.view_sessions(ctx) }
+
+                <ApiTokens username={account.username.clone()} />
+
                 <div>
                     <h2>{ "account" }</h2>
                     <div>{ format!("created: {}", created) }</div>
@@ -183,6 +342,137 @@ impl Account {
         }
     }
 
+    fn view_verification(&self, ctx: &Context<Self>, account: &UserAccount) -> Html {
+        if let Some(verified_at) = account.verified_at {
+            let verified_at = DateTime::<Utc>::from_utc(verified_at, Utc)
+                .with_timezone(&Local)
+                .format(DATETIME_FORMAT)
+                .to_string();
+
+            return html! { <p>{ format!("verified: {}", verified_at) }</p> };
+        }
+
+        let onclick = ctx.link().callback(|_| AccountMsg::VerificationRequested);
+
+        html! {
+            <div>
+                <p>{ "not verified - password reset emails are only sent to verified addresses" }</p>
+                <button {onclick}>{ "resend verification email" }</button>
+                { view_response(&self.verification_response, "verification email sent") }
+            </div>
+        }
+    }
+
+    fn view_username_form(&self, ctx: &Context<Self>) -> Html {
+        let onchange = input_callback(ctx, AccountMsg::UsernameChanged);
+        let onsubmit = submit_callback(ctx, || AccountMsg::UsernameChangeSubmitted);
+
+        html! {
+            <form {onsubmit}>
+                { view_response(&self.username_response, "username changed") }
+                <input type="text" placeholder="new username" value={self.new_username.clone()} {onchange} required=true/>
+                <input type="submit" value="change username"/>
+            </form>
+        }
+    }
+
+    fn view_email_form(&self, ctx: &Context<Self>) -> Html {
+        let onchange = input_callback(ctx, AccountMsg::EmailChanged);
+        let onsubmit = submit_callback(ctx, || AccountMsg::EmailChangeSubmitted);
+
+        html! {
+            <form {onsubmit}>
+                { view_response(&self.email_response, "email changed") }
+                <input type="email" placeholder="new email" value={self.new_email.clone()} {onchange} required=true/>
+                <input type="submit" value="change email"/>
+            </form>
+        }
+    }
+
+    fn view_password_form(&self, ctx: &Context<Self>) -> Html {
+        let onchange_current = input_callback(ctx, AccountMsg::CurrentPasswordChanged);
+        let onchange_new = input_callback(ctx, AccountMsg::NewPasswordChanged);
+        let onchange_confirm = input_callback(ctx, AccountMsg::ConfirmPasswordChanged);
+        let onsubmit = submit_callback(ctx, || AccountMsg::PasswordChangeSubmitted);
+
+        html! {
+            <form {onsubmit}>
+                { view_response(&self.password_response, "password changed, other sessions were logged out") }
+                <div>
+                    <label for="current-password">{ "current password:" }</label>
+                    <input id="current-password" type="password" value={self.current_password.clone()} onchange={onchange_current} required=true/>
+                </div>
+                <div>
+                    <label for="new-password">{ "new password:" }</label>
+                    <input id="new-password" type="password" value={self.new_password.clone()} onchange={onchange_new} required=true/>
+                </div>
+                <div>
+                    <label for="confirm-password">{ "confirm new password:" }</label>
+                    <input id="confirm-password" type="password" value={self.confirm_password.clone()} onchange={onchange_confirm} required=true/>
+                </div>
+                <input type="submit" value="change password"/>
+            </form>
+        }
+    }
+
+    fn view_sessions(&self, ctx: &Context<Self>) -> Html {
+        let format_time = |time| {
+            DateTime::<Utc>::from_utc(time, Utc)
+                .with_timezone(&Local)
+                .format(DATETIME_FORMAT)
+                .to_string()
+        };
+
+        html! {
+            <div>
+                <h2>{ "sessions" }</h2>
+                {
+                    if let Some(Err(revoke_err)) = &self.revoke_response {
+                        html!{<p>{ revoke_err }</p>}
+                    } else {
+                        html!{}
+                    }
+                }
+                {
+                    match &self.sessions {
+                        Some(Ok(sessions)) => html! {
+                            <table>
+                                <tr>
+                                    <th>{ "device" }</th>
+                                    <th>{ "signed in" }</th>
+                                    <th>{ "last seen" }</th>
+                                    <th></th>
+                                </tr>
+                                {
+                                    sessions.iter().map(|session| {
+                                        let onclick = {
+                                            let session = session.clone();
+                                            ctx.link().callback(move |_| AccountMsg::RevokeSession(session.clone()))
+                                        };
+
+                                        html! {
+                                            <tr key={ session.id }>
+                                                <td>
+                                                    { session.user_agent.clone().unwrap_or_else(|| "unknown".into()) }
+                                                    { if session.current { " (this browser)" } else { "" } }
+                                                </td>
+                                                <td>{ format_time(session.created) }</td>
+                                                <td>{ format_time(session.last_seen) }</td>
+                                                <td><button {onclick}>{ "revoke" }</button></td>
+                                            </tr>
+                                        }
+                                    }).collect::<Html>()
+                                }
+                            </table>
+                        },
+                        Some(Err(err)) => html! { <p>{ err }</p> },
+                        None => html! { <Loading /> },
+                    }
+                }
+            </div>
+        }
+    }
+
     fn view_not_logged_in(&self, _ctx: &Context<Self>) -> Html {
         html! {
             <>
@@ -229,4 +519,108 @@ impl Account {
             scope.send_message(AccountMsg::ReceivedResponse(response));
         });
     }
+
+    /// Send `form` to one of the user update routes, turning the response into
+    /// a message with `msg`.
+    fn patch_user<F, M>(&self, ctx: &Context<Self>, route: &'static str, form: &F, msg: M)
+    where
+        F: serde::Serialize,
+        M: Fn(ResponseResult<()>) -> AccountMsg + 'static,
+    {
+        let username = match self.app_context.borrow_mut().username() {
+            Some(username) => username.to_owned(),
+            None => return,
+        };
+
+        let path = fully_qualified_path(route).expect("could not create path");
+        let query = [("username", username)];
+
+        let client = Arc::new(self.client.clone());
+        let request = client.patch(path).query(&query).json(form);
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let on_ok = ResponseAction::from(|_| Ok(()));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(msg(response));
+        });
+    }
+
+    fn request_verification(&self, ctx: &Context<Self>) {
+        let username = match self.app_context.borrow_mut().username() {
+            Some(username) => username.to_owned(),
+            None => return,
+        };
+
+        let path = fully_qualified_path(routes::EMAIL_VERIFICATION_REQUEST)
+            .expect("could not create path");
+        let query = [("username", username)];
+
+        let client = Arc::new(self.client.clone());
+        let request = client.post(path).query(&query);
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let on_ok = ResponseAction::from(|_| Ok(()));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(AccountMsg::ReceivedVerificationResponse(response));
+        });
+    }
+
+    fn sessions_path(&self, username: &str) -> String {
+        fully_qualified_path(
+            &replace_pattern(routes::SESSIONS, path_patterns::SESSIONS, username)
+                .expect("could not replace pattern in route"),
+        )
+        .expect("could not create path")
+    }
+
+    fn get_sessions(&self, ctx: &Context<Self>, username: &str) {
+        let path = self.sessions_path(username);
+
+        let scope = ctx.link().clone();
+        let client = Arc::new(self.client.clone());
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = client.get(path);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    let sessions: Vec<SessionInfo> = response
+                        .json()
+                        .await
+                        .expect("could not get sessions from response");
+                    Ok(sessions)
+                })
+            }));
+
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(AccountMsg::ReceivedSessionsResponse(response));
+        });
+    }
+
+    fn revoke_session(&self, ctx: &Context<Self>, username: &str, session: SessionInfo) {
+        let path = self.sessions_path(username);
+
+        let scope = ctx.link().clone();
+        let client = Arc::new(self.client.clone());
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = client.delete(path).json(&session);
+            let on_ok = ResponseAction::from(|_| Ok(()));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await.map(|_| session);
+
+            scope.send_message(AccountMsg::ReceivedRevokeSessionResponse(response));
+        });
+    }
+}
+
+fn view_response<T>(response: &Option<ResponseResult<T>>, success: &str) -> Html {
+    match response {
+        Some(Ok(_)) => html! { <p>{ success }</p> },
+        Some(Err(err)) => html! { <p>{ err }</p> },
+        None => html! {},
+    }
 }
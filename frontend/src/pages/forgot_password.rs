@@ -0,0 +1,107 @@
+use reqwest::Client;
+use shared::models::PasswordResetRequest;
+use shared::routes;
+use std::sync::Arc;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::Header;
+use crate::requests::{fully_qualified_path, Requester, ResponseAction};
+use crate::ResponseResult;
+
+pub enum ForgotPasswordMsg {
+    EmailChanged(String),
+    Submitted,
+    ResponseReceived(ResponseResult<()>),
+}
+
+/// Ask for a password reset link by email.
+pub struct ForgotPassword {
+    email: String,
+    client: Client,
+    response: Option<ResponseResult<()>>,
+}
+
+impl Component for ForgotPassword {
+    type Message = ForgotPasswordMsg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        ForgotPassword {
+            email: String::new(),
+            client: Client::new(),
+            response: None,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let onchange_email = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| ForgotPasswordMsg::EmailChanged(input.value()))
+        });
+
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            ForgotPasswordMsg::Submitted
+        });
+
+        html! {
+            <>
+                <Header heading="forgot password" title="forgot password" />
+
+                <form {onsubmit}>
+                    {
+                        match &self.response {
+                            Some(Ok(())) => html! {
+                                <p>{ "if that email belongs to an account, a reset link is on its way" }</p>
+                            },
+                            Some(Err(error_msg)) => html! {
+                                <p>{ error_msg }</p>
+                            },
+                            None => html! { <></> },
+                        }
+                    }
+                    <div>
+                        <label for="email">{ "email:" }</label>
+                        <input id="email" type="email" placeholder="email" onchange={onchange_email} required=true/>
+                    </div>
+                    <input type="submit" value="send reset link"/>
+                </form>
+            </>
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ForgotPasswordMsg::EmailChanged(email) => {
+                self.email = email;
+                log::trace!("changed email");
+            }
+            ForgotPasswordMsg::Submitted => {
+                log::trace!("requesting password reset");
+                let request = PasswordResetRequest::new(self.email.clone());
+
+                let path = fully_qualified_path(routes::PASSWORD_RESET_REQUEST)
+                    .expect("could not build fully qualified path");
+
+                let scope = ctx.link().clone();
+                let client = Arc::new(self.client.clone());
+                wasm_bindgen_futures::spawn_local(async move {
+                    let request = client.post(path).json(&request);
+                    let on_ok = ResponseAction::from(|_| Ok(()));
+
+                    let requester = Requester::default();
+                    let response = requester.make(request, on_ok).await;
+
+                    scope.send_message(ForgotPasswordMsg::ResponseReceived(response));
+                })
+            }
+            ForgotPasswordMsg::ResponseReceived(response) => {
+                self.response = Some(response);
+            }
+        }
+        true
+    }
+}
@@ -6,7 +6,9 @@ use shared::{path_patterns, routes};
 use yew::context::ContextHandle;
 use yew::prelude::*;
 
-use crate::components::{AppContext, Header, Loading, NewPrediction, PredictionPanel};
+use crate::components::{
+    AppContext, DebtPlanner, Header, LedgerImport, Loading, NewPrediction, PredictionPanel,
+};
 use crate::requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction};
 use crate::{InternalResponseError, ResponseResult};
 
@@ -94,6 +96,8 @@ impl Home {
                 { format!("you have {} predictions created", predictions.len()) }
 
                 <NewPrediction oncreate={on_data_update.clone()} />
+                <LedgerImport oncreate={on_data_update.clone()} />
+                <DebtPlanner oncreate={on_data_update.clone()} />
 
                 {
                     predictions.into_iter().map(|pred| html!{
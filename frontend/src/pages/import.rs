@@ -0,0 +1,549 @@
+use reqwest::{Client, RequestBuilder};
+use shared::imports::{
+    csv::CsvImportRequest,
+    qif::{QifDateOrder, QifImportRequest},
+    CsvMapping, DecimalSeparator, ImportPreview, ImportSummary, SignConvention,
+    StatementImportRequest,
+};
+use shared::{path_patterns, routes};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{File, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::components::{AppContext, Header, Unauthorized};
+use crate::requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction};
+use crate::{InternalResponseError, ResponseResult};
+
+const DATE_FMT: &str = "%x";
+
+#[derive(Clone, Copy, PartialEq)]
+enum StatementFormat {
+    Csv,
+    Ofx,
+    Qif,
+}
+
+pub enum ImportMsg {
+    AppContextUpdated(AppContext),
+    FileSelected(File),
+    FileRead(Option<String>),
+    FormatChanged(String),
+    QifDateOrderChanged(String),
+    HasHeadersChanged(bool),
+    DelimiterChanged(String),
+    DateColumnChanged(String),
+    DateFormatChanged(String),
+    AmountColumnChanged(String),
+    DescriptionColumnChanged(String),
+    SignConventionChanged(String),
+    CreditColumnChanged(String),
+    DecimalSeparatorChanged(String),
+    PreviewRequested,
+    ReceivedPreview(ResponseResult<ImportPreview>),
+    ImportRequested,
+    ReceivedImport(ResponseResult<ImportSummary>),
+}
+
+pub struct Import {
+    app_context: AppContext,
+    client: Client,
+    contents: Option<String>,
+    format: StatementFormat,
+    qif_date_order: QifDateOrder,
+    mapping: CsvMapping,
+    credit_column: usize,
+    mapping_error: Option<String>,
+    preview: Option<ResponseResult<ImportPreview>>,
+    summary: Option<ResponseResult<ImportSummary>>,
+}
+
+impl Component for Import {
+    type Message = ImportMsg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (app_context, _) = ctx
+            .link()
+            .context(ctx.link().callback(ImportMsg::AppContextUpdated))
+            .expect("no AppContext provided");
+
+        Import {
+            app_context,
+            client: Client::new(),
+            contents: None,
+            format: StatementFormat::Csv,
+            qif_date_order: QifDateOrder::MonthFirst,
+            mapping: CsvMapping::default(),
+            credit_column: 3,
+            mapping_error: None,
+            preview: None,
+            summary: None,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if !self.app_context.borrow_mut().is_logged_in() {
+            return html! {
+                <>
+                    <Header title="import" heading="import"/>
+
+                    <Unauthorized />
+                </>
+            };
+        }
+
+        html! {
+            <>
+                <Header title="import" heading="import"/>
+
+                { self.view_mapping_form(ctx) }
+                { self.view_preview(ctx) }
+                { self.view_summary() }
+            </>
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ImportMsg::AppContextUpdated(context) => self.app_context = context,
+            ImportMsg::FileSelected(file) => {
+                let scope = ctx.link().clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let contents = JsFuture::from(file.text())
+                        .await
+                        .ok()
+                        .and_then(|text| text.as_string());
+
+                    scope.send_message(ImportMsg::FileRead(contents));
+                });
+            }
+            ImportMsg::FileRead(contents) => {
+                if contents.is_none() {
+                    self.mapping_error = Some("could not read selected file".into());
+                }
+                self.contents = contents;
+                self.preview = None;
+                self.summary = None;
+            }
+            ImportMsg::FormatChanged(format) => {
+                self.format = match format.as_str() {
+                    "ofx" => StatementFormat::Ofx,
+                    "qif" => StatementFormat::Qif,
+                    _ => StatementFormat::Csv,
+                };
+                self.preview = None;
+                self.summary = None;
+            }
+            ImportMsg::QifDateOrderChanged(date_order) => {
+                self.qif_date_order = match date_order.as_str() {
+                    "day-first" => QifDateOrder::DayFirst,
+                    _ => QifDateOrder::MonthFirst,
+                }
+            }
+            ImportMsg::HasHeadersChanged(has_headers) => self.mapping.has_headers = has_headers,
+            ImportMsg::DelimiterChanged(delimiter) => match delimiter.chars().next() {
+                Some(delimiter) if delimiter.is_ascii() => self.mapping.delimiter = delimiter,
+                _ => self.mapping_error = Some(format!("invalid delimiter ({})", delimiter)),
+            },
+            ImportMsg::DateColumnChanged(column) => {
+                if let Some(column) = self.parse_column(&column) {
+                    self.mapping.date_column = column;
+                }
+            }
+            ImportMsg::DateFormatChanged(format) => self.mapping.date_format = format,
+            ImportMsg::AmountColumnChanged(column) => {
+                if let Some(column) = self.parse_column(&column) {
+                    self.mapping.amount_column = column;
+                }
+            }
+            ImportMsg::DescriptionColumnChanged(column) => {
+                if let Some(column) = self.parse_column(&column) {
+                    self.mapping.description_column = column;
+                }
+            }
+            ImportMsg::SignConventionChanged(convention) => {
+                self.mapping.sign_convention = match convention.as_str() {
+                    "debits-positive" => SignConvention::DebitsPositive,
+                    "split" => SignConvention::SplitColumns {
+                        credit_column: self.credit_column,
+                    },
+                    _ => SignConvention::DebitsNegative,
+                }
+            }
+            ImportMsg::CreditColumnChanged(column) => {
+                if let Some(column) = self.parse_column(&column) {
+                    self.credit_column = column;
+                    if let SignConvention::SplitColumns { .. } = self.mapping.sign_convention {
+                        self.mapping.sign_convention = SignConvention::SplitColumns {
+                            credit_column: column,
+                        };
+                    }
+                }
+            }
+            ImportMsg::DecimalSeparatorChanged(separator) => {
+                self.mapping.decimal_separator = match separator.as_str() {
+                    "comma" => DecimalSeparator::Comma,
+                    _ => DecimalSeparator::Point,
+                }
+            }
+            ImportMsg::PreviewRequested => self.send_statement(ctx, false),
+            ImportMsg::ReceivedPreview(response) => self.preview = Some(response),
+            ImportMsg::ImportRequested => self.send_statement(ctx, true),
+            ImportMsg::ReceivedImport(response) => {
+                if response.is_ok() {
+                    self.preview = None;
+                }
+                self.summary = Some(response);
+            }
+        }
+        true
+    }
+}
+
+// Sub-views
+impl Import {
+    fn view_mapping_form(&self, ctx: &Context<Self>) -> Html {
+        let oninput_format = ctx.link().callback(|ev: InputEvent| {
+            let event = ev.dyn_into::<Event>().unwrap_throw();
+            let event_target = event.target().unwrap_throw();
+            let target = event_target.dyn_into::<HtmlSelectElement>().unwrap_throw();
+            ImportMsg::FormatChanged(target.value())
+        });
+        let onchange_file = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input
+                .and_then(|input| input.files())
+                .and_then(|files| files.get(0))
+                .map(ImportMsg::FileSelected)
+        });
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            ImportMsg::PreviewRequested
+        });
+
+        let accept = match self.format {
+            StatementFormat::Csv => ".csv,text/csv",
+            StatementFormat::Ofx => ".ofx,.qfx",
+            StatementFormat::Qif => ".qif",
+        };
+
+        html! {
+            <form {onsubmit}>
+                <h2>{ "statement" }</h2>
+                {
+                    if let Some(err) = &self.mapping_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <div>
+                    <label for="statement-format">{ "format:" }</label>
+                    <select name="statement-format" id="statement-format" oninput={oninput_format}>
+                        <option value="csv" selected=true>{ "csv" }</option>
+                        <option value="ofx">{ "ofx / qfx" }</option>
+                        <option value="qif">{ "qif" }</option>
+                    </select>
+                </div>
+                <div>
+                    <label for="statement-file">{ "file:" }</label>
+                    <input type="file" id="statement-file" {accept} onchange={onchange_file} required=true/>
+                </div>
+                {
+                    match self.format {
+                        StatementFormat::Csv => self.view_csv_mapping(ctx),
+                        StatementFormat::Ofx => html! {},
+                        StatementFormat::Qif => self.view_qif_options(ctx),
+                    }
+                }
+                <input type="submit" value="preview"/>
+            </form>
+        }
+    }
+
+    fn view_qif_options(&self, ctx: &Context<Self>) -> Html {
+        let oninput_date_order = ctx.link().callback(|ev: InputEvent| {
+            let event = ev.dyn_into::<Event>().unwrap_throw();
+            let event_target = event.target().unwrap_throw();
+            let target = event_target.dyn_into::<HtmlSelectElement>().unwrap_throw();
+            ImportMsg::QifDateOrderChanged(target.value())
+        });
+
+        html! {
+            <div>
+                <label for="qif-date-order">{ "dates:" }</label>
+                <select name="qif-date-order" id="qif-date-order" oninput={oninput_date_order}>
+                    <option value="month-first" selected={self.qif_date_order == QifDateOrder::MonthFirst}>{ "month first (01/31/2022)" }</option>
+                    <option value="day-first" selected={self.qif_date_order == QifDateOrder::DayFirst}>{ "day first (31/01/2022)" }</option>
+                </select>
+            </div>
+        }
+    }
+
+    fn view_csv_mapping(&self, ctx: &Context<Self>) -> Html {
+        let onchange_headers = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| ImportMsg::HasHeadersChanged(input.checked()))
+        });
+        let onchange_delimiter = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| ImportMsg::DelimiterChanged(input.value()))
+        });
+        let onchange_date_column = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| ImportMsg::DateColumnChanged(input.value()))
+        });
+        let onchange_date_format = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| ImportMsg::DateFormatChanged(input.value()))
+        });
+        let onchange_amount_column = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| ImportMsg::AmountColumnChanged(input.value()))
+        });
+        let onchange_description_column = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| ImportMsg::DescriptionColumnChanged(input.value()))
+        });
+        let oninput_sign_convention = ctx.link().callback(|ev: InputEvent| {
+            let event = ev.dyn_into::<Event>().unwrap_throw();
+            let event_target = event.target().unwrap_throw();
+            let target = event_target.dyn_into::<HtmlSelectElement>().unwrap_throw();
+            ImportMsg::SignConventionChanged(target.value())
+        });
+        let onchange_credit_column = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| ImportMsg::CreditColumnChanged(input.value()))
+        });
+        let oninput_decimal_separator = ctx.link().callback(|ev: InputEvent| {
+            let event = ev.dyn_into::<Event>().unwrap_throw();
+            let event_target = event.target().unwrap_throw();
+            let target = event_target.dyn_into::<HtmlSelectElement>().unwrap_throw();
+            ImportMsg::DecimalSeparatorChanged(target.value())
+        });
+
+        let is_split = matches!(
+            self.mapping.sign_convention,
+            SignConvention::SplitColumns { .. }
+        );
+
+        html! {
+            <>
+                <div>
+                    <label for="has-headers">{ "first row is a header:" }</label>
+                    <input type="checkbox" id="has-headers" checked={self.mapping.has_headers} onchange={onchange_headers}/>
+                </div>
+                <div>
+                    <label for="delimiter">{ "delimiter:" }</label>
+                    <input type="text" id="delimiter" maxlength="1" value={self.mapping.delimiter.to_string()} onchange={onchange_delimiter}/>
+                </div>
+                <div>
+                    <label for="date-column">{ "date column #:" }</label>
+                    <input type="number" id="date-column" min=1 step=1 value={(self.mapping.date_column + 1).to_string()} onchange={onchange_date_column}/>
+                </div>
+                <div>
+                    <label for="date-format">{ "date format:" }</label>
+                    <input type="text" id="date-format" value={self.mapping.date_format.clone()} onchange={onchange_date_format}/>
+                </div>
+                <div>
+                    <label for="amount-column">{ if is_split { "debit column #:" } else { "amount column #:" } }</label>
+                    <input type="number" id="amount-column" min=1 step=1 value={(self.mapping.amount_column + 1).to_string()} onchange={onchange_amount_column}/>
+                </div>
+                <div>
+                    <label for="description-column">{ "description column #:" }</label>
+                    <input type="number" id="description-column" min=1 step=1 value={(self.mapping.description_column + 1).to_string()} onchange={onchange_description_column}/>
+                </div>
+                <div>
+                    <label for="sign-convention">{ "amounts:" }</label>
+                    <select name="sign-convention" id="sign-convention" oninput={oninput_sign_convention}>
+                        <option value="debits-negative" selected={self.mapping.sign_convention == SignConvention::DebitsNegative}>{ "money out is negative" }</option>
+                        <option value="debits-positive" selected={self.mapping.sign_convention == SignConvention::DebitsPositive}>{ "money out is positive" }</option>
+                        <option value="split" selected={is_split}>{ "separate debit & credit columns" }</option>
+                    </select>
+                </div>
+                <div>
+                    <label for="decimal-separator">{ "decimal separator:" }</label>
+                    <select name="decimal-separator" id="decimal-separator" oninput={oninput_decimal_separator}>
+                        <option value="point" selected={self.mapping.decimal_separator == DecimalSeparator::Point}>{ "point (1,234.56)" }</option>
+                        <option value="comma" selected={self.mapping.decimal_separator == DecimalSeparator::Comma}>{ "comma (1.234,56)" }</option>
+                    </select>
+                </div>
+                {
+                    if is_split {
+                        html! {
+                            <div>
+                                <label for="credit-column">{ "credit column #:" }</label>
+                                <input type="number" id="credit-column" min=1 step=1 value={(self.credit_column + 1).to_string()} onchange={onchange_credit_column}/>
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </>
+        }
+    }
+
+    fn view_preview(&self, ctx: &Context<Self>) -> Html {
+        match &self.preview {
+            Some(Ok(preview)) => {
+                let onclick_import = ctx.link().callback(|_| ImportMsg::ImportRequested);
+
+                html! {
+                    <div>
+                        <h2>{ format!("preview ({} transactions)", preview.transactions.len()) }</h2>
+                        <table>
+                            <tr>
+                                <th>{ "date" }</th>
+                                <th>{ "amount" }</th>
+                                <th>{ "description" }</th>
+                            </tr>
+                            {
+                                preview.transactions.iter().map(|transaction| html! {
+                                    <tr>
+                                        <td>{ transaction.posted_on.format(DATE_FMT).to_string() }</td>
+                                        <td>{ format!("{:.2}", transaction.amount) }</td>
+                                        <td>{ transaction.description.clone() }</td>
+                                    </tr>
+                                }).collect::<Html>()
+                            }
+                        </table>
+                        {
+                            preview.skipped.iter().map(|skipped| html! {
+                                <p>{ format!("skipped line {}: {}", skipped.line, skipped.reason) }</p>
+                            }).collect::<Html>()
+                        }
+                        <button onclick={onclick_import} disabled={preview.transactions.is_empty()}>
+                            { "import transactions" }
+                        </button>
+                    </div>
+                }
+            }
+            Some(Err(err)) => html! { <p>{ format!("error previewing statement: {}", err) }</p> },
+            None => html! {},
+        }
+    }
+
+    fn view_summary(&self) -> Html {
+        match &self.summary {
+            Some(Ok(summary)) => html! {
+                <p>{ format!("imported {} transactions ({} already imported, {} rows skipped)", summary.imported, summary.duplicates, summary.skipped.len()) }</p>
+            },
+            Some(Err(err)) => html! { <p>{ format!("error importing statement: {}", err) }</p> },
+            None => html! {},
+        }
+    }
+}
+
+impl Import {
+    /// Columns are shown to the user counting from 1
+    fn parse_column(&mut self, column: &str) -> Option<usize> {
+        match column.parse::<usize>() {
+            Ok(column) if column >= 1 => {
+                self.mapping_error = None;
+                Some(column - 1)
+            }
+            _ => {
+                self.mapping_error = Some(format!("invalid column number ({})", column));
+                None
+            }
+        }
+    }
+
+    /// Build the preview (`POST`) or import (`PUT`) request for the selected
+    /// statement format
+    fn statement_request(
+        &self,
+        client: &Client,
+        username: &str,
+        contents: String,
+        commit: bool,
+    ) -> RequestBuilder {
+        let (route, pattern) = match self.format {
+            StatementFormat::Csv => (routes::IMPORT_CSV, path_patterns::IMPORT_CSV),
+            StatementFormat::Ofx => (routes::IMPORT_OFX, path_patterns::IMPORT_OFX),
+            StatementFormat::Qif => (routes::IMPORT_QIF, path_patterns::IMPORT_QIF),
+        };
+
+        let path = fully_qualified_path(
+            &replace_pattern(route, pattern, username).expect("could not replace pattern in route"),
+        )
+        .expect("could not create path");
+
+        let request = if commit {
+            client.put(path)
+        } else {
+            client.post(path)
+        };
+
+        match self.format {
+            StatementFormat::Csv => request.json(&CsvImportRequest {
+                contents,
+                mapping: self.mapping.clone(),
+            }),
+            StatementFormat::Ofx => request.json(&StatementImportRequest { contents }),
+            StatementFormat::Qif => request.json(&QifImportRequest {
+                contents,
+                date_order: self.qif_date_order,
+            }),
+        }
+    }
+
+    fn send_statement(&self, ctx: &Context<Self>, commit: bool) {
+        let username = match self.app_context.borrow_mut().username() {
+            Some(username) => username.to_owned(),
+            None => return,
+        };
+
+        let contents = match &self.contents {
+            Some(contents) => contents.clone(),
+            None => return,
+        };
+
+        let request = self.statement_request(&self.client, &username, contents, commit);
+
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            if commit {
+                let on_ok = ResponseAction::new(Box::new(|response| {
+                    Box::pin(async {
+                        response.json::<ImportSummary>().await.map_err(|err| {
+                            InternalResponseError::ResponseAwaitError(
+                                "import summary",
+                                err.to_string(),
+                            )
+                        })
+                    })
+                }));
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(ImportMsg::ReceivedImport(response));
+            } else {
+                let on_ok = ResponseAction::new(Box::new(|response| {
+                    Box::pin(async {
+                        response.json::<ImportPreview>().await.map_err(|err| {
+                            InternalResponseError::ResponseAwaitError(
+                                "import preview",
+                                err.to_string(),
+                            )
+                        })
+                    })
+                }));
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(ImportMsg::ReceivedPreview(response));
+            }
+        });
+    }
+}
@@ -1,7 +1,6 @@
-use reqwest::{Client, StatusCode};
-use shared::models::{User, UserLoginRequestForm};
+use reqwest::{Client, RequestBuilder};
+use shared::models::{LoginResponse, TwoFactorLoginForm, UserLoginForm};
 use shared::routes;
-use std::sync::Arc;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
@@ -17,16 +16,21 @@ pub enum LoginMsg {
     AppContextUpdated(AppContext),
     UsernameChanged(String),
     PasswordChanged(String),
+    CodeChanged(String),
     Submitted,
-    ResponseReceived(ResponseResult<Route>),
+    ResponseReceived(ResponseResult<LoginResponse>),
 }
 
 pub struct Login {
     app_context: AppContext,
     username: String,
     password: String,
+    code: String,
+    /// Set once the password is accepted for an account with two factor
+    /// authentication, until a code is.
+    two_factor_token: Option<String>,
     client: Client,
-    response: Option<ResponseResult<Route>>,
+    response: Option<ResponseResult<LoginResponse>>,
 }
 
 impl Component for Login {
@@ -43,35 +47,31 @@ impl Component for Login {
             app_context,
             username: String::new(),
             password: String::new(),
+            code: String::new(),
+            two_factor_token: None,
             client: Client::new(),
             response: None,
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
-        if let Some(Ok(redirect_to)) = &self.response {
+        if let Some(Ok(LoginResponse::LoggedIn)) = &self.response {
             return html! {
-                <Redirect<Route> to={redirect_to.clone()} />
+                <Redirect<Route> to={Route::Home} />
             };
         }
 
-        let onchange_username = ctx.link().batch_callback(|ev: Event| {
-            let target = ev.target();
-            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
-            input.map(|input| LoginMsg::UsernameChanged(input.value()))
-        });
-
-        let onchange_password = ctx.link().batch_callback(|ev: Event| {
-            let target = ev.target();
-            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
-            input.map(|input| LoginMsg::PasswordChanged(input.value()))
-        });
-
         let onsubmit = ctx.link().callback(|ev: FocusEvent| {
             ev.prevent_default();
             LoginMsg::Submitted
         });
 
+        let fields = if self.two_factor_token.is_some() {
+            self.view_code_field(ctx)
+        } else {
+            self.view_password_fields(ctx)
+        };
+
         html! {
             <>
                 <Header heading="login" title="login" />
@@ -83,6 +83,9 @@ impl Component for Login {
                                 InternalResponseError::Unauthorized => html! {
                                     <p>{ "incorrect credentials" }</p>
                                 },
+                                InternalResponseError::ResponseAwaitError(_, message) => html! {
+                                    <p>{ message }</p>
+                                },
                                 _ => html! {
                                     <p>{ error_msg }</p>
                                 },
@@ -92,16 +95,11 @@ impl Component for Login {
                             html! { <></> }
                         }
                     }
-                    <div>
-                        <label for="username">{ "username:" }</label>
-                        <input id="username" type="text" placeholder="username" onchange={onchange_username} required=true/>
-                    </div>
-                    <div>
-                        <label for="password">{ "password:" }</label>
-                        <input id="password" type="password" placeholder="password" onchange={onchange_password} required=true/>
-                    </div>
+                    { fields }
                     <input type="submit" value="login"/>
                 </form>
+
+                <Link<Route> to={Route::ForgotPassword}>{ "forgot password?" }</Link<Route>>
             </>
         }
     }
@@ -116,79 +114,115 @@ impl Component for Login {
                 self.password = password;
                 log::trace!("changed password");
             }
+            LoginMsg::CodeChanged(code) => {
+                self.code = code;
+                log::trace!("changed code");
+            }
             LoginMsg::Submitted => {
                 log::trace!("submitting form");
-                let user_form = UserLoginRequestForm::new(self.username.clone());
-
-                let path = fully_qualified_path(routes::LOGIN_REQUEST_PASSWORD.into())
-                    .expect("could not build fully qualified path");
-
-                let scope = ctx.link().clone();
-                let client = Arc::new(self.client.clone());
-                let password = self.password.clone();
-                wasm_bindgen_futures::spawn_local(async move {
-                    let response = client.put(path).json(&user_form).send().await.ok();
-
-                    let response = if let Some(response) = response {
-                        match response.status() {
-                            StatusCode::OK => {
-                                let user: User = response
-                                    .json()
-                                    .await
-                                    .expect("could not retrieve user from response");
-
-                                if user
-                                    .verify_user(&user_form.username, &password)
-                                    .expect("could not verify user")
-                                {
-                                    login_user(client, user).await
-                                } else {
-                                    Err(InternalResponseError::Unauthorized)
-                                }
-                            }
-                            _ => response.text().await.map_or(
-                                Err(InternalResponseError::Other(
-                                    "could not get body text".into(),
-                                )),
-                                |err_text| {
-                                    Err(InternalResponseError::ResponseAwaitError(
-                                        "error text body",
-                                        err_text,
-                                    ))
-                                },
-                            ),
-                        }
-                    } else {
-                        Err(InternalResponseError::Other(
-                            "could not post login credentials:".into(),
-                        ))
-                    };
+                let request = match &self.two_factor_token {
+                    Some(token) => {
+                        let form = TwoFactorLoginForm::new(token.clone(), self.code.clone());
+                        let path = fully_qualified_path(routes::LOGIN_TWO_FACTOR)
+                            .expect("could not build fully qualified path");
+                        self.client.post(path).json(&form)
+                    }
+                    None => {
+                        let form = UserLoginForm::new(self.username.clone(), self.password.clone());
+                        let path = fully_qualified_path(routes::LOGIN)
+                            .expect("could not build fully qualified path");
+                        self.client.post(path).json(&form)
+                    }
+                };
 
-                    scope.send_message(LoginMsg::ResponseReceived(response));
-                })
+                self.submit(ctx, request);
             }
             LoginMsg::AppContextUpdated(_) => todo!(),
             LoginMsg::ResponseReceived(response) => {
-                if response.is_ok() {
-                    self.app_context
-                        .borrow_mut()
-                        .login(self.username.clone())
-                        .expect("could not log in");
+                match &response {
+                    Ok(LoginResponse::LoggedIn) => {
+                        self.app_context
+                            .borrow_mut()
+                            .login(self.username.clone())
+                            .expect("could not log in");
+                    }
+                    Ok(LoginResponse::TwoFactorRequired { token }) => {
+                        self.two_factor_token = Some(token.clone());
+                        self.password.clear();
+                    }
+                    Err(_) => self.code.clear(),
                 }
 
-                self.response = Some(response);
+                self.response = match response {
+                    Ok(LoginResponse::TwoFactorRequired { .. }) => None,
+                    response => Some(response),
+                };
             }
         }
         true
     }
 }
 
-async fn login_user(client: Arc<Client>, user: User) -> ResponseResult<Route> {
-    let path = fully_qualified_path(routes::LOGIN.into())
-        .expect("could not build fully qualified path for second request");
-    let request = client.patch(path).json(&user);
-    let on_ok = ResponseAction::new(Box::new(|_| Box::pin(async move { Ok(Route::Home) })));
+impl Login {
+    fn view_password_fields(&self, ctx: &Context<Self>) -> Html {
+        let onchange_username = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| LoginMsg::UsernameChanged(input.value()))
+        });
+
+        let onchange_password = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| LoginMsg::PasswordChanged(input.value()))
+        });
+
+        html! {
+            <>
+                <div>
+                    <label for="username">{ "username:" }</label>
+                    <input id="username" type="text" placeholder="username" onchange={onchange_username} required=true/>
+                </div>
+                <div>
+                    <label for="password">{ "password:" }</label>
+                    <input id="password" type="password" placeholder="password" onchange={onchange_password} required=true/>
+                </div>
+            </>
+        }
+    }
 
-    let requester = Requester::default();
-    requester.make(request, on_ok).await
+    fn view_code_field(&self, ctx: &Context<Self>) -> Html {
+        let onchange_code = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| LoginMsg::CodeChanged(input.value()))
+        });
+
+        html! {
+            <div>
+                <p>{ "enter the code from your authenticator app, or a recovery code" }</p>
+                <label for="code">{ "code:" }</label>
+                <input id="code" type="text" autocomplete="one-time-code" placeholder="123456"
+                    value={self.code.clone()} onchange={onchange_code} required=true/>
+            </div>
+        }
+    }
+
+    fn submit(&self, ctx: &Context<Self>, request: RequestBuilder) {
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<LoginResponse>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError("login response", err.to_string())
+                    })
+                })
+            }));
+
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(LoginMsg::ResponseReceived(response));
+        })
+    }
 }
@@ -0,0 +1,170 @@
+use reqwest::Client;
+use serde::Deserialize;
+use shared::models::PasswordResetForm;
+use shared::routes;
+use std::sync::Arc;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::history::Location;
+use yew_router::prelude::*;
+
+use crate::app::Route;
+use crate::components::Header;
+use crate::requests::{fully_qualified_path, Requester, ResponseAction};
+use crate::ResponseResult;
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: String,
+}
+
+pub enum ResetPasswordMsg {
+    PasswordChanged(String),
+    ConfirmPasswordChanged(String),
+    Submitted,
+    ResponseReceived(ResponseResult<()>),
+}
+
+/// Set a new password from the link in a password reset email.
+pub struct ResetPassword {
+    token: Option<String>,
+    password: String,
+    confirm_password: String,
+    client: Client,
+    response: Option<ResponseResult<()>>,
+}
+
+impl Component for ResetPassword {
+    type Message = ResetPasswordMsg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let token = ctx
+            .link()
+            .location()
+            .and_then(|location| location.query::<TokenQuery>().ok())
+            .map(|query| query.token);
+
+        ResetPassword {
+            token,
+            password: String::new(),
+            confirm_password: String::new(),
+            client: Client::new(),
+            response: None,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let header = html! {
+            <Header heading="reset password" title="reset password" />
+        };
+
+        if self.token.is_none() {
+            return html! {
+                <>
+                    { header }
+                    <p>{ "this reset link is incomplete, please request another" }</p>
+                    <Link<Route> to={Route::ForgotPassword}>{ "forgot password" }</Link<Route>>
+                </>
+            };
+        }
+
+        if let Some(Ok(())) = &self.response {
+            return html! {
+                <>
+                    { header }
+                    <p>{ "your password has been reset" }</p>
+                    <Link<Route> to={Route::Login}>{ "login" }</Link<Route>>
+                </>
+            };
+        }
+
+        let onchange_password = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| ResetPasswordMsg::PasswordChanged(input.value()))
+        });
+
+        let onchange_confirm_password = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| ResetPasswordMsg::ConfirmPasswordChanged(input.value()))
+        });
+
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            ResetPasswordMsg::Submitted
+        });
+
+        html! {
+            <>
+                { header }
+
+                <form {onsubmit}>
+                    {
+                        if let Some(Err(error_msg)) = &self.response {
+                            html! { <p>{ error_msg }</p> }
+                        } else {
+                            html! { <></> }
+                        }
+                    }
+                    <div>
+                        <label for="password">{ "new password:" }</label>
+                        <input id="password" type="password" placeholder="new password" onchange={onchange_password} required=true/>
+                    </div>
+                    <div>
+                        <label for="confirm-password">{ "confirm password:" }</label>
+                        <input id="confirm-password" type="password" placeholder="confirm password" onchange={onchange_confirm_password} required=true/>
+                    </div>
+                    <input type="submit" value="reset password"/>
+                </form>
+            </>
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ResetPasswordMsg::PasswordChanged(password) => {
+                self.password = password;
+                log::trace!("changed password");
+            }
+            ResetPasswordMsg::ConfirmPasswordChanged(confirm_password) => {
+                self.confirm_password = confirm_password;
+                log::trace!("changed confirm password");
+            }
+            ResetPasswordMsg::Submitted => {
+                let token = match &self.token {
+                    Some(token) => token.clone(),
+                    None => return false,
+                };
+
+                log::trace!("submitting password reset");
+                let form = PasswordResetForm::new(
+                    token,
+                    self.password.clone(),
+                    self.confirm_password.clone(),
+                );
+
+                let path = fully_qualified_path(routes::PASSWORD_RESET)
+                    .expect("could not build fully qualified path");
+
+                let scope = ctx.link().clone();
+                let client = Arc::new(self.client.clone());
+                wasm_bindgen_futures::spawn_local(async move {
+                    let request = client.post(path).json(&form);
+                    let on_ok = ResponseAction::from(|_| Ok(()));
+
+                    let requester = Requester::default();
+                    let response = requester.make(request, on_ok).await;
+
+                    scope.send_message(ResetPasswordMsg::ResponseReceived(response));
+                })
+            }
+            ResetPasswordMsg::ResponseReceived(response) => {
+                self.response = Some(response);
+            }
+        }
+        true
+    }
+}
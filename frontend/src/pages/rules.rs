@@ -0,0 +1,524 @@
+use reqwest::Client;
+use shared::models::{
+    predictions::PredictionWithDeltas, CategorizationRule, CategorizationSummary,
+    NewCategorizationRule,
+};
+use shared::{path_patterns, routes};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::components::{AppContext, Header, Unauthorized};
+use crate::requests::{fully_qualified_path, replace_pattern, Requester, ResponseAction};
+use crate::{InternalResponseError, ResponseResult};
+
+pub enum RulesMsg {
+    AppContextUpdated(AppContext),
+    ReceivedRules(ResponseResult<Vec<CategorizationRule>>),
+    ReceivedPredictions(ResponseResult<Vec<PredictionWithDeltas>>),
+    CategoryChanged(String),
+    PatternChanged(String),
+    MinAmountChanged(String),
+    MaxAmountChanged(String),
+    AccountChanged(String),
+    DeltaChanged(String),
+    Submitted,
+    ReceivedCreateResponse(ResponseResult<CategorizationRule>),
+    DeleteRequested(CategorizationRule),
+    ReceivedDeleteResponse(ResponseResult<()>),
+    ApplyRequested,
+    ReceivedApplyResponse(ResponseResult<CategorizationSummary>),
+}
+
+pub struct Rules {
+    app_context: AppContext,
+    client: Client,
+    rules: Option<ResponseResult<Vec<CategorizationRule>>>,
+    predictions: Vec<PredictionWithDeltas>,
+    category: String,
+    pattern: String,
+    min_amount: Option<f32>,
+    max_amount: Option<f32>,
+    account: String,
+    delta_id: Option<i32>,
+    form_error: Option<String>,
+    delete_response: Option<ResponseResult<()>>,
+    apply_response: Option<ResponseResult<CategorizationSummary>>,
+}
+
+fn optional(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+impl Component for Rules {
+    type Message = RulesMsg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (app_context, _) = ctx
+            .link()
+            .context(ctx.link().callback(RulesMsg::AppContextUpdated))
+            .expect("no AppContext provided");
+
+        Rules {
+            app_context,
+            client: Client::new(),
+            rules: None,
+            predictions: Vec::new(),
+            category: String::new(),
+            pattern: String::new(),
+            min_amount: None,
+            max_amount: None,
+            account: String::new(),
+            delta_id: None,
+            form_error: None,
+            delete_response: None,
+            apply_response: None,
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            self.get_rules_if_logged_in(ctx);
+            self.get_predictions_if_logged_in(ctx);
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if !self.app_context.borrow_mut().is_logged_in() {
+            return html! {
+                <>
+                    <Header title="rules" heading="rules"/>
+
+                    <Unauthorized />
+                </>
+            };
+        }
+
+        html! {
+            <>
+                <Header title="rules" heading="categorization rules"/>
+
+                <p>{ "imported transactions get the category of the first rule they match" }</p>
+
+                { self.view_rules(ctx) }
+                { self.view_new_rule_form(ctx) }
+                { self.view_apply(ctx) }
+            </>
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            RulesMsg::AppContextUpdated(context) => self.app_context = context,
+            RulesMsg::ReceivedRules(response) => self.rules = Some(response),
+            RulesMsg::ReceivedPredictions(response) => match response {
+                Ok(predictions) => self.predictions = predictions,
+                Err(err) => log::error!("error getting predictions: {}", err),
+            },
+            RulesMsg::CategoryChanged(category) => self.category = category,
+            RulesMsg::PatternChanged(pattern) => self.pattern = pattern,
+            RulesMsg::MinAmountChanged(amount) => self.min_amount = self.parse_amount(&amount),
+            RulesMsg::MaxAmountChanged(amount) => self.max_amount = self.parse_amount(&amount),
+            RulesMsg::AccountChanged(account) => self.account = account,
+            RulesMsg::DeltaChanged(delta_id) => self.delta_id = delta_id.parse::<i32>().ok(),
+            RulesMsg::Submitted => self.put_rule_if_logged_in(ctx),
+            RulesMsg::ReceivedCreateResponse(response) => match response {
+                Ok(rule) => {
+                    if let Some(Ok(rules)) = &mut self.rules {
+                        rules.push(rule);
+                    }
+                    self.form_error = None;
+                }
+                Err(err) => self.form_error = Some(format!("error creating rule: {}", err)),
+            },
+            RulesMsg::DeleteRequested(rule) => self.delete_rule_if_logged_in(ctx, rule),
+            RulesMsg::ReceivedDeleteResponse(response) => {
+                if response.is_ok() {
+                    self.get_rules_if_logged_in(ctx);
+                }
+                self.delete_response = Some(response);
+            }
+            RulesMsg::ApplyRequested => self.apply_rules_if_logged_in(ctx),
+            RulesMsg::ReceivedApplyResponse(response) => self.apply_response = Some(response),
+        }
+        true
+    }
+}
+
+// Sub-views
+impl Rules {
+    fn delta_label(&self, delta_id: i32) -> String {
+        self.predictions
+            .iter()
+            .flat_map(|prediction| {
+                prediction
+                    .deltas()
+                    .iter()
+                    .map(move |delta| (prediction.name(), delta))
+            })
+            .find(|(_, delta)| delta.id() == delta_id)
+            .map_or_else(
+                || format!("delta #{}", delta_id),
+                |(prediction, delta)| format!("{} / {}", prediction, delta.name()),
+            )
+    }
+
+    fn view_rules(&self, ctx: &Context<Self>) -> Html {
+        match &self.rules {
+            Some(Ok(rules)) if rules.is_empty() => html! { <p>{ "no rules yet" }</p> },
+            Some(Ok(rules)) => html! {
+                <>
+                    {
+                        if let Some(Err(err)) = &self.delete_response {
+                            html! { <p>{ format!("error deleting rule: {}", err) }</p> }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    <table>
+                        <tr>
+                            <th>{ "category" }</th>
+                            <th>{ "description matches" }</th>
+                            <th>{ "amount" }</th>
+                            <th>{ "account" }</th>
+                            <th>{ "delta" }</th>
+                            <th></th>
+                        </tr>
+                        {
+                            rules.iter().map(|rule| {
+                                let amount = match (rule.min_amount(), rule.max_amount()) {
+                                    (Some(min), Some(max)) => format!("{:.2} to {:.2}", min, max),
+                                    (Some(min), None) => format!("at least {:.2}", min),
+                                    (None, Some(max)) => format!("at most {:.2}", max),
+                                    (None, None) => "any".to_string(),
+                                };
+
+                                let to_delete = rule.clone();
+                                let onclick_delete = ctx
+                                    .link()
+                                    .callback(move |_| RulesMsg::DeleteRequested(to_delete.clone()));
+
+                                html! {
+                                    <tr key={ rule.id() }>
+                                        <td>{ rule.category() }</td>
+                                        <td>{ rule.description_pattern().unwrap_or("any") }</td>
+                                        <td>{ amount }</td>
+                                        <td>{ rule.account().unwrap_or("any") }</td>
+                                        <td>{ rule.delta_id().map_or_else(String::new, |delta_id| self.delta_label(delta_id)) }</td>
+                                        <td><i class="fa fa-trash" aria-hidden="true" onclick={onclick_delete}></i></td>
+                                    </tr>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </table>
+                </>
+            },
+            Some(Err(err)) => html! { <p>{ format!("error getting rules: {}", err) }</p> },
+            None => html! {},
+        }
+    }
+
+    fn view_new_rule_form(&self, ctx: &Context<Self>) -> Html {
+        let onchange_category = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| RulesMsg::CategoryChanged(input.value()))
+        });
+        let onchange_pattern = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| RulesMsg::PatternChanged(input.value()))
+        });
+        let onchange_min = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| RulesMsg::MinAmountChanged(input.value()))
+        });
+        let onchange_max = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| RulesMsg::MaxAmountChanged(input.value()))
+        });
+        let onchange_account = ctx.link().batch_callback(|ev: Event| {
+            let target = ev.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|input| RulesMsg::AccountChanged(input.value()))
+        });
+        let oninput_delta = ctx.link().callback(|ev: InputEvent| {
+            let event = ev.dyn_into::<Event>().unwrap_throw();
+            let event_target = event.target().unwrap_throw();
+            let target = event_target.dyn_into::<HtmlSelectElement>().unwrap_throw();
+            RulesMsg::DeltaChanged(target.value())
+        });
+        let onsubmit = ctx.link().callback(|ev: FocusEvent| {
+            ev.prevent_default();
+            RulesMsg::Submitted
+        });
+
+        html! {
+            <form {onsubmit}>
+                <h2>{ "new rule" }</h2>
+                {
+                    if let Some(err) = &self.form_error {
+                        html! { <p>{ err }</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <div>
+                    <label for="rule-category">{ "category:" }</label>
+                    <input type="text" id="rule-category" onchange={onchange_category} required=true/>
+                </div>
+                <div>
+                    <label for="rule-pattern">{ "description matches (regex):" }</label>
+                    <input type="text" id="rule-pattern" placeholder="e.g. safeway|kroger" onchange={onchange_pattern}/>
+                </div>
+                <div>
+                    <label for="rule-min">{ "minimum amount:" }</label>
+                    <input type="number" id="rule-min" step=0.01 onchange={onchange_min}/>
+                </div>
+                <div>
+                    <label for="rule-max">{ "maximum amount:" }</label>
+                    <input type="number" id="rule-max" step=0.01 onchange={onchange_max}/>
+                </div>
+                <div>
+                    <label for="rule-account">{ "account:" }</label>
+                    <input type="text" id="rule-account" onchange={onchange_account}/>
+                </div>
+                <div>
+                    <label for="rule-delta">{ "link to delta:" }</label>
+                    <select name="rule-delta" id="rule-delta" oninput={oninput_delta}>
+                        <option value="" selected=true>{ "none" }</option>
+                        {
+                            self.predictions.iter().flat_map(|prediction| {
+                                prediction.deltas().iter().map(move |delta| html! {
+                                    <option value={delta.id().to_string()}>
+                                        { format!("{} / {}", prediction.name(), delta.name()) }
+                                    </option>
+                                })
+                            }).collect::<Html>()
+                        }
+                    </select>
+                </div>
+                <input type="submit" value="add rule"/>
+            </form>
+        }
+    }
+
+    fn view_apply(&self, ctx: &Context<Self>) -> Html {
+        let onclick = ctx.link().callback(|_| RulesMsg::ApplyRequested);
+
+        html! {
+            <div>
+                <button {onclick}>{ "re-apply rules to all transactions" }</button>
+                {
+                    match &self.apply_response {
+                        Some(Ok(summary)) => html! {
+                            <p>{ format!("{} transactions categorized, {} uncategorized", summary.categorized, summary.uncategorized) }</p>
+                        },
+                        Some(Err(err)) => html! { <p>{ format!("error applying rules: {}", err) }</p> },
+                        None => html! {},
+                    }
+                }
+            </div>
+        }
+    }
+}
+
+impl Rules {
+    fn parse_amount(&mut self, amount: &str) -> Option<f32> {
+        let amount = optional(amount)?;
+        match amount.parse::<f32>() {
+            Ok(amount) => {
+                self.form_error = None;
+                Some(amount)
+            }
+            Err(_) => {
+                self.form_error = Some(format!("invalid amount ({})", amount));
+                None
+            }
+        }
+    }
+
+    fn path(&self, username: &str) -> String {
+        fully_qualified_path(
+            &replace_pattern(routes::RULES, path_patterns::RULES, username)
+                .expect("could not replace pattern in route"),
+        )
+        .expect("could not create path")
+    }
+
+    fn get_rules_if_logged_in(&self, ctx: &Context<Self>) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.get_rules(ctx, username);
+        }
+    }
+
+    fn get_rules(&self, ctx: &Context<Self>, username: &str) {
+        let path = self.path(username);
+
+        let client = self.client.clone();
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = client.get(path);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response
+                        .json::<Vec<CategorizationRule>>()
+                        .await
+                        .map_err(|err| {
+                            InternalResponseError::ResponseAwaitError("rules", err.to_string())
+                        })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(RulesMsg::ReceivedRules(response));
+        });
+    }
+
+    fn get_predictions_if_logged_in(&self, ctx: &Context<Self>) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.get_predictions(ctx, username);
+        }
+    }
+
+    fn get_predictions(&self, ctx: &Context<Self>, username: &str) {
+        let path = fully_qualified_path(
+            &replace_pattern(routes::PREDICTIONS, path_patterns::PREDICTIONS, username)
+                .expect("could not replace pattern in route"),
+        )
+        .expect("could not create path");
+
+        let client = self.client.clone();
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = client.get(path);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response
+                        .json::<Vec<PredictionWithDeltas>>()
+                        .await
+                        .map_err(|err| {
+                            InternalResponseError::ResponseAwaitError(
+                                "predictions",
+                                err.to_string(),
+                            )
+                        })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(RulesMsg::ReceivedPredictions(response));
+        });
+    }
+
+    fn put_rule_if_logged_in(&mut self, ctx: &Context<Self>) {
+        let username = match self.app_context.borrow_mut().username() {
+            Some(username) => username.to_owned(),
+            None => return,
+        };
+
+        let rule = NewCategorizationRule::new(
+            username.clone(),
+            self.category.clone(),
+            optional(&self.pattern),
+            self.min_amount,
+            self.max_amount,
+            optional(&self.account),
+            self.delta_id,
+        );
+
+        match rule {
+            Ok(rule) => self.put_rule(ctx, &username, rule),
+            Err(err) => self.form_error = Some(err.to_string()),
+        }
+    }
+
+    fn put_rule(&self, ctx: &Context<Self>, username: &str, rule: NewCategorizationRule) {
+        let path = self.path(username);
+
+        let client = self.client.clone();
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = client.put(path).json(&rule);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response.json::<CategorizationRule>().await.map_err(|err| {
+                        InternalResponseError::ResponseAwaitError("rule", err.to_string())
+                    })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(RulesMsg::ReceivedCreateResponse(response));
+        });
+    }
+
+    fn delete_rule_if_logged_in(&self, ctx: &Context<Self>, rule: CategorizationRule) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.delete_rule(ctx, username, rule);
+        }
+    }
+
+    fn delete_rule(&self, ctx: &Context<Self>, username: &str, rule: CategorizationRule) {
+        let path = self.path(username);
+
+        let client = self.client.clone();
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = client.delete(path).json(&rule);
+            let on_ok = ResponseAction::from(|_| Ok(()));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(RulesMsg::ReceivedDeleteResponse(response));
+        });
+    }
+
+    fn apply_rules_if_logged_in(&self, ctx: &Context<Self>) {
+        if let Some(username) = self.app_context.borrow_mut().username() {
+            self.apply_rules(ctx, username);
+        }
+    }
+
+    fn apply_rules(&self, ctx: &Context<Self>, username: &str) {
+        let path = fully_qualified_path(
+            &replace_pattern(routes::RULES_APPLY, path_patterns::RULES_APPLY, username)
+                .expect("could not replace pattern in route"),
+        )
+        .expect("could not create path");
+
+        let client = self.client.clone();
+        let scope = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let request = client.post(path);
+            let on_ok = ResponseAction::new(Box::new(|response| {
+                Box::pin(async {
+                    response
+                        .json::<CategorizationSummary>()
+                        .await
+                        .map_err(|err| {
+                            InternalResponseError::ResponseAwaitError(
+                                "categorization summary",
+                                err.to_string(),
+                            )
+                        })
+                })
+            }));
+            let requester = Requester::default();
+            let response = requester.make(request, on_ok).await;
+
+            scope.send_message(RulesMsg::ReceivedApplyResponse(response));
+        });
+    }
+}
@@ -0,0 +1,102 @@
+use reqwest::Client;
+use serde::Deserialize;
+use shared::models::EmailVerificationForm;
+use shared::routes;
+use std::sync::Arc;
+use yew::prelude::*;
+use yew_router::history::Location;
+use yew_router::prelude::*;
+
+use crate::app::Route;
+use crate::components::{Header, Loading};
+use crate::requests::{fully_qualified_path, Requester, ResponseAction};
+use crate::ResponseResult;
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: String,
+}
+
+pub enum VerifyEmailMsg {
+    ResponseReceived(ResponseResult<()>),
+}
+
+/// Verify an email address from the link in a verification email. The token
+/// is submitted as soon as the page opens.
+pub struct VerifyEmail {
+    token: Option<String>,
+    response: Option<ResponseResult<()>>,
+}
+
+impl Component for VerifyEmail {
+    type Message = VerifyEmailMsg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let token = ctx
+            .link()
+            .location()
+            .and_then(|location| location.query::<TokenQuery>().ok())
+            .map(|query| query.token);
+
+        if let Some(token) = &token {
+            let form = EmailVerificationForm::new(token.clone());
+            let path = fully_qualified_path(routes::EMAIL_VERIFICATION)
+                .expect("could not build fully qualified path");
+
+            let scope = ctx.link().clone();
+            let client = Arc::new(Client::new());
+            wasm_bindgen_futures::spawn_local(async move {
+                let request = client.post(path).json(&form);
+                let on_ok = ResponseAction::from(|_| Ok(()));
+
+                let requester = Requester::default();
+                let response = requester.make(request, on_ok).await;
+
+                scope.send_message(VerifyEmailMsg::ResponseReceived(response));
+            })
+        }
+
+        VerifyEmail {
+            token,
+            response: None,
+        }
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        let body = match (&self.token, &self.response) {
+            (None, _) => html! {
+                <p>{ "this verification link is incomplete, please request another from your account" }</p>
+            },
+            (Some(_), None) => html! { <Loading /> },
+            (Some(_), Some(Ok(()))) => html! {
+                <>
+                    <p>{ "your email has been verified" }</p>
+                    <Link<Route> to={Route::Account}>{ "account" }</Link<Route>>
+                </>
+            },
+            (Some(_), Some(Err(error_msg))) => html! {
+                <>
+                    <p>{ error_msg }</p>
+                    <Link<Route> to={Route::Account}>{ "request another from your account" }</Link<Route>>
+                </>
+            },
+        };
+
+        html! {
+            <>
+                <Header heading="verify email" title="verify email" />
+                { body }
+            </>
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            VerifyEmailMsg::ResponseReceived(response) => {
+                self.response = Some(response);
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,8 @@
+CREATE TABLE transactions (
+    id SERIAL PRIMARY KEY,
+    username VARCHAR NOT NULL REFERENCES users(username) ON UPDATE CASCADE ON DELETE CASCADE,
+    posted_on DATE NOT NULL,
+    amount REAL NOT NULL,
+    description VARCHAR NOT NULL,
+    imported TIMESTAMP NOT NULL
+)
@@ -0,0 +1,5 @@
+DROP INDEX transactions_username_fitid_idx;
+
+ALTER TABLE transactions
+    DROP COLUMN fitid,
+    DROP COLUMN account;
@@ -0,0 +1,5 @@
+ALTER TABLE transactions
+    ADD COLUMN fitid VARCHAR,
+    ADD COLUMN account VARCHAR;
+
+CREATE INDEX transactions_username_fitid_idx ON transactions (username, fitid) WHERE fitid IS NOT NULL;
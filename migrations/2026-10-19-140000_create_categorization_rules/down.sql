@@ -0,0 +1,5 @@
+ALTER TABLE transactions
+    DROP COLUMN delta_id,
+    DROP COLUMN category;
+
+DROP TABLE categorization_rules;
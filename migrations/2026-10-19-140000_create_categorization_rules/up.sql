@@ -0,0 +1,14 @@
+CREATE TABLE categorization_rules (
+    id SERIAL PRIMARY KEY,
+    username VARCHAR NOT NULL REFERENCES users(username) ON UPDATE CASCADE ON DELETE CASCADE,
+    category VARCHAR NOT NULL,
+    description_pattern VARCHAR,
+    min_amount REAL,
+    max_amount REAL CHECK(min_amount IS NULL OR max_amount IS NULL OR min_amount <= max_amount),
+    account VARCHAR,
+    delta_id INTEGER REFERENCES deltas(id) ON DELETE SET NULL
+);
+
+ALTER TABLE transactions
+    ADD COLUMN category VARCHAR,
+    ADD COLUMN delta_id INTEGER REFERENCES deltas(id) ON DELETE SET NULL;
@@ -0,0 +1,7 @@
+DELETE FROM deltas WHERE repetition = 5;
+
+ALTER TABLE deltas DROP CONSTRAINT deltas_repeat_weekday_check;
+ALTER TABLE deltas ADD CONSTRAINT deltas_repeat_weekday_check CHECK((repetition = 2 AND repeat_weekday = ANY('{Mon,Tue,Wed,Thu,Fri,Sat,Sun}'::VARCHAR[])) OR (repetition != 2 AND repeat_weekday IS NULL));
+
+ALTER TABLE deltas DROP CONSTRAINT deltas_repetition_check;
+ALTER TABLE deltas ADD CONSTRAINT deltas_repetition_check CHECK(repetition BETWEEN 1 AND 4);
@@ -0,0 +1,6 @@
+-- 5 = biweekly, which repeats on a weekday like weekly does
+ALTER TABLE deltas DROP CONSTRAINT deltas_repetition_check;
+ALTER TABLE deltas ADD CONSTRAINT deltas_repetition_check CHECK(repetition BETWEEN 1 AND 5);
+
+ALTER TABLE deltas DROP CONSTRAINT deltas_repeat_weekday_check;
+ALTER TABLE deltas ADD CONSTRAINT deltas_repeat_weekday_check CHECK((repetition IN (2, 5) AND repeat_weekday = ANY('{Mon,Tue,Wed,Thu,Fri,Sat,Sun}'::VARCHAR[])) OR (repetition NOT IN (2, 5) AND repeat_weekday IS NULL));
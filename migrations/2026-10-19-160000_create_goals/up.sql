@@ -0,0 +1,9 @@
+CREATE TABLE goals (
+    id SERIAL PRIMARY KEY,
+    prediction_id INTEGER NOT NULL REFERENCES predictions(id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    target_balance REAL NOT NULL,
+    target_date DATE CHECK(target_date IS NULL OR target_date > start_on),
+    starting_balance REAL NOT NULL,
+    start_on DATE NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP TABLE investment_contributions;
+DROP TABLE investment_accounts;
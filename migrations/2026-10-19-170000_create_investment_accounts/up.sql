@@ -0,0 +1,15 @@
+CREATE TABLE investment_accounts (
+    id SERIAL PRIMARY KEY,
+    prediction_id INTEGER NOT NULL REFERENCES predictions(id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    starting_balance REAL NOT NULL,
+    start_on DATE NOT NULL,
+    expected_return REAL NOT NULL,
+    volatility REAL NOT NULL CHECK(volatility >= 0)
+);
+
+CREATE TABLE investment_contributions (
+    investment_account_id INTEGER NOT NULL REFERENCES investment_accounts(id) ON DELETE CASCADE,
+    delta_id INTEGER NOT NULL REFERENCES deltas(id) ON DELETE CASCADE,
+    PRIMARY KEY (investment_account_id, delta_id)
+);
@@ -0,0 +1,2 @@
+DROP TABLE asset_valuations;
+DROP TABLE assets;
@@ -0,0 +1,15 @@
+CREATE TABLE assets (
+    id SERIAL PRIMARY KEY,
+    prediction_id INTEGER NOT NULL REFERENCES predictions(id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    is_liability BOOLEAN NOT NULL DEFAULT FALSE,
+    annual_rate REAL
+);
+
+CREATE TABLE asset_valuations (
+    id SERIAL PRIMARY KEY,
+    asset_id INTEGER NOT NULL REFERENCES assets(id) ON DELETE CASCADE,
+    valued_on DATE NOT NULL,
+    value REAL NOT NULL CHECK(value >= 0),
+    UNIQUE (asset_id, valued_on)
+);
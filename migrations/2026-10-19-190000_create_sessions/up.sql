@@ -0,0 +1,10 @@
+CREATE TABLE sessions (
+    id SERIAL PRIMARY KEY,
+    token VARCHAR NOT NULL UNIQUE,
+    username VARCHAR NOT NULL REFERENCES users(username) ON UPDATE CASCADE ON DELETE CASCADE,
+    created TIMESTAMP NOT NULL,
+    last_seen TIMESTAMP NOT NULL,
+    user_agent VARCHAR
+);
+
+CREATE INDEX sessions_username_idx ON sessions(username);
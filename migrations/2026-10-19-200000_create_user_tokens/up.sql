@@ -0,0 +1,11 @@
+CREATE TABLE user_tokens (
+    id SERIAL PRIMARY KEY,
+    username VARCHAR NOT NULL REFERENCES users(username) ON UPDATE CASCADE ON DELETE CASCADE,
+    purpose VARCHAR NOT NULL CHECK(purpose IN ('password_reset')),
+    token_hash VARCHAR NOT NULL UNIQUE,
+    created TIMESTAMP NOT NULL,
+    expires TIMESTAMP NOT NULL,
+    used TIMESTAMP
+);
+
+CREATE INDEX user_tokens_username_idx ON user_tokens(username);
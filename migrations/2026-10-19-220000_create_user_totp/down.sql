@@ -0,0 +1,8 @@
+DELETE FROM user_tokens WHERE purpose = 'two_factor_login';
+
+ALTER TABLE user_tokens DROP CONSTRAINT user_tokens_purpose_check;
+ALTER TABLE user_tokens ADD CONSTRAINT user_tokens_purpose_check
+    CHECK(purpose IN ('password_reset', 'email_verification'));
+
+DROP TABLE recovery_codes;
+DROP TABLE user_totp;
@@ -0,0 +1,20 @@
+CREATE TABLE user_totp (
+    username VARCHAR PRIMARY KEY REFERENCES users(username) ON UPDATE CASCADE ON DELETE CASCADE,
+    secret VARCHAR NOT NULL,
+    created TIMESTAMP NOT NULL,
+    enabled_at TIMESTAMP,
+    last_step BIGINT
+);
+
+CREATE TABLE recovery_codes (
+    id SERIAL PRIMARY KEY,
+    username VARCHAR NOT NULL REFERENCES users(username) ON UPDATE CASCADE ON DELETE CASCADE,
+    code_hash VARCHAR NOT NULL,
+    used TIMESTAMP
+);
+
+CREATE INDEX recovery_codes_username_idx ON recovery_codes(username);
+
+ALTER TABLE user_tokens DROP CONSTRAINT user_tokens_purpose_check;
+ALTER TABLE user_tokens ADD CONSTRAINT user_tokens_purpose_check
+    CHECK(purpose IN ('password_reset', 'email_verification', 'two_factor_login'));
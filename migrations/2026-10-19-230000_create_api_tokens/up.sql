@@ -0,0 +1,12 @@
+CREATE TABLE api_tokens (
+    id SERIAL PRIMARY KEY,
+    username VARCHAR NOT NULL REFERENCES users(username) ON UPDATE CASCADE ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    token_hash VARCHAR NOT NULL UNIQUE,
+    scopes VARCHAR NOT NULL,
+    created TIMESTAMP NOT NULL,
+    last_used TIMESTAMP,
+    expires TIMESTAMP
+);
+
+CREATE INDEX api_tokens_username_idx ON api_tokens(username);
@@ -0,0 +1,5 @@
+-- Hashed tokens cannot be turned back into the tokens cookies carry
+DELETE FROM sessions;
+
+ALTER TABLE sessions RENAME CONSTRAINT sessions_token_hash_key TO sessions_token_key;
+ALTER TABLE sessions RENAME COLUMN token_hash TO token;
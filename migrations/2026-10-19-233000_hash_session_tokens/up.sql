@@ -0,0 +1,4 @@
+ALTER TABLE sessions RENAME COLUMN token TO token_hash;
+ALTER TABLE sessions RENAME CONSTRAINT sessions_token_key TO sessions_token_hash_key;
+
+UPDATE sessions SET token_hash = encode(sha256(convert_to(token_hash, 'UTF8')), 'hex');
@@ -0,0 +1,3 @@
+-- Backfilled addresses cannot be told apart from ones verified by link, so
+-- there is nothing to undo.
+SELECT 1;
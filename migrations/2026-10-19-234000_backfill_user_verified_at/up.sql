@@ -0,0 +1,4 @@
+-- Accounts created before email verification existed were never sent a link,
+-- so trust the address they signed up with rather than locking them out of
+-- password resets.
+UPDATE users SET verified_at = created WHERE verified_at IS NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE deltas
+    ADD COLUMN kind VARCHAR NOT NULL DEFAULT 'other'
+    CHECK (kind IN ('other', 'income', 'pre_tax_deduction', 'withholding'));
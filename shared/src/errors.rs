@@ -17,6 +17,36 @@ pub enum MoolahSharedError {
     #[error("unable to deseriealize Delta: {0}")]
     DeltaDeserializationError(&'static str),
 
+    #[error("API token error: {0}")]
+    ApiTokenError(String),
+
+    #[error("asset error: {0}")]
+    AssetError(String),
+
     #[error("delta repetition error: {0}")]
     RepetitionError(String),
+
+    #[error("categorization rule error: {0}")]
+    RuleError(String),
+
+    #[error("export error: {0}")]
+    ExportError(String),
+
+    #[error("import error: {0}")]
+    ImportError(String),
+
+    #[error("goal error: {0}")]
+    GoalError(String),
+
+    #[error("generator error: {0}")]
+    GeneratorError(String),
+
+    #[error("investment account error: {0}")]
+    InvestmentError(String),
+
+    #[error("retirement projection error: {0}")]
+    RetirementError(String),
+
+    #[error("tax table error: {0}")]
+    TaxError(String),
 }
@@ -0,0 +1,128 @@
+pub mod journal;
+#[cfg(test)]
+mod tests;
+
+pub use journal::{JournalFormat, JournalQuery};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Delta, Repetition};
+use crate::projection::Occurrence;
+use crate::MoolahSharedError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportQuery {
+    pub prediction_id: i32,
+    pub format: ExportFormat,
+    /// Only used by the schedule export, where the running balance starts
+    #[serde(default)]
+    pub starting_balance: f32,
+}
+
+/// A delta flattened into a single row, with its repetition spelled out in
+/// the same columns the database uses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeltaRow {
+    pub id: i32,
+    pub name: String,
+    pub value: f32,
+    pub positive_uncertainty: f32,
+    pub negative_uncertainty: f32,
+    pub repetition: String,
+    pub start_on: NaiveDate,
+    pub end_on: Option<NaiveDate>,
+    pub repeat_day: Option<i16>,
+    pub repeat_weekday: Option<String>,
+    pub kind: String,
+}
+
+impl From<&Delta> for DeltaRow {
+    fn from(delta: &Delta) -> Self {
+        let (repetition, start_on, end_on, repeat_day, repeat_weekday) = match delta.repetition() {
+            Repetition::Monthly {
+                from,
+                to,
+                repeat_on_day,
+            } => ("monthly", from, Some(to), Some(repeat_on_day.into()), None),
+            Repetition::Weekly {
+                from,
+                to,
+                repeat_on_weekday,
+            } => (
+                "weekly",
+                from,
+                Some(to),
+                None,
+                Some(repeat_on_weekday.to_string()),
+            ),
+            Repetition::Biweekly {
+                from,
+                to,
+                repeat_on_weekday,
+            } => (
+                "biweekly",
+                from,
+                Some(to),
+                None,
+                Some(repeat_on_weekday.to_string()),
+            ),
+            Repetition::Daily { from, to } => ("daily", from, Some(to), None, None),
+            Repetition::Once { on } => ("once", on, None, None, None),
+        };
+
+        DeltaRow {
+            id: delta.id(),
+            name: delta.name().to_string(),
+            value: delta.value(),
+            positive_uncertainty: delta.positive_uncertainty(),
+            negative_uncertainty: delta.negative_uncertainty(),
+            repetition: repetition.to_string(),
+            start_on,
+            end_on,
+            repeat_day,
+            repeat_weekday,
+            kind: delta.kind().as_str().to_string(),
+        }
+    }
+}
+
+fn to_csv<T: Serialize>(rows: impl IntoIterator<Item = T>) -> Result<String, MoolahSharedError> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+
+    for row in rows {
+        writer
+            .serialize(row)
+            .map_err(|err| MoolahSharedError::ExportError(err.to_string()))?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| MoolahSharedError::ExportError(err.to_string()))?;
+
+    String::from_utf8(bytes).map_err(|err| MoolahSharedError::ExportError(err.to_string()))
+}
+
+pub fn deltas_csv(deltas: &[Delta]) -> Result<String, MoolahSharedError> {
+    to_csv(deltas.iter().map(DeltaRow::from))
+}
+
+pub fn schedule_csv(occurrences: &[Occurrence]) -> Result<String, MoolahSharedError> {
+    to_csv(occurrences)
+}
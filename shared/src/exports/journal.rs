@@ -0,0 +1,263 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Delta, Repetition};
+use crate::projection;
+use crate::MoolahSharedError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalFormat {
+    Ledger,
+    Hledger,
+    Beancount,
+}
+
+impl JournalFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            JournalFormat::Ledger => "ledger",
+            JournalFormat::Hledger => "journal",
+            JournalFormat::Beancount => "beancount",
+        }
+    }
+
+    fn date(&self, date: NaiveDate) -> String {
+        match self {
+            JournalFormat::Ledger => date.format("%Y/%m/%d").to_string(),
+            JournalFormat::Hledger | JournalFormat::Beancount => {
+                date.format("%Y-%m-%d").to_string()
+            }
+        }
+    }
+
+    fn amount(&self, value: f32) -> String {
+        match self {
+            JournalFormat::Ledger | JournalFormat::Hledger => format!("${:.2}", value),
+            JournalFormat::Beancount => format!("{:.2} USD", value),
+        }
+    }
+}
+
+fn default_account() -> String {
+    "Assets:Checking".to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalQuery {
+    pub prediction_id: i32,
+    pub format: JournalFormat,
+    /// The account every delta is posted to; the other side goes to
+    /// `Income:Forecast` or `Expenses:Forecast`
+    #[serde(default = "default_account")]
+    pub account: String,
+}
+
+const INCOME_ACCOUNT: &str = "Income:Forecast";
+const EXPENSES_ACCOUNT: &str = "Expenses:Forecast";
+
+fn validate_account(account: &str) -> Result<(), MoolahSharedError> {
+    let valid = !account.is_empty()
+        && account.split(':').all(|part| {
+            part.chars()
+                .next()
+                .map(|c| c.is_ascii_uppercase())
+                .unwrap_or(false)
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(MoolahSharedError::ExportError(format!(
+            "invalid account name '{}' (expected e.g. Assets:Checking)",
+            account
+        )))
+    }
+}
+
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// The period expression for a repetition, if the journal format can generate
+/// exactly the same dates from it.
+///
+/// Monthly repetitions on days after the 28th are left out, since moolah
+/// clips them to the end of shorter months and the plain-text tools do not
+/// agree on how to do that.
+fn period(repetition: &Repetition, format: JournalFormat) -> Option<String> {
+    let dates = repetition.dates();
+    let (first, last) = (dates.first()?, dates.last()?);
+    let range = format!(
+        "from {} to {}",
+        format.date(*first),
+        format.date(*last + Duration::days(1))
+    );
+
+    match (repetition, format) {
+        (_, JournalFormat::Beancount) | (Repetition::Once { .. }, _) => None,
+        (Repetition::Monthly { repeat_on_day, .. }, _) => {
+            let day: u32 = (*repeat_on_day).into();
+            if day > 28 {
+                None
+            } else if format == JournalFormat::Hledger {
+                Some(format!("every {} day of month {}", ordinal(day), range))
+            } else {
+                Some(format!("monthly {}", range))
+            }
+        }
+        (
+            Repetition::Weekly {
+                repeat_on_weekday, ..
+            },
+            JournalFormat::Hledger,
+        ) => Some(format!(
+            "every {} day of week {}",
+            ordinal(repeat_on_weekday.number_from_monday()),
+            range
+        )),
+        (Repetition::Weekly { .. }, _) => Some(format!("weekly {}", range)),
+        (Repetition::Biweekly { .. }, _) => Some(format!("every 2 weeks {}", range)),
+        (Repetition::Daily { .. }, _) => Some(format!("daily {}", range)),
+    }
+}
+
+fn write_postings(
+    out: &mut String,
+    delta: &Delta,
+    account: &str,
+    format: JournalFormat,
+) -> std::fmt::Result {
+    if delta.positive_uncertainty() != 0. || delta.negative_uncertainty() != 0. {
+        match format {
+            JournalFormat::Beancount => writeln!(
+                out,
+                "  uncertainty: \"+{:.2} / -{:.2}\"",
+                delta.positive_uncertainty(),
+                delta.negative_uncertainty()
+            )?,
+            _ => writeln!(
+                out,
+                "    ; uncertainty: +{:.2} / -{:.2}",
+                delta.positive_uncertainty(),
+                delta.negative_uncertainty()
+            )?,
+        }
+    }
+
+    let other = if delta.value() >= 0. {
+        INCOME_ACCOUNT
+    } else {
+        EXPENSES_ACCOUNT
+    };
+
+    writeln!(out, "    {}  {}", account, format.amount(delta.value()))?;
+    writeln!(out, "    {}", other)?;
+    writeln!(out)
+}
+
+/// Write a prediction's deltas as a plain-text accounting journal.
+///
+/// Deltas whose repetition has an equivalent period expression become periodic
+/// transactions (ledger and hledger only); everything else is expanded into
+/// dated transactions. Deltas that don't move the balance are left out.
+pub fn journal(
+    prediction_name: &str,
+    deltas: &[Delta],
+    format: JournalFormat,
+    account: &str,
+) -> Result<String, MoolahSharedError> {
+    validate_account(account)?;
+
+    let mut periodic = Vec::new();
+    let mut dated = Vec::new();
+    for delta in deltas.iter().filter(|delta| delta.kind().moves_balance()) {
+        match period(&delta.repetition(), format) {
+            Some(period) => periodic.push((delta, period)),
+            None => dated.push(delta.clone()),
+        }
+    }
+
+    let by_id = dated
+        .iter()
+        .map(|delta| (delta.id(), delta))
+        .collect::<HashMap<_, _>>();
+    let occurrences = projection::schedule(&dated, 0.);
+
+    let mut out = String::new();
+    write_journal(
+        &mut out,
+        prediction_name,
+        &periodic,
+        &occurrences,
+        &by_id,
+        format,
+        account,
+    )
+    .map_err(|err| MoolahSharedError::ExportError(err.to_string()))?;
+
+    Ok(out)
+}
+
+fn write_journal(
+    out: &mut String,
+    prediction_name: &str,
+    periodic: &[(&Delta, String)],
+    occurrences: &[projection::Occurrence],
+    by_id: &HashMap<i32, &Delta>,
+    format: JournalFormat,
+    account: &str,
+) -> std::fmt::Result {
+    writeln!(out, "; moolah prediction: {}", prediction_name)?;
+    writeln!(out)?;
+
+    if format == JournalFormat::Beancount {
+        if let Some(first) = occurrences.first() {
+            for open in [account, INCOME_ACCOUNT, EXPENSES_ACCOUNT] {
+                writeln!(out, "{} open {}", format.date(first.date), open)?;
+            }
+            writeln!(out)?;
+        }
+    }
+
+    for (delta, period) in periodic {
+        match format {
+            JournalFormat::Hledger => writeln!(out, "~ {}  {}", period, delta.name())?,
+            _ => {
+                writeln!(out, "~ {}", period)?;
+                writeln!(out, "    ; {}", delta.name())?;
+            }
+        }
+        write_postings(out, delta, account, format)?;
+    }
+
+    for occurrence in occurrences {
+        let delta = by_id[&occurrence.delta_id];
+        match format {
+            JournalFormat::Beancount => writeln!(
+                out,
+                "{} * \"{}\"",
+                format.date(occurrence.date),
+                delta.name().replace('\\', "\\\\").replace('"', "\\\"")
+            )?,
+            _ => writeln!(out, "{} {}", format.date(occurrence.date), delta.name())?,
+        }
+        write_postings(out, delta, account, format)?;
+    }
+
+    Ok(())
+}
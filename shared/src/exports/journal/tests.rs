@@ -0,0 +1,155 @@
+use chrono::Weekday;
+
+use super::*;
+use crate::models::deltas::app::repetition::MonthDay;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn deltas() -> Vec<Delta> {
+    vec![
+        Delta::new(
+            1,
+            1,
+            "paycheck".into(),
+            1000.,
+            0.,
+            0.,
+            Repetition::Monthly {
+                from: date(2022, 1, 1),
+                to: date(2022, 2, 28),
+                repeat_on_day: MonthDay::new(15).unwrap(),
+            },
+        ),
+        Delta::new(
+            2,
+            1,
+            "groceries".into(),
+            -100.,
+            20.,
+            50.,
+            Repetition::Weekly {
+                from: date(2022, 1, 1),
+                to: date(2022, 1, 20),
+                repeat_on_weekday: Weekday::Tue,
+            },
+        ),
+        Delta::new(
+            3,
+            1,
+            "rent".into(),
+            -1200.,
+            0.,
+            0.,
+            Repetition::Monthly {
+                from: date(2022, 1, 1),
+                to: date(2022, 2, 28),
+                repeat_on_day: MonthDay::new(31).unwrap(),
+            },
+        ),
+        Delta::new(
+            4,
+            1,
+            "\"big\" gift".into(),
+            25.,
+            0.,
+            0.,
+            Repetition::Once {
+                on: date(2022, 1, 20),
+            },
+        ),
+    ]
+}
+
+#[test]
+fn test_ledger_journal() {
+    assert_eq!(
+        journal("2022", &deltas(), JournalFormat::Ledger, "Assets:Checking").unwrap(),
+        "; moolah prediction: 2022\n\
+         \n\
+         ~ monthly from 2022/01/15 to 2022/02/16\n    \
+         ; paycheck\n    \
+         Assets:Checking  $1000.00\n    \
+         Income:Forecast\n\
+         \n\
+         ~ weekly from 2022/01/04 to 2022/01/19\n    \
+         ; groceries\n    \
+         ; uncertainty: +20.00 / -50.00\n    \
+         Assets:Checking  $-100.00\n    \
+         Expenses:Forecast\n\
+         \n\
+         2022/01/20 \"big\" gift\n    \
+         Assets:Checking  $25.00\n    \
+         Income:Forecast\n\
+         \n\
+         2022/01/31 rent\n    \
+         Assets:Checking  $-1200.00\n    \
+         Expenses:Forecast\n\
+         \n\
+         2022/02/28 rent\n    \
+         Assets:Checking  $-1200.00\n    \
+         Expenses:Forecast\n\
+         \n"
+    );
+}
+
+#[test]
+fn test_hledger_periodic_rules() {
+    let journal = journal("2022", &deltas(), JournalFormat::Hledger, "Assets:Bank").unwrap();
+
+    assert!(journal.contains("~ every 15th day of month from 2022-01-15 to 2022-02-16  paycheck\n"));
+    assert!(journal.contains("~ every 2nd day of week from 2022-01-04 to 2022-01-19  groceries\n"));
+    assert!(journal.contains("2022-01-31 rent\n    Assets:Bank  $-1200.00\n"));
+    assert!(!journal.contains("~ monthly"));
+}
+
+#[test]
+fn test_beancount_expands_everything() {
+    let journal = journal(
+        "2022",
+        &deltas(),
+        JournalFormat::Beancount,
+        "Assets:Checking",
+    )
+    .unwrap();
+
+    assert!(!journal.contains('~'));
+    assert!(journal.contains(
+        "2022-01-04 open Assets:Checking\n\
+         2022-01-04 open Income:Forecast\n\
+         2022-01-04 open Expenses:Forecast\n"
+    ));
+    assert!(journal.contains(
+        "2022-01-04 * \"groceries\"\n  \
+         uncertainty: \"+20.00 / -50.00\"\n    \
+         Assets:Checking  -100.00 USD\n    \
+         Expenses:Forecast\n"
+    ));
+    assert!(journal.contains("2022-01-20 * \"\\\"big\\\" gift\"\n"));
+    assert_eq!(journal.matches(" * \"paycheck\"").count(), 2);
+    assert_eq!(journal.matches(" * \"groceries\"").count(), 3);
+}
+
+#[test]
+fn test_invalid_account() {
+    assert!(journal("2022", &deltas(), JournalFormat::Ledger, "checking").is_err());
+    assert!(journal(
+        "2022",
+        &deltas(),
+        JournalFormat::Ledger,
+        "Assets:  Checking"
+    )
+    .is_err());
+    assert!(journal("2022", &deltas(), JournalFormat::Ledger, "").is_err());
+}
+
+#[test]
+fn test_ordinal() {
+    assert_eq!(ordinal(1), "1st");
+    assert_eq!(ordinal(2), "2nd");
+    assert_eq!(ordinal(3), "3rd");
+    assert_eq!(ordinal(11), "11th");
+    assert_eq!(ordinal(22), "22nd");
+    assert_eq!(ordinal(28), "28th");
+}
@@ -0,0 +1,76 @@
+use super::*;
+use crate::models::deltas::app::repetition::MonthDay;
+use crate::projection;
+
+fn deltas() -> Vec<Delta> {
+    vec![
+        Delta::new(
+            1,
+            1,
+            "rent".into(),
+            -1200.,
+            0.,
+            0.,
+            Repetition::Monthly {
+                from: NaiveDate::from_ymd(2022, 1, 1),
+                to: NaiveDate::from_ymd(2022, 2, 28),
+                repeat_on_day: MonthDay::new(1).unwrap(),
+            },
+        ),
+        Delta::new(
+            2,
+            1,
+            "gym, monthly".into(),
+            -30.5,
+            1.,
+            0.,
+            Repetition::Weekly {
+                from: NaiveDate::from_ymd(2022, 1, 1),
+                to: NaiveDate::from_ymd(2022, 1, 8),
+                repeat_on_weekday: chrono::Weekday::Tue,
+            },
+        ),
+        Delta::new(
+            3,
+            1,
+            "bonus".into(),
+            500.,
+            0.,
+            100.,
+            Repetition::Once {
+                on: NaiveDate::from_ymd(2022, 1, 31),
+            },
+        ),
+    ]
+}
+
+#[test]
+fn test_deltas_csv() {
+    assert_eq!(
+        deltas_csv(&deltas()).unwrap(),
+        "id,name,value,positive_uncertainty,negative_uncertainty,repetition,start_on,end_on,repeat_day,repeat_weekday,kind\n\
+         1,rent,-1200.0,0.0,0.0,monthly,2022-01-01,2022-02-28,1,,other\n\
+         2,\"gym, monthly\",-30.5,1.0,0.0,weekly,2022-01-01,2022-01-08,,Tue,other\n\
+         3,bonus,500.0,0.0,100.0,once,2022-01-31,,,,other\n"
+    );
+}
+
+#[test]
+fn test_schedule_csv() {
+    let occurrences = projection::schedule(&deltas(), 2000.);
+
+    assert_eq!(
+        schedule_csv(&occurrences).unwrap(),
+        "date,delta_id,name,value,balance,balance_high,balance_low\n\
+         2022-01-01,1,rent,-1200.0,800.0,800.0,800.0\n\
+         2022-01-04,2,\"gym, monthly\",-30.5,769.5,770.5,769.5\n\
+         2022-01-31,3,bonus,500.0,1269.5,1270.5,1169.5\n\
+         2022-02-01,1,rent,-1200.0,69.5,70.5,-30.5\n"
+    );
+}
+
+#[test]
+fn test_export_extension() {
+    assert_eq!(ExportFormat::Csv.extension(), "csv");
+    assert_eq!(ExportFormat::Json.extension(), "json");
+}
@@ -0,0 +1,75 @@
+//! Builders that turn a few facts about a financial arrangement into the
+//! deltas that model it.
+
+pub mod debt;
+pub mod loan;
+pub mod paycheck;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::models::{deltas::app::repetition::MonthDay, NewDelta, Repetition};
+use crate::MoolahSharedError;
+
+/// Round to whole cents, the way a lender or payroll system would.
+fn round_cents(value: f64) -> f64 {
+    (value * 100.).round() / 100.
+}
+
+/// Monthly payment dates starting at `first`, on the same day of the month
+/// (or the last day of shorter months).
+fn payment_dates(first: NaiveDate, n: u32) -> Result<Vec<NaiveDate>, MoolahSharedError> {
+    let repetition = Repetition::Monthly {
+        from: first,
+        to: first + Duration::days(31 * (n as i64 + 1)),
+        repeat_on_day: MonthDay::new(first.day() as i16)?,
+    };
+
+    Ok(repetition.dates().into_iter().take(n as usize).collect())
+}
+
+/// Turn a month-by-month list of payments into as few deltas as possible:
+/// each run of equal payments becomes a monthly delta on `day` and a lone
+/// payment becomes a one-time delta. Payments are recorded as negative values.
+fn monthly_payment_deltas(
+    prediction_id: i32,
+    name: &str,
+    day: u32,
+    payments: &[(NaiveDate, f32)],
+) -> Result<Vec<NewDelta>, MoolahSharedError> {
+    let mut deltas = Vec::new();
+    let mut start = 0;
+
+    while start < payments.len() {
+        let amount = payments[start].1;
+        let end = payments[start..]
+            .iter()
+            .position(|(_, payment)| *payment != amount)
+            .map_or(payments.len(), |offset| start + offset);
+
+        let repetition = if end - start == 1 {
+            Repetition::Once {
+                on: payments[start].0,
+            }
+        } else {
+            Repetition::Monthly {
+                from: payments[start].0,
+                to: payments[end - 1].0,
+                repeat_on_day: MonthDay::new(day as i16)?,
+            }
+        };
+
+        if amount != 0. {
+            deltas.push(NewDelta::new(
+                prediction_id,
+                name.to_string(),
+                -amount,
+                0.,
+                0.,
+                repetition,
+            ));
+        }
+        start = end;
+    }
+
+    Ok(deltas)
+}
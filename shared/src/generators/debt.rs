@@ -0,0 +1,289 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::{monthly_payment_deltas, payment_dates, round_cents};
+use crate::models::NewDelta;
+use crate::MoolahSharedError;
+
+/// The longest a payoff plan may run, in months.
+const MAX_MONTHS: u32 = 600;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Debt {
+    pub name: String,
+    pub balance: f32,
+    /// Annual interest rate as a percentage, e.g. `19.99`
+    pub annual_rate: f32,
+    pub minimum_payment: f32,
+}
+
+/// The order extra money is thrown at debts once every minimum is paid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PayoffStrategy {
+    /// Smallest balance first
+    Snowball,
+    /// Highest interest rate first
+    Avalanche,
+}
+
+impl PayoffStrategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PayoffStrategy::Snowball => "snowball",
+            PayoffStrategy::Avalanche => "avalanche",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtPayoffRequest {
+    /// Name of the scenario prediction the payments are added to
+    pub name: String,
+    pub debts: Vec<Debt>,
+    /// Everything available for debt payments each month
+    pub monthly_budget: f32,
+    pub first_payment_on: NaiveDate,
+    /// The strategy whose payments are added to the scenario
+    pub strategy: PayoffStrategy,
+}
+
+impl DebtPayoffRequest {
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        if self.debts.is_empty() {
+            return Err(MoolahSharedError::GeneratorError(
+                "add at least one debt".into(),
+            ));
+        }
+
+        for debt in &self.debts {
+            if debt.name.trim().is_empty() {
+                return Err(MoolahSharedError::GeneratorError(
+                    "every debt needs a name".into(),
+                ));
+            }
+            if !debt.balance.is_finite() || debt.balance <= 0. {
+                return Err(MoolahSharedError::GeneratorError(format!(
+                    "balance of {} must be greater than 0",
+                    debt.name
+                )));
+            }
+            if !debt.annual_rate.is_finite() || debt.annual_rate < 0. {
+                return Err(MoolahSharedError::GeneratorError(format!(
+                    "interest rate of {} cannot be negative",
+                    debt.name
+                )));
+            }
+            if !debt.minimum_payment.is_finite() || debt.minimum_payment <= 0. {
+                return Err(MoolahSharedError::GeneratorError(format!(
+                    "minimum payment of {} must be greater than 0",
+                    debt.name
+                )));
+            }
+        }
+
+        let minimums = self
+            .debts
+            .iter()
+            .map(|debt| debt.minimum_payment)
+            .sum::<f32>();
+        if !self.monthly_budget.is_finite() || self.monthly_budget < minimums {
+            return Err(MoolahSharedError::GeneratorError(format!(
+                "monthly budget must cover the minimum payments (${:.2})",
+                minimums
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtPayment {
+    pub date: NaiveDate,
+    pub payment: f32,
+    pub interest: f32,
+    /// What is still owed after this payment
+    pub remaining: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtPayoff {
+    pub name: String,
+    pub payments: Vec<DebtPayment>,
+    pub total_interest: f32,
+    pub payoff_on: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtPayoffPlan {
+    pub strategy: PayoffStrategy,
+    /// Every debt, in the order the strategy pays them off
+    pub debts: Vec<DebtPayoff>,
+    pub total_interest: f32,
+    pub payoff_on: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtPayoffComparison {
+    pub snowball: DebtPayoffPlan,
+    pub avalanche: DebtPayoffPlan,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtPayoffSummary {
+    pub prediction_id: i32,
+    pub plan: DebtPayoffPlan,
+}
+
+/// Run both strategies against the same debts and budget.
+pub fn compare(request: &DebtPayoffRequest) -> Result<DebtPayoffComparison, MoolahSharedError> {
+    Ok(DebtPayoffComparison {
+        snowball: plan(request, PayoffStrategy::Snowball)?,
+        avalanche: plan(request, PayoffStrategy::Avalanche)?,
+    })
+}
+
+/// Simulate paying the debts down month by month: interest accrues on each
+/// balance, every debt gets its minimum payment, and whatever is left of the
+/// budget goes to the first debt in the strategy's order that is still owed.
+/// Once a debt is paid off its minimum rolls into the budget for the rest.
+pub fn plan(
+    request: &DebtPayoffRequest,
+    strategy: PayoffStrategy,
+) -> Result<DebtPayoffPlan, MoolahSharedError> {
+    request.validate()?;
+
+    let mut debts = request.debts.iter().collect::<Vec<_>>();
+    match strategy {
+        PayoffStrategy::Snowball => debts.sort_by(|a, b| {
+            a.balance
+                .total_cmp(&b.balance)
+                .then(b.annual_rate.total_cmp(&a.annual_rate))
+        }),
+        PayoffStrategy::Avalanche => debts.sort_by(|a, b| {
+            b.annual_rate
+                .total_cmp(&a.annual_rate)
+                .then(a.balance.total_cmp(&b.balance))
+        }),
+    }
+
+    let mut balances = debts
+        .iter()
+        .map(|debt| debt.balance as f64)
+        .collect::<Vec<_>>();
+    let mut payoffs = debts
+        .iter()
+        .map(|debt| DebtPayoff {
+            name: debt.name.trim().to_string(),
+            payments: Vec::new(),
+            total_interest: 0.,
+            payoff_on: request.first_payment_on,
+        })
+        .collect::<Vec<_>>();
+
+    for date in payment_dates(request.first_payment_on, MAX_MONTHS)? {
+        if balances.iter().all(|balance| *balance <= 0.) {
+            break;
+        }
+
+        let mut owed = Vec::with_capacity(debts.len());
+        let mut paid = Vec::with_capacity(debts.len());
+        let mut budget = request.monthly_budget as f64;
+
+        for (debt, balance) in debts.iter().zip(&balances) {
+            let interest = if *balance > 0. {
+                round_cents(balance * debt.annual_rate as f64 / 100. / 12.)
+            } else {
+                0.
+            };
+            let payment = round_cents((debt.minimum_payment as f64).min(balance + interest));
+
+            budget -= payment;
+            owed.push((interest, round_cents(balance + interest)));
+            paid.push(payment);
+        }
+
+        for ((_, owed), payment) in owed.iter().zip(paid.iter_mut()) {
+            if budget <= 0. {
+                break;
+            }
+            let extra = round_cents(budget.min(owed - *payment));
+            *payment += extra;
+            budget -= extra;
+        }
+
+        for (i, ((interest, owed), payment)) in owed.into_iter().zip(paid).enumerate() {
+            if owed <= 0. {
+                continue;
+            }
+
+            balances[i] = round_cents(owed - payment);
+            payoffs[i].total_interest += interest as f32;
+            payoffs[i].payoff_on = date;
+            payoffs[i].payments.push(DebtPayment {
+                date,
+                payment: round_cents(payment) as f32,
+                interest: interest as f32,
+                remaining: balances[i] as f32,
+            });
+        }
+    }
+
+    if balances.iter().any(|balance| *balance > 0.) {
+        return Err(MoolahSharedError::GeneratorError(format!(
+            "debts are not paid off within {} years on this budget",
+            MAX_MONTHS / 12
+        )));
+    }
+
+    for payoff in payoffs.iter_mut() {
+        payoff.total_interest = round_cents(payoff.total_interest as f64) as f32;
+    }
+
+    Ok(DebtPayoffPlan {
+        strategy,
+        total_interest: round_cents(
+            payoffs
+                .iter()
+                .map(|payoff| payoff.total_interest as f64)
+                .sum(),
+        ) as f32,
+        payoff_on: payoffs
+            .iter()
+            .map(|payoff| payoff.payoff_on)
+            .max()
+            .unwrap_or(request.first_payment_on),
+        debts: payoffs,
+    })
+}
+
+impl DebtPayoffPlan {
+    /// Each debt's payments as deltas, grouping months with the same payment
+    /// into a single monthly delta.
+    pub fn new_deltas(
+        &self,
+        prediction_id: i32,
+        first_payment_on: NaiveDate,
+    ) -> Result<Vec<NewDelta>, MoolahSharedError> {
+        let mut deltas = Vec::new();
+
+        for payoff in &self.debts {
+            let payments = payoff
+                .payments
+                .iter()
+                .map(|payment| (payment.date, payment.payment))
+                .collect::<Vec<_>>();
+
+            deltas.extend(monthly_payment_deltas(
+                prediction_id,
+                &format!("{} payment", payoff.name),
+                first_payment_on.day(),
+                &payments,
+            )?);
+        }
+
+        Ok(deltas)
+    }
+}
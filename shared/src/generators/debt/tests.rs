@@ -0,0 +1,164 @@
+use super::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn debt(name: &str, balance: f32, annual_rate: f32, minimum_payment: f32) -> Debt {
+    Debt {
+        name: name.into(),
+        balance,
+        annual_rate,
+        minimum_payment,
+    }
+}
+
+fn request(monthly_budget: f32) -> DebtPayoffRequest {
+    DebtPayoffRequest {
+        name: "debt free".into(),
+        debts: vec![
+            debt("credit card", 5000., 22., 100.),
+            debt("car", 3000., 5., 150.),
+            debt("store card", 800., 26., 25.),
+        ],
+        monthly_budget,
+        first_payment_on: date(2026, 1, 15),
+        strategy: PayoffStrategy::Avalanche,
+    }
+}
+
+fn interest_free() -> DebtPayoffRequest {
+    DebtPayoffRequest {
+        debts: vec![debt("a", 300., 0., 50.), debt("b", 200., 0., 50.)],
+        ..request(150.)
+    }
+}
+
+#[test]
+fn test_strategy_order() {
+    let snowball = plan(&request(600.), PayoffStrategy::Snowball).unwrap();
+    let avalanche = plan(&request(600.), PayoffStrategy::Avalanche).unwrap();
+
+    let names = |plan: &DebtPayoffPlan| {
+        plan.debts
+            .iter()
+            .map(|payoff| payoff.name.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&snowball), vec!["store card", "car", "credit card"]);
+    assert_eq!(names(&avalanche), vec!["store card", "credit card", "car"]);
+}
+
+#[test]
+fn test_avalanche_pays_less_interest() {
+    let comparison = compare(&request(600.)).unwrap();
+
+    assert!(comparison.avalanche.total_interest < comparison.snowball.total_interest);
+    assert!(comparison.avalanche.payoff_on <= comparison.snowball.payoff_on);
+}
+
+#[test]
+fn test_every_debt_paid_off() {
+    let plan = plan(&request(600.), PayoffStrategy::Snowball).unwrap();
+
+    for payoff in &plan.debts {
+        let last = payoff.payments.last().unwrap();
+        assert_eq!(last.remaining, 0.);
+        assert_eq!(payoff.payoff_on, last.date);
+    }
+    assert_eq!(plan.payoff_on, plan.debts[2].payoff_on);
+
+    // the whole budget goes to the debts until the last month
+    let months = plan.debts[2].payments.len();
+    for month in 0..months - 1 {
+        let total = plan
+            .debts
+            .iter()
+            .filter_map(|payoff| payoff.payments.get(month))
+            .map(|payment| payment.payment)
+            .sum::<f32>();
+        assert!(
+            (total - 600.).abs() < 0.01,
+            "month {} paid {}",
+            month,
+            total
+        );
+    }
+}
+
+#[test]
+fn test_interest_free_payoff() {
+    let plan = plan(&interest_free(), PayoffStrategy::Snowball).unwrap();
+
+    assert_eq!(plan.total_interest, 0.);
+    assert_eq!(plan.debts[0].name, "b");
+    assert_eq!(plan.debts[0].payoff_on, date(2026, 2, 15));
+    assert_eq!(plan.payoff_on, date(2026, 4, 15));
+}
+
+#[test]
+fn test_new_deltas() {
+    let deltas = plan(&interest_free(), PayoffStrategy::Snowball)
+        .unwrap()
+        .new_deltas(7, date(2026, 1, 15))
+        .unwrap();
+
+    // b gets the extra 50 until it is paid off, then a gets b's payment too
+    let summary = deltas
+        .iter()
+        .map(|delta| {
+            (
+                delta.name().to_string(),
+                delta.value(),
+                delta.repetition().dates().len(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("b payment".to_string(), -100., 2),
+            ("a payment".to_string(), -50., 2),
+            ("a payment".to_string(), -150., 1),
+            ("a payment".to_string(), -50., 1),
+        ]
+    );
+    assert_eq!(deltas[3].repetition().dates()[0], date(2026, 4, 15));
+
+    let total = deltas
+        .iter()
+        .map(|delta| delta.value() * delta.repetition().dates().len() as f32)
+        .sum::<f32>();
+    assert_eq!(total, -500.);
+}
+
+#[test]
+fn test_invalid_requests() {
+    // budget below the minimums
+    assert!(plan(&request(200.), PayoffStrategy::Snowball).is_err());
+    assert!(plan(
+        &DebtPayoffRequest {
+            debts: vec![],
+            ..request(600.)
+        },
+        PayoffStrategy::Snowball
+    )
+    .is_err());
+    assert!(plan(
+        &DebtPayoffRequest {
+            debts: vec![debt("a", 1000., -1., 50.)],
+            ..request(600.)
+        },
+        PayoffStrategy::Snowball
+    )
+    .is_err());
+    // the budget never covers the interest
+    assert!(plan(
+        &DebtPayoffRequest {
+            debts: vec![debt("a", 100000., 30., 50.)],
+            ..request(50.)
+        },
+        PayoffStrategy::Snowball
+    )
+    .is_err());
+}
@@ -0,0 +1,193 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::{payment_dates, round_cents};
+use crate::models::{deltas::app::repetition::MonthDay, NewDelta, Repetition};
+use crate::MoolahSharedError;
+
+/// The longest loan term accepted, in months.
+const MAX_TERM_MONTHS: u32 = 600;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoanRequest {
+    pub prediction_id: i32,
+    pub name: String,
+    pub principal: f32,
+    /// Annual interest rate as a percentage, e.g. `6.5`
+    pub annual_rate: f32,
+    pub term_months: u32,
+    pub first_payment_on: NaiveDate,
+    /// Paid towards principal on top of every regular payment
+    #[serde(default)]
+    pub extra_monthly_payment: f32,
+}
+
+impl LoanRequest {
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        if self.name.trim().is_empty() {
+            return Err(MoolahSharedError::GeneratorError(
+                "loan needs a name".into(),
+            ));
+        }
+        if !self.principal.is_finite() || self.principal <= 0. {
+            return Err(MoolahSharedError::GeneratorError(
+                "principal must be greater than 0".into(),
+            ));
+        }
+        if !self.annual_rate.is_finite() || self.annual_rate < 0. {
+            return Err(MoolahSharedError::GeneratorError(
+                "interest rate cannot be negative".into(),
+            ));
+        }
+        if self.term_months == 0 || self.term_months > MAX_TERM_MONTHS {
+            return Err(MoolahSharedError::GeneratorError(format!(
+                "term must be between 1 and {} months",
+                MAX_TERM_MONTHS
+            )));
+        }
+        if !self.extra_monthly_payment.is_finite() || self.extra_monthly_payment < 0. {
+            return Err(MoolahSharedError::GeneratorError(
+                "extra payment cannot be negative".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoanPayment {
+    pub number: u32,
+    pub date: NaiveDate,
+    pub payment: f32,
+    pub principal: f32,
+    pub interest: f32,
+    /// What is still owed after this payment
+    pub remaining: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoanSchedule {
+    pub name: String,
+    /// The regular monthly payment, including any extra payment
+    pub monthly_payment: f32,
+    pub payments: Vec<LoanPayment>,
+    pub total_interest: f32,
+    pub payoff_on: NaiveDate,
+}
+
+/// Work out a fixed-rate loan's payments month by month, splitting each into
+/// interest on the remaining balance and principal.
+pub fn amortize(request: &LoanRequest) -> Result<LoanSchedule, MoolahSharedError> {
+    request.validate()?;
+
+    let n = request.term_months;
+    let rate = request.annual_rate as f64 / 100. / 12.;
+    let principal = request.principal as f64;
+
+    let scheduled = if rate == 0. {
+        principal / n as f64
+    } else {
+        principal * rate / (1. - (1. + rate).powi(-(n as i32)))
+    };
+    let monthly_payment =
+        round_cents(scheduled) + round_cents(request.extra_monthly_payment as f64);
+
+    let mut balance = principal;
+    let mut total_interest = 0.;
+    let mut payments = Vec::new();
+
+    for (i, date) in payment_dates(request.first_payment_on, n)?
+        .into_iter()
+        .enumerate()
+    {
+        let interest = round_cents(balance * rate);
+        // rounding the payment down can leave a few cents after the last one
+        let is_last = i as u32 + 1 == n;
+        let payment = if is_last || monthly_payment >= balance + interest {
+            round_cents(balance + interest)
+        } else {
+            monthly_payment
+        };
+
+        balance = round_cents(balance + interest - payment);
+        total_interest += interest;
+
+        payments.push(LoanPayment {
+            number: i as u32 + 1,
+            date,
+            payment: payment as f32,
+            principal: round_cents(payment - interest) as f32,
+            interest: interest as f32,
+            remaining: balance as f32,
+        });
+
+        if balance <= 0. {
+            break;
+        }
+    }
+
+    let payoff_on = payments
+        .last()
+        .map(|payment| payment.date)
+        .unwrap_or(request.first_payment_on);
+
+    Ok(LoanSchedule {
+        name: request.name.trim().to_string(),
+        monthly_payment: monthly_payment as f32,
+        payments,
+        total_interest: round_cents(total_interest) as f32,
+        payoff_on,
+    })
+}
+
+impl LoanSchedule {
+    /// The payments as deltas: one monthly delta for the regular payments and,
+    /// when the final payment is smaller, a one-time delta for it.
+    pub fn into_new_deltas(self, prediction_id: i32) -> Result<Vec<NewDelta>, MoolahSharedError> {
+        let name = format!("{} payment", self.name);
+
+        let (last, regular) = match self.payments.split_last() {
+            Some(split) => split,
+            None => return Ok(Vec::new()),
+        };
+
+        let (regular, last) = if last.payment == self.monthly_payment {
+            (&self.payments[..], None)
+        } else {
+            (regular, Some(last))
+        };
+
+        let mut deltas = Vec::new();
+
+        if let (Some(first), Some(final_regular)) = (regular.first(), regular.last()) {
+            deltas.push(NewDelta::new(
+                prediction_id,
+                name.clone(),
+                -self.monthly_payment,
+                0.,
+                0.,
+                Repetition::Monthly {
+                    from: first.date,
+                    to: final_regular.date,
+                    repeat_on_day: MonthDay::new(first.date.day() as i16)?,
+                },
+            ));
+        }
+
+        if let Some(last) = last {
+            deltas.push(NewDelta::new(
+                prediction_id,
+                format!("{} (final)", name),
+                -last.payment,
+                0.,
+                0.,
+                Repetition::Once { on: last.date },
+            ));
+        }
+
+        Ok(deltas)
+    }
+}
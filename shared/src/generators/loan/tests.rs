@@ -0,0 +1,113 @@
+use super::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn request(principal: f32, annual_rate: f32, term_months: u32) -> LoanRequest {
+    LoanRequest {
+        prediction_id: 1,
+        name: "mortgage".into(),
+        principal,
+        annual_rate,
+        term_months,
+        first_payment_on: date(2026, 1, 31),
+        extra_monthly_payment: 0.,
+    }
+}
+
+#[test]
+fn test_amortize_mortgage() {
+    let schedule = amortize(&request(200000., 6., 360)).unwrap();
+
+    assert_eq!(schedule.monthly_payment, 1199.1);
+    assert_eq!(schedule.payments.len(), 360);
+    assert_eq!(
+        schedule.payments[0],
+        LoanPayment {
+            number: 1,
+            date: date(2026, 1, 31),
+            payment: 1199.1,
+            principal: 199.1,
+            interest: 1000.,
+            remaining: 199800.9,
+        }
+    );
+    assert_eq!(schedule.payments[1].date, date(2026, 2, 28));
+    assert_eq!(schedule.payments.last().unwrap().remaining, 0.);
+    assert_eq!(schedule.payoff_on, date(2055, 12, 31));
+    assert!((schedule.total_interest - 231676.).abs() < 5.);
+}
+
+#[test]
+fn test_extra_payments_shorten_loan() {
+    let regular = amortize(&request(200000., 6., 360)).unwrap();
+    let extra = amortize(&LoanRequest {
+        extra_monthly_payment: 200.,
+        ..request(200000., 6., 360)
+    })
+    .unwrap();
+
+    assert_eq!(extra.monthly_payment, 1399.1);
+    assert!(extra.payments.len() < 280);
+    assert!(extra.payoff_on < regular.payoff_on);
+    assert!(extra.total_interest < regular.total_interest - 60000.);
+    assert_eq!(extra.payments.last().unwrap().remaining, 0.);
+}
+
+#[test]
+fn test_zero_interest() {
+    let schedule = amortize(&request(1200., 0., 12)).unwrap();
+
+    assert_eq!(schedule.monthly_payment, 100.);
+    assert_eq!(schedule.total_interest, 0.);
+    assert!(schedule
+        .payments
+        .iter()
+        .all(|payment| payment.payment == 100. && payment.interest == 0.));
+}
+
+#[test]
+fn test_into_new_deltas() {
+    let schedule = amortize(&LoanRequest {
+        extra_monthly_payment: 50.,
+        ..request(1000., 12., 12)
+    })
+    .unwrap();
+    let last = schedule.payments.last().unwrap().clone();
+    let n = schedule.payments.len();
+
+    let deltas = schedule.into_new_deltas(3).unwrap();
+
+    assert_eq!(deltas.len(), 2);
+    assert_eq!(deltas[0].name(), "mortgage payment");
+    assert_eq!(deltas[0].value(), -138.85);
+    assert_eq!(deltas[0].repetition().dates().len(), n - 1);
+    assert_eq!(deltas[1].name(), "mortgage payment (final)");
+    assert_eq!(deltas[1].value(), -last.payment);
+    assert_eq!(*deltas[1].repetition(), Repetition::Once { on: last.date });
+}
+
+#[test]
+fn test_into_new_deltas_even_payments() {
+    let deltas = amortize(&request(1200., 0., 12))
+        .unwrap()
+        .into_new_deltas(3)
+        .unwrap();
+
+    assert_eq!(deltas.len(), 1);
+    assert_eq!(deltas[0].repetition().dates().len(), 12);
+}
+
+#[test]
+fn test_invalid_requests() {
+    assert!(amortize(&request(0., 6., 360)).is_err());
+    assert!(amortize(&request(1000., -1., 360)).is_err());
+    assert!(amortize(&request(1000., 6., 0)).is_err());
+    assert!(amortize(&request(1000., 6., 601)).is_err());
+    assert!(amortize(&LoanRequest {
+        name: " ".into(),
+        ..request(1000., 6., 12)
+    })
+    .is_err());
+}
@@ -0,0 +1,319 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::round_cents;
+use crate::models::{deltas::app::repetition::MonthDay, DeltaKind, NewDelta, Repetition};
+use crate::taxes::{self, TaxBracket};
+use crate::MoolahSharedError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PayFrequency {
+    /// Every week on the weekday of the first paycheck
+    Weekly,
+    /// Every other week on the weekday of the first paycheck
+    Biweekly,
+    /// On the 15th and the last day of every month
+    SemiMonthly,
+    /// Every month on the day of the first paycheck
+    Monthly,
+}
+
+impl PayFrequency {
+    pub fn periods_per_year(&self) -> u32 {
+        match self {
+            PayFrequency::Weekly => 52,
+            PayFrequency::Biweekly => 26,
+            PayFrequency::SemiMonthly => 24,
+            PayFrequency::Monthly => 12,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PayFrequency::Weekly => "weekly",
+            PayFrequency::Biweekly => "every other week",
+            PayFrequency::SemiMonthly => "twice a month",
+            PayFrequency::Monthly => "monthly",
+        }
+    }
+
+    /// The repetitions paychecks between `from` and `to` fall on.
+    fn repetitions(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<Repetition>, MoolahSharedError> {
+        Ok(match self {
+            PayFrequency::Weekly => vec![Repetition::Weekly {
+                from,
+                to,
+                repeat_on_weekday: from.weekday(),
+            }],
+            PayFrequency::Biweekly => vec![Repetition::Biweekly {
+                from,
+                to,
+                repeat_on_weekday: from.weekday(),
+            }],
+            PayFrequency::SemiMonthly => vec![
+                Repetition::Monthly {
+                    from,
+                    to,
+                    repeat_on_day: MonthDay::new(15)?,
+                },
+                Repetition::Monthly {
+                    from,
+                    to,
+                    repeat_on_day: MonthDay::new(31)?,
+                },
+            ],
+            PayFrequency::Monthly => vec![Repetition::Monthly {
+                from,
+                to,
+                repeat_on_day: MonthDay::new(from.day() as i16)?,
+            }],
+        })
+    }
+}
+
+/// Money taken out of every paycheck before taxes, such as a 401(k) or HSA
+/// contribution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Deduction {
+    pub name: String,
+    /// Dollars per paycheck, or a percentage of gross pay when `percent` is set
+    pub amount: f32,
+    #[serde(default)]
+    pub percent: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WithholdingMethod {
+    /// A percentage of every paycheck's taxable pay
+    Flat { rate: f32 },
+    /// Annual marginal brackets applied to taxable pay as if every paycheck
+    /// were the same all year
+    Brackets { brackets: Vec<TaxBracket> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Withholding {
+    pub name: String,
+    pub method: WithholdingMethod,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaycheckRequest {
+    pub prediction_id: i32,
+    pub name: String,
+    /// Gross salary for a full year
+    pub gross_annual: f32,
+    pub frequency: PayFrequency,
+    pub first_pay_on: NaiveDate,
+    pub last_pay_on: NaiveDate,
+    #[serde(default)]
+    pub pre_tax_deductions: Vec<Deduction>,
+    #[serde(default)]
+    pub withholdings: Vec<Withholding>,
+}
+
+impl PaycheckRequest {
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        if self.name.trim().is_empty() {
+            return Err(MoolahSharedError::GeneratorError(
+                "paycheck needs a name".into(),
+            ));
+        }
+        if !self.gross_annual.is_finite() || self.gross_annual <= 0. {
+            return Err(MoolahSharedError::GeneratorError(
+                "gross salary must be greater than 0".into(),
+            ));
+        }
+        if self.last_pay_on < self.first_pay_on {
+            return Err(MoolahSharedError::GeneratorError(
+                "last paycheck cannot be before the first".into(),
+            ));
+        }
+
+        for deduction in &self.pre_tax_deductions {
+            if deduction.name.trim().is_empty() {
+                return Err(MoolahSharedError::GeneratorError(
+                    "every deduction needs a name".into(),
+                ));
+            }
+            if !deduction.amount.is_finite()
+                || deduction.amount < 0.
+                || (deduction.percent && deduction.amount > 100.)
+            {
+                return Err(MoolahSharedError::GeneratorError(format!(
+                    "invalid amount for {}",
+                    deduction.name
+                )));
+            }
+        }
+
+        for withholding in &self.withholdings {
+            if withholding.name.trim().is_empty() {
+                return Err(MoolahSharedError::GeneratorError(
+                    "every withholding needs a name".into(),
+                ));
+            }
+            match &withholding.method {
+                WithholdingMethod::Flat { rate } => {
+                    if !rate.is_finite() || *rate < 0. || *rate > 100. {
+                        return Err(MoolahSharedError::GeneratorError(format!(
+                            "{} rate must be between 0 and 100",
+                            withholding.name
+                        )));
+                    }
+                }
+                WithholdingMethod::Brackets { brackets } => taxes::validate_brackets(brackets)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One named amount on a pay stub.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaycheckLine {
+    pub name: String,
+    pub amount: f32,
+}
+
+/// What a single paycheck looks like.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Paycheck {
+    pub name: String,
+    pub frequency: PayFrequency,
+    pub gross: f32,
+    pub deductions: Vec<PaycheckLine>,
+    /// Pay that withholding is calculated on: gross less pre-tax deductions
+    pub taxable: f32,
+    pub withholdings: Vec<PaycheckLine>,
+    pub net: f32,
+    /// Every date between the first and last paycheck that pay arrives on
+    pub pay_dates: Vec<NaiveDate>,
+}
+
+/// Work out a single paycheck: take pre-tax deductions off gross pay, withhold
+/// from what is left, and pay out the rest.
+pub fn paycheck(request: &PaycheckRequest) -> Result<Paycheck, MoolahSharedError> {
+    request.validate()?;
+
+    let periods = request.frequency.periods_per_year() as f64;
+    let gross = round_cents(request.gross_annual as f64 / periods);
+
+    let deductions = request
+        .pre_tax_deductions
+        .iter()
+        .map(|deduction| PaycheckLine {
+            name: deduction.name.trim().to_string(),
+            amount: if deduction.percent {
+                round_cents(gross * deduction.amount as f64 / 100.)
+            } else {
+                round_cents(deduction.amount as f64)
+            } as f32,
+        })
+        .collect::<Vec<_>>();
+    let taxable = round_cents(
+        gross
+            - deductions
+                .iter()
+                .map(|line| line.amount as f64)
+                .sum::<f64>(),
+    );
+
+    let withholdings = request
+        .withholdings
+        .iter()
+        .map(|withholding| PaycheckLine {
+            name: withholding.name.trim().to_string(),
+            amount: match &withholding.method {
+                WithholdingMethod::Flat { rate } => round_cents(taxable * *rate as f64 / 100.),
+                WithholdingMethod::Brackets { brackets } => {
+                    round_cents(taxes::tax_on(brackets, taxable.max(0.) * periods) / periods)
+                }
+            } as f32,
+        })
+        .collect::<Vec<_>>();
+    let net = round_cents(
+        taxable
+            - withholdings
+                .iter()
+                .map(|line| line.amount as f64)
+                .sum::<f64>(),
+    );
+
+    if net < 0. {
+        return Err(MoolahSharedError::GeneratorError(
+            "deductions and withholding are more than the gross pay".into(),
+        ));
+    }
+
+    let mut pay_dates = request
+        .frequency
+        .repetitions(request.first_pay_on, request.last_pay_on)?
+        .iter()
+        .flat_map(Repetition::dates)
+        .collect::<Vec<_>>();
+    pay_dates.sort();
+
+    Ok(Paycheck {
+        name: request.name.trim().to_string(),
+        frequency: request.frequency,
+        gross: gross as f32,
+        deductions,
+        taxable: taxable as f32,
+        withholdings,
+        net: net as f32,
+        pay_dates,
+    })
+}
+
+impl Paycheck {
+    /// The paycheck as deltas on every pay date: net pay as income, and a
+    /// separate delta for every deduction and withholding taken out before it.
+    /// Only net pay moves the balance; the deduction deltas are there for the
+    /// tax estimate and anything else that follows them on their own.
+    pub fn new_deltas(
+        &self,
+        prediction_id: i32,
+        first_pay_on: NaiveDate,
+        last_pay_on: NaiveDate,
+    ) -> Result<Vec<NewDelta>, MoolahSharedError> {
+        let repetitions = self.frequency.repetitions(first_pay_on, last_pay_on)?;
+
+        let lines = std::iter::once((
+            format!("{} net pay", self.name),
+            self.net,
+            DeltaKind::Income,
+        ))
+        .chain(self.deductions.iter().map(|line| {
+            (
+                format!("{} {} (pre-tax)", self.name, line.name),
+                -line.amount,
+                DeltaKind::PreTaxDeduction,
+            )
+        }))
+        .chain(self.withholdings.iter().map(|line| {
+            (
+                format!("{} {} withholding", self.name, line.name),
+                -line.amount,
+                DeltaKind::Withholding,
+            )
+        }));
+
+        Ok(lines
+            .filter(|(_, amount, _)| *amount != 0.)
+            .flat_map(|(name, amount, kind)| {
+                repetitions.iter().map(move |repetition| {
+                    NewDelta::new(prediction_id, name.clone(), amount, 0., 0., *repetition)
+                        .with_kind(kind)
+                })
+            })
+            .collect())
+    }
+}
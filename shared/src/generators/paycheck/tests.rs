@@ -0,0 +1,197 @@
+use super::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn request(frequency: PayFrequency) -> PaycheckRequest {
+    PaycheckRequest {
+        prediction_id: 1,
+        name: "salary".into(),
+        gross_annual: 78000.,
+        frequency,
+        first_pay_on: date(2026, 1, 9),
+        last_pay_on: date(2026, 12, 31),
+        pre_tax_deductions: vec![
+            Deduction {
+                name: "401k".into(),
+                amount: 6.,
+                percent: true,
+            },
+            Deduction {
+                name: "HSA".into(),
+                amount: 100.,
+                percent: false,
+            },
+        ],
+        withholdings: vec![
+            Withholding {
+                name: "federal".into(),
+                method: WithholdingMethod::Brackets {
+                    brackets: vec![
+                        TaxBracket {
+                            over: 0.,
+                            rate: 10.,
+                        },
+                        TaxBracket {
+                            over: 20000.,
+                            rate: 20.,
+                        },
+                    ],
+                },
+            },
+            Withholding {
+                name: "state".into(),
+                method: WithholdingMethod::Flat { rate: 5. },
+            },
+        ],
+    }
+}
+
+#[test]
+fn test_biweekly_paycheck() {
+    let paycheck = paycheck(&request(PayFrequency::Biweekly)).unwrap();
+
+    assert_eq!(paycheck.gross, 3000.);
+    assert_eq!(
+        paycheck.deductions,
+        vec![
+            PaycheckLine {
+                name: "401k".into(),
+                amount: 180.
+            },
+            PaycheckLine {
+                name: "HSA".into(),
+                amount: 100.
+            },
+        ]
+    );
+    assert_eq!(paycheck.taxable, 2720.);
+    // 70720 a year: 2000 + 10144 in brackets, over 26 paychecks
+    assert_eq!(paycheck.withholdings[0].amount, 467.08);
+    assert_eq!(paycheck.withholdings[1].amount, 136.);
+    assert_eq!(paycheck.net, 2116.92);
+    assert_eq!(paycheck.pay_dates.len(), 26);
+    assert_eq!(paycheck.pay_dates[1], date(2026, 1, 23));
+}
+
+#[test]
+fn test_semi_monthly_pay_dates() {
+    let paycheck = paycheck(&PaycheckRequest {
+        first_pay_on: date(2026, 1, 1),
+        ..request(PayFrequency::SemiMonthly)
+    })
+    .unwrap();
+
+    assert_eq!(paycheck.gross, 3250.);
+    assert_eq!(paycheck.pay_dates.len(), 24);
+    assert_eq!(
+        paycheck.pay_dates[..4],
+        [
+            date(2026, 1, 15),
+            date(2026, 1, 31),
+            date(2026, 2, 15),
+            date(2026, 2, 28)
+        ]
+    );
+}
+
+#[test]
+fn test_new_deltas_pay_net_and_record_deductions() {
+    let request = request(PayFrequency::Monthly);
+    let paycheck = paycheck(&request).unwrap();
+    let deltas = paycheck
+        .new_deltas(4, request.first_pay_on, request.last_pay_on)
+        .unwrap();
+
+    let names = deltas.iter().map(|delta| delta.name()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            "salary net pay",
+            "salary 401k (pre-tax)",
+            "salary HSA (pre-tax)",
+            "salary federal withholding",
+            "salary state withholding",
+        ]
+    );
+    assert!(deltas
+        .iter()
+        .all(|delta| delta.repetition().dates().len() == 12));
+    let kinds = deltas.iter().map(|delta| delta.kind()).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            DeltaKind::Income,
+            DeltaKind::PreTaxDeduction,
+            DeltaKind::PreTaxDeduction,
+            DeltaKind::Withholding,
+            DeltaKind::Withholding,
+        ]
+    );
+
+    let paid = deltas
+        .iter()
+        .filter(|delta| delta.kind().moves_balance())
+        .map(|delta| delta.value())
+        .collect::<Vec<_>>();
+    assert_eq!(paid, vec![paycheck.net]);
+
+    let deducted = deltas
+        .iter()
+        .filter(|delta| !delta.kind().moves_balance())
+        .map(|delta| delta.value())
+        .sum::<f32>();
+    assert!((paycheck.net - deducted - paycheck.gross).abs() < 0.01);
+}
+
+#[test]
+fn test_no_deductions_or_withholding() {
+    let request = PaycheckRequest {
+        pre_tax_deductions: vec![],
+        withholdings: vec![],
+        ..request(PayFrequency::Weekly)
+    };
+    let paycheck = paycheck(&request).unwrap();
+
+    assert_eq!(paycheck.net, 1500.);
+    assert_eq!(
+        paycheck
+            .new_deltas(4, request.first_pay_on, request.last_pay_on)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_invalid_requests() {
+    assert!(paycheck(&PaycheckRequest {
+        gross_annual: 0.,
+        ..request(PayFrequency::Monthly)
+    })
+    .is_err());
+    assert!(paycheck(&PaycheckRequest {
+        last_pay_on: date(2025, 12, 31),
+        ..request(PayFrequency::Monthly)
+    })
+    .is_err());
+    assert!(paycheck(&PaycheckRequest {
+        withholdings: vec![Withholding {
+            name: "everything".into(),
+            method: WithholdingMethod::Flat { rate: 101. },
+        }],
+        ..request(PayFrequency::Monthly)
+    })
+    .is_err());
+    // deductions larger than the paycheck
+    assert!(paycheck(&PaycheckRequest {
+        pre_tax_deductions: vec![Deduction {
+            name: "401k".into(),
+            amount: 10000.,
+            percent: false,
+        }],
+        ..request(PayFrequency::Monthly)
+    })
+    .is_err());
+}
@@ -0,0 +1,176 @@
+pub mod csv;
+pub mod ledger;
+pub mod ofx;
+pub mod qif;
+#[cfg(test)]
+mod tests;
+
+pub use self::csv::{CsvMapping, DecimalSeparator, SignConvention};
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// A single statement row after it has been read from an export but before it
+/// belongs to a user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParsedTransaction {
+    pub posted_on: NaiveDate,
+    pub amount: f32,
+    pub description: String,
+    /// The bank's own transaction id, when the export format carries one
+    pub fitid: Option<String>,
+    pub account: Option<String>,
+}
+
+/// A row of a statement export that could not be turned into a transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkippedRow {
+    pub line: u64,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ImportPreview {
+    pub transactions: Vec<ParsedTransaction>,
+    pub skipped: Vec<SkippedRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub duplicates: usize,
+    pub skipped: Vec<SkippedRow>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct StatementImportRequest {
+    pub contents: String,
+}
+
+fn cents(amount: f32) -> i64 {
+    (amount * 100.).round() as i64
+}
+
+/// Drop the rows of a statement that have already been imported.
+///
+/// Rows carrying a FITID are matched on (account, FITID). Rows without one are
+/// matched on (account, date, amount), counting occurrences so that two identical
+/// purchases on the same day are only treated as duplicates if both were
+/// imported before. Returns the new rows and the number of duplicates dropped.
+pub fn deduplicate(
+    incoming: Vec<ParsedTransaction>,
+    existing: &[ParsedTransaction],
+) -> (Vec<ParsedTransaction>, usize) {
+    let mut seen_fitids = existing
+        .iter()
+        .filter_map(|transaction| {
+            transaction
+                .fitid
+                .clone()
+                .map(|fitid| (transaction.account.clone(), fitid))
+        })
+        .collect::<HashSet<_>>();
+
+    let date_amount = |transaction: &ParsedTransaction| {
+        (
+            transaction.account.clone(),
+            transaction.posted_on,
+            cents(transaction.amount),
+        )
+    };
+
+    let mut seen_date_amounts = HashMap::<(Option<String>, NaiveDate, i64), usize>::new();
+    for transaction in existing {
+        *seen_date_amounts
+            .entry(date_amount(transaction))
+            .or_default() += 1;
+    }
+
+    let mut duplicates = 0;
+    let transactions = incoming
+        .into_iter()
+        .filter(|transaction| {
+            let is_new = match &transaction.fitid {
+                Some(fitid) => seen_fitids.insert((transaction.account.clone(), fitid.clone())),
+                None => match seen_date_amounts.get_mut(&date_amount(transaction)) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        false
+                    }
+                    _ => true,
+                },
+            };
+
+            if !is_new {
+                duplicates += 1;
+            }
+            is_new
+        })
+        .collect();
+
+    (transactions, duplicates)
+}
+
+/// Parse a monetary amount as banks tend to print them: with currency symbols,
+/// thousands separators, and negatives shown either with a minus sign or in
+/// parentheses. The decimal separator is `.` and thousands are grouped with `,`.
+/// Amounts too large to hold are rejected.
+pub fn parse_amount(raw: &str) -> Option<f32> {
+    parse_decimal_amount(raw, '.')
+}
+
+/// Like `parse_amount`, with `decimal` (`.` or `,`) separating the cents and
+/// the other of the two grouping thousands. Amounts that only make sense with
+/// the separators swapped, like `1.234,56` read with a decimal point, are
+/// rejected rather than misread.
+pub fn parse_decimal_amount(raw: &str, decimal: char) -> Option<f32> {
+    let raw = raw.trim();
+    let grouping = if decimal == ',' { '.' } else { ',' };
+
+    let negative = raw.contains('-') || (raw.starts_with('(') && raw.ends_with(')'));
+
+    let mut whole = String::new();
+    let mut fraction: Option<String> = None;
+    // Digits since the last thousands separator, if there has been one
+    let mut group: Option<usize> = None;
+    for c in raw.chars() {
+        if c.is_ascii_digit() {
+            match &mut fraction {
+                Some(fraction) => fraction.push(c),
+                None => {
+                    whole.push(c);
+                    if let Some(digits) = &mut group {
+                        *digits += 1;
+                    }
+                }
+            }
+        } else if c == grouping || c == decimal {
+            if fraction.is_some() || matches!(group, Some(digits) if digits != 3) {
+                return None;
+            }
+            if c == grouping {
+                if whole.is_empty() {
+                    return None;
+                }
+                group = Some(0);
+            } else {
+                fraction = Some(String::new());
+            }
+        }
+    }
+
+    if fraction.is_none() && matches!(group, Some(digits) if digits != 3) {
+        return None;
+    }
+    if whole.is_empty() && fraction.as_deref().unwrap_or_default().is_empty() {
+        return None;
+    }
+
+    format!("{}.{}", whole, fraction.unwrap_or_default())
+        .parse::<f32>()
+        .ok()
+        .filter(|amount| amount.is_finite())
+        .map(|amount| if negative { -amount } else { amount })
+}
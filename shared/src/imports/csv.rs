@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{parse_decimal_amount, ImportPreview, ParsedTransaction, SkippedRow};
+use crate::errors::MoolahSharedError;
+
+/// How the exporting bank signs the money moving in and out of the account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignConvention {
+    /// Money leaving the account is negative, which is how moolah stores it.
+    DebitsNegative,
+    /// Money leaving the account is positive (common for credit card exports).
+    DebitsPositive,
+    /// Debits live in the amount column and credits in a separate column, both
+    /// unsigned.
+    SplitColumns { credit_column: usize },
+}
+
+/// What separates the whole units of an amount from the cents.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecimalSeparator {
+    /// `1,234.56`
+    #[default]
+    Point,
+    /// `1.234,56`, common in European exports
+    Comma,
+}
+
+impl DecimalSeparator {
+    pub fn as_char(&self) -> char {
+        match self {
+            DecimalSeparator::Point => '.',
+            DecimalSeparator::Comma => ',',
+        }
+    }
+}
+
+/// Which (zero-based) columns of a bank's CSV export hold each transaction
+/// field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CsvMapping {
+    pub has_headers: bool,
+    pub delimiter: char,
+    pub date_column: usize,
+    pub date_format: String,
+    pub amount_column: usize,
+    pub description_column: usize,
+    pub sign_convention: SignConvention,
+    #[serde(default)]
+    pub decimal_separator: DecimalSeparator,
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        CsvMapping {
+            has_headers: true,
+            delimiter: ',',
+            date_column: 0,
+            date_format: "%m/%d/%Y".into(),
+            amount_column: 1,
+            description_column: 2,
+            sign_convention: SignConvention::DebitsNegative,
+            decimal_separator: DecimalSeparator::Point,
+        }
+    }
+}
+
+impl CsvMapping {
+    /// Check the mapping can be used to read an export. Mappings arriving
+    /// from a client should be validated before parsing.
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        if !self.delimiter.is_ascii() {
+            return Err(MoolahSharedError::ImportError(format!(
+                "the delimiter must be a single ASCII character, not '{}'",
+                self.delimiter
+            )));
+        }
+
+        if self.delimiter == self.decimal_separator.as_char() {
+            return Err(MoolahSharedError::ImportError(
+                "the delimiter cannot also be the decimal separator".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CsvImportRequest {
+    pub contents: String,
+    pub mapping: CsvMapping,
+}
+
+/// Read a CSV export as `mapping` describes. The mapping must be valid.
+pub fn parse(contents: &str, mapping: &CsvMapping) -> ImportPreview {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .has_headers(mapping.has_headers)
+        .delimiter(mapping.delimiter as u8)
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(contents.as_bytes());
+
+    let mut preview = ImportPreview::default();
+
+    for (i, record) in reader.records().enumerate() {
+        let fallback_line = i as u64 + if mapping.has_headers { 2 } else { 1 };
+
+        match record {
+            Ok(record) => {
+                let line = record
+                    .position()
+                    .map_or(fallback_line, |position| position.line());
+
+                if record.iter().all(|field| field.is_empty()) {
+                    continue;
+                }
+
+                match parse_record(&record, mapping) {
+                    Ok(transaction) => preview.transactions.push(transaction),
+                    Err(reason) => preview.skipped.push(SkippedRow { line, reason }),
+                }
+            }
+            Err(err) => preview.skipped.push(SkippedRow {
+                line: err
+                    .position()
+                    .map_or(fallback_line, |position| position.line()),
+                reason: err.to_string(),
+            }),
+        }
+    }
+
+    preview
+}
+
+fn field<'r>(
+    record: &'r ::csv::StringRecord,
+    column: usize,
+    name: &str,
+) -> Result<&'r str, String> {
+    record
+        .get(column)
+        .ok_or_else(|| format!("missing {} column ({})", name, column))
+}
+
+fn parse_record(
+    record: &::csv::StringRecord,
+    mapping: &CsvMapping,
+) -> Result<ParsedTransaction, String> {
+    let raw_date = field(record, mapping.date_column, "date")?;
+    let posted_on = NaiveDate::parse_from_str(raw_date, &mapping.date_format).map_err(|_| {
+        format!(
+            "could not parse date '{}' with format '{}'",
+            raw_date, mapping.date_format
+        )
+    })?;
+
+    let parse_amount = |raw| parse_decimal_amount(raw, mapping.decimal_separator.as_char());
+
+    let raw_amount = field(record, mapping.amount_column, "amount")?;
+    let amount = match mapping.sign_convention {
+        SignConvention::DebitsNegative => parse_amount(raw_amount),
+        SignConvention::DebitsPositive => parse_amount(raw_amount).map(|amount| -amount),
+        SignConvention::SplitColumns { credit_column } => {
+            let raw_credit = field(record, credit_column, "credit")?;
+            match (parse_amount(raw_amount), parse_amount(raw_credit)) {
+                (Some(debit), _) if debit != 0. => Some(-debit.abs()),
+                (_, Some(credit)) => Some(credit.abs()),
+                (debit, None) => debit,
+            }
+        }
+    }
+    .ok_or_else(|| format!("could not parse amount '{}'", raw_amount))?;
+
+    let description = field(record, mapping.description_column, "description")?.to_string();
+
+    Ok(ParsedTransaction {
+        posted_on,
+        amount,
+        description,
+        fitid: None,
+        account: None,
+    })
+}
@@ -0,0 +1,181 @@
+use super::*;
+use crate::imports::parse_amount;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+#[test]
+fn test_default_mapping() {
+    let contents = "Date,Amount,Description\n\
+                    01/05/2022,-45.10,GROCERY STORE\n\
+                    01/15/2022,\"1,200.00\",PAYROLL\n";
+
+    let preview = parse(contents, &CsvMapping::default());
+
+    assert!(preview.skipped.is_empty());
+    assert_eq!(
+        preview.transactions,
+        vec![
+            ParsedTransaction {
+                posted_on: date(2022, 1, 5),
+                amount: -45.10,
+                description: "GROCERY STORE".into(),
+                fitid: None,
+                account: None,
+            },
+            ParsedTransaction {
+                posted_on: date(2022, 1, 15),
+                amount: 1200.,
+                description: "PAYROLL".into(),
+                fitid: None,
+                account: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_reordered_columns_without_headers() {
+    let contents = "COFFEE SHOP;2022-03-01;$4.50\n";
+    let mapping = CsvMapping {
+        has_headers: false,
+        delimiter: ';',
+        date_column: 1,
+        date_format: "%Y-%m-%d".into(),
+        amount_column: 2,
+        description_column: 0,
+        sign_convention: SignConvention::DebitsPositive,
+        decimal_separator: DecimalSeparator::Point,
+    };
+
+    let preview = parse(contents, &mapping);
+
+    assert_eq!(
+        preview.transactions,
+        vec![ParsedTransaction {
+            posted_on: date(2022, 3, 1),
+            amount: -4.5,
+            description: "COFFEE SHOP".into(),
+            fitid: None,
+            account: None,
+        }]
+    );
+}
+
+#[test]
+fn test_split_columns() {
+    let contents = "Date,Description,Debit,Credit\n\
+                    02/01/2022,RENT,950.00,\n\
+                    02/02/2022,REFUND,,12.00\n";
+    let mapping = CsvMapping {
+        amount_column: 2,
+        description_column: 1,
+        sign_convention: SignConvention::SplitColumns { credit_column: 3 },
+        ..Default::default()
+    };
+
+    let amounts = parse(contents, &mapping)
+        .transactions
+        .into_iter()
+        .map(|transaction| transaction.amount)
+        .collect::<Vec<_>>();
+
+    assert_eq!(amounts, vec![-950., 12.]);
+}
+
+#[test]
+fn test_bad_rows_are_skipped() {
+    let contents = "Date,Amount,Description\n\
+                    not a date,-1.00,BAD DATE\n\
+                    01/02/2022,abc,BAD AMOUNT\n\
+                    01/03/2022,-3.00\n\
+                    \n\
+                    01/04/2022,-4.00,GOOD\n";
+
+    let preview = parse(contents, &CsvMapping::default());
+
+    assert_eq!(preview.transactions.len(), 1);
+    assert_eq!(preview.transactions[0].description, "GOOD");
+    assert_eq!(
+        preview
+            .skipped
+            .iter()
+            .map(|skipped| skipped.line)
+            .collect::<Vec<_>>(),
+        vec![2, 3, 4]
+    );
+}
+
+#[test]
+fn test_parse_amount() {
+    assert_eq!(parse_amount("12.50"), Some(12.5));
+    assert_eq!(parse_amount("-12.50"), Some(-12.5));
+    assert_eq!(parse_amount("($1,012.50)"), Some(-1012.5));
+    assert_eq!(parse_amount("12.50-"), Some(-12.5));
+    assert_eq!(parse_amount(""), None);
+    assert_eq!(parse_amount("n/a"), None);
+}
+
+#[test]
+fn test_ambiguous_amounts_are_rejected() {
+    assert_eq!(parse_amount("1.234,56"), None);
+    assert_eq!(parse_amount("12,50"), None);
+    assert_eq!(parse_amount("1,234.5,6"), None);
+    assert_eq!(parse_amount("1.2.3"), None);
+}
+
+#[test]
+fn test_decimal_comma() {
+    assert_eq!(parse_decimal_amount("1.234,56", ','), Some(1234.56));
+    assert_eq!(parse_decimal_amount("-12,50 €", ','), Some(-12.5));
+    assert_eq!(parse_decimal_amount("1,234.56", ','), None);
+
+    let contents = "Datum;Betrag;Beschreibung\n\
+                    01.03.2022;-1.234,56;MIETE\n";
+    let mapping = CsvMapping {
+        delimiter: ';',
+        date_format: "%d.%m.%Y".into(),
+        decimal_separator: DecimalSeparator::Comma,
+        ..Default::default()
+    };
+
+    let preview = parse(contents, &mapping);
+
+    assert!(preview.skipped.is_empty());
+    assert_eq!(preview.transactions[0].amount, -1234.56);
+}
+
+#[test]
+fn test_validate_mapping() {
+    assert!(CsvMapping::default().validate().is_ok());
+
+    let non_ascii = CsvMapping {
+        delimiter: '§',
+        ..Default::default()
+    };
+    assert!(non_ascii.validate().is_err());
+
+    let clashing = CsvMapping {
+        decimal_separator: DecimalSeparator::Comma,
+        ..Default::default()
+    };
+    assert!(clashing.validate().is_err());
+}
+
+#[test]
+fn test_overflowing_amounts_are_skipped() {
+    let contents = format!(
+        "Date,Amount,Description\n\
+         01/02/2022,{},TOO MUCH\n\
+         01/03/2022,-3.00,GOOD\n",
+        "9".repeat(60)
+    );
+
+    let preview = parse(&contents, &CsvMapping::default());
+
+    assert_eq!(preview.transactions.len(), 1);
+    assert_eq!(preview.transactions[0].description, "GOOD");
+    assert_eq!(preview.skipped.len(), 1);
+    assert_eq!(preview.skipped[0].line, 2);
+}
@@ -0,0 +1,474 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{deltas::app::repetition::MonthDay, NewDelta, Repetition};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerImportRequest {
+    /// The name of the prediction created from the rules
+    pub name: String,
+    pub contents: String,
+    /// The account whose balance the prediction tracks. When left out, the
+    /// posting without an amount in each rule is taken to be that account.
+    pub account: Option<String>,
+    /// Used for rules without a `from` date
+    pub from: NaiveDate,
+    /// Used for rules without a `to` date
+    pub to: NaiveDate,
+}
+
+/// A delta read from a periodic transaction, before it belongs to a
+/// prediction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerDelta {
+    pub name: String,
+    pub value: f32,
+    pub positive_uncertainty: f32,
+    pub negative_uncertainty: f32,
+    pub repetition: Repetition,
+}
+
+impl LedgerDelta {
+    pub fn into_new_delta(self, prediction_id: i32) -> NewDelta {
+        NewDelta::new(
+            prediction_id,
+            self.name,
+            self.value,
+            self.positive_uncertainty,
+            self.negative_uncertainty,
+            self.repetition,
+        )
+    }
+}
+
+/// A periodic transaction that has no equivalent in moolah's deltas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnrepresentableRule {
+    pub line: u64,
+    pub rule: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerImportPreview {
+    pub deltas: Vec<LedgerDelta>,
+    pub unrepresentable: Vec<UnrepresentableRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerImportSummary {
+    pub prediction_id: i32,
+    pub imported: usize,
+    pub unrepresentable: Vec<UnrepresentableRule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Interval {
+    Daily,
+    Weekly(Option<Weekday>),
+    Biweekly,
+    Monthly(Option<MonthDay>),
+}
+
+#[derive(Debug)]
+struct Period {
+    interval: Interval,
+    from: Option<NaiveDate>,
+    /// Exclusive, as in ledger and hledger
+    to: Option<NaiveDate>,
+}
+
+struct Posting {
+    account: String,
+    amount: Option<f32>,
+}
+
+struct Rule {
+    line: u64,
+    header: String,
+    postings: Vec<String>,
+    comments: Vec<String>,
+}
+
+/// Read a date the way ledger and hledger write them, allowing a year or a
+/// year and month on its own for the first day of that period.
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    let parts = text
+        .split(['/', '-', '.'])
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    match parts.as_slice() {
+        [year] => NaiveDate::from_ymd_opt(*year as i32, 1, 1),
+        [year, month] => NaiveDate::from_ymd_opt(*year as i32, *month, 1),
+        [year, month, day] => NaiveDate::from_ymd_opt(*year as i32, *month, *day),
+        _ => None,
+    }
+}
+
+fn parse_ordinal(text: &str) -> Option<u32> {
+    let digits = text.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    if digits.len() == text.len() {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+fn parse_weekday(text: &str) -> Option<Weekday> {
+    match text {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_every(tokens: &[&str]) -> Result<(Interval, usize), String> {
+    let unsupported = || {
+        Err(format!(
+            "unsupported interval 'every {}'",
+            tokens.iter().take(4).cloned().collect::<Vec<_>>().join(" ")
+        ))
+    };
+
+    match tokens {
+        ["day", ..] => Ok((Interval::Daily, 1)),
+        ["week", ..] => Ok((Interval::Weekly(None), 1)),
+        ["fortnight", ..] => Ok((Interval::Biweekly, 1)),
+        ["month", ..] => Ok((Interval::Monthly(None), 1)),
+        [nth, "day", "of", "month", ..] if parse_ordinal(nth).is_some() => {
+            match MonthDay::new(parse_ordinal(nth).unwrap_or_default() as i16) {
+                Ok(day) => Ok((Interval::Monthly(Some(day)), 4)),
+                Err(_) => Err(format!("'{}' is not a day of the month", nth)),
+            }
+        }
+        [nth, "day", "of", "week", ..] if parse_ordinal(nth).is_some() => {
+            match parse_ordinal(nth).unwrap_or_default() {
+                n @ 1..=7 => Ok((Interval::Weekly(Some(WEEKDAYS[n as usize - 1])), 4)),
+                _ => Err(format!("'{}' is not a day of the week", nth)),
+            }
+        }
+        [n, unit, ..] if n.parse::<u32>().is_ok() => {
+            let interval = match (n.parse::<u32>().unwrap_or_default(), *unit) {
+                (1, "day" | "days") => Interval::Daily,
+                (7, "days") | (1, "week" | "weeks") => Interval::Weekly(None),
+                (14, "days") | (2, "weeks") => Interval::Biweekly,
+                (1, "month" | "months") => Interval::Monthly(None),
+                _ => return unsupported(),
+            };
+            Ok((interval, 2))
+        }
+        [weekday, ..] if parse_weekday(weekday).is_some() => {
+            Ok((Interval::Weekly(parse_weekday(weekday)), 1))
+        }
+        _ => unsupported(),
+    }
+}
+
+fn parse_period(expression: &str) -> Result<Period, String> {
+    let expression = expression.to_lowercase();
+    let tokens = expression.split_whitespace().collect::<Vec<_>>();
+
+    let mut interval = None;
+    let mut from = None;
+    let mut to = None;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i] {
+            "daily" => interval = Some(Interval::Daily),
+            "weekly" => interval = Some(Interval::Weekly(None)),
+            "biweekly" | "fortnightly" => interval = Some(Interval::Biweekly),
+            "monthly" => interval = Some(Interval::Monthly(None)),
+            "every" => {
+                let (every, used) = parse_every(&tokens[i + 1..])?;
+                interval = Some(every);
+                i += used;
+            }
+            "from" | "since" | "to" | "until" => {
+                let date = tokens
+                    .get(i + 1)
+                    .and_then(|token| parse_date(token))
+                    .ok_or_else(|| format!("expected a date after '{}'", tokens[i]))?;
+                if matches!(tokens[i], "from" | "since") {
+                    from = Some(date);
+                } else {
+                    to = Some(date);
+                }
+                i += 1;
+            }
+            other => return Err(format!("unsupported period expression '{}'", other)),
+        }
+        i += 1;
+    }
+
+    let interval = interval.ok_or_else(|| "rule has no interval".to_string())?;
+
+    Ok(Period { interval, from, to })
+}
+
+fn parse_amount(text: &str) -> Result<Option<f32>, String> {
+    // leave out prices and balance assertions
+    let text = text.split(['@', '=']).next().unwrap_or_default().trim();
+
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    let digits = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.')
+        .collect::<String>();
+
+    match digits.parse::<f32>() {
+        Ok(value) if text.contains('-') => Ok(Some(-value)),
+        Ok(value) => Ok(Some(value)),
+        Err(_) => Err(format!("could not read amount '{}'", text)),
+    }
+}
+
+/// Split a line at the first run of two spaces or a tab, which is how ledger
+/// separates an account from its amount (and hledger a period from its
+/// description).
+fn split_columns(line: &str) -> (&str, &str) {
+    let at = [line.find("  "), line.find('\t')]
+        .into_iter()
+        .flatten()
+        .min();
+
+    match at {
+        Some(at) => (line[..at].trim(), line[at..].trim()),
+        None => (line.trim(), ""),
+    }
+}
+
+fn parse_posting(line: &str) -> Result<Posting, String> {
+    let line = line.split(';').next().unwrap_or_default();
+    let (account, amount) = split_columns(line);
+
+    Ok(Posting {
+        account: account
+            .trim_matches(|c| matches!(c, '(' | ')' | '[' | ']'))
+            .to_string(),
+        amount: parse_amount(amount)?,
+    })
+}
+
+lazy_static! {
+    static ref UNCERTAINTY: Regex =
+        Regex::new(r"(?i)^uncertainty:\s*\+\s*([\d.]+)\s*/\s*-\s*([\d.]+)").unwrap();
+    static ref TAG: Regex = Regex::new(r"^:?[\w-]+:").unwrap();
+}
+
+fn read_rules(contents: &str) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    let mut current: Option<Rule> = None;
+
+    for (index, line) in contents.lines().enumerate() {
+        if let Some(header) = line.strip_prefix('~') {
+            rules.extend(current.take());
+            current = Some(Rule {
+                line: index as u64 + 1,
+                header: header.trim().to_string(),
+                postings: Vec::new(),
+                comments: Vec::new(),
+            });
+        } else if line.starts_with([' ', '\t']) && !line.trim().is_empty() {
+            if let Some(rule) = &mut current {
+                match line.trim().strip_prefix(';') {
+                    Some(comment) => rule.comments.push(comment.trim().to_string()),
+                    None => rule.postings.push(line.trim().to_string()),
+                }
+            }
+        } else {
+            rules.extend(current.take());
+        }
+    }
+    rules.extend(current);
+
+    rules
+}
+
+fn repetition(period: &Period, request: &LedgerImportRequest) -> Result<Repetition, String> {
+    let from = period.from.unwrap_or(request.from);
+    let to = match period.to {
+        Some(to) => to - Duration::days(1),
+        None => request.to,
+    };
+
+    if to < from {
+        return Err(format!("rule ends ({}) before it starts ({})", to, from));
+    }
+
+    // without a start date, ledger lines periods up with the calendar
+    let weekday = match period.from {
+        Some(from) => from.weekday(),
+        None => Weekday::Mon,
+    };
+
+    Ok(match period.interval {
+        Interval::Daily => Repetition::Daily { from, to },
+        Interval::Weekly(repeat_on_weekday) => Repetition::Weekly {
+            from,
+            to,
+            repeat_on_weekday: repeat_on_weekday.unwrap_or(weekday),
+        },
+        Interval::Biweekly => Repetition::Biweekly {
+            from,
+            to,
+            repeat_on_weekday: weekday,
+        },
+        Interval::Monthly(repeat_on_day) => Repetition::Monthly {
+            from,
+            to,
+            repeat_on_day: match (repeat_on_day, period.from) {
+                (Some(day), _) => day,
+                (None, Some(from)) => {
+                    MonthDay::new(from.day() as i16).map_err(|err| err.to_string())?
+                }
+                (None, None) => MonthDay::new(1).map_err(|err| err.to_string())?,
+            },
+        },
+    })
+}
+
+fn convert(rule: &Rule, request: &LedgerImportRequest) -> Result<Vec<LedgerDelta>, String> {
+    let header = rule.header.split(';').next().unwrap_or_default();
+    let (expression, description) = split_columns(header);
+    let period = parse_period(expression)?;
+    let repetition = repetition(&period, request)?;
+
+    let postings = rule
+        .postings
+        .iter()
+        .map(|line| parse_posting(line))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if postings.is_empty() {
+        return Err("rule has no postings".into());
+    }
+    if postings.iter().filter(|p| p.amount.is_none()).count() > 1 {
+        return Err("more than one posting has no amount".into());
+    }
+
+    let (positive_uncertainty, negative_uncertainty) = rule
+        .comments
+        .iter()
+        .find_map(|comment| UNCERTAINTY.captures(comment))
+        .map(|captures| {
+            (
+                captures[1].parse().unwrap_or_default(),
+                captures[2].parse().unwrap_or_default(),
+            )
+        })
+        .unwrap_or((0., 0.));
+
+    let description = if description.is_empty() {
+        rule.comments
+            .iter()
+            .find(|comment| !comment.is_empty() && !TAG.is_match(comment))
+            .map(String::as_str)
+            .unwrap_or_default()
+    } else {
+        description
+    };
+
+    let cash = request
+        .account
+        .as_deref()
+        .and_then(|account| {
+            postings
+                .iter()
+                .position(|p| p.account.eq_ignore_ascii_case(account))
+        })
+        .or_else(|| postings.iter().position(|p| p.amount.is_none()))
+        .ok_or_else(|| {
+            "could not tell which posting is the tracked account; \
+             choose the account or leave its amount out"
+                .to_string()
+        })?;
+
+    let others = postings
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != cash)
+        .map(|(_, posting)| posting)
+        .collect::<Vec<_>>();
+
+    let delta = |name: String, value: f32| LedgerDelta {
+        name,
+        value,
+        positive_uncertainty,
+        negative_uncertainty,
+        repetition,
+    };
+
+    match postings[cash].amount {
+        Some(value) => {
+            let name = if description.is_empty() {
+                others
+                    .iter()
+                    .map(|p| p.account.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            } else {
+                description.to_string()
+            };
+            Ok(vec![delta(name, value)])
+        }
+        None => Ok(others
+            .iter()
+            .filter_map(|posting| {
+                let amount = posting.amount?;
+                let name = match (description.is_empty(), others.len()) {
+                    (true, _) => posting.account.clone(),
+                    (false, 1) => description.to_string(),
+                    (false, _) => format!("{}: {}", description, posting.account),
+                };
+                Some(delta(name, -amount))
+            })
+            .collect()),
+    }
+}
+
+/// Read the periodic transactions (`~ ...`) of a ledger or hledger journal as
+/// deltas. Dated transactions are left out, and rules that have no equivalent
+/// delta are reported rather than approximated.
+pub fn parse(request: &LedgerImportRequest) -> LedgerImportPreview {
+    let mut deltas = Vec::new();
+    let mut unrepresentable = Vec::new();
+
+    for rule in read_rules(&request.contents) {
+        match convert(&rule, request) {
+            Ok(converted) => deltas.extend(converted),
+            Err(reason) => unrepresentable.push(UnrepresentableRule {
+                line: rule.line,
+                rule: format!("~ {}", rule.header),
+                reason,
+            }),
+        }
+    }
+
+    LedgerImportPreview {
+        deltas,
+        unrepresentable,
+    }
+}
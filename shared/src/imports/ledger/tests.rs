@@ -0,0 +1,251 @@
+use super::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn request(contents: &str, account: Option<&str>) -> LedgerImportRequest {
+    LedgerImportRequest {
+        name: "forecast".into(),
+        contents: contents.into(),
+        account: account.map(String::from),
+        from: date(2026, 1, 1),
+        to: date(2026, 12, 31),
+    }
+}
+
+#[test]
+fn test_parse_periodic_rules() {
+    let contents = "\
+; forecast
+2026/01/01 opening balance
+    Assets:Checking  $500.00
+    Equity:Opening
+
+~ every 2 weeks from 2026/01/02 to 2026/03/01  paycheck
+    Assets:Checking  $2,100.00
+    Income:Salary
+
+~ monthly from 2026/01/05
+    ; rent
+    Expenses:Rent  $1200
+    Assets:Checking
+
+~ every 20th day of month to 2026/04/01  phone
+    Expenses:Phone  45.00 USD
+    Assets:Checking
+
+~ every 3rd day of week from 2026-02-01 to 2026-03-01  groceries
+    ; uncertainty: +20.00 / -50.00
+    Assets:Checking  $-100.00
+    Expenses:Food
+
+~ daily from 2026/01/01 to 2026/01/08  coffee
+    Expenses:Coffee  $4
+    Assets:Checking
+";
+
+    let preview = parse(&request(contents, Some("Assets:Checking")));
+
+    assert_eq!(preview.unrepresentable, vec![]);
+    assert_eq!(
+        preview.deltas,
+        vec![
+            LedgerDelta {
+                name: "paycheck".into(),
+                value: 2100.,
+                positive_uncertainty: 0.,
+                negative_uncertainty: 0.,
+                repetition: Repetition::Biweekly {
+                    from: date(2026, 1, 2),
+                    to: date(2026, 2, 28),
+                    repeat_on_weekday: Weekday::Fri,
+                },
+            },
+            LedgerDelta {
+                name: "rent".into(),
+                value: -1200.,
+                positive_uncertainty: 0.,
+                negative_uncertainty: 0.,
+                repetition: Repetition::Monthly {
+                    from: date(2026, 1, 5),
+                    to: date(2026, 12, 31),
+                    repeat_on_day: MonthDay::new(5).unwrap(),
+                },
+            },
+            LedgerDelta {
+                name: "phone".into(),
+                value: -45.,
+                positive_uncertainty: 0.,
+                negative_uncertainty: 0.,
+                repetition: Repetition::Monthly {
+                    from: date(2026, 1, 1),
+                    to: date(2026, 3, 31),
+                    repeat_on_day: MonthDay::new(20).unwrap(),
+                },
+            },
+            LedgerDelta {
+                name: "groceries".into(),
+                value: -100.,
+                positive_uncertainty: 20.,
+                negative_uncertainty: 50.,
+                repetition: Repetition::Weekly {
+                    from: date(2026, 2, 1),
+                    to: date(2026, 2, 28),
+                    repeat_on_weekday: Weekday::Wed,
+                },
+            },
+            LedgerDelta {
+                name: "coffee".into(),
+                value: -4.,
+                positive_uncertainty: 0.,
+                negative_uncertainty: 0.,
+                repetition: Repetition::Daily {
+                    from: date(2026, 1, 1),
+                    to: date(2026, 1, 7),
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_budget_entries() {
+    let contents = "\
+~ monthly
+    Expenses:Food  $400
+    Expenses:Transport  $150
+    Income:Salary  $-3000
+    Assets
+";
+
+    let preview = parse(&request(contents, None));
+
+    assert_eq!(preview.unrepresentable, vec![]);
+    assert_eq!(
+        preview
+            .deltas
+            .iter()
+            .map(|delta| (delta.name.as_str(), delta.value))
+            .collect::<Vec<_>>(),
+        vec![
+            ("Expenses:Food", -400.),
+            ("Expenses:Transport", -150.),
+            ("Income:Salary", 3000.),
+        ]
+    );
+    assert_eq!(
+        preview.deltas[0].repetition,
+        Repetition::Monthly {
+            from: date(2026, 1, 1),
+            to: date(2026, 12, 31),
+            repeat_on_day: MonthDay::new(1).unwrap(),
+        }
+    );
+}
+
+#[test]
+fn test_reports_unrepresentable_rules() {
+    let contents = "\
+~ yearly from 2026/03/01  insurance
+    Expenses:Insurance  $600
+    Assets:Checking
+
+~ every 3 weeks  cleaning
+    Expenses:Cleaning  $80
+    Assets:Checking
+
+~ monthly in 2026  gym
+    Expenses:Gym  $30
+    Assets:Checking
+
+~ monthly from 2026/05/01 to 2026/02/01  backwards
+    Expenses:Other  $1
+    Assets:Checking
+
+~ monthly  split
+    Expenses:Food  $400
+    Assets:Checking  $-400
+
+~ weekly  ok
+    Expenses:Food  $100
+    Assets:Checking
+";
+
+    let preview = parse(&request(contents, None));
+
+    assert_eq!(
+        preview
+            .unrepresentable
+            .iter()
+            .map(|rule| (rule.line, rule.rule.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, "~ yearly from 2026/03/01  insurance"),
+            (5, "~ every 3 weeks  cleaning"),
+            (9, "~ monthly in 2026  gym"),
+            (13, "~ monthly from 2026/05/01 to 2026/02/01  backwards"),
+            (17, "~ monthly  split"),
+        ]
+    );
+    assert_eq!(preview.deltas.len(), 1);
+    assert_eq!(preview.deltas[0].name, "ok");
+    assert_eq!(
+        preview.deltas[0].repetition,
+        Repetition::Weekly {
+            from: date(2026, 1, 1),
+            to: date(2026, 12, 31),
+            repeat_on_weekday: Weekday::Mon,
+        }
+    );
+}
+
+#[test]
+fn test_reads_exported_journal() {
+    use crate::exports::journal::{journal, JournalFormat};
+    use crate::models::Delta;
+
+    let deltas = vec![Delta::new(
+        1,
+        1,
+        "groceries".into(),
+        -100.,
+        20.,
+        50.,
+        Repetition::Biweekly {
+            from: date(2026, 1, 2),
+            to: date(2026, 3, 1),
+            repeat_on_weekday: Weekday::Fri,
+        },
+    )];
+
+    for format in [JournalFormat::Ledger, JournalFormat::Hledger] {
+        let contents = journal("2026", &deltas, format, "Assets:Checking").unwrap();
+        let preview = parse(&request(&contents, Some("Assets:Checking")));
+
+        assert_eq!(
+            preview.deltas,
+            vec![LedgerDelta {
+                name: "groceries".into(),
+                value: -100.,
+                positive_uncertainty: 20.,
+                negative_uncertainty: 50.,
+                repetition: Repetition::Biweekly {
+                    from: date(2026, 1, 2),
+                    to: date(2026, 2, 27),
+                    repeat_on_weekday: Weekday::Fri,
+                },
+            }]
+        );
+    }
+}
+
+#[test]
+fn test_parse_date() {
+    assert_eq!(parse_date("2026/01/02"), Some(date(2026, 1, 2)));
+    assert_eq!(parse_date("2026-1-2"), Some(date(2026, 1, 2)));
+    assert_eq!(parse_date("2026.03"), Some(date(2026, 3, 1)));
+    assert_eq!(parse_date("2026"), Some(date(2026, 1, 1)));
+    assert_eq!(parse_date("2026/02/30"), None);
+    assert_eq!(parse_date("today"), None);
+}
@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use super::{ImportPreview, ParsedTransaction, SkippedRow};
+
+/// One opening or closing tag in an OFX document, along with the text that
+/// directly follows it.
+///
+/// OFX 1.x is SGML where leaf elements are never closed (`<TRNAMT>-4.50`)
+/// while OFX 2.x is XML (`<TRNAMT>-4.50</TRNAMT>`). Reading the text after each
+/// opening tag and only paying attention to the closing tags of aggregates
+/// handles both flavors (and QFX, which is OFX with extra Intuit tags).
+struct Element<'c> {
+    line: u64,
+    name: String,
+    closing: bool,
+    value: &'c str,
+}
+
+fn elements(contents: &str) -> Vec<Element<'_>> {
+    let mut elements = Vec::new();
+    let mut line = 1;
+    let mut rest = contents;
+
+    while let Some(start) = rest.find('<') {
+        line += rest[..start].matches('\n').count() as u64;
+
+        let after_open = &rest[start + 1..];
+        let end = match after_open.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+        let tag = &after_open[..end];
+        let after_tag = &after_open[end + 1..];
+        let value = after_tag[..after_tag.find('<').unwrap_or(after_tag.len())].trim();
+
+        // skip the `<?xml ...?>` / `<?OFX ...?>` declarations and comments
+        if !tag.starts_with('?') && !tag.starts_with('!') {
+            let (closing, name) = match tag.strip_prefix('/') {
+                Some(name) => (true, name),
+                None => (false, tag),
+            };
+
+            elements.push(Element {
+                line,
+                name: name
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .trim_end_matches('/')
+                    .to_ascii_uppercase(),
+                closing,
+                value,
+            });
+        }
+
+        line += tag.matches('\n').count() as u64;
+        rest = after_tag;
+    }
+
+    elements
+}
+
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// OFX dates look like `YYYYMMDD[HHMMSS[.XXX]][[gmt offset:tz name]]`; only
+/// the day matters here.
+fn parse_date(raw: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(raw.get(..8)?, "%Y%m%d").ok()
+}
+
+/// OFX amounts are signed, may use either `.` or `,` as the decimal separator,
+/// and never group thousands. Anything that is not a finite number, like `NaN`
+/// or `inf`, is rejected.
+fn parse_amount(raw: &str) -> Option<f32> {
+    let raw = raw.trim().replace('+', "");
+    let raw = if raw.contains('.') {
+        raw.replace(',', "")
+    } else {
+        raw.replace(',', ".")
+    };
+    raw.parse::<f32>().ok().filter(|amount| amount.is_finite())
+}
+
+fn parse_transaction(
+    fields: &HashMap<String, String>,
+    account: &Option<String>,
+) -> Result<ParsedTransaction, String> {
+    let field = |name: &str| fields.get(name).filter(|value| !value.is_empty());
+
+    let raw_date = field("DTPOSTED").ok_or("missing DTPOSTED")?;
+    let posted_on =
+        parse_date(raw_date).ok_or_else(|| format!("could not parse date '{}'", raw_date))?;
+
+    let raw_amount = field("TRNAMT").ok_or("missing TRNAMT")?;
+    let amount = parse_amount(raw_amount)
+        .ok_or_else(|| format!("could not parse amount '{}'", raw_amount))?;
+
+    let description = field("NAME")
+        .or_else(|| field("MEMO"))
+        .or_else(|| field("TRNTYPE"))
+        .cloned()
+        .unwrap_or_default();
+
+    Ok(ParsedTransaction {
+        posted_on,
+        amount,
+        description,
+        fitid: field("FITID").cloned(),
+        account: account.clone(),
+    })
+}
+
+/// Parse the `STMTTRN` records out of an OFX or QFX statement download.
+pub fn parse(contents: &str) -> ImportPreview {
+    let mut preview = ImportPreview::default();
+    let mut account = None;
+    // Only the statement's own account counts; a transfer's `BANKACCTTO` or
+    // `CCACCTTO` also holds an `ACCTID`
+    let mut in_account_from = false;
+    let mut transaction: Option<(u64, HashMap<String, String>)> = None;
+
+    for element in elements(contents) {
+        match (element.name.as_str(), element.closing) {
+            ("STMTTRN", false) => {
+                if let Some((line, _)) = transaction.replace((element.line, HashMap::new())) {
+                    preview.skipped.push(SkippedRow {
+                        line,
+                        reason: "transaction is missing its closing tag".into(),
+                    });
+                }
+            }
+            ("STMTTRN", true) => {
+                if let Some((line, fields)) = transaction.take() {
+                    match parse_transaction(&fields, &account) {
+                        Ok(parsed) => preview.transactions.push(parsed),
+                        Err(reason) => preview.skipped.push(SkippedRow { line, reason }),
+                    }
+                }
+            }
+            ("BANKACCTFROM" | "CCACCTFROM", closing) => in_account_from = !closing,
+            ("ACCTID", false) if in_account_from && !element.value.is_empty() => {
+                account = Some(decode_entities(element.value))
+            }
+            (name, false) => {
+                if let Some((_, fields)) = transaction.as_mut() {
+                    fields
+                        .entry(name.to_string())
+                        .or_insert_with(|| decode_entities(element.value));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((line, _)) = transaction {
+        preview.skipped.push(SkippedRow {
+            line,
+            reason: "transaction is missing its closing tag".into(),
+        });
+    }
+
+    preview
+}
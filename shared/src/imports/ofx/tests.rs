@@ -0,0 +1,151 @@
+use super::*;
+
+#[test]
+fn test_sgml_statement() {
+    let contents = "OFXHEADER:100\n\
+                    DATA:OFXSGML\n\
+                    VERSION:102\n\
+                    \n\
+                    <OFX>\n\
+                    <BANKMSGSRSV1><STMTTRNRS><STMTRS>\n\
+                    <BANKACCTFROM>\n\
+                    <BANKID>123456789\n\
+                    <ACCTID>000111222\n\
+                    <ACCTTYPE>CHECKING\n\
+                    </BANKACCTFROM>\n\
+                    <BANKTRANLIST>\n\
+                    <STMTTRN>\n\
+                    <TRNTYPE>DEBIT\n\
+                    <DTPOSTED>20220105120000[-5:EST]\n\
+                    <TRNAMT>-45.10\n\
+                    <FITID>2022010501\n\
+                    <NAME>GROCERY STORE\n\
+                    </STMTTRN>\n\
+                    <STMTTRN>\n\
+                    <TRNTYPE>CREDIT\n\
+                    <DTPOSTED>20220115\n\
+                    <TRNAMT>1200,00\n\
+                    <FITID>2022011501\n\
+                    <MEMO>PAYROLL &amp; BONUS\n\
+                    </STMTTRN>\n\
+                    </BANKTRANLIST>\n\
+                    </STMTRS></STMTTRNRS></BANKMSGSRSV1>\n\
+                    </OFX>\n";
+
+    let preview = parse(contents);
+
+    assert!(preview.skipped.is_empty());
+    assert_eq!(
+        preview.transactions,
+        vec![
+            ParsedTransaction {
+                posted_on: NaiveDate::from_ymd(2022, 1, 5),
+                amount: -45.10,
+                description: "GROCERY STORE".into(),
+                fitid: Some("2022010501".into()),
+                account: Some("000111222".into()),
+            },
+            ParsedTransaction {
+                posted_on: NaiveDate::from_ymd(2022, 1, 15),
+                amount: 1200.,
+                description: "PAYROLL & BONUS".into(),
+                fitid: Some("2022011501".into()),
+                account: Some("000111222".into()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_xml_statement() {
+    let contents = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                    <?OFX OFXHEADER=\"200\" VERSION=\"220\"?>\n\
+                    <OFX><CREDITCARDMSGSRSV1><CCSTMTTRNRS><CCSTMTRS>\n\
+                    <CCACCTFROM><ACCTID>4111</ACCTID></CCACCTFROM>\n\
+                    <BANKTRANLIST>\n\
+                    <STMTTRN><TRNTYPE>DEBIT</TRNTYPE><DTPOSTED>20220301</DTPOSTED>\
+                    <TRNAMT>-4.50</TRNAMT><FITID>abc</FITID><NAME>COFFEE SHOP</NAME></STMTTRN>\n\
+                    </BANKTRANLIST>\n\
+                    </CCSTMTRS></CCSTMTTRNRS></CREDITCARDMSGSRSV1></OFX>\n";
+
+    let preview = parse(contents);
+
+    assert_eq!(
+        preview.transactions,
+        vec![ParsedTransaction {
+            posted_on: NaiveDate::from_ymd(2022, 3, 1),
+            amount: -4.5,
+            description: "COFFEE SHOP".into(),
+            fitid: Some("abc".into()),
+            account: Some("4111".into()),
+        }]
+    );
+}
+
+#[test]
+fn test_bad_transactions_are_skipped() {
+    let contents = "<OFX>\n\
+                    <STMTTRN>\n\
+                    <DTPOSTED>2022\n\
+                    <TRNAMT>-1.00\n\
+                    </STMTTRN>\n\
+                    <STMTTRN>\n\
+                    <DTPOSTED>20220102\n\
+                    </STMTTRN>\n\
+                    <STMTTRN>\n\
+                    <DTPOSTED>20220103\n\
+                    <TRNAMT>-3.00\n\
+                    <NAME>GOOD\n\
+                    </STMTTRN>\n\
+                    <STMTTRN>\n\
+                    <DTPOSTED>20220104\n\
+                    </OFX>\n";
+
+    let preview = parse(contents);
+
+    assert_eq!(preview.transactions.len(), 1);
+    assert_eq!(preview.transactions[0].description, "GOOD");
+    assert_eq!(
+        preview
+            .skipped
+            .iter()
+            .map(|skipped| skipped.line)
+            .collect::<Vec<_>>(),
+        vec![2, 6, 14]
+    );
+}
+
+#[test]
+fn test_transfer_account_is_not_the_statement_account() {
+    let contents = "<OFX>\n\
+                    <BANKACCTFROM><ACCTID>111</ACCTID></BANKACCTFROM>\n\
+                    <STMTTRN><DTPOSTED>20220301</DTPOSTED><TRNAMT>-10</TRNAMT>\
+                    <BANKACCTTO><ACCTID>999</ACCTID></BANKACCTTO></STMTTRN>\n\
+                    <STMTTRN><DTPOSTED>20220302</DTPOSTED><TRNAMT>-20</TRNAMT></STMTTRN>\n\
+                    </OFX>\n";
+
+    let preview = parse(contents);
+
+    assert_eq!(
+        preview
+            .transactions
+            .iter()
+            .map(|transaction| transaction.account.as_deref())
+            .collect::<Vec<_>>(),
+        vec![Some("111"), Some("111")]
+    );
+}
+
+#[test]
+fn test_non_finite_amounts_are_skipped() {
+    let contents = "<OFX>\n\
+                    <STMTTRN><DTPOSTED>20220301</DTPOSTED><TRNAMT>NaN</TRNAMT></STMTTRN>\n\
+                    <STMTTRN><DTPOSTED>20220302</DTPOSTED><TRNAMT>inf</TRNAMT></STMTTRN>\n\
+                    <STMTTRN><DTPOSTED>20220303</DTPOSTED><TRNAMT>1e39</TRNAMT></STMTTRN>\n\
+                    </OFX>\n";
+
+    let preview = parse(contents);
+
+    assert!(preview.transactions.is_empty());
+    assert_eq!(preview.skipped.len(), 3);
+}
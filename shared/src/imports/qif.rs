@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{parse_amount, ImportPreview, ParsedTransaction, SkippedRow};
+
+/// QIF dates carry no indication of whether they are written month or day
+/// first, so the user has to say which.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QifDateOrder {
+    MonthFirst,
+    DayFirst,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct QifImportRequest {
+    pub contents: String,
+    pub date_order: QifDateOrder,
+}
+
+/// Parse the many date spellings found in QIF files: `1/5/2022`, `01/05/22`,
+/// `1/ 5'22` (where the apostrophe marks a year after 2000), `05.01.2022` and
+/// `2022-01-05`.
+fn parse_date(raw: &str, date_order: QifDateOrder) -> Option<NaiveDate> {
+    let raw = raw.trim();
+
+    if let Ok(date) = NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        return Some(date);
+    }
+
+    let parts = raw
+        .split(['/', '\'', '-', '.'])
+        .map(str::trim)
+        .collect::<Vec<_>>();
+
+    if parts.len() != 3 {
+        return None;
+    }
+
+    let first = parts[0].parse::<u32>().ok()?;
+    let second = parts[1].parse::<u32>().ok()?;
+    let year = parts[2].parse::<i32>().ok()?;
+
+    let year = match parts[2].len() {
+        1 | 2 if raw.contains('\'') || year < 70 => 2000 + year,
+        1 | 2 => 1900 + year,
+        _ => year,
+    };
+
+    let (month, day) = match date_order {
+        QifDateOrder::MonthFirst => (first, second),
+        QifDateOrder::DayFirst => (second, first),
+    };
+
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+#[derive(Default)]
+struct Record {
+    line: u64,
+    date: Option<String>,
+    amount: Option<String>,
+    payee: Option<String>,
+    memo: Option<String>,
+}
+
+impl Record {
+    fn is_empty(&self) -> bool {
+        self.date.is_none() && self.amount.is_none() && self.payee.is_none() && self.memo.is_none()
+    }
+
+    fn parse(
+        self,
+        date_order: QifDateOrder,
+        account: &Option<String>,
+    ) -> Result<ParsedTransaction, String> {
+        let raw_date = self.date.ok_or("missing date (D) field")?;
+        let posted_on = parse_date(&raw_date, date_order)
+            .ok_or_else(|| format!("could not parse date '{}'", raw_date))?;
+
+        let raw_amount = self.amount.ok_or("missing amount (T) field")?;
+        let amount = parse_amount(&raw_amount)
+            .ok_or_else(|| format!("could not parse amount '{}'", raw_amount))?;
+
+        Ok(ParsedTransaction {
+            posted_on,
+            amount,
+            description: self.payee.or(self.memo).unwrap_or_default(),
+            fitid: None,
+            account: account.clone(),
+        })
+    }
+}
+
+/// Parse the cash, bank and credit card transactions out of a QIF export.
+/// Investment, category, class and memorized transaction lists are ignored.
+pub fn parse(contents: &str, date_order: QifDateOrder) -> ImportPreview {
+    let mut preview = ImportPreview::default();
+
+    let mut account = None;
+    let mut in_account_header = false;
+    let mut in_transactions = false;
+    let mut record = Record::default();
+
+    for (i, raw_line) in contents.lines().enumerate() {
+        let line = raw_line.trim_end();
+        let line_number = i as u64 + 1;
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('!') {
+            let header = header.trim().to_ascii_lowercase();
+            if header == "account" {
+                in_account_header = true;
+            } else if let Some(kind) = header.strip_prefix("type:") {
+                in_transactions =
+                    matches!(kind.trim(), "bank" | "cash" | "ccard" | "oth a" | "oth l");
+            }
+            record = Record::default();
+            continue;
+        }
+
+        if in_account_header {
+            match line.split_at(line.chars().next().map_or(0, char::len_utf8)) {
+                ("N", name) => account = Some(name.trim().to_string()),
+                ("^", _) => in_account_header = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        if !in_transactions {
+            continue;
+        }
+
+        if record.is_empty() {
+            record.line = line_number;
+        }
+
+        let (code, value) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
+        let value = Some(value.trim().to_string());
+        match code {
+            "D" => record.date = value,
+            "T" | "U" => record.amount = record.amount.take().or(value),
+            "P" => record.payee = value,
+            "M" => record.memo = value,
+            "^" => {
+                let finished = std::mem::take(&mut record);
+                let line = finished.line;
+                match finished.parse(date_order, &account) {
+                    Ok(parsed) => preview.transactions.push(parsed),
+                    Err(reason) => preview.skipped.push(SkippedRow { line, reason }),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !record.is_empty() {
+        preview.skipped.push(SkippedRow {
+            line: record.line,
+            reason: "transaction is missing its closing '^'".into(),
+        });
+    }
+
+    preview
+}
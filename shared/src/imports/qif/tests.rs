@@ -0,0 +1,144 @@
+use super::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+#[test]
+fn test_bank_account() {
+    let contents = "!Account\n\
+                    NChecking\n\
+                    TBank\n\
+                    ^\n\
+                    !Type:Bank\n\
+                    D1/ 5'22\n\
+                    T-45.10\n\
+                    PGROCERY STORE\n\
+                    LFood:Groceries\n\
+                    ^\n\
+                    D01/15/2022\n\
+                    U1,200.00\n\
+                    T1,200.00\n\
+                    MPAYROLL\n\
+                    ^\n";
+
+    let preview = parse(contents, QifDateOrder::MonthFirst);
+
+    assert!(preview.skipped.is_empty());
+    assert_eq!(
+        preview.transactions,
+        vec![
+            ParsedTransaction {
+                posted_on: date(2022, 1, 5),
+                amount: -45.10,
+                description: "GROCERY STORE".into(),
+                fitid: None,
+                account: Some("Checking".into()),
+            },
+            ParsedTransaction {
+                posted_on: date(2022, 1, 15),
+                amount: 1200.,
+                description: "PAYROLL".into(),
+                fitid: None,
+                account: Some("Checking".into()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_dates() {
+    assert_eq!(
+        parse_date("1/5/2022", QifDateOrder::MonthFirst),
+        Some(date(2022, 1, 5))
+    );
+    assert_eq!(
+        parse_date("1/5/2022", QifDateOrder::DayFirst),
+        Some(date(2022, 5, 1))
+    );
+    assert_eq!(
+        parse_date("12/31/99", QifDateOrder::MonthFirst),
+        Some(date(1999, 12, 31))
+    );
+    assert_eq!(
+        parse_date("05.01.2022", QifDateOrder::DayFirst),
+        Some(date(2022, 1, 5))
+    );
+    assert_eq!(
+        parse_date("2022-01-05", QifDateOrder::DayFirst),
+        Some(date(2022, 1, 5))
+    );
+    assert_eq!(parse_date("13/13/2022", QifDateOrder::MonthFirst), None);
+    assert_eq!(parse_date("yesterday", QifDateOrder::MonthFirst), None);
+}
+
+#[test]
+fn test_non_cash_lists_are_ignored() {
+    let contents = "!Type:Cat\n\
+                    NFood\n\
+                    E\n\
+                    ^\n\
+                    !Type:Invst\n\
+                    D1/5/2022\n\
+                    NBuy\n\
+                    T100.00\n\
+                    ^\n\
+                    !Type:CCard\n\
+                    D1/6/2022\n\
+                    T-12.00\n\
+                    PBOOKSTORE\n\
+                    ^\n";
+
+    let preview = parse(contents, QifDateOrder::MonthFirst);
+
+    assert_eq!(preview.transactions.len(), 1);
+    assert_eq!(preview.transactions[0].description, "BOOKSTORE");
+    assert_eq!(preview.transactions[0].account, None);
+}
+
+#[test]
+fn test_bad_records_are_skipped() {
+    let contents = "!Type:Bank\n\
+                    Dnot a date\n\
+                    T-1.00\n\
+                    ^\n\
+                    D1/2/2022\n\
+                    PNO AMOUNT\n\
+                    ^\n\
+                    D1/3/2022\n\
+                    T-3.00\n\
+                    PGOOD\n\
+                    ^\n\
+                    D1/4/2022\n\
+                    T-4.00\n";
+
+    let preview = parse(contents, QifDateOrder::MonthFirst);
+
+    assert_eq!(preview.transactions.len(), 1);
+    assert_eq!(preview.transactions[0].description, "GOOD");
+    assert_eq!(
+        preview
+            .skipped
+            .iter()
+            .map(|skipped| skipped.line)
+            .collect::<Vec<_>>(),
+        vec![2, 5, 12]
+    );
+}
+
+#[test]
+fn test_multibyte_account_header_line() {
+    let contents = "!Account\n\
+                    €uro savings\n\
+                    NSavings\n\
+                    ^\n\
+                    !Type:Bank\n\
+                    D1/3/2022\n\
+                    T-3.00\n\
+                    ^\n";
+
+    let preview = parse(contents, QifDateOrder::MonthFirst);
+
+    assert_eq!(preview.transactions.len(), 1);
+    assert_eq!(preview.transactions[0].account.as_deref(), Some("Savings"));
+}
@@ -0,0 +1,82 @@
+use super::*;
+
+fn transaction(day: u32, amount: f32, fitid: Option<&str>) -> ParsedTransaction {
+    ParsedTransaction {
+        posted_on: NaiveDate::from_ymd(2022, 1, day),
+        amount,
+        description: "STORE".into(),
+        fitid: fitid.map(String::from),
+        account: Some("checking".into()),
+    }
+}
+
+#[test]
+fn test_deduplicate_by_fitid() {
+    let existing = vec![transaction(1, -10., Some("a"))];
+    let incoming = vec![
+        transaction(1, -10., Some("a")),
+        // same date and amount but a different bank id, so a different purchase
+        transaction(1, -10., Some("b")),
+        transaction(2, -20., Some("c")),
+        // repeated within the statement itself
+        transaction(2, -20., Some("c")),
+    ];
+
+    let (transactions, duplicates) = deduplicate(incoming, &existing);
+
+    assert_eq!(duplicates, 2);
+    assert_eq!(
+        transactions,
+        vec![
+            transaction(1, -10., Some("b")),
+            transaction(2, -20., Some("c"))
+        ]
+    );
+}
+
+#[test]
+fn test_deduplicate_fitid_per_account() {
+    let existing = vec![transaction(1, -10., Some("a"))];
+    let mut other_account = transaction(1, -10., Some("a"));
+    other_account.account = Some("savings".into());
+
+    let (transactions, duplicates) = deduplicate(vec![other_account.clone()], &existing);
+
+    assert_eq!(duplicates, 0);
+    assert_eq!(transactions, vec![other_account]);
+}
+
+#[test]
+fn test_deduplicate_date_and_amount_per_account() {
+    let existing = vec![transaction(1, -10., None)];
+    let mut other_account = transaction(1, -10., None);
+    other_account.account = Some("savings".into());
+
+    let (transactions, duplicates) = deduplicate(
+        vec![transaction(1, -10., None), other_account.clone()],
+        &existing,
+    );
+
+    assert_eq!(duplicates, 1);
+    assert_eq!(transactions, vec![other_account]);
+}
+
+#[test]
+fn test_deduplicate_by_date_and_amount() {
+    let existing = vec![transaction(1, -10., None), transaction(3, -5., None)];
+    let incoming = vec![
+        transaction(1, -10.001, None),
+        // a second identical purchase that day has not been seen before
+        transaction(1, -10., None),
+        transaction(2, -10., None),
+        transaction(3, -5., None),
+    ];
+
+    let (transactions, duplicates) = deduplicate(incoming, &existing);
+
+    assert_eq!(duplicates, 2);
+    assert_eq!(
+        transactions,
+        vec![transaction(1, -10., None), transaction(2, -10., None)]
+    );
+}
@@ -4,9 +4,15 @@ extern crate diesel;
 extern crate lazy_static;
 
 pub mod errors;
+pub mod exports;
+pub mod generators;
+pub mod imports;
 pub mod models;
 pub mod path_patterns;
+pub mod projection;
+pub mod recurring;
 pub mod routes;
 pub mod schema;
+pub mod taxes;
 
 pub use errors::MoolahSharedError;
@@ -1,7 +1,43 @@
+pub mod api_tokens;
+pub mod assets;
 pub mod deltas;
+pub mod goals;
+pub mod investments;
 pub mod predictions;
+pub mod rules;
+pub mod sessions;
+pub mod totp;
+pub mod transactions;
+pub mod user_tokens;
 pub mod users;
 
-pub use deltas::{DbDelta, Delta, NewDbDelta, NewDelta, Repetition};
+pub use api_tokens::{
+    ApiScope, ApiToken, ApiTokenInfo, CreatedApiToken, NewApiToken, NewApiTokenRequest,
+};
+pub use assets::{
+    Asset, AssetQuery, AssetValuation, AssetWithValuations, NewAsset, NewAssetRequest,
+    NewAssetValuation,
+};
+pub use deltas::{DbDelta, Delta, DeltaKind, DeltaQuery, NewDbDelta, NewDelta, Repetition};
+pub use goals::{Goal, GoalProgress, GoalQuery, NewGoal};
+pub use investments::{
+    InvestmentAccount, InvestmentAccountWithContributions, InvestmentContribution,
+    InvestmentProjection, InvestmentProjectionQuery, InvestmentQuery, NewInvestmentAccount,
+    NewInvestmentAccountRequest,
+};
 pub use predictions::{NewPrediction, Prediction, PredictionWithDeltas};
-pub use users::{NewUser, User, UserAccount, UserLoginRequestForm, UserRegisterForm};
+pub use rules::{
+    Categorization, CategorizationRule, CategorizationSummary, NewCategorizationRule, RuleSet,
+};
+pub use sessions::{NewSession, Session, SessionExpiry, SessionInfo};
+pub use totp::{
+    LoginResponse, NewRecoveryCode, NewUserTotp, RecoveryCode, TotpCodeForm, TotpEnrollment,
+    TotpStatus, TwoFactorLoginForm, UserTotp,
+};
+pub use transactions::{NewTransaction, Transaction};
+pub use user_tokens::{NewUserToken, TokenPurpose, UserToken};
+pub use users::{
+    EmailVerificationForm, NewUser, PasswordResetForm, PasswordResetRequest, User, UserAccount,
+    UserEmailChangeForm, UserLoginForm, UserPasswordChangeForm, UserRegisterForm,
+    UserUsernameChangeForm,
+};
@@ -0,0 +1,308 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use super::user_tokens::{hash_token, random_token};
+use crate::errors::MoolahSharedError;
+use crate::routes;
+use crate::schema::api_tokens;
+
+/// What every API token starts with, so a leaked one is easy to recognize.
+pub const TOKEN_PREFIX: &str = "moolah_";
+
+/// Length of the random part of an API token.
+pub const TOKEN_LENGTH: usize = 40;
+
+/// Longest name a token can be given.
+pub const MAX_NAME_LENGTH: usize = 64;
+
+/// Longest a token can be made to last, in days.
+pub const MAX_LIFETIME_DAYS: i64 = 365;
+
+/// Routes that manage the account itself. A token cannot be used on these, so
+/// a leaked token cannot lock its user out or mint more tokens.
+const ACCOUNT_ROUTES: [&str; 15] = [
+    routes::LOGIN,
+    routes::LOGOUT,
+    routes::LOGIN_TWO_FACTOR,
+    routes::USER,
+    routes::USER_PASSWORD,
+    routes::USER_EMAIL,
+    routes::USER_USERNAME,
+    routes::TOTP,
+    routes::TOTP_RECOVERY_CODES,
+    routes::PASSWORD_RESET_REQUEST,
+    routes::PASSWORD_RESET,
+    routes::EMAIL_VERIFICATION_REQUEST,
+    routes::EMAIL_VERIFICATION,
+    routes::SESSIONS,
+    routes::API_TOKENS,
+];
+
+const IMPORT_ROUTES: [&str; 4] = [
+    routes::IMPORT_CSV,
+    routes::IMPORT_OFX,
+    routes::IMPORT_QIF,
+    routes::IMPORT_LEDGER,
+];
+
+/// Routes whose `POST`s only calculate from what is sent and stored, without
+/// changing anything.
+const PREVIEW_ROUTES: [&str; 5] = [
+    routes::GENERATE_LOAN,
+    routes::GENERATE_DEBT_PAYOFF,
+    routes::GENERATE_PAYCHECK,
+    routes::TAX_ESTIMATE,
+    routes::RETIREMENT_PROJECTION,
+];
+
+/// What an API token lets its holder do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScope {
+    /// Fetch anything, including projections and exports
+    Read,
+    /// Create, change and delete predictions and everything in them
+    Write,
+    /// Preview and import transactions
+    Import,
+}
+
+impl ApiScope {
+    pub const ALL: [ApiScope; 3] = [ApiScope::Read, ApiScope::Write, ApiScope::Import];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::Read => "read",
+            ApiScope::Write => "write",
+            ApiScope::Import => "import",
+        }
+    }
+
+    pub fn parse(scope: &str) -> Option<Self> {
+        ApiScope::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == scope)
+    }
+
+    /// The scope a token needs for a `method` request to the route with
+    /// `pattern`, one of [`routes`], or `None` if no token may make it.
+    /// Patterns are compared whole, so a request has to be matched to its
+    /// route first; its path may be spelled in ways no prefix would catch.
+    pub fn required(method: &str, pattern: &str) -> Option<Self> {
+        let is = |candidates: &[&str]| {
+            candidates.iter().any(|candidate| {
+                candidate.trim_start_matches('/') == pattern.trim_start_matches('/')
+            })
+        };
+
+        if is(&ACCOUNT_ROUTES) {
+            None
+        } else if is(&IMPORT_ROUTES) {
+            Some(ApiScope::Import)
+        } else if method == "GET" || method == "HEAD" || method == "POST" && is(&PREVIEW_ROUTES) {
+            Some(ApiScope::Read)
+        } else {
+            Some(ApiScope::Write)
+        }
+    }
+}
+
+/// A token a user created to call the API from scripts. Only a hash of the
+/// token is stored; the token itself is shown once, when it is created.
+/// Scopes are stored comma separated, as in `read,import`.
+#[derive(Debug, Queryable, Identifiable, Clone, PartialEq)]
+pub struct ApiToken {
+    pub(crate) id: i32,
+    pub(crate) username: String,
+    pub(crate) name: String,
+    pub(crate) token_hash: String,
+    pub(crate) scopes: String,
+    pub(crate) created: NaiveDateTime,
+    pub(crate) last_used: Option<NaiveDateTime>,
+    pub(crate) expires: Option<NaiveDateTime>,
+}
+
+impl ApiToken {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn last_used(&self) -> Option<NaiveDateTime> {
+        self.last_used
+    }
+
+    /// The token's scopes, skipping any this version does not know.
+    pub fn scopes(&self) -> Vec<ApiScope> {
+        self.scopes.split(',').filter_map(ApiScope::parse).collect()
+    }
+
+    pub fn allows(&self, scope: ApiScope) -> bool {
+        self.scopes
+            .split(',')
+            .any(|granted| granted == scope.as_str())
+    }
+
+    pub fn is_expired(&self, now: NaiveDateTime) -> bool {
+        self.expires.map(|expires| now >= expires).unwrap_or(false)
+    }
+
+    /// What the user gets to see about the token.
+    pub fn info(&self) -> ApiTokenInfo {
+        ApiTokenInfo {
+            id: self.id,
+            name: self.name.clone(),
+            scopes: self.scopes(),
+            created: self.created,
+            last_used: self.last_used,
+            expires: self.expires,
+        }
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "api_tokens"]
+pub struct NewApiToken {
+    username: String,
+    name: String,
+    token_hash: String,
+    scopes: String,
+    created: NaiveDateTime,
+    expires: Option<NaiveDateTime>,
+}
+
+impl NewApiToken {
+    /// A token for `username` as `request` describes, along with the token
+    /// itself to show the user. The token cannot be recovered afterwards.
+    pub fn new(
+        username: String,
+        request: &NewApiTokenRequest,
+        now: NaiveDateTime,
+    ) -> Result<(Self, String), MoolahSharedError> {
+        request.validate()?;
+
+        let token = format!("{}{}", TOKEN_PREFIX, random_token(TOKEN_LENGTH));
+
+        let mut scopes = Vec::new();
+        for scope in &request.scopes {
+            if !scopes.contains(scope) {
+                scopes.push(*scope);
+            }
+        }
+
+        let new_token = NewApiToken {
+            username,
+            name: request.name.trim().to_string(),
+            token_hash: hash_token(&token),
+            scopes: scopes
+                .iter()
+                .map(ApiScope::as_str)
+                .collect::<Vec<_>>()
+                .join(","),
+            created: now,
+            expires: request
+                .expires_in_days
+                .map(|days| now + Duration::days(days)),
+        };
+
+        Ok((new_token, token))
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A token the user asked for from the account page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewApiTokenRequest {
+    name: String,
+    scopes: Vec<ApiScope>,
+    /// Days until the token stops working, or `None` for it to work until
+    /// revoked
+    expires_in_days: Option<i64>,
+}
+
+impl NewApiTokenRequest {
+    pub fn new(
+        name: String,
+        scopes: Vec<ApiScope>,
+        expires_in_days: Option<i64>,
+    ) -> Result<Self, MoolahSharedError> {
+        let request = NewApiTokenRequest {
+            name: name.trim().to_string(),
+            scopes,
+            expires_in_days,
+        };
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Check a token can be created from the request. Requests arriving from
+    /// a client should be validated again, since deserializing skips the
+    /// checks in `new`.
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(MoolahSharedError::ApiTokenError(
+                "token needs a name".into(),
+            ));
+        }
+
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(MoolahSharedError::ApiTokenError(format!(
+                "token names can be at most {} characters",
+                MAX_NAME_LENGTH
+            )));
+        }
+
+        if self.scopes.is_empty() {
+            return Err(MoolahSharedError::ApiTokenError(
+                "token needs at least one scope".into(),
+            ));
+        }
+
+        if let Some(days) = self.expires_in_days {
+            if !(1..=MAX_LIFETIME_DAYS).contains(&days) {
+                return Err(MoolahSharedError::ApiTokenError(format!(
+                    "tokens can last between 1 and {} days",
+                    MAX_LIFETIME_DAYS
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A token as listed on the account page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiTokenInfo {
+    pub id: i32,
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    pub created: NaiveDateTime,
+    pub last_used: Option<NaiveDateTime>,
+    pub expires: Option<NaiveDateTime>,
+}
+
+/// A newly created token, the only time the token itself is sent back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+    pub info: ApiTokenInfo,
+    pub token: String,
+}
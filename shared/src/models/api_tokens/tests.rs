@@ -0,0 +1,207 @@
+use chrono::NaiveDate;
+
+use super::*;
+
+fn at(day: u32, hour: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd(2022, 3, day).and_hms(hour, 0, 0)
+}
+
+fn token(scopes: &str, expires: Option<NaiveDateTime>) -> ApiToken {
+    ApiToken {
+        id: 1,
+        username: "alice".into(),
+        name: "cron".into(),
+        token_hash: hash_token("token"),
+        scopes: scopes.into(),
+        created: at(1, 0),
+        last_used: None,
+        expires,
+    }
+}
+
+fn request(scopes: Vec<ApiScope>, expires_in_days: Option<i64>) -> NewApiTokenRequest {
+    NewApiTokenRequest {
+        name: "cron".into(),
+        scopes,
+        expires_in_days,
+    }
+}
+
+mod required {
+    use super::*;
+
+    #[test]
+    fn test_get_reads() {
+        assert_eq!(
+            Some(ApiScope::Read),
+            ApiScope::required("GET", routes::PREDICTIONS)
+        );
+        assert_eq!(
+            Some(ApiScope::Read),
+            ApiScope::required("GET", routes::INVESTMENTS_PROJECTION)
+        );
+    }
+
+    #[test]
+    fn test_changes_write() {
+        assert_eq!(
+            Some(ApiScope::Write),
+            ApiScope::required("PUT", routes::PREDICTIONS)
+        );
+        assert_eq!(
+            Some(ApiScope::Write),
+            ApiScope::required("POST", routes::DELTAS)
+        );
+        assert_eq!(
+            Some(ApiScope::Write),
+            ApiScope::required("PUT", routes::GENERATE_LOAN)
+        );
+    }
+
+    #[test]
+    fn test_previews_read() {
+        assert_eq!(
+            Some(ApiScope::Read),
+            ApiScope::required("POST", routes::GENERATE_LOAN)
+        );
+        assert_eq!(
+            Some(ApiScope::Read),
+            ApiScope::required("POST", routes::RETIREMENT_PROJECTION)
+        );
+    }
+
+    #[test]
+    fn test_imports() {
+        assert_eq!(
+            Some(ApiScope::Import),
+            ApiScope::required("POST", routes::IMPORT_CSV)
+        );
+        assert_eq!(
+            Some(ApiScope::Import),
+            ApiScope::required("PUT", routes::IMPORT_OFX)
+        );
+    }
+
+    #[test]
+    fn test_account_routes_refused() {
+        assert_eq!(None, ApiScope::required("GET", routes::USER));
+        assert_eq!(None, ApiScope::required("PATCH", routes::USER_PASSWORD));
+        assert_eq!(None, ApiScope::required("POST", routes::LOGIN));
+        assert_eq!(None, ApiScope::required("GET", routes::SESSIONS));
+        assert_eq!(None, ApiScope::required("POST", routes::API_TOKENS));
+    }
+
+    #[test]
+    fn test_leading_slash_ignored() {
+        assert_eq!(None, ApiScope::required("GET", "/api/tokens/{username}"));
+        assert_eq!(None, ApiScope::required("GET", "api/login"));
+    }
+}
+
+mod api_token {
+    use super::*;
+
+    #[test]
+    fn test_allows() {
+        let token = token("read,import", None);
+
+        assert!(token.allows(ApiScope::Read));
+        assert!(token.allows(ApiScope::Import));
+        assert!(!token.allows(ApiScope::Write));
+    }
+
+    #[test]
+    fn test_unknown_scopes_skipped() {
+        let token = token("read,admin", None);
+
+        assert_eq!(vec![ApiScope::Read], token.scopes());
+    }
+
+    #[test]
+    fn test_expiry() {
+        assert!(!token("read", None).is_expired(at(30, 0)));
+        assert!(!token("read", Some(at(2, 0))).is_expired(at(1, 23)));
+        assert!(token("read", Some(at(2, 0))).is_expired(at(2, 0)));
+    }
+}
+
+mod new_api_token {
+    use super::*;
+
+    #[test]
+    fn test_hashed() {
+        let (new_token, token) = NewApiToken::new(
+            "alice".into(),
+            &request(vec![ApiScope::Read], None),
+            at(1, 0),
+        )
+        .unwrap();
+
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert_eq!(TOKEN_PREFIX.len() + TOKEN_LENGTH, token.len());
+        assert_eq!(hash_token(&token), new_token.token_hash);
+    }
+
+    #[test]
+    fn test_scopes_deduplicated() {
+        let scopes = vec![ApiScope::Import, ApiScope::Read, ApiScope::Import];
+        let (new_token, _) =
+            NewApiToken::new("alice".into(), &request(scopes, None), at(1, 0)).unwrap();
+
+        assert_eq!("import,read", new_token.scopes);
+    }
+
+    #[test]
+    fn test_expires() {
+        let (new_token, _) = NewApiToken::new(
+            "alice".into(),
+            &request(vec![ApiScope::Read], Some(30)),
+            at(1, 0),
+        )
+        .unwrap();
+
+        assert_eq!(Some(at(31, 0)), new_token.expires);
+    }
+
+    #[test]
+    fn test_invalid_request() {
+        assert!(NewApiToken::new("alice".into(), &request(vec![], None), at(1, 0)).is_err());
+    }
+}
+
+mod new_api_token_request {
+    use super::*;
+
+    #[test]
+    fn test_valid() {
+        assert!(NewApiTokenRequest::new("cron".into(), vec![ApiScope::Read], Some(90)).is_ok());
+    }
+
+    #[test]
+    fn test_blank_name() {
+        assert!(NewApiTokenRequest::new("  ".into(), vec![ApiScope::Read], None).is_err());
+    }
+
+    #[test]
+    fn test_long_name() {
+        let name = "a".repeat(MAX_NAME_LENGTH + 1);
+
+        assert!(NewApiTokenRequest::new(name, vec![ApiScope::Read], None).is_err());
+    }
+
+    #[test]
+    fn test_no_scopes() {
+        assert!(NewApiTokenRequest::new("cron".into(), vec![], None).is_err());
+    }
+
+    #[test]
+    fn test_lifetime() {
+        assert!(NewApiTokenRequest::new("cron".into(), vec![ApiScope::Read], Some(0)).is_err());
+        assert!(NewApiTokenRequest::new(
+            "cron".into(),
+            vec![ApiScope::Read],
+            Some(MAX_LIFETIME_DAYS + 1)
+        )
+        .is_err());
+    }
+}
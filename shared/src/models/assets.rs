@@ -0,0 +1,210 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::Prediction;
+use crate::schema::{asset_valuations, assets};
+use crate::MoolahSharedError;
+
+/// Something owned (a home, a car) or owed (a mortgage, a car loan) whose
+/// value counts toward net worth.
+#[derive(
+    Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, Clone, PartialEq,
+)]
+#[belongs_to(Prediction)]
+pub struct Asset {
+    pub(crate) id: i32,
+    pub(crate) prediction_id: i32,
+    pub(crate) name: String,
+    pub(crate) is_liability: bool,
+    pub(crate) annual_rate: Option<f32>,
+}
+
+impl Asset {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn prediction_id(&self) -> i32 {
+        self.prediction_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_liability(&self) -> bool {
+        self.is_liability
+    }
+
+    /// Yearly change in value as a percentage, negative for depreciation
+    pub fn annual_rate(&self) -> Option<f32> {
+        self.annual_rate
+    }
+}
+
+/// What an asset was worth on a given day.
+#[derive(
+    Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, Clone, PartialEq,
+)]
+#[belongs_to(Asset)]
+pub struct AssetValuation {
+    pub(crate) id: i32,
+    pub(crate) asset_id: i32,
+    pub(crate) valued_on: NaiveDate,
+    pub(crate) value: f32,
+}
+
+impl AssetValuation {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn asset_id(&self) -> i32 {
+        self.asset_id
+    }
+
+    pub fn valued_on(&self) -> NaiveDate {
+        self.valued_on
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+}
+
+/// An asset along with every valuation of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetWithValuations {
+    pub asset: Asset,
+    pub valuations: Vec<AssetValuation>,
+}
+
+impl AssetWithValuations {
+    /// The asset's value on `date`, grown at its annual rate from the latest
+    /// valuation on or before then. `None` before it was first valued.
+    pub fn value_on(&self, date: NaiveDate) -> Option<f32> {
+        let latest = self
+            .valuations
+            .iter()
+            .filter(|valuation| valuation.valued_on <= date)
+            .max_by_key(|valuation| valuation.valued_on)?;
+
+        let rate = self.asset.annual_rate.unwrap_or(0.) as f64 / 100.;
+        let years = (date - latest.valued_on).num_days() as f64 / 365.25;
+
+        Some((latest.value as f64 * (1. + rate).max(0.).powf(years)) as f32)
+    }
+
+    /// The asset's value on `date` counted toward net worth, negative for a
+    /// liability.
+    pub fn net_value_on(&self, date: NaiveDate) -> f32 {
+        let value = self.value_on(date).unwrap_or(0.);
+        if self.asset.is_liability {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
+#[derive(Debug, Insertable, Serialize, Deserialize, Clone, PartialEq)]
+#[table_name = "assets"]
+pub struct NewAsset {
+    prediction_id: i32,
+    name: String,
+    is_liability: bool,
+    annual_rate: Option<f32>,
+}
+
+impl NewAsset {
+    pub fn new(
+        prediction_id: i32,
+        name: String,
+        is_liability: bool,
+        annual_rate: Option<f32>,
+    ) -> Result<Self, MoolahSharedError> {
+        let asset = NewAsset {
+            prediction_id,
+            name: name.trim().to_string(),
+            is_liability,
+            annual_rate,
+        };
+        asset.validate()?;
+        Ok(asset)
+    }
+
+    /// Check the asset can be stored. Assets arriving from a client should be
+    /// validated again, since deserializing skips the checks in `new`.
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        if self.name.trim().is_empty() {
+            return Err(MoolahSharedError::AssetError("asset needs a name".into()));
+        }
+        match self.annual_rate {
+            Some(rate) if !rate.is_finite() || rate <= -100. => Err(MoolahSharedError::AssetError(
+                "annual rate must be greater than -100%".into(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn prediction_id(&self) -> i32 {
+        self.prediction_id
+    }
+}
+
+#[derive(Debug, Insertable, Serialize, Deserialize, Clone, PartialEq)]
+#[table_name = "asset_valuations"]
+pub struct NewAssetValuation {
+    asset_id: i32,
+    valued_on: NaiveDate,
+    value: f32,
+}
+
+impl NewAssetValuation {
+    pub fn new(asset_id: i32, valued_on: NaiveDate, value: f32) -> Result<Self, MoolahSharedError> {
+        let valuation = NewAssetValuation {
+            asset_id,
+            valued_on,
+            value,
+        };
+        valuation.validate()?;
+        Ok(valuation)
+    }
+
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        if !self.value.is_finite() || self.value < 0. {
+            return Err(MoolahSharedError::AssetError(
+                "value cannot be negative".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn asset_id(&self) -> i32 {
+        self.asset_id
+    }
+
+    pub fn valued_on(&self) -> NaiveDate {
+        self.valued_on
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+}
+
+/// A new asset along with what it is worth to begin with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewAssetRequest {
+    pub asset: NewAsset,
+    pub valued_on: NaiveDate,
+    pub value: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AssetQuery {
+    pub prediction_id: i32,
+}
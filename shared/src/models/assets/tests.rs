@@ -0,0 +1,68 @@
+use super::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn valuation(id: i32, valued_on: NaiveDate, value: f32) -> AssetValuation {
+    AssetValuation {
+        id,
+        asset_id: 1,
+        valued_on,
+        value,
+    }
+}
+
+fn asset(is_liability: bool, annual_rate: Option<f32>) -> AssetWithValuations {
+    AssetWithValuations {
+        asset: Asset {
+            id: 1,
+            prediction_id: 1,
+            name: "home".into(),
+            is_liability,
+            annual_rate,
+        },
+        valuations: vec![
+            valuation(2, date(2026, 1, 1), 300000.),
+            valuation(3, date(2025, 1, 1), 280000.),
+        ],
+    }
+}
+
+#[test]
+fn test_value_on_uses_latest_valuation() {
+    let home = asset(false, None);
+
+    assert_eq!(home.value_on(date(2024, 12, 31)), None);
+    assert_eq!(home.value_on(date(2025, 6, 1)), Some(280000.));
+    assert_eq!(home.value_on(date(2026, 1, 1)), Some(300000.));
+    assert_eq!(home.value_on(date(2030, 1, 1)), Some(300000.));
+}
+
+#[test]
+fn test_value_on_applies_rate() {
+    let home = asset(false, Some(3.));
+    let car = asset(false, Some(-15.));
+
+    let value = home.value_on(date(2028, 1, 1)).unwrap();
+    assert!((value - 300000. * 1.03f32.powi(2)).abs() < 50.);
+
+    let value = car.value_on(date(2027, 1, 1)).unwrap();
+    assert!((value - 255000.).abs() < 50.);
+}
+
+#[test]
+fn test_liabilities_count_against_net_worth() {
+    assert_eq!(asset(false, None).net_value_on(date(2026, 2, 1)), 300000.);
+    assert_eq!(asset(true, None).net_value_on(date(2026, 2, 1)), -300000.);
+    assert_eq!(asset(true, None).net_value_on(date(2020, 1, 1)), 0.);
+}
+
+#[test]
+fn test_validate() {
+    assert!(NewAsset::new(1, "car".into(), false, Some(-15.)).is_ok());
+    assert!(NewAsset::new(1, " ".into(), false, None).is_err());
+    assert!(NewAsset::new(1, "car".into(), false, Some(-100.)).is_err());
+    assert!(NewAssetValuation::new(1, date(2026, 1, 1), 1000.).is_ok());
+    assert!(NewAssetValuation::new(1, date(2026, 1, 1), -1.).is_err());
+}
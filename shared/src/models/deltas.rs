@@ -1,5 +1,5 @@
 pub mod app;
 pub mod db;
 
-pub use app::{Delta, NewDelta, Repetition};
+pub use app::{Delta, DeltaKind, DeltaQuery, NewDelta, Repetition};
 pub use db::{DbDelta, NewDbDelta};
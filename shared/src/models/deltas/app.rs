@@ -1,5 +1,7 @@
 pub mod delta;
+pub mod kind;
 pub mod repetition;
 
-pub use delta::{Delta, NewDelta};
+pub use delta::{Delta, DeltaQuery, NewDelta};
+pub use kind::DeltaKind;
 pub use repetition::Repetition;
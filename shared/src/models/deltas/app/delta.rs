@@ -7,7 +7,7 @@ use std::str::FromStr;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
-use super::Repetition;
+use super::{DeltaKind, Repetition};
 use crate::models::deltas::db::DbDateRepetition;
 use crate::{models::DbDelta, MoolahSharedError};
 
@@ -20,6 +20,8 @@ pub struct Delta {
     positive_uncertainty: f32,
     negative_uncertainty: f32,
     repetition: Repetition,
+    #[serde(default)]
+    kind: DeltaKind,
     // dates: Vec<NaiveDate>,
 }
 
@@ -41,10 +43,16 @@ impl Delta {
             positive_uncertainty,
             negative_uncertainty,
             repetition,
+            kind: DeltaKind::Other,
             // dates: repetition.dates(),
         }
     }
 
+    pub fn with_kind(mut self, kind: DeltaKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
     pub fn id(&self) -> i32 {
         self.id
     }
@@ -73,6 +81,10 @@ impl Delta {
         self.repetition
     }
 
+    pub fn kind(&self) -> DeltaKind {
+        self.kind
+    }
+
     // pub fn dates(&self) -> &Vec<NaiveDate> {
     //     &self.dates
     // }
@@ -87,6 +99,7 @@ impl Hash for Delta {
         // self.positive_uncertainty.hash(state);
         // self.negative_uncertainty.hash(state);
         self.repetition.hash(state);
+        self.kind.hash(state);
         // self.dates.hash(state);
     }
 }
@@ -164,6 +177,20 @@ impl TryFrom<DbDelta> for Delta {
                     ))
                 })?,
             },
+            DbDateRepetition::Biweekly => Repetition::Biweekly {
+                from: value.start_on,
+                to: value.end_on.ok_or(MoolahSharedError::DeltaConversionError(
+                    "biweekly repetition does not have end date",
+                ))?,
+                repeat_on_weekday: chrono::Weekday::from_str(&value.repeat_weekday.ok_or(
+                    MoolahSharedError::DeltaConversionError(
+                        "biweekly repetition does not have repeat weekday",
+                    ),
+                )?)
+                .map_err(|_| {
+                    MoolahSharedError::DeltaConversionError("could not convert to weekday")
+                })?,
+            },
             DbDateRepetition::Daily => Repetition::Daily {
                 from: value.start_on,
                 to: value.end_on.ok_or_else(|| {
@@ -183,7 +210,8 @@ impl TryFrom<DbDelta> for Delta {
             value.positive_uncertainty,
             value.negative_uncertainty,
             repetition,
-        ))
+        )
+        .with_kind(value.kind))
     }
 }
 
@@ -195,6 +223,7 @@ pub struct NewDelta {
     positive_uncertainty: f32,
     negative_uncertainty: f32,
     repetition: Repetition,
+    kind: DeltaKind,
 }
 
 impl NewDelta {
@@ -213,9 +242,15 @@ impl NewDelta {
             positive_uncertainty,
             negative_uncertainty,
             repetition,
+            kind: DeltaKind::Other,
         }
     }
 
+    pub fn with_kind(mut self, kind: DeltaKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
     pub fn prediction_id(&self) -> i32 {
         self.prediction_id
     }
@@ -239,17 +274,27 @@ impl NewDelta {
     pub fn repetition(&self) -> &Repetition {
         &self.repetition
     }
+
+    pub fn kind(&self) -> DeltaKind {
+        self.kind
+    }
 }
 
-// impl From<Delta> for NewDelta {
-//     fn from(delta: Delta) -> Self {
-//         NewDelta {
-//             prediction_id: delta.prediction_id,
-//             name: delta.name,
-//             value: delta.value,
-//             positive_uncertainty: delta.positive_uncertainty,
-//             negative_uncertainty: delta.negative_uncertainty,
-//             repetition: delta.repetition,
-//         }
-//     }
-// }
+impl From<Delta> for NewDelta {
+    fn from(delta: Delta) -> Self {
+        NewDelta {
+            prediction_id: delta.prediction_id,
+            name: delta.name,
+            value: delta.value,
+            positive_uncertainty: delta.positive_uncertainty,
+            negative_uncertainty: delta.negative_uncertainty,
+            repetition: delta.repetition,
+            kind: delta.kind,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DeltaQuery {
+    pub delta_id: i32,
+}
@@ -20,6 +20,7 @@ mod monthly {
             Some(NaiveDate::from_ymd(2022, 4, 23)),
             Some(12),
             None,
+            DeltaKind::Other,
         );
         let expected = Delta::new(
             1,
@@ -52,6 +53,7 @@ mod monthly {
             Some(NaiveDate::from_ymd(2022, 4, 23)),
             Some(12),
             Some(Weekday::Mon.to_string()),
+            DeltaKind::Other,
         );
         let expected = Delta::new(
             1,
@@ -84,6 +86,7 @@ mod monthly {
             Some(NaiveDate::from_ymd(2022, 4, 23)),
             None,
             None,
+            DeltaKind::Other,
         );
 
         assert!(Delta::try_from(db_delta).is_err());
@@ -103,6 +106,7 @@ mod monthly {
             None,
             Some(12),
             None,
+            DeltaKind::Other,
         );
 
         assert!(Delta::try_from(db_delta).is_err());
@@ -126,6 +130,7 @@ mod weekly {
             Some(NaiveDate::from_ymd(2022, 4, 23)),
             None,
             Some("Mon".into()),
+            DeltaKind::Other,
         );
         let expected = Delta::new(
             1,
@@ -158,6 +163,7 @@ mod weekly {
             Some(NaiveDate::from_ymd(2022, 4, 23)),
             Some(12),
             Some("Mon".into()),
+            DeltaKind::Other,
         );
         let expected = Delta::new(
             1,
@@ -190,6 +196,7 @@ mod weekly {
             None,
             None,
             Some("Mon".into()),
+            DeltaKind::Other,
         );
 
         assert!(Delta::try_from(db_delta).is_err());
@@ -209,6 +216,7 @@ mod weekly {
             Some(NaiveDate::from_ymd(2022, 4, 23)),
             None,
             None,
+            DeltaKind::Other,
         );
         assert!(Delta::try_from(db_delta).is_err());
     }
@@ -231,6 +239,7 @@ mod daily {
             Some(NaiveDate::from_ymd(2022, 4, 23)),
             None,
             None,
+            DeltaKind::Other,
         );
         let expected = Delta::new(
             1,
@@ -262,6 +271,7 @@ mod daily {
             Some(NaiveDate::from_ymd(2022, 4, 23)),
             Some(12),
             Some("Fri".into()),
+            DeltaKind::Other,
         );
         let expected = Delta::new(
             1,
@@ -293,6 +303,7 @@ mod daily {
             None,
             None,
             None,
+            DeltaKind::Other,
         );
         assert!(Delta::try_from(db_delta).is_err());
     }
@@ -315,6 +326,7 @@ mod once {
             None,
             None,
             None,
+            DeltaKind::Other,
         );
         let expected = Delta::new(
             1,
@@ -345,6 +357,7 @@ mod once {
             Some(NaiveDate::from_ymd(2022, 4, 23)),
             Some(12),
             Some("Thu".into()),
+            DeltaKind::Other,
         );
         let expected = Delta::new(
             1,
@@ -361,3 +374,96 @@ mod once {
         assert_eq!(expected, db_delta.try_into().unwrap());
     }
 }
+
+mod updates {
+    use super::*;
+    use crate::models::NewDbDelta;
+
+    fn round_trip(delta: Delta) -> Delta {
+        let new_db_delta = NewDbDelta::from(NewDelta::from(delta.clone()));
+        DbDelta::new(
+            delta.id(),
+            new_db_delta.prediction_id,
+            new_db_delta.name,
+            new_db_delta.value,
+            new_db_delta.positive_uncertainty,
+            new_db_delta.negative_uncertainty,
+            new_db_delta.repetition,
+            new_db_delta.start_on,
+            new_db_delta.end_on,
+            new_db_delta.repeat_day,
+            new_db_delta.repeat_weekday,
+            new_db_delta.kind,
+        )
+        .try_into()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_monthly_round_trip() {
+        let delta = Delta::new(
+            3,
+            1,
+            "rent".into(),
+            -1200.,
+            0.,
+            0.,
+            Repetition::Monthly {
+                from: NaiveDate::from_ymd(2022, 1, 1),
+                to: NaiveDate::from_ymd(2022, 12, 31),
+                repeat_on_day: MonthDay::new(1).unwrap(),
+            },
+        );
+
+        assert_eq!(delta.clone(), round_trip(delta));
+    }
+
+    #[test]
+    fn test_biweekly_round_trip() {
+        let delta = Delta::new(
+            4,
+            1,
+            "pay".into(),
+            1500.,
+            10.,
+            5.,
+            Repetition::Biweekly {
+                from: NaiveDate::from_ymd(2022, 1, 7),
+                to: NaiveDate::from_ymd(2022, 6, 30),
+                repeat_on_weekday: Weekday::Fri,
+            },
+        )
+        .with_kind(DeltaKind::Income);
+
+        assert_eq!(delta.clone(), round_trip(delta));
+    }
+
+    #[test]
+    fn test_kind_strings() {
+        for kind in DeltaKind::ALL {
+            assert_eq!(kind, kind.as_str().parse().unwrap());
+        }
+        assert!("salary".parse::<DeltaKind>().is_err());
+    }
+
+    #[test]
+    fn test_once_clears_repeating_fields() {
+        let delta = Delta::new(
+            5,
+            1,
+            "bonus".into(),
+            500.,
+            0.,
+            0.,
+            Repetition::Once {
+                on: NaiveDate::from_ymd(2022, 3, 15),
+            },
+        );
+
+        let new_db_delta = NewDbDelta::from(NewDelta::from(delta));
+
+        assert_eq!(None, new_db_delta.end_on);
+        assert_eq!(None, new_db_delta.repeat_day);
+        assert_eq!(None, new_db_delta.repeat_weekday);
+    }
+}
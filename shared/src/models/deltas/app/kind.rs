@@ -0,0 +1,110 @@
+use std::io::Write;
+use std::str::FromStr;
+
+use diesel::{
+    backend::Backend,
+    serialize::Output,
+    sql_types::Text,
+    types::{FromSql, ToSql},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::MoolahSharedError;
+
+/// What a delta stands for, for anything that has to treat some deltas
+/// differently from the rest, like the tax estimate. Deltas are `Other`
+/// unless they're marked otherwise.
+///
+/// Deductions and withholding are taken out of pay before it's paid, so they
+/// are kept as a record alongside the income they came out of but never move
+/// the balance themselves.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    AsExpression,
+    FromSqlRow,
+)]
+#[serde(rename_all = "snake_case")]
+#[sql_type = "Text"]
+pub enum DeltaKind {
+    #[default]
+    Other,
+    /// Taxable pay as it's paid, such as a paycheck's net pay
+    Income,
+    /// Money held back from pay before it's paid, like a 401k contribution
+    PreTaxDeduction,
+    /// Tax withheld from pay before it's paid
+    Withholding,
+}
+
+impl DeltaKind {
+    pub const ALL: [DeltaKind; 4] = [
+        DeltaKind::Other,
+        DeltaKind::Income,
+        DeltaKind::PreTaxDeduction,
+        DeltaKind::Withholding,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeltaKind::Other => "other",
+            DeltaKind::Income => "income",
+            DeltaKind::PreTaxDeduction => "pre_tax_deduction",
+            DeltaKind::Withholding => "withholding",
+        }
+    }
+
+    /// Whether the delta adds to or takes from the balance when it occurs.
+    pub fn moves_balance(&self) -> bool {
+        !matches!(self, DeltaKind::PreTaxDeduction | DeltaKind::Withholding)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeltaKind::Other => "other",
+            DeltaKind::Income => "income",
+            DeltaKind::PreTaxDeduction => "pre-tax deduction",
+            DeltaKind::Withholding => "withholding",
+        }
+    }
+}
+
+impl FromStr for DeltaKind {
+    type Err = MoolahSharedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DeltaKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or(MoolahSharedError::DeltaConversionError(
+                "unrecognized delta kind",
+            ))
+    }
+}
+
+impl<DB> ToSql<Text, DB> for DeltaKind
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> diesel::serialize::Result {
+        self.as_str().to_sql(out)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for DeltaKind
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> diesel::deserialize::Result<Self> {
+        Ok(String::from_sql(bytes)?.parse()?)
+    }
+}
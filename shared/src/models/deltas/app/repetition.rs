@@ -20,6 +20,11 @@ pub enum Repetition {
         to: NaiveDate,
         repeat_on_weekday: chrono::Weekday,
     },
+    Biweekly {
+        from: NaiveDate,
+        to: NaiveDate,
+        repeat_on_weekday: chrono::Weekday,
+    },
     Daily {
         from: NaiveDate,
         to: NaiveDate,
@@ -119,6 +124,27 @@ impl Repetition {
                     .take(weeks.try_into().expect("could not convert weeks to usize"))
                     .collect()
             }
+            Repetition::Biweekly {
+                from: start,
+                to: end,
+                repeat_on_weekday: weekday,
+            } => {
+                let mut start_offset_days: i64 = ((weekday.number_from_monday() as i32)
+                    - (start.weekday().number_from_monday() as i32))
+                    .into();
+                if start_offset_days < 0 {
+                    start_offset_days += 7;
+                }
+
+                let mut date = *start + chrono::Duration::days(start_offset_days);
+
+                let mut dates: Vec<NaiveDate> = Vec::new();
+                while date <= *end {
+                    dates.push(date);
+                    date += chrono::Duration::weeks(2);
+                }
+                dates
+            }
             Repetition::Daily { from, to } => {
                 let days = (*to - *from).num_days() + 1;
                 from.iter_days()
@@ -128,6 +154,78 @@ impl Repetition {
             Repetition::Once { on } => vec![*on],
         }
     }
+
+    /// The dates falling between `from` and `to` (inclusive), without expanding
+    /// any of the repetition outside that window. Use this rather than
+    /// filtering [`Repetition::dates`] when looking at a slice of a long range.
+    pub fn dates_between(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        match self.within(from, to) {
+            Some(repetition) => repetition.dates(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The same repetition narrowed to the window from `from` to `to`, or
+    /// `None` if it never happens in it.
+    fn within(&self, from: NaiveDate, to: NaiveDate) -> Option<Repetition> {
+        let narrow = |start: NaiveDate, end: NaiveDate| {
+            let start = start.max(from);
+            let end = end.min(to);
+            (start <= end).then_some((start, end))
+        };
+
+        match *self {
+            Repetition::Monthly {
+                from: start,
+                to: end,
+                repeat_on_day,
+            } => narrow(start, end).map(|(from, to)| Repetition::Monthly {
+                from,
+                to,
+                repeat_on_day,
+            }),
+            Repetition::Weekly {
+                from: start,
+                to: end,
+                repeat_on_weekday,
+            } => narrow(start, end).map(|(from, to)| Repetition::Weekly {
+                from,
+                to,
+                repeat_on_weekday,
+            }),
+            Repetition::Biweekly {
+                from: start,
+                to: end,
+                repeat_on_weekday,
+            } => {
+                // start from the first occurrence, moved forward by whole
+                // fortnights so the narrowed repetition lands on the same weeks
+                let start = Repetition::Biweekly {
+                    from: start,
+                    to: start + chrono::Duration::days(6),
+                    repeat_on_weekday,
+                }
+                .dates()[0];
+                let start = if start < from {
+                    let fortnights = ((from - start).num_days() + 13) / 14;
+                    start + chrono::Duration::weeks(2 * fortnights)
+                } else {
+                    start
+                };
+                let end = end.min(to);
+                (start <= end).then_some(Repetition::Biweekly {
+                    from: start,
+                    to: end,
+                    repeat_on_weekday,
+                })
+            }
+            Repetition::Daily {
+                from: start,
+                to: end,
+            } => narrow(start, end).map(|(from, to)| Repetition::Daily { from, to }),
+            Repetition::Once { on } => (from <= on && on <= to).then_some(Repetition::Once { on }),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone, Copy)]
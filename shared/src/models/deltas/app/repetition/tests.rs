@@ -496,6 +496,46 @@ mod weekly_dates {
     }
 }
 
+mod biweekly_dates {
+    use super::*;
+    use chrono::Weekday;
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(
+            Repetition::Biweekly {
+                from: NaiveDate::from_ymd(2026, 1, 2),
+                to: NaiveDate::from_ymd(2026, 2, 13),
+                repeat_on_weekday: Weekday::Fri
+            }
+            .dates(),
+            vec![
+                NaiveDate::from_ymd(2026, 1, 2),
+                NaiveDate::from_ymd(2026, 1, 16),
+                NaiveDate::from_ymd(2026, 1, 30),
+                NaiveDate::from_ymd(2026, 2, 13),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_mismatched_start() {
+        assert_eq!(
+            Repetition::Biweekly {
+                from: NaiveDate::from_ymd(2026, 1, 3),
+                to: NaiveDate::from_ymd(2026, 2, 12),
+                repeat_on_weekday: Weekday::Fri
+            }
+            .dates(),
+            vec![
+                NaiveDate::from_ymd(2026, 1, 9),
+                NaiveDate::from_ymd(2026, 1, 23),
+                NaiveDate::from_ymd(2026, 2, 6),
+            ]
+        )
+    }
+}
+
 mod daily_dates {
     use super::*;
 
@@ -527,3 +567,114 @@ mod once_dates {
         assert_eq!(*dates.first().unwrap(), NaiveDate::from_ymd(2022, 1, 27));
     }
 }
+
+mod dates_between {
+    use super::*;
+
+    fn assert_matches_filtered(repetition: Repetition, from: NaiveDate, to: NaiveDate) {
+        let filtered = repetition
+            .dates()
+            .into_iter()
+            .filter(|date| from <= *date && *date <= to)
+            .collect::<Vec<_>>();
+
+        assert_eq!(repetition.dates_between(from, to), filtered);
+    }
+
+    #[test]
+    fn test_monthly() {
+        let repetition = Repetition::Monthly {
+            from: NaiveDate::from_ymd(2022, 1, 15),
+            to: NaiveDate::from_ymd(2060, 12, 31),
+            repeat_on_day: MonthDay::new(31).unwrap(),
+        };
+
+        let dates = repetition.dates_between(
+            NaiveDate::from_ymd(2040, 2, 1),
+            NaiveDate::from_ymd(2040, 4, 30),
+        );
+        assert_eq!(
+            dates,
+            vec![
+                NaiveDate::from_ymd(2040, 2, 29),
+                NaiveDate::from_ymd(2040, 3, 31),
+                NaiveDate::from_ymd(2040, 4, 30)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_biweekly_keeps_its_weeks() {
+        let repetition = Repetition::Biweekly {
+            from: NaiveDate::from_ymd(2022, 1, 3),
+            to: NaiveDate::from_ymd(2023, 12, 31),
+            repeat_on_weekday: chrono::Weekday::Fri,
+        };
+
+        assert_matches_filtered(
+            repetition,
+            NaiveDate::from_ymd(2022, 1, 10),
+            NaiveDate::from_ymd(2022, 3, 1),
+        );
+        assert_matches_filtered(
+            repetition,
+            NaiveDate::from_ymd(2022, 1, 5),
+            NaiveDate::from_ymd(2022, 2, 1),
+        );
+        assert_matches_filtered(
+            repetition,
+            NaiveDate::from_ymd(2022, 1, 17),
+            NaiveDate::from_ymd(2022, 1, 31),
+        );
+        assert_matches_filtered(
+            repetition,
+            NaiveDate::from_ymd(2023, 6, 1),
+            NaiveDate::from_ymd(2024, 6, 1),
+        );
+    }
+
+    #[test]
+    fn test_weekly_and_daily() {
+        let from = NaiveDate::from_ymd(2022, 2, 9);
+        let to = NaiveDate::from_ymd(2022, 3, 2);
+
+        assert_matches_filtered(
+            Repetition::Weekly {
+                from: NaiveDate::from_ymd(2022, 1, 1),
+                to: NaiveDate::from_ymd(2022, 12, 31),
+                repeat_on_weekday: chrono::Weekday::Wed,
+            },
+            from,
+            to,
+        );
+        assert_matches_filtered(
+            Repetition::Daily {
+                from: NaiveDate::from_ymd(2022, 1, 1),
+                to: NaiveDate::from_ymd(2022, 2, 20),
+            },
+            from,
+            to,
+        );
+    }
+
+    #[test]
+    fn test_outside_window() {
+        let once = Repetition::Once {
+            on: NaiveDate::from_ymd(2022, 1, 27),
+        };
+
+        assert!(once
+            .dates_between(
+                NaiveDate::from_ymd(2022, 2, 1),
+                NaiveDate::from_ymd(2022, 3, 1)
+            )
+            .is_empty());
+        assert_eq!(
+            once.dates_between(
+                NaiveDate::from_ymd(2022, 1, 1),
+                NaiveDate::from_ymd(2022, 1, 27)
+            ),
+            vec![NaiveDate::from_ymd(2022, 1, 27)]
+        );
+    }
+}
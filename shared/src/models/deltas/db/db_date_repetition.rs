@@ -19,6 +19,7 @@ pub enum DbDateRepetition {
     Weekly = 2,
     Daily = 3,
     Once = 4,
+    Biweekly = 5,
 }
 
 impl TryFrom<i16> for DbDateRepetition {
@@ -30,6 +31,7 @@ impl TryFrom<i16> for DbDateRepetition {
             2 => Ok(DbDateRepetition::Weekly),
             3 => Ok(DbDateRepetition::Daily),
             4 => Ok(DbDateRepetition::Once),
+            5 => Ok(DbDateRepetition::Biweekly),
             _ => Err(MoolahSharedError::RepetitionError(
                 "unrecognized repetition variant".into(),
             )),
@@ -46,6 +48,7 @@ impl TryFrom<String> for DbDateRepetition {
             "weekly" => Ok(Self::Weekly),
             "daily" => Ok(Self::Daily),
             "once" => Ok(Self::Once),
+            "biweekly" => Ok(Self::Biweekly),
             _ => Err(MoolahSharedError::RepetitionError(
                 "unrecognized repetition variant".into(),
             )),
@@ -66,6 +69,11 @@ impl From<Repetition> for DbDateRepetition {
                 to: _,
                 repeat_on_weekday: _,
             } => Self::Weekly,
+            Repetition::Biweekly {
+                from: _,
+                to: _,
+                repeat_on_weekday: _,
+            } => Self::Biweekly,
             Repetition::Daily { from: _, to: _ } => Self::Daily,
             Repetition::Once { on: _ } => Self::Once,
         }
@@ -99,6 +107,7 @@ where
             2 => Ok(Self::Weekly),
             3 => Ok(Self::Daily),
             4 => Ok(Self::Once),
+            5 => Ok(Self::Biweekly),
             x => Err(format!("unrecognized variant {}", x).into()),
         }
     }
@@ -117,6 +126,7 @@ mod tests {
         assert_eq!(DbDateRepetition::Weekly, 2.try_into().unwrap());
         assert_eq!(DbDateRepetition::Daily, 3.try_into().unwrap());
         assert_eq!(DbDateRepetition::Once, 4.try_into().unwrap());
+        assert_eq!(DbDateRepetition::Biweekly, 5.try_into().unwrap());
     }
 
     #[test]
@@ -143,6 +153,16 @@ mod tests {
             .into()
         );
 
+        assert_eq!(
+            DbDateRepetition::Biweekly,
+            Repetition::Biweekly {
+                from: now,
+                to: now,
+                repeat_on_weekday: now.weekday()
+            }
+            .into()
+        );
+
         assert_eq!(
             DbDateRepetition::Daily,
             Repetition::Daily { from: now, to: now }.into()
@@ -2,7 +2,7 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 use super::db_date_repetition::DbDateRepetition;
-use crate::models::{NewDelta, Prediction, Repetition};
+use crate::models::{DeltaKind, NewDelta, Prediction, Repetition};
 use crate::schema::deltas;
 
 #[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Associations)]
@@ -20,6 +20,7 @@ pub struct DbDelta {
     pub(crate) end_on: Option<NaiveDate>,
     pub(crate) repeat_day: Option<i16>,
     pub(crate) repeat_weekday: Option<String>,
+    pub(crate) kind: DeltaKind,
 }
 
 impl DbDelta {
@@ -35,6 +36,7 @@ impl DbDelta {
         end_on: Option<NaiveDate>,
         repeat_day: Option<i16>,
         repeat_weekday: Option<String>,
+        kind: DeltaKind,
     ) -> Self {
         DbDelta {
             id,
@@ -48,12 +50,14 @@ impl DbDelta {
             end_on,
             repeat_day,
             repeat_weekday,
+            kind,
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Insertable)]
+#[derive(Debug, Deserialize, Serialize, Insertable, AsChangeset)]
 #[table_name = "deltas"]
+#[changeset_options(treat_none_as_null = "true")]
 pub struct NewDbDelta {
     pub(crate) prediction_id: i32,
     pub(crate) name: String,
@@ -65,6 +69,8 @@ pub struct NewDbDelta {
     pub(crate) end_on: Option<NaiveDate>,
     pub(crate) repeat_day: Option<i16>,
     pub(crate) repeat_weekday: Option<String>,
+    #[serde(default)]
+    pub(crate) kind: DeltaKind,
 }
 
 impl NewDbDelta {
@@ -107,6 +113,7 @@ impl From<NewDelta> for NewDbDelta {
                 end_on: Some(*to),
                 repeat_day: Some(repeat_on_day.into()),
                 repeat_weekday: None,
+                kind: new_delta.kind(),
             },
             Repetition::Weekly {
                 from,
@@ -123,6 +130,24 @@ impl From<NewDelta> for NewDbDelta {
                 end_on: Some(*to),
                 repeat_day: None,
                 repeat_weekday: Some(repeat_on_weekday.to_string()),
+                kind: new_delta.kind(),
+            },
+            Repetition::Biweekly {
+                from,
+                to,
+                repeat_on_weekday,
+            } => NewDbDelta {
+                prediction_id: new_delta.prediction_id(),
+                name: new_delta.name().to_string(),
+                value: new_delta.value(),
+                positive_uncertainty: new_delta.positive_uncertainty(),
+                negative_uncertainty: new_delta.negative_uncertainty(),
+                repetition: new_delta.repetition().into(),
+                start_on: *from,
+                end_on: Some(*to),
+                repeat_day: None,
+                repeat_weekday: Some(repeat_on_weekday.to_string()),
+                kind: new_delta.kind(),
             },
             Repetition::Daily { from, to } => NewDbDelta {
                 prediction_id: new_delta.prediction_id(),
@@ -135,6 +160,7 @@ impl From<NewDelta> for NewDbDelta {
                 end_on: Some(*to),
                 repeat_day: None,
                 repeat_weekday: None,
+                kind: new_delta.kind(),
             },
             Repetition::Once { on } => NewDbDelta {
                 prediction_id: new_delta.prediction_id(),
@@ -147,6 +173,7 @@ impl From<NewDelta> for NewDbDelta {
                 end_on: None,
                 repeat_day: None,
                 repeat_weekday: None,
+                kind: new_delta.kind(),
             },
         }
     }
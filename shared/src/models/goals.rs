@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::deltas::app::repetition::MonthDay;
+use super::{Delta, Prediction, Repetition};
+use crate::projection;
+use crate::schema::goals;
+use crate::MoolahSharedError;
+
+/// A balance a prediction should reach, optionally by a given date.
+#[derive(
+    Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, Clone, PartialEq,
+)]
+#[belongs_to(Prediction)]
+pub struct Goal {
+    id: i32,
+    prediction_id: i32,
+    name: String,
+    target_balance: f32,
+    target_date: Option<NaiveDate>,
+    starting_balance: f32,
+    start_on: NaiveDate,
+}
+
+impl Goal {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn prediction_id(&self) -> i32 {
+        self.prediction_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn target_balance(&self) -> f32 {
+        self.target_balance
+    }
+
+    pub fn target_date(&self) -> Option<NaiveDate> {
+        self.target_date
+    }
+
+    /// The balance at the start of `start_on`, before any of that day's deltas
+    pub fn starting_balance(&self) -> f32 {
+        self.starting_balance
+    }
+
+    pub fn start_on(&self) -> NaiveDate {
+        self.start_on
+    }
+
+    /// Project the prediction's deltas forward from the goal's starting
+    /// balance and work out when (and whether) the goal is met.
+    pub fn progress(&self, deltas: &[Delta]) -> GoalProgress {
+        let mut balance = self.starting_balance;
+        let mut reached_on = if balance >= self.target_balance {
+            Some(self.start_on)
+        } else {
+            None
+        };
+        let mut balance_on_target_date = balance;
+
+        for occurrence in projection::schedule(deltas, 0.)
+            .into_iter()
+            .filter(|occurrence| occurrence.date >= self.start_on)
+        {
+            balance += occurrence.value;
+
+            if reached_on.is_none() && balance >= self.target_balance {
+                reached_on = Some(occurrence.date);
+            }
+
+            if matches!(self.target_date, Some(date) if occurrence.date <= date) {
+                balance_on_target_date = balance;
+            }
+        }
+
+        let target_date = match self.target_date {
+            Some(date) => date,
+            None => {
+                return GoalProgress {
+                    goal_id: self.id,
+                    reached_on,
+                    balance_on_target_date: None,
+                    shortfall: None,
+                    extra_monthly_contribution: None,
+                }
+            }
+        };
+
+        let shortfall = (self.target_balance - balance_on_target_date).max(0.);
+
+        GoalProgress {
+            goal_id: self.id,
+            reached_on,
+            balance_on_target_date: Some(balance_on_target_date),
+            shortfall: Some(shortfall),
+            extra_monthly_contribution: self.extra_monthly_contribution(shortfall, target_date),
+        }
+    }
+
+    /// Split a shortfall over the monthly contributions that fit between the
+    /// start of the goal and its target date, each made on the same day of
+    /// the month as the start.
+    fn extra_monthly_contribution(&self, shortfall: f32, target_date: NaiveDate) -> Option<f32> {
+        if shortfall == 0. {
+            return Some(0.);
+        }
+
+        let contributions = Repetition::Monthly {
+            from: self.start_on + Duration::days(1),
+            to: target_date,
+            repeat_on_day: MonthDay::new(self.start_on.day() as i16).ok()?,
+        }
+        .dates()
+        .len();
+
+        if contributions == 0 {
+            None
+        } else {
+            Some(shortfall / contributions as f32)
+        }
+    }
+}
+
+#[derive(Debug, Insertable, Serialize, Deserialize, Clone, PartialEq)]
+#[table_name = "goals"]
+pub struct NewGoal {
+    prediction_id: i32,
+    name: String,
+    target_balance: f32,
+    target_date: Option<NaiveDate>,
+    starting_balance: f32,
+    start_on: NaiveDate,
+}
+
+impl NewGoal {
+    pub fn new(
+        prediction_id: i32,
+        name: String,
+        target_balance: f32,
+        target_date: Option<NaiveDate>,
+        starting_balance: f32,
+        start_on: NaiveDate,
+    ) -> Result<Self, MoolahSharedError> {
+        let goal = NewGoal {
+            prediction_id,
+            name: name.trim().to_string(),
+            target_balance,
+            target_date,
+            starting_balance,
+            start_on,
+        };
+        goal.validate()?;
+        Ok(goal)
+    }
+
+    /// Check the goal can be stored. Goals arriving from a client should be
+    /// validated again, since deserializing skips the checks in `new`.
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        if self.name.trim().is_empty() {
+            return Err(MoolahSharedError::GoalError("goal needs a name".into()));
+        }
+
+        if let Some(target_date) = self.target_date {
+            if target_date <= self.start_on {
+                return Err(MoolahSharedError::GoalError(format!(
+                    "target date ({}) must be after the start date ({})",
+                    target_date, self.start_on
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn prediction_id(&self) -> i32 {
+        self.prediction_id
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GoalQuery {
+    pub prediction_id: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoalProgress {
+    pub goal_id: i32,
+    /// The first date the projected balance meets the target, if it does
+    /// within the prediction
+    pub reached_on: Option<NaiveDate>,
+    pub balance_on_target_date: Option<f32>,
+    /// How far short of the target the balance is on the target date
+    pub shortfall: Option<f32>,
+    /// What would need to be added each month to close the shortfall
+    pub extra_monthly_contribution: Option<f32>,
+}
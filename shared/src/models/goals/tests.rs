@@ -0,0 +1,113 @@
+use super::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn deltas() -> Vec<Delta> {
+    vec![Delta::new(
+        1,
+        1,
+        "savings".into(),
+        500.,
+        0.,
+        0.,
+        Repetition::Monthly {
+            from: date(2025, 6, 1),
+            to: date(2026, 12, 31),
+            repeat_on_day: MonthDay::new(1).unwrap(),
+        },
+    )]
+}
+
+fn goal(target_balance: f32, target_date: Option<NaiveDate>) -> Goal {
+    Goal {
+        id: 7,
+        prediction_id: 1,
+        name: "emergency fund".into(),
+        target_balance,
+        target_date,
+        starting_balance: 1000.,
+        start_on: date(2026, 1, 1),
+    }
+}
+
+#[test]
+fn test_goal_met_by_target_date() {
+    assert_eq!(
+        goal(3000., Some(date(2026, 4, 15))).progress(&deltas()),
+        GoalProgress {
+            goal_id: 7,
+            reached_on: Some(date(2026, 4, 1)),
+            balance_on_target_date: Some(3000.),
+            shortfall: Some(0.),
+            extra_monthly_contribution: Some(0.),
+        }
+    );
+}
+
+#[test]
+fn test_goal_short_at_target_date() {
+    let progress = goal(5000., Some(date(2026, 4, 15))).progress(&deltas());
+
+    assert_eq!(progress.reached_on, Some(date(2026, 8, 1)));
+    assert_eq!(progress.balance_on_target_date, Some(3000.));
+    assert_eq!(progress.shortfall, Some(2000.));
+    // contributions on 2/1, 3/1 and 4/1
+    assert!((progress.extra_monthly_contribution.unwrap() - 666.6667).abs() < 0.001);
+}
+
+#[test]
+fn test_goal_without_target_date() {
+    assert_eq!(
+        goal(4000., None).progress(&deltas()),
+        GoalProgress {
+            goal_id: 7,
+            reached_on: Some(date(2026, 6, 1)),
+            balance_on_target_date: None,
+            shortfall: None,
+            extra_monthly_contribution: None,
+        }
+    );
+}
+
+#[test]
+fn test_goal_never_reached() {
+    let progress = goal(100000., Some(date(2026, 12, 31))).progress(&deltas());
+
+    assert_eq!(progress.reached_on, None);
+    assert_eq!(progress.balance_on_target_date, Some(7000.));
+    assert_eq!(progress.shortfall, Some(93000.));
+    // contributions on the first of february through december
+    assert!((progress.extra_monthly_contribution.unwrap() - 93000. / 11.).abs() < 0.01);
+}
+
+#[test]
+fn test_goal_already_reached() {
+    let progress = goal(500., None).progress(&deltas());
+
+    assert_eq!(progress.reached_on, Some(date(2026, 1, 1)));
+}
+
+#[test]
+fn test_target_date_too_soon_for_contributions() {
+    let mut goal = goal(5000., Some(date(2026, 1, 20)));
+    goal.start_on = date(2026, 1, 2);
+
+    assert_eq!(goal.progress(&deltas()).extra_monthly_contribution, None);
+}
+
+#[test]
+fn test_new_goal_validation() {
+    assert!(NewGoal::new(1, "car".into(), 1000., None, 0., date(2026, 1, 1)).is_ok());
+    assert!(NewGoal::new(1, " ".into(), 1000., None, 0., date(2026, 1, 1)).is_err());
+    assert!(NewGoal::new(
+        1,
+        "car".into(),
+        1000.,
+        Some(date(2026, 1, 1)),
+        0.,
+        date(2026, 1, 1)
+    )
+    .is_err());
+}
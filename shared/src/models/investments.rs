@@ -0,0 +1,237 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::{Delta, Prediction};
+use crate::projection::growth::{self, GrowthAssumptions, GrowthPoint};
+use crate::schema::{investment_accounts, investment_contributions};
+use crate::MoolahSharedError;
+
+/// An account whose balance grows with the market, fed by some of a
+/// prediction's deltas.
+#[derive(
+    Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, Clone, PartialEq,
+)]
+#[belongs_to(Prediction)]
+pub struct InvestmentAccount {
+    pub(crate) id: i32,
+    pub(crate) prediction_id: i32,
+    pub(crate) name: String,
+    pub(crate) starting_balance: f32,
+    pub(crate) start_on: NaiveDate,
+    pub(crate) expected_return: f32,
+    pub(crate) volatility: f32,
+}
+
+impl InvestmentAccount {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn prediction_id(&self) -> i32 {
+        self.prediction_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn starting_balance(&self) -> f32 {
+        self.starting_balance
+    }
+
+    pub fn start_on(&self) -> NaiveDate {
+        self.start_on
+    }
+
+    /// Average annual return as a percentage
+    pub fn expected_return(&self) -> f32 {
+        self.expected_return
+    }
+
+    /// Standard deviation of annual returns as a percentage
+    pub fn volatility(&self) -> f32 {
+        self.volatility
+    }
+
+    pub fn assumptions(&self) -> GrowthAssumptions {
+        GrowthAssumptions {
+            expected_return: self.expected_return,
+            volatility: self.volatility,
+        }
+    }
+}
+
+/// Links a delta to the investment account it pays into.
+#[derive(
+    Debug,
+    Queryable,
+    Insertable,
+    Identifiable,
+    Associations,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+)]
+#[belongs_to(InvestmentAccount)]
+#[table_name = "investment_contributions"]
+#[primary_key(investment_account_id, delta_id)]
+pub struct InvestmentContribution {
+    pub investment_account_id: i32,
+    pub delta_id: i32,
+}
+
+/// An investment account along with the deltas that pay into it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvestmentAccountWithContributions {
+    pub account: InvestmentAccount,
+    pub contribution_delta_ids: Vec<i32>,
+}
+
+impl InvestmentAccountWithContributions {
+    /// Project the account's growth from its start until `until`, paying in
+    /// the prediction's contributing deltas as they happen.
+    pub fn projection(
+        &self,
+        deltas: &[Delta],
+        until: NaiveDate,
+        runs: u32,
+    ) -> InvestmentProjection {
+        let contributions = deltas
+            .iter()
+            .filter(|delta| self.contribution_delta_ids.contains(&delta.id()))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        InvestmentProjection {
+            investment_account_id: self.account.id,
+            points: growth::project(
+                self.account.starting_balance,
+                self.account.start_on,
+                until,
+                self.account.assumptions(),
+                &contributions,
+                runs,
+                self.account.id as u64,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Insertable, Serialize, Deserialize, Clone, PartialEq)]
+#[table_name = "investment_accounts"]
+pub struct NewInvestmentAccount {
+    prediction_id: i32,
+    name: String,
+    starting_balance: f32,
+    start_on: NaiveDate,
+    expected_return: f32,
+    volatility: f32,
+}
+
+impl NewInvestmentAccount {
+    pub fn new(
+        prediction_id: i32,
+        name: String,
+        starting_balance: f32,
+        start_on: NaiveDate,
+        expected_return: f32,
+        volatility: f32,
+    ) -> Result<Self, MoolahSharedError> {
+        let account = NewInvestmentAccount {
+            prediction_id,
+            name: name.trim().to_string(),
+            starting_balance,
+            start_on,
+            expected_return,
+            volatility,
+        };
+        account.validate()?;
+        Ok(account)
+    }
+
+    /// Check the account can be stored. Accounts arriving from a client should
+    /// be validated again, since deserializing skips the checks in `new`.
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        if self.name.trim().is_empty() {
+            return Err(MoolahSharedError::InvestmentError(
+                "investment account needs a name".into(),
+            ));
+        }
+        if !self.starting_balance.is_finite() || self.starting_balance < 0. {
+            return Err(MoolahSharedError::InvestmentError(
+                "starting balance cannot be negative".into(),
+            ));
+        }
+        if !self.expected_return.is_finite() || self.expected_return <= -100. {
+            return Err(MoolahSharedError::InvestmentError(
+                "expected return must be greater than -100%".into(),
+            ));
+        }
+        if !self.volatility.is_finite() || self.volatility < 0. {
+            return Err(MoolahSharedError::InvestmentError(
+                "volatility cannot be negative".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn prediction_id(&self) -> i32 {
+        self.prediction_id
+    }
+}
+
+/// A new account along with the deltas that pay into it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewInvestmentAccountRequest {
+    pub account: NewInvestmentAccount,
+    #[serde(default)]
+    pub contribution_delta_ids: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InvestmentQuery {
+    pub prediction_id: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InvestmentProjectionQuery {
+    pub prediction_id: i32,
+    pub until: NaiveDate,
+    #[serde(default = "default_runs")]
+    pub runs: u32,
+}
+
+impl InvestmentProjectionQuery {
+    /// Check an account starting on `start_on` can be projected as asked,
+    /// keeping the work a single request can ask for bounded.
+    pub fn validate(&self, start_on: NaiveDate) -> Result<(), MoolahSharedError> {
+        if self.runs > growth::MAX_RUNS {
+            return Err(MoolahSharedError::InvestmentError(format!(
+                "projections can use at most {} runs",
+                growth::MAX_RUNS
+            )));
+        }
+        if self.until.year() - start_on.year() > growth::MAX_YEARS {
+            return Err(MoolahSharedError::InvestmentError(format!(
+                "projections can run at most {} years",
+                growth::MAX_YEARS
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn default_runs() -> u32 {
+    growth::DEFAULT_RUNS
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvestmentProjection {
+    pub investment_account_id: i32,
+    pub points: Vec<GrowthPoint>,
+}
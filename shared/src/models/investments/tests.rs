@@ -0,0 +1,91 @@
+use super::*;
+use crate::models::deltas::app::repetition::MonthDay;
+use crate::models::Repetition;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn delta(id: i32, value: f32) -> Delta {
+    Delta::new(
+        id,
+        1,
+        format!("delta {}", id),
+        value,
+        0.,
+        0.,
+        Repetition::Monthly {
+            from: date(2026, 1, 15),
+            to: date(2026, 12, 31),
+            repeat_on_day: MonthDay::new(15).unwrap(),
+        },
+    )
+}
+
+fn account() -> InvestmentAccountWithContributions {
+    InvestmentAccountWithContributions {
+        account: InvestmentAccount {
+            id: 3,
+            prediction_id: 1,
+            name: "brokerage".into(),
+            starting_balance: 500.,
+            start_on: date(2026, 1, 1),
+            expected_return: 0.,
+            volatility: 0.,
+        },
+        contribution_delta_ids: vec![2],
+    }
+}
+
+#[test]
+fn test_new_investment_account_validates() {
+    let new = |name: &str, balance, expected_return, volatility| {
+        NewInvestmentAccount::new(
+            1,
+            name.into(),
+            balance,
+            date(2026, 1, 1),
+            expected_return,
+            volatility,
+        )
+    };
+
+    assert!(new("brokerage", 100., 7., 15.).is_ok());
+    assert!(new("  ", 100., 7., 15.).is_err());
+    assert!(new("brokerage", -1., 7., 15.).is_err());
+    assert!(new("brokerage", 100., -100., 15.).is_err());
+    assert!(new("brokerage", 100., 7., -1.).is_err());
+    assert!(new("brokerage", 100., f32::NAN, 15.).is_err());
+}
+
+#[test]
+fn test_projection_only_uses_contributing_deltas() {
+    let deltas = vec![delta(1, 2000.), delta(2, -100.)];
+    let projection = account().projection(&deltas, date(2027, 1, 1), 10);
+    let last = projection.points.last().unwrap();
+
+    assert_eq!(projection.investment_account_id, 3);
+    assert_eq!(last.contributed, 1200.);
+    assert_eq!(last.expected, 1700.);
+}
+
+#[test]
+fn test_projection_query_validates() {
+    let query = |until, runs| InvestmentProjectionQuery {
+        prediction_id: 1,
+        until,
+        runs,
+    };
+    let start_on = date(2026, 1, 1);
+
+    assert!(query(date(2126, 12, 31), growth::MAX_RUNS)
+        .validate(start_on)
+        .is_ok());
+    assert!(query(date(2027, 1, 1), growth::MAX_RUNS + 1)
+        .validate(start_on)
+        .is_err());
+    assert!(query(date(2127, 1, 1), 10).validate(start_on).is_err());
+    assert!(query(date(2027, 1, 1), u32::MAX)
+        .validate(start_on)
+        .is_err());
+}
@@ -0,0 +1,232 @@
+#[cfg(test)]
+mod tests;
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::imports::ParsedTransaction;
+use crate::schema::categorization_rules;
+use crate::MoolahSharedError;
+
+/// A user-defined rule assigning a category (and optionally a delta) to every
+/// transaction matching all of its criteria.
+#[derive(Debug, Queryable, Identifiable, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CategorizationRule {
+    id: i32,
+    username: String,
+    category: String,
+    description_pattern: Option<String>,
+    min_amount: Option<f32>,
+    max_amount: Option<f32>,
+    account: Option<String>,
+    delta_id: Option<i32>,
+}
+
+impl CategorizationRule {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn description_pattern(&self) -> Option<&str> {
+        self.description_pattern.as_deref()
+    }
+
+    pub fn min_amount(&self) -> Option<f32> {
+        self.min_amount
+    }
+
+    pub fn max_amount(&self) -> Option<f32> {
+        self.max_amount
+    }
+
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    pub fn delta_id(&self) -> Option<i32> {
+        self.delta_id
+    }
+}
+
+#[derive(Debug, Insertable, Serialize, Deserialize, Clone, PartialEq)]
+#[table_name = "categorization_rules"]
+pub struct NewCategorizationRule {
+    username: String,
+    category: String,
+    description_pattern: Option<String>,
+    min_amount: Option<f32>,
+    max_amount: Option<f32>,
+    account: Option<String>,
+    delta_id: Option<i32>,
+}
+
+impl NewCategorizationRule {
+    pub fn new(
+        username: String,
+        category: String,
+        description_pattern: Option<String>,
+        min_amount: Option<f32>,
+        max_amount: Option<f32>,
+        account: Option<String>,
+        delta_id: Option<i32>,
+    ) -> Result<Self, MoolahSharedError> {
+        let rule = NewCategorizationRule {
+            username: username.to_lowercase(),
+            category: category.trim().to_string(),
+            description_pattern,
+            min_amount,
+            max_amount,
+            account,
+            delta_id,
+        };
+        rule.validate()?;
+        Ok(rule)
+    }
+
+    /// Check the rule can be used. Rules arriving from a client should be
+    /// validated again, since deserializing skips the checks in `new`.
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        if self.category.trim().is_empty() {
+            return Err(MoolahSharedError::RuleError("rule needs a category".into()));
+        }
+
+        if self.description_pattern.is_none()
+            && self.min_amount.is_none()
+            && self.max_amount.is_none()
+            && self.account.is_none()
+        {
+            return Err(MoolahSharedError::RuleError(
+                "rule needs at least one of a description pattern, amount range or account".into(),
+            ));
+        }
+
+        if let (Some(min), Some(max)) = (self.min_amount, self.max_amount) {
+            if min > max {
+                return Err(MoolahSharedError::RuleError(format!(
+                    "minimum amount ({}) is greater than maximum amount ({})",
+                    min, max
+                )));
+            }
+        }
+
+        if let Some(pattern) = &self.description_pattern {
+            compile(pattern)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn delta_id(&self) -> Option<i32> {
+        self.delta_id
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex, MoolahSharedError> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|err| {
+            MoolahSharedError::RuleError(format!(
+                "invalid description pattern '{}': {}",
+                pattern, err
+            ))
+        })
+}
+
+/// What a rule assigns to a matching transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Categorization {
+    pub category: String,
+    pub delta_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategorizationSummary {
+    pub categorized: usize,
+    pub uncategorized: usize,
+}
+
+fn matches(
+    rule: &CategorizationRule,
+    pattern: Option<&Regex>,
+    transaction: &ParsedTransaction,
+) -> bool {
+    if let Some(pattern) = pattern {
+        if !pattern.is_match(&transaction.description) {
+            return false;
+        }
+    }
+
+    if let Some(min) = rule.min_amount {
+        if transaction.amount < min {
+            return false;
+        }
+    }
+
+    if let Some(max) = rule.max_amount {
+        if transaction.amount > max {
+            return false;
+        }
+    }
+
+    if let Some(account) = &rule.account {
+        match &transaction.account {
+            Some(other) if account.eq_ignore_ascii_case(other) => {}
+            _ => return false,
+        }
+    }
+
+    true
+}
+
+/// A user's rules, ready to be matched against transactions.
+///
+/// Rules are tried in the order given and the first match wins, so more
+/// specific rules should come first.
+pub struct RuleSet {
+    rules: Vec<(CategorizationRule, Option<Regex>)>,
+}
+
+impl RuleSet {
+    pub fn new(rules: Vec<CategorizationRule>) -> Result<Self, MoolahSharedError> {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let pattern = rule
+                    .description_pattern
+                    .as_deref()
+                    .map(compile)
+                    .transpose()?;
+                Ok((rule, pattern))
+            })
+            .collect::<Result<Vec<_>, MoolahSharedError>>()?;
+
+        Ok(RuleSet { rules })
+    }
+
+    pub fn categorize(&self, transaction: &ParsedTransaction) -> Option<Categorization> {
+        self.rules
+            .iter()
+            .find(|(rule, pattern)| matches(rule, pattern.as_ref(), transaction))
+            .map(|(rule, _)| Categorization {
+                category: rule.category.clone(),
+                delta_id: rule.delta_id,
+            })
+    }
+}
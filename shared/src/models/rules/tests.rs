@@ -0,0 +1,141 @@
+use chrono::NaiveDate;
+
+use super::*;
+
+fn rule(
+    id: i32,
+    category: &str,
+    description_pattern: Option<&str>,
+    amounts: (Option<f32>, Option<f32>),
+    account: Option<&str>,
+) -> CategorizationRule {
+    CategorizationRule {
+        id,
+        username: "user".into(),
+        category: category.into(),
+        description_pattern: description_pattern.map(String::from),
+        min_amount: amounts.0,
+        max_amount: amounts.1,
+        account: account.map(String::from),
+        delta_id: Some(id * 10),
+    }
+}
+
+fn transaction(amount: f32, description: &str, account: Option<&str>) -> ParsedTransaction {
+    ParsedTransaction {
+        posted_on: NaiveDate::from_ymd(2022, 1, 1),
+        amount,
+        description: description.into(),
+        fitid: None,
+        account: account.map(String::from),
+    }
+}
+
+fn category(rules: &RuleSet, transaction: &ParsedTransaction) -> Option<String> {
+    rules
+        .categorize(transaction)
+        .map(|categorization| categorization.category)
+}
+
+#[test]
+fn test_description_pattern_is_case_insensitive() {
+    let rules = RuleSet::new(vec![rule(
+        1,
+        "groceries",
+        Some(r"^(safeway|kroger)\b"),
+        (None, None),
+        None,
+    )])
+    .unwrap();
+
+    assert_eq!(
+        rules.categorize(&transaction(-45., "SAFEWAY #1234", None)),
+        Some(Categorization {
+            category: "groceries".into(),
+            delta_id: Some(10),
+        })
+    );
+    assert_eq!(
+        category(&rules, &transaction(-45., "Kroger", None)),
+        Some("groceries".into())
+    );
+    assert_eq!(
+        category(&rules, &transaction(-45., "NOT SAFEWAY", None)),
+        None
+    );
+}
+
+#[test]
+fn test_amount_range_and_account() {
+    let rules = RuleSet::new(vec![
+        rule(1, "big purchases", None, (None, Some(-500.)), Some("Visa")),
+        rule(2, "income", None, (Some(0.), None), None),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        category(&rules, &transaction(-750., "TV", Some("visa"))),
+        Some("big purchases".into())
+    );
+    assert_eq!(
+        category(&rules, &transaction(-750., "TV", Some("checking"))),
+        None
+    );
+    assert_eq!(category(&rules, &transaction(-750., "TV", None)), None);
+    assert_eq!(
+        category(&rules, &transaction(-499., "TV", Some("visa"))),
+        None
+    );
+    assert_eq!(
+        category(&rules, &transaction(1200., "PAYROLL", None)),
+        Some("income".into())
+    );
+}
+
+#[test]
+fn test_first_matching_rule_wins() {
+    let rules = RuleSet::new(vec![
+        rule(1, "coffee", Some("coffee"), (None, None), None),
+        rule(
+            2,
+            "eating out",
+            Some("coffee|restaurant"),
+            (None, None),
+            None,
+        ),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        category(&rules, &transaction(-4., "COFFEE SHOP", None)),
+        Some("coffee".into())
+    );
+    assert_eq!(
+        category(&rules, &transaction(-40., "RESTAURANT", None)),
+        Some("eating out".into())
+    );
+}
+
+#[test]
+fn test_new_rule_validation() {
+    let new_rule = |category: &str, pattern: Option<&str>, min: Option<f32>, max: Option<f32>| {
+        NewCategorizationRule::new(
+            "User".into(),
+            category.into(),
+            pattern.map(String::from),
+            min,
+            max,
+            None,
+            None,
+        )
+    };
+
+    let valid = new_rule("rent", Some("landlord"), None, None).unwrap();
+    assert_eq!(valid.username(), "user");
+
+    assert!(new_rule(" ", Some("landlord"), None, None).is_err());
+    assert!(new_rule("rent", None, None, None).is_err());
+    assert!(new_rule("rent", Some("(unclosed"), None, None).is_err());
+    assert!(new_rule("rent", None, Some(10.), Some(-10.)).is_err());
+    assert!(new_rule("rent", None, Some(-10.), Some(10.)).is_ok());
+}
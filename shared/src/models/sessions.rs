@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use super::user_tokens::{hash_token, random_token};
+use crate::schema::sessions;
+
+/// Length of the random token the auth cookie carries.
+pub const TOKEN_LENGTH: usize = 48;
+
+/// A signed-in browser. The token is what the auth cookie carries; only a hash
+/// of it is stored, so reading the table does not give away live sessions. Use
+/// [`SessionInfo`] to show a session to its user.
+#[derive(Debug, Queryable, Identifiable, Clone, PartialEq)]
+pub struct Session {
+    pub(crate) id: i32,
+    pub(crate) token_hash: String,
+    pub(crate) username: String,
+    pub(crate) created: NaiveDateTime,
+    pub(crate) last_seen: NaiveDateTime,
+    pub(crate) user_agent: Option<String>,
+}
+
+impl Session {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn created(&self) -> NaiveDateTime {
+        self.created
+    }
+
+    pub fn last_seen(&self) -> NaiveDateTime {
+        self.last_seen
+    }
+
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
+
+    /// Whether the session has sat unused for longer than the idle timeout or
+    /// was started longer ago than the absolute timeout.
+    pub fn is_expired(&self, now: NaiveDateTime, expiry: &SessionExpiry) -> bool {
+        now - self.last_seen > expiry.idle || now - self.created > expiry.absolute
+    }
+
+    /// What the user gets to see about the session. `current` marks the
+    /// session making the request.
+    pub fn info(&self, current: bool) -> SessionInfo {
+        SessionInfo {
+            id: self.id,
+            created: self.created,
+            last_seen: self.last_seen,
+            user_agent: self.user_agent.clone(),
+            current,
+        }
+    }
+}
+
+/// How long a session lasts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SessionExpiry {
+    /// Longest a session may go unused
+    pub idle: Duration,
+    /// Longest a session may last however often it is used
+    pub absolute: Duration,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "sessions"]
+pub struct NewSession {
+    token_hash: String,
+    username: String,
+    created: NaiveDateTime,
+    last_seen: NaiveDateTime,
+    user_agent: Option<String>,
+}
+
+impl NewSession {
+    /// Start a session for `username` with a fresh random token, along with
+    /// the token itself for the auth cookie. The token cannot be recovered
+    /// afterwards.
+    pub fn new(username: String, user_agent: Option<String>, now: NaiveDateTime) -> (Self, String) {
+        let token = random_token(TOKEN_LENGTH);
+
+        let session = NewSession {
+            token_hash: hash_token(&token),
+            username,
+            created: now,
+            last_seen: now,
+            user_agent,
+        };
+
+        (session, token)
+    }
+
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+}
+
+/// A session as listed on the account page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub id: i32,
+    pub created: NaiveDateTime,
+    pub last_seen: NaiveDateTime,
+    pub user_agent: Option<String>,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
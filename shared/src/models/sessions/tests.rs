@@ -0,0 +1,79 @@
+use chrono::NaiveDate;
+
+use super::*;
+
+fn at(day: u32, hour: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd(2022, 3, day).and_hms(hour, 0, 0)
+}
+
+fn session(created: NaiveDateTime, last_seen: NaiveDateTime) -> Session {
+    Session {
+        id: 1,
+        token_hash: hash_token("token"),
+        username: "alice".into(),
+        created,
+        last_seen,
+        user_agent: Some("Firefox".into()),
+    }
+}
+
+fn expiry() -> SessionExpiry {
+    SessionExpiry {
+        idle: Duration::hours(12),
+        absolute: Duration::days(7),
+    }
+}
+
+mod is_expired {
+    use super::*;
+
+    #[test]
+    fn test_fresh() {
+        let session = session(at(1, 0), at(1, 6));
+
+        assert!(!session.is_expired(at(1, 12), &expiry()));
+    }
+
+    #[test]
+    fn test_idle() {
+        let session = session(at(1, 0), at(1, 6));
+
+        assert!(!session.is_expired(at(1, 18), &expiry()));
+        assert!(session.is_expired(at(1, 19), &expiry()));
+    }
+
+    #[test]
+    fn test_absolute() {
+        let session = session(at(1, 0), at(8, 0));
+
+        assert!(!session.is_expired(at(8, 0), &expiry()));
+        assert!(session.is_expired(at(8, 1), &expiry()));
+    }
+}
+
+#[test]
+fn test_info_leaves_out_token() {
+    let info = session(at(1, 0), at(1, 6)).info(true);
+
+    assert_eq!(
+        SessionInfo {
+            id: 1,
+            created: at(1, 0),
+            last_seen: at(1, 6),
+            user_agent: Some("Firefox".into()),
+            current: true,
+        },
+        info
+    );
+}
+
+#[test]
+fn test_new_session_tokens() {
+    let (first, first_token) = NewSession::new("alice".into(), None, at(1, 0));
+    let (_, second_token) = NewSession::new("alice".into(), None, at(1, 0));
+
+    assert_eq!(TOKEN_LENGTH, first_token.len());
+    assert!(first_token.chars().all(|c| c.is_ascii_alphanumeric()));
+    assert_ne!(first_token, second_token);
+    assert_eq!(hash_token(&first_token), first.token_hash());
+}
@@ -0,0 +1,264 @@
+#[cfg(test)]
+mod tests;
+
+use base32::Alphabet;
+use chrono::NaiveDateTime;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+
+use super::user_tokens::{hash_token, random_token};
+use crate::schema::{recovery_codes, user_totp};
+
+/// Seconds each code is valid for.
+pub const TOTP_PERIOD: i64 = 30;
+
+/// Digits in each code.
+pub const TOTP_DIGITS: u32 = 6;
+
+/// Steps either side of the current one still accepted, to allow for clock
+/// drift and slow typing.
+pub const TOTP_SKEW: i64 = 1;
+
+/// Bytes of secret shared with the authenticator, as RFC 4226 recommends.
+const SECRET_LENGTH: usize = 20;
+
+/// Recovery codes issued when two factor authentication is enabled.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Characters in each half of a recovery code.
+const RECOVERY_CODE_HALF_LENGTH: usize = 5;
+
+/// Name authenticator apps list accounts under.
+const ISSUER: &str = "moolah";
+
+const SECRET_ALPHABET: Alphabet = Alphabet::RFC4648 { padding: false };
+
+/// HOTP (RFC 4226): the `digits` digit code for `counter` under `key`.
+pub fn hotp(key: &[u8], counter: u64, digits: u32) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    binary % 10u32.pow(digits)
+}
+
+/// The time step (RFC 6238) `time` falls in.
+pub fn time_step(time: NaiveDateTime) -> i64 {
+    time.timestamp().div_euclid(TOTP_PERIOD)
+}
+
+/// A user's TOTP secret. Two factor authentication is only on once a code
+/// from the authenticator has confirmed it, setting `enabled_at`.
+#[derive(Debug, Queryable, Identifiable, Clone, PartialEq)]
+#[table_name = "user_totp"]
+#[primary_key(username)]
+pub struct UserTotp {
+    pub(crate) username: String,
+    pub(crate) secret: String,
+    pub(crate) created: NaiveDateTime,
+    pub(crate) enabled_at: Option<NaiveDateTime>,
+    pub(crate) last_step: Option<i64>,
+}
+
+impl UserTotp {
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+
+    /// The step `code` was generated for, if it is right for a step near
+    /// `now` that has not been used yet. Marking the step used stops a code
+    /// being replayed.
+    pub fn verify(&self, code: &str, now: NaiveDateTime) -> Option<i64> {
+        let code = code.trim().replace(' ', "");
+        if code.len() != TOTP_DIGITS as usize {
+            return None;
+        }
+        let code = code.parse::<u32>().ok()?;
+        let key = base32::decode(SECRET_ALPHABET, &self.secret)?;
+
+        let current = time_step(now);
+        (current - TOTP_SKEW..=current + TOTP_SKEW)
+            .filter(|step| self.last_step.map(|last| *step > last).unwrap_or(true))
+            .find(|step| *step >= 0 && hotp(&key, *step as u64, TOTP_DIGITS) == code)
+    }
+
+    /// The `otpauth://` URI authenticator apps enroll from, usually as a QR
+    /// code. Usernames are letters and digits, so need no escaping.
+    pub fn provisioning_uri(&self) -> String {
+        format!(
+            "otpauth://totp/{issuer}:{username}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={digits}&period={period}",
+            issuer = ISSUER,
+            username = self.username,
+            secret = self.secret,
+            digits = TOTP_DIGITS,
+            period = TOTP_PERIOD,
+        )
+    }
+
+    pub fn enrollment(&self) -> TotpEnrollment {
+        TotpEnrollment {
+            secret: self.secret.clone(),
+            provisioning_uri: self.provisioning_uri(),
+            qr_code: None,
+        }
+    }
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[table_name = "user_totp"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct NewUserTotp {
+    username: String,
+    secret: String,
+    created: NaiveDateTime,
+    enabled_at: Option<NaiveDateTime>,
+    last_step: Option<i64>,
+}
+
+impl NewUserTotp {
+    /// A fresh random secret for `username`, not enabled until confirmed.
+    pub fn new(username: String, now: NaiveDateTime) -> Self {
+        let mut secret = [0u8; SECRET_LENGTH];
+        rand::thread_rng().fill_bytes(&mut secret);
+
+        NewUserTotp {
+            username,
+            secret: base32::encode(SECRET_ALPHABET, &secret),
+            created: now,
+            enabled_at: None,
+            last_step: None,
+        }
+    }
+}
+
+/// A single-use code to log in with in place of an authenticator code. Only a
+/// hash is stored.
+#[derive(Debug, Queryable, Identifiable, Clone, PartialEq)]
+pub struct RecoveryCode {
+    pub(crate) id: i32,
+    pub(crate) username: String,
+    pub(crate) code_hash: String,
+    pub(crate) used: Option<NaiveDateTime>,
+}
+
+impl RecoveryCode {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn used(&self) -> Option<NaiveDateTime> {
+        self.used
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "recovery_codes"]
+pub struct NewRecoveryCode {
+    username: String,
+    code_hash: String,
+}
+
+impl NewRecoveryCode {
+    /// A new set of recovery codes for `username`, along with the codes
+    /// themselves to show the user once.
+    pub fn generate(username: &str) -> (Vec<Self>, Vec<String>) {
+        (0..RECOVERY_CODE_COUNT)
+            .map(|_| {
+                let code = format!(
+                    "{}-{}",
+                    random_token(RECOVERY_CODE_HALF_LENGTH),
+                    random_token(RECOVERY_CODE_HALF_LENGTH)
+                )
+                .to_lowercase();
+
+                let new_code = NewRecoveryCode {
+                    username: username.to_string(),
+                    code_hash: hash_recovery_code(&code),
+                };
+
+                (new_code, code)
+            })
+            .unzip()
+    }
+}
+
+/// The hash a recovery code is stored and looked up by. Codes are compared
+/// without regard to case or surrounding whitespace.
+pub fn hash_recovery_code(code: &str) -> String {
+    hash_token(&code.trim().to_lowercase())
+}
+
+/// What the account page needs to enroll an authenticator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub provisioning_uri: String,
+    /// The provisioning URI as an SVG QR code `data:` URI.
+    pub qr_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TotpStatus {
+    pub enabled: bool,
+    pub recovery_codes_left: i64,
+}
+
+/// A code from the authenticator, or a recovery code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpCodeForm {
+    code: String,
+}
+
+impl TotpCodeForm {
+    pub fn new(code: String) -> Self {
+        TotpCodeForm { code }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+}
+
+/// The second step of logging in to an account with two factor
+/// authentication: the token from the first step and a code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwoFactorLoginForm {
+    token: String,
+    code: String,
+}
+
+impl TwoFactorLoginForm {
+    pub fn new(token: String, code: String) -> Self {
+        TwoFactorLoginForm { token, code }
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+}
+
+/// Whether a correct username and password logged the user in, or they still
+/// need to give a code along with `token`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LoginResponse {
+    LoggedIn,
+    TwoFactorRequired { token: String },
+}
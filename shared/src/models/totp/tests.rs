@@ -0,0 +1,127 @@
+use chrono::NaiveDate;
+
+use super::*;
+
+/// The SHA-1 secret from the RFC 6238 test vectors.
+const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+fn at_timestamp(timestamp: i64) -> NaiveDateTime {
+    NaiveDateTime::from_timestamp(timestamp, 0)
+}
+
+fn totp(last_step: Option<i64>) -> UserTotp {
+    UserTotp {
+        username: "alice".into(),
+        secret: base32::encode(SECRET_ALPHABET, RFC_SECRET),
+        created: NaiveDate::from_ymd(2022, 1, 1).and_hms(0, 0, 0),
+        enabled_at: None,
+        last_step,
+    }
+}
+
+mod hotp {
+    use super::*;
+
+    #[test]
+    fn test_rfc_4226_vectors() {
+        let expected = [755224, 287082, 359152, 969429, 338314];
+
+        for (counter, code) in expected.iter().enumerate() {
+            assert_eq!(*code, hotp(RFC_SECRET, counter as u64, 6));
+        }
+    }
+
+    #[test]
+    fn test_rfc_6238_vectors() {
+        let expected = [
+            (59, 94287082),
+            (1111111109, 7081804),
+            (1111111111, 14050471),
+            (1234567890, 89005924),
+            (2000000000, 69279037),
+            (20000000000, 65353130),
+        ];
+
+        for (timestamp, code) in expected {
+            let step = time_step(at_timestamp(timestamp));
+            assert_eq!(code, hotp(RFC_SECRET, step as u64, 8));
+        }
+    }
+}
+
+mod verify {
+    use super::*;
+
+    #[test]
+    fn test_current_step() {
+        let now = at_timestamp(1111111111);
+
+        assert_eq!(Some(time_step(now)), totp(None).verify("050471", now));
+        assert_eq!(Some(time_step(now)), totp(None).verify(" 050 471 ", now));
+    }
+
+    #[test]
+    fn test_skew() {
+        let now = at_timestamp(1111111111);
+
+        assert!(totp(None)
+            .verify("050471", now + chrono::Duration::seconds(30))
+            .is_some());
+        assert!(totp(None)
+            .verify("050471", now + chrono::Duration::seconds(90))
+            .is_none());
+    }
+
+    #[test]
+    fn test_wrong_code() {
+        let now = at_timestamp(1111111111);
+
+        assert_eq!(None, totp(None).verify("050472", now));
+        assert_eq!(None, totp(None).verify("50471", now));
+        assert_eq!(None, totp(None).verify("abcdef", now));
+    }
+
+    #[test]
+    fn test_replay() {
+        let now = at_timestamp(1111111111);
+        let step = totp(None).verify("050471", now).unwrap();
+
+        assert_eq!(None, totp(Some(step)).verify("050471", now));
+    }
+}
+
+#[test]
+fn test_new_secret() {
+    let now = NaiveDate::from_ymd(2022, 1, 1).and_hms(0, 0, 0);
+    let new_totp = NewUserTotp::new("alice".into(), now);
+
+    assert_eq!(
+        Some(SECRET_LENGTH),
+        base32::decode(SECRET_ALPHABET, &new_totp.secret).map(|secret| secret.len())
+    );
+    assert_ne!(
+        new_totp.secret,
+        NewUserTotp::new("alice".into(), now).secret
+    );
+    assert_eq!(None, new_totp.enabled_at);
+}
+
+#[test]
+fn test_provisioning_uri() {
+    assert_eq!(
+        "otpauth://totp/moolah:alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=moolah&algorithm=SHA1&digits=6&period=30",
+        totp(None).provisioning_uri()
+    );
+}
+
+#[test]
+fn test_recovery_codes() {
+    let (new_codes, codes) = NewRecoveryCode::generate("alice");
+
+    assert_eq!(RECOVERY_CODE_COUNT, codes.len());
+    for (new_code, code) in new_codes.iter().zip(&codes) {
+        assert_eq!(11, code.len());
+        assert_eq!(hash_recovery_code(code), new_code.code_hash);
+        assert_eq!(new_code.code_hash, hash_recovery_code(&code.to_uppercase()));
+    }
+}
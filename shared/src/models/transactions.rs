@@ -0,0 +1,145 @@
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use super::Categorization;
+use crate::imports::ParsedTransaction;
+use crate::schema::transactions;
+
+#[derive(Debug, Queryable, Identifiable, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Transaction {
+    id: i32,
+    username: String,
+    posted_on: NaiveDate,
+    amount: f32,
+    description: String,
+    imported: NaiveDateTime,
+    fitid: Option<String>,
+    account: Option<String>,
+    category: Option<String>,
+    delta_id: Option<i32>,
+}
+
+impl Transaction {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn posted_on(&self) -> NaiveDate {
+        self.posted_on
+    }
+
+    pub fn amount(&self) -> f32 {
+        self.amount
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn imported(&self) -> NaiveDateTime {
+        self.imported
+    }
+
+    pub fn fitid(&self) -> Option<&str> {
+        self.fitid.as_deref()
+    }
+
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    pub fn delta_id(&self) -> Option<i32> {
+        self.delta_id
+    }
+}
+
+impl From<Transaction> for ParsedTransaction {
+    fn from(transaction: Transaction) -> Self {
+        ParsedTransaction {
+            posted_on: transaction.posted_on,
+            amount: transaction.amount,
+            description: transaction.description,
+            fitid: transaction.fitid,
+            account: transaction.account,
+        }
+    }
+}
+
+#[derive(Debug, Insertable, Serialize, Deserialize, Clone, PartialEq)]
+#[table_name = "transactions"]
+pub struct NewTransaction {
+    username: String,
+    posted_on: NaiveDate,
+    amount: f32,
+    description: String,
+    imported: NaiveDateTime,
+    fitid: Option<String>,
+    account: Option<String>,
+    category: Option<String>,
+    delta_id: Option<i32>,
+}
+
+impl NewTransaction {
+    pub fn new(
+        username: String,
+        parsed: ParsedTransaction,
+        categorization: Option<Categorization>,
+    ) -> Self {
+        let (category, delta_id) = match categorization {
+            Some(categorization) => (Some(categorization.category), categorization.delta_id),
+            None => (None, None),
+        };
+
+        NewTransaction {
+            username: username.to_lowercase(),
+            posted_on: parsed.posted_on,
+            amount: parsed.amount,
+            description: parsed.description,
+            imported: Local::now().naive_utc(),
+            fitid: parsed.fitid,
+            account: parsed.account,
+            category,
+            delta_id,
+        }
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn posted_on(&self) -> NaiveDate {
+        self.posted_on
+    }
+
+    pub fn amount(&self) -> f32 {
+        self.amount
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn fitid(&self) -> Option<&str> {
+        self.fitid.as_deref()
+    }
+
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    pub fn delta_id(&self) -> Option<i32> {
+        self.delta_id
+    }
+}
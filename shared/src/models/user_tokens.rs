@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Duration, NaiveDateTime};
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+
+use crate::schema::user_tokens;
+
+/// Length of the random token mailed to the user.
+pub const TOKEN_LENGTH: usize = 48;
+
+/// What a user token lets its holder do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenPurpose {
+    PasswordReset,
+    EmailVerification,
+    TwoFactorLogin,
+}
+
+impl TokenPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenPurpose::PasswordReset => "password_reset",
+            TokenPurpose::EmailVerification => "email_verification",
+            TokenPurpose::TwoFactorLogin => "two_factor_login",
+        }
+    }
+}
+
+/// A single-use, time-limited token mailed to a user. Only a hash of the token
+/// is stored, so the table alone is no use to anyone reading it.
+#[derive(Debug, Queryable, Identifiable, Clone, PartialEq)]
+pub struct UserToken {
+    pub(crate) id: i32,
+    pub(crate) username: String,
+    pub(crate) purpose: String,
+    pub(crate) token_hash: String,
+    pub(crate) created: NaiveDateTime,
+    pub(crate) expires: NaiveDateTime,
+    pub(crate) used: Option<NaiveDateTime>,
+}
+
+impl UserToken {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn expires(&self) -> NaiveDateTime {
+        self.expires
+    }
+
+    pub fn used(&self) -> Option<NaiveDateTime> {
+        self.used
+    }
+
+    /// Whether the token can still be redeemed for `purpose` at `now`.
+    pub fn is_usable(&self, purpose: TokenPurpose, now: NaiveDateTime) -> bool {
+        self.purpose == purpose.as_str() && self.used.is_none() && now < self.expires
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "user_tokens"]
+pub struct NewUserToken {
+    username: String,
+    purpose: String,
+    token_hash: String,
+    created: NaiveDateTime,
+    expires: NaiveDateTime,
+}
+
+impl NewUserToken {
+    /// A token for `username` lasting `lifetime` from `now`, along with the
+    /// token itself to mail out. The token cannot be recovered afterwards.
+    pub fn new(
+        username: String,
+        purpose: TokenPurpose,
+        lifetime: Duration,
+        now: NaiveDateTime,
+    ) -> (Self, String) {
+        let token = random_token(TOKEN_LENGTH);
+
+        let new_token = NewUserToken {
+            username,
+            purpose: purpose.as_str().to_string(),
+            token_hash: hash_token(&token),
+            created: now,
+            expires: now + lifetime,
+        };
+
+        (new_token, token)
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn expires(&self) -> NaiveDateTime {
+        self.expires
+    }
+}
+
+/// The hash a token is stored and looked up by.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// A random string of letters and digits.
+pub(crate) fn random_token(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
@@ -0,0 +1,87 @@
+use chrono::NaiveDate;
+
+use super::*;
+
+fn at(day: u32, hour: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd(2022, 3, day).and_hms(hour, 0, 0)
+}
+
+fn token(expires: NaiveDateTime, used: Option<NaiveDateTime>) -> UserToken {
+    UserToken {
+        id: 1,
+        username: "alice".into(),
+        purpose: TokenPurpose::PasswordReset.as_str().into(),
+        token_hash: hash_token("token"),
+        created: at(1, 0),
+        expires,
+        used,
+    }
+}
+
+mod is_usable {
+    use super::*;
+
+    #[test]
+    fn test_fresh() {
+        assert!(token(at(1, 1), None).is_usable(TokenPurpose::PasswordReset, at(1, 0)));
+    }
+
+    #[test]
+    fn test_expired() {
+        assert!(!token(at(1, 1), None).is_usable(TokenPurpose::PasswordReset, at(1, 1)));
+    }
+
+    #[test]
+    fn test_other_purpose() {
+        assert!(!token(at(1, 1), None).is_usable(TokenPurpose::EmailVerification, at(1, 0)));
+    }
+
+    #[test]
+    fn test_used() {
+        let token = token(at(1, 1), Some(at(1, 0)));
+
+        assert!(!token.is_usable(TokenPurpose::PasswordReset, at(1, 0)));
+    }
+}
+
+mod new {
+    use super::*;
+
+    #[test]
+    fn test_stores_hash_only() {
+        let (new_token, plain) = NewUserToken::new(
+            "alice".into(),
+            TokenPurpose::PasswordReset,
+            Duration::hours(1),
+            at(1, 0),
+        );
+
+        assert_eq!(TOKEN_LENGTH, plain.len());
+        assert_ne!(plain, new_token.token_hash);
+        assert_eq!(hash_token(&plain), new_token.token_hash);
+        assert_eq!(at(1, 1), new_token.expires());
+    }
+
+    #[test]
+    fn test_tokens_differ() {
+        let new = || {
+            NewUserToken::new(
+                "alice".into(),
+                TokenPurpose::PasswordReset,
+                Duration::hours(1),
+                at(1, 0),
+            )
+            .1
+        };
+
+        assert_ne!(new(), new());
+    }
+}
+
+#[test]
+fn test_hash_token() {
+    assert_eq!(
+        "3c469e9d6c5875d37a43f353d4f88e61fcf812c66eee3457465a40b0da4153e0",
+        hash_token("token")
+    );
+}
@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod tests;
+
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
@@ -13,9 +16,15 @@ use crate::MoolahSharedError;
 lazy_static! {
     static ref PASSWORD_REGEX: Regex = Regex::new(r"[a-zA-Z\d@#$%^&-+=()!? ]{8,24}$").unwrap();
     static ref USERNAME_REGEX: Regex = Regex::new(r"^[a-zA-Z0-9]{4,18}$").unwrap();
+    /// Checked against when there is no such user, so a login for a missing
+    /// account takes as long as one with a wrong password.
+    static ref DUMMY_PASSWORD_HASH: String =
+        hash_password("not anyone's password").expect("could not hash dummy password");
 }
 
-#[derive(Queryable, Debug, Identifiable, Serialize, Deserialize)]
+/// A user as stored, password hash included. Never sent to the browser; use
+/// [`UserAccount`] for that.
+#[derive(Queryable, Debug, Identifiable)]
 pub struct User {
     pub id: i32,
     pub username: String,
@@ -23,9 +32,16 @@ pub struct User {
     pub password: String,
     pub created: NaiveDateTime,
     pub last_login: NaiveDateTime,
+    pub verified_at: Option<NaiveDateTime>,
 }
 
 impl User {
+    /// Whether the user has followed a verification link sent to their
+    /// current email address.
+    pub fn is_verified(&self) -> bool {
+        self.verified_at.is_some()
+    }
+
     pub fn verify_user(&self, username: &str, password: &str) -> Result<bool, MoolahSharedError> {
         if username.to_lowercase() != self.username.to_lowercase() {
             return Ok(false);
@@ -38,6 +54,17 @@ impl User {
     }
 }
 
+/// Do the work of [`User::verify_user`] for a user that does not exist, so
+/// the time a login takes does not give away whether the account does. Never
+/// verifies.
+pub fn verify_missing_user(password: &str) -> Result<bool, MoolahSharedError> {
+    let parsed_hash = PasswordHash::new(&DUMMY_PASSWORD_HASH)?;
+    Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .ok();
+    Ok(false)
+}
+
 #[derive(Debug, Deserialize, Serialize, Insertable)]
 #[table_name = "users"]
 pub struct NewUser {
@@ -54,10 +81,7 @@ impl TryFrom<UserRegisterForm> for NewUser {
     fn try_from(form: UserRegisterForm) -> Result<Self, Self::Error> {
         form.validate()?;
 
-        let salt = SaltString::generate(&mut OsRng);
-        let pass_hash = Argon2::default()
-            .hash_password(form.password.as_bytes(), &salt)?
-            .to_string();
+        let pass_hash = hash_password(&form.password)?;
 
         let now = Local::now().naive_utc();
 
@@ -71,6 +95,14 @@ impl TryFrom<UserRegisterForm> for NewUser {
     }
 }
 
+/// Hash a password with Argon2 and a fresh salt, ready to be stored.
+fn hash_password(password: &str) -> Result<String, MoolahSharedError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
 #[derive(Debug, Validate)]
 pub struct UserRegisterForm {
     #[validate(
@@ -114,15 +146,170 @@ impl UserRegisterForm {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UserPasswordChangeForm {
+    current_password: String,
+    #[validate(
+        length(min = 8, max = 24, message = "password should be 8-24 characters"),
+        regex(
+            path = "PASSWORD_REGEX",
+            message = "password should be made up of letters, numbers, digits, and the following special characters '@#$%^&-+=()!? '"
+        )
+    )]
+    password: String,
+    #[validate(must_match(
+        other = "password",
+        message = "confirm password should match password"
+    ))]
+    confirm_password: String,
+}
+
+impl UserPasswordChangeForm {
+    pub fn new(current_password: String, password: String, confirm_password: String) -> Self {
+        UserPasswordChangeForm {
+            current_password,
+            password,
+            confirm_password,
+        }
+    }
+
+    pub fn current_password(&self) -> &str {
+        &self.current_password
+    }
+
+    /// Check the new password and hash it for storage.
+    pub fn new_password_hash(&self) -> Result<String, MoolahSharedError> {
+        self.validate()?;
+        hash_password(&self.password)
+    }
+}
+
+/// Ask for a password reset link to be mailed to the account's address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordResetRequest {
+    email: String,
+}
+
+impl PasswordResetRequest {
+    pub fn new(email: String) -> Self {
+        PasswordResetRequest {
+            email: email.trim().to_lowercase(),
+        }
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+}
+
+/// Set a new password using the token from a password reset email.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct PasswordResetForm {
+    token: String,
+    #[validate(
+        length(min = 8, max = 24, message = "password should be 8-24 characters"),
+        regex(
+            path = "PASSWORD_REGEX",
+            message = "password should be made up of letters, numbers, digits, and the following special characters '@#$%^&-+=()!? '"
+        )
+    )]
+    password: String,
+    #[validate(must_match(
+        other = "password",
+        message = "confirm password should match password"
+    ))]
+    confirm_password: String,
+}
+
+impl PasswordResetForm {
+    pub fn new(token: String, password: String, confirm_password: String) -> Self {
+        PasswordResetForm {
+            token,
+            password,
+            confirm_password,
+        }
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Check the new password and hash it for storage.
+    pub fn new_password_hash(&self) -> Result<String, MoolahSharedError> {
+        self.validate()?;
+        hash_password(&self.password)
+    }
+}
+
+/// The token from an email verification link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailVerificationForm {
+    token: String,
+}
+
+impl EmailVerificationForm {
+    pub fn new(token: String) -> Self {
+        EmailVerificationForm { token }
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UserEmailChangeForm {
+    #[validate(email(message = "please enter a valid email"))]
+    email: String,
+}
+
+impl UserEmailChangeForm {
+    pub fn new(email: String) -> Self {
+        UserEmailChangeForm {
+            email: email.to_lowercase(),
+        }
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UserUsernameChangeForm {
+    #[validate(
+        length(min = 4, max = 18, message = "username should be 4-18 characters"),
+        regex(
+            path = "USERNAME_REGEX",
+            message = "username should be only made up of letters, numbers, and digits"
+        )
+    )]
+    username: String,
+}
+
+impl UserUsernameChangeForm {
+    pub fn new(username: String) -> Self {
+        UserUsernameChangeForm {
+            username: username.to_lowercase(),
+        }
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
-pub struct UserLoginRequestForm {
+pub struct UserLoginForm {
     pub username: String,
+    pub password: String,
 }
 
-impl UserLoginRequestForm {
-    pub fn new(username: String) -> Self {
-        UserLoginRequestForm {
+impl UserLoginForm {
+    pub fn new(username: String, password: String) -> Self {
+        UserLoginForm {
             username: username.to_lowercase(),
+            password,
         }
     }
 }
@@ -133,6 +320,7 @@ pub struct UserAccount {
     pub email: String,
     pub created: NaiveDateTime,
     pub last_login: NaiveDateTime,
+    pub verified_at: Option<NaiveDateTime>,
 }
 
 impl From<User> for UserAccount {
@@ -142,6 +330,7 @@ impl From<User> for UserAccount {
             email: user.email,
             created: user.created,
             last_login: user.last_login,
+            verified_at: user.verified_at,
         }
     }
 }
@@ -0,0 +1,138 @@
+use chrono::NaiveDate;
+
+use super::*;
+
+fn user(password: &str) -> User {
+    let created = NaiveDate::from_ymd(2022, 1, 1).and_hms(0, 0, 0);
+    User {
+        id: 1,
+        username: "alice".into(),
+        email: "alice@example.com".into(),
+        password: hash_password(password).unwrap(),
+        created,
+        last_login: created,
+        verified_at: None,
+    }
+}
+
+#[test]
+fn test_is_verified() {
+    let verified_at = NaiveDate::from_ymd(2022, 1, 2).and_hms(0, 0, 0);
+
+    assert!(!user("password").is_verified());
+    assert!(User {
+        verified_at: Some(verified_at),
+        ..user("password")
+    }
+    .is_verified());
+    assert_eq!(
+        Some(verified_at),
+        UserAccount::from(User {
+            verified_at: Some(verified_at),
+            ..user("password")
+        })
+        .verified_at
+    );
+}
+
+#[test]
+fn test_verify_missing_user() {
+    assert!(!verify_missing_user("password").unwrap());
+    assert!(!verify_missing_user("not anyone's password").unwrap());
+}
+
+mod password_change {
+    use super::*;
+
+    #[test]
+    fn test_new_hash_verifies() {
+        let form = UserPasswordChangeForm::new(
+            "old password".into(),
+            "new password".into(),
+            "new password".into(),
+        );
+
+        let user = User {
+            password: form.new_password_hash().unwrap(),
+            ..user("old password")
+        };
+
+        assert!(user.verify_user("alice", "new password").unwrap());
+        assert!(!user.verify_user("alice", "old password").unwrap());
+    }
+
+    #[test]
+    fn test_mismatched_confirmation() {
+        let form = UserPasswordChangeForm::new(
+            "old password".into(),
+            "new password".into(),
+            "new passwort".into(),
+        );
+
+        assert!(form.new_password_hash().is_err());
+    }
+
+    #[test]
+    fn test_too_short() {
+        let form =
+            UserPasswordChangeForm::new("old password".into(), "short".into(), "short".into());
+
+        assert!(form.new_password_hash().is_err());
+    }
+}
+
+#[test]
+fn test_email_change() {
+    let form = UserEmailChangeForm::new("Alice@Example.com".into());
+
+    assert_eq!("alice@example.com", form.email());
+    assert!(form.validate().is_ok());
+    assert!(UserEmailChangeForm::new("not an email".into())
+        .validate()
+        .is_err());
+}
+
+#[test]
+fn test_username_change() {
+    let form = UserUsernameChangeForm::new("Alice2".into());
+
+    assert_eq!("alice2", form.username());
+    assert!(form.validate().is_ok());
+    assert!(UserUsernameChangeForm::new("al".into()).validate().is_err());
+    assert!(UserUsernameChangeForm::new("alice smith".into())
+        .validate()
+        .is_err());
+}
+
+mod password_reset {
+    use super::*;
+
+    #[test]
+    fn test_request_normalises_email() {
+        let request = PasswordResetRequest::new(" Alice@Example.com ".into());
+
+        assert_eq!("alice@example.com", request.email());
+    }
+
+    #[test]
+    fn test_new_hash_verifies() {
+        let form =
+            PasswordResetForm::new("token".into(), "new password".into(), "new password".into());
+
+        let user = User {
+            password: form.new_password_hash().unwrap(),
+            ..user("old password")
+        };
+
+        assert_eq!("token", form.token());
+        assert!(user.verify_user("alice", "new password").unwrap());
+    }
+
+    #[test]
+    fn test_mismatched_confirmation() {
+        let form =
+            PasswordResetForm::new("token".into(), "new password".into(), "new passwort".into());
+
+        assert!(form.new_password_hash().is_err());
+    }
+}
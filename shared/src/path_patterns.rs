@@ -1,2 +1,27 @@
+pub const SESSIONS: &str = r"\{username\}";
+pub const API_TOKENS: &str = r"\{username\}";
 pub const PREDICTIONS: &str = r"\{username\}";
 pub const DELTAS: &str = r"\{username\}";
+pub const ASSETS: &str = r"\{username\}";
+pub const ASSET_VALUATIONS: &str = r"\{username\}";
+pub const GOALS: &str = r"\{username\}";
+pub const GOALS_PROGRESS: &str = r"\{username\}";
+pub const INVESTMENTS: &str = r"\{username\}";
+pub const INVESTMENTS_PROJECTION: &str = r"\{username\}";
+pub const NET_WORTH: &str = r"\{username\}";
+pub const GENERATE_LOAN: &str = r"\{username\}";
+pub const GENERATE_DEBT_PAYOFF: &str = r"\{username\}";
+pub const GENERATE_PAYCHECK: &str = r"\{username\}";
+pub const TAX_ESTIMATE: &str = r"\{username\}";
+pub const RETIREMENT_PROJECTION: &str = r"\{username\}";
+pub const TRANSACTIONS: &str = r"\{username\}";
+pub const IMPORT_CSV: &str = r"\{username\}";
+pub const IMPORT_OFX: &str = r"\{username\}";
+pub const IMPORT_QIF: &str = r"\{username\}";
+pub const IMPORT_LEDGER: &str = r"\{username\}";
+pub const RECURRING: &str = r"\{username\}";
+pub const RULES: &str = r"\{username\}";
+pub const RULES_APPLY: &str = r"\{username\}";
+pub const EXPORT_DELTAS: &str = r"\{username\}";
+pub const EXPORT_SCHEDULE: &str = r"\{username\}";
+pub const EXPORT_JOURNAL: &str = r"\{username\}";
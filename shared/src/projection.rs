@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests;
+
+pub mod growth;
+pub mod net_worth;
+pub mod retirement;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::Delta;
+
+/// One date a delta happens on, along with the balance right after it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Occurrence {
+    pub date: NaiveDate,
+    pub delta_id: i32,
+    pub name: String,
+    pub value: f32,
+    pub balance: f32,
+    /// The balance if every occurrence so far came in at its positive
+    /// uncertainty
+    pub balance_high: f32,
+    /// The balance if every occurrence so far came in at its negative
+    /// uncertainty
+    pub balance_low: f32,
+}
+
+/// Expand every delta's repetition into dated occurrences, in date order, and
+/// keep a running balance from `starting_balance`. Deltas that don't move the
+/// balance, like paycheck deductions, are left out.
+pub fn schedule(deltas: &[Delta], starting_balance: f32) -> Vec<Occurrence> {
+    let mut occurrences = deltas
+        .iter()
+        .filter(|delta| delta.kind().moves_balance())
+        .flat_map(|delta| {
+            delta
+                .repetition()
+                .dates()
+                .into_iter()
+                .map(move |date| (date, delta))
+        })
+        .collect::<Vec<_>>();
+    occurrences.sort_by_key(|(date, delta)| (*date, delta.id()));
+
+    let mut balance = starting_balance;
+    let mut high = starting_balance;
+    let mut low = starting_balance;
+
+    occurrences
+        .into_iter()
+        .map(|(date, delta)| {
+            balance += delta.value();
+            high += delta.value() + delta.positive_uncertainty();
+            low += delta.value() - delta.negative_uncertainty();
+
+            Occurrence {
+                date,
+                delta_id: delta.id(),
+                name: delta.name().to_string(),
+                value: delta.value(),
+                balance,
+                balance_high: high,
+                balance_low: low,
+            }
+        })
+        .collect()
+}
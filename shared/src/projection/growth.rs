@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Datelike, NaiveDate};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use serde::{Deserialize, Serialize};
+
+use crate::models::deltas::app::repetition::MonthDay;
+use crate::models::{Delta, Repetition};
+
+/// How many simulated paths a projection uses unless asked for another number.
+pub const DEFAULT_RUNS: u32 = 500;
+
+/// Most simulated paths a projection may ask for.
+pub const MAX_RUNS: u32 = 10_000;
+
+/// Furthest past its start a projection may run, in years.
+pub const MAX_YEARS: i32 = 100;
+
+/// The growth an account is expected to see, both as annual percentages.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GrowthAssumptions {
+    /// Average annual return, e.g. `7`
+    pub expected_return: f32,
+    /// Standard deviation of annual returns, e.g. `15`
+    pub volatility: f32,
+}
+
+/// An account's balance at the end of one month of a projection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrowthPoint {
+    pub date: NaiveDate,
+    /// Everything paid in (less anything taken out) so far
+    pub contributed: f32,
+    /// The balance if every month returned exactly the expected return
+    pub expected: f32,
+    /// 10th percentile of the simulated balances
+    pub low: f32,
+    pub median: f32,
+    /// 90th percentile of the simulated balances
+    pub high: f32,
+}
+
+/// Grow a balance month by month from `start_on` to `until`.
+///
+/// Money moves into the account whenever one of the `contributions` deltas
+/// happens: a delta taking money out of the prediction (a negative value) pays
+/// the same amount into the account. Alongside the expected path, `runs`
+/// Monte Carlo paths draw each month's return from a lognormal distribution
+/// with the given volatility, and their spread gives the low and high bands.
+/// The same `seed` always gives the same projection.
+pub fn project(
+    starting_balance: f32,
+    start_on: NaiveDate,
+    until: NaiveDate,
+    assumptions: GrowthAssumptions,
+    contributions: &[Delta],
+    runs: u32,
+    seed: u64,
+) -> Vec<GrowthPoint> {
+    let steps = month_ends(start_on, until);
+    let flows = monthly_flows(start_on, &steps, contributions);
+
+    let annual_return = (1. + assumptions.expected_return as f64 / 100.).max(f64::EPSILON);
+    let expected_growth = annual_return.powf(1. / 12.);
+
+    // lognormal monthly returns whose mean matches the expected return
+    let sigma = assumptions.volatility as f64 / 100. / 12f64.sqrt();
+    let mu = annual_return.ln() / 12. - sigma * sigma / 2.;
+
+    let runs = runs.max(1) as usize;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut balances = vec![starting_balance as f64; runs];
+    let mut expected = starting_balance as f64;
+    let mut contributed = 0.;
+
+    steps
+        .into_iter()
+        .zip(flows)
+        .map(|(date, (flow, spread))| {
+            expected = expected * expected_growth + flow;
+            contributed += flow;
+
+            for balance in balances.iter_mut() {
+                let shock: f64 = rng.sample(StandardNormal);
+                let flow = flow + spread * (rng.gen::<f64>() * 2. - 1.);
+                *balance = *balance * (mu + sigma * shock).exp() + flow;
+            }
+
+            let mut sorted = balances.clone();
+            sorted.sort_by(|a, b| a.total_cmp(b));
+
+            GrowthPoint {
+                date,
+                contributed: contributed as f32,
+                expected: expected as f32,
+                low: percentile(&sorted, 0.1) as f32,
+                median: percentile(&sorted, 0.5) as f32,
+                high: percentile(&sorted, 0.9) as f32,
+            }
+        })
+        .collect()
+}
+
+/// One date a month after `start_on`, on its day of the month, through `until`.
+fn month_ends(start_on: NaiveDate, until: NaiveDate) -> Vec<NaiveDate> {
+    let day = MonthDay::new(start_on.day() as i16).expect("day of a date is a valid month day");
+
+    Repetition::Monthly {
+        from: start_on,
+        to: until,
+        repeat_on_day: day,
+    }
+    .dates()
+    .into_iter()
+    .filter(|date| *date > start_on)
+    .collect()
+}
+
+/// Net money paid into the account during each step, along with how far the
+/// contributing deltas' uncertainty lets that drift either way.
+fn monthly_flows(
+    start_on: NaiveDate,
+    steps: &[NaiveDate],
+    contributions: &[Delta],
+) -> Vec<(f64, f64)> {
+    let mut flows = vec![(0., 0.); steps.len()];
+    let last_step = match steps.last() {
+        Some(last_step) => *last_step,
+        None => return flows,
+    };
+
+    for delta in contributions {
+        let spread = delta
+            .positive_uncertainty()
+            .max(delta.negative_uncertainty()) as f64;
+
+        for date in delta.repetition().dates_between(start_on, last_step) {
+            if let Some(step) = steps.iter().position(|step| date <= *step) {
+                flows[step].0 -= delta.value() as f64;
+                flows[step].1 += spread;
+            }
+        }
+    }
+
+    flows
+}
+
+/// The value `fraction` of the way through already sorted `values`.
+fn percentile(sorted: &[f64], fraction: f64) -> f64 {
+    let index = ((sorted.len() - 1) as f64 * fraction).round() as usize;
+    sorted[index]
+}
@@ -0,0 +1,123 @@
+use super::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn monthly_contribution(value: f32, uncertainty: f32) -> Delta {
+    Delta::new(
+        1,
+        1,
+        "retirement".into(),
+        value,
+        uncertainty,
+        uncertainty,
+        Repetition::Monthly {
+            from: date(2026, 1, 1),
+            to: date(2026, 12, 31),
+            repeat_on_day: MonthDay::new(1).unwrap(),
+        },
+    )
+}
+
+fn assumptions(expected_return: f32, volatility: f32) -> GrowthAssumptions {
+    GrowthAssumptions {
+        expected_return,
+        volatility,
+    }
+}
+
+#[test]
+fn test_project_steps_monthly() {
+    let points = project(
+        1000.,
+        date(2026, 1, 1),
+        date(2026, 12, 31),
+        assumptions(0., 0.),
+        &[],
+        10,
+        0,
+    );
+
+    assert_eq!(points.len(), 11);
+    assert_eq!(points[0].date, date(2026, 2, 1));
+    assert_eq!(points[10].date, date(2026, 12, 1));
+}
+
+#[test]
+fn test_project_without_volatility_follows_expected() {
+    let points = project(
+        1000.,
+        date(2026, 1, 1),
+        date(2027, 1, 1),
+        assumptions(12., 0.),
+        &[],
+        10,
+        0,
+    );
+    let last = points.last().unwrap();
+
+    assert_eq!(last.date, date(2027, 1, 1));
+    assert!((last.expected - 1120.).abs() < 0.01);
+    assert!((last.low - last.expected).abs() < 0.01);
+    assert!((last.median - last.expected).abs() < 0.01);
+    assert!((last.high - last.expected).abs() < 0.01);
+}
+
+#[test]
+fn test_project_accumulates_contributions() {
+    let points = project(
+        0.,
+        date(2026, 1, 1),
+        date(2027, 1, 1),
+        assumptions(0., 0.),
+        &[monthly_contribution(-100., 0.)],
+        10,
+        0,
+    );
+    let last = points.last().unwrap();
+
+    assert_eq!(last.contributed, 1200.);
+    assert_eq!(last.expected, 1200.);
+    assert_eq!(last.median, 1200.);
+}
+
+#[test]
+fn test_project_is_reproducible() {
+    let run = |seed| {
+        project(
+            1000.,
+            date(2026, 1, 1),
+            date(2030, 1, 1),
+            assumptions(7., 15.),
+            &[monthly_contribution(-100., 20.)],
+            100,
+            seed,
+        )
+    };
+
+    assert_eq!(run(3), run(3));
+    assert_ne!(run(3), run(4));
+}
+
+#[test]
+fn test_project_band_widens_with_volatility() {
+    let spread = |volatility| {
+        let points = project(
+            1000.,
+            date(2026, 1, 1),
+            date(2036, 1, 1),
+            assumptions(7., volatility),
+            &[],
+            200,
+            1,
+        );
+        let last = points.last().unwrap();
+        last.high - last.low
+    };
+
+    let calm = spread(5.);
+    let wild = spread(25.);
+    assert!(calm > 0.);
+    assert!(wild > calm);
+}
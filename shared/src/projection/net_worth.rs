@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::growth::{self, GrowthPoint};
+use crate::models::deltas::app::repetition::MonthDay;
+use crate::models::{AssetWithValuations, Delta, InvestmentAccountWithContributions, Repetition};
+use crate::MoolahSharedError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NetWorthQuery {
+    pub prediction_id: i32,
+    /// Cash on hand on `from`
+    pub starting_balance: f32,
+    pub from: NaiveDate,
+    pub until: NaiveDate,
+}
+
+impl NetWorthQuery {
+    /// Check the series stays within the projection horizon. `start_on` is the
+    /// earliest date anything in it is projected from: `from`, or the start of
+    /// an investment account opened before it.
+    pub fn validate(&self, start_on: NaiveDate) -> Result<(), MoolahSharedError> {
+        let start_on = start_on.min(self.from);
+        if self.until.year() - start_on.year() > growth::MAX_YEARS {
+            return Err(MoolahSharedError::AssetError(format!(
+                "net worth can be projected at most {} years",
+                growth::MAX_YEARS
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Everything owned and owed on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetWorthPoint {
+    pub date: NaiveDate,
+    pub cash: f32,
+    pub investments: f32,
+    pub assets: f32,
+    pub liabilities: f32,
+    pub net_worth: f32,
+}
+
+/// Net worth once a month from `query.from` until `query.until`.
+///
+/// Cash starts at `query.starting_balance` and follows the prediction's
+/// deltas, investment accounts follow their expected growth, and assets and
+/// liabilities follow their valuations and rates.
+pub fn series(
+    query: &NetWorthQuery,
+    deltas: &[Delta],
+    investments: &[InvestmentAccountWithContributions],
+    assets: &[AssetWithValuations],
+) -> Vec<NetWorthPoint> {
+    if query.until < query.from {
+        return Vec::new();
+    }
+
+    let day = MonthDay::new(query.from.day() as i16).expect("day of a date is a valid month day");
+    let dates = Repetition::Monthly {
+        from: query.from,
+        to: query.until,
+        repeat_on_day: day,
+    }
+    .dates();
+
+    let growth = investments
+        .iter()
+        .map(|account| {
+            (
+                account.account.start_on(),
+                account.account.starting_balance(),
+                account.projection(deltas, query.until, 1).points,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut cash = query.starting_balance as f64;
+    let mut counted_through = query.from;
+
+    dates
+        .into_iter()
+        .map(|date| {
+            if date > counted_through {
+                cash += deltas
+                    .iter()
+                    .filter(|delta| delta.kind().moves_balance())
+                    .map(|delta| {
+                        delta.value() as f64
+                            * delta
+                                .repetition()
+                                .dates_between(counted_through.succ(), date)
+                                .len() as f64
+                    })
+                    .sum::<f64>();
+                counted_through = date;
+            }
+
+            let investments = growth
+                .iter()
+                .map(|(start_on, starting_balance, points)| {
+                    expected_on(*start_on, *starting_balance, points, date)
+                })
+                .sum::<f32>();
+            let (owned, owed) = assets.iter().map(|asset| asset.net_value_on(date)).fold(
+                (0., 0.),
+                |(owned, owed), value| {
+                    if value >= 0. {
+                        (owned + value, owed)
+                    } else {
+                        (owned, owed - value)
+                    }
+                },
+            );
+
+            NetWorthPoint {
+                date,
+                cash: round_cents(cash),
+                investments: round_cents(investments as f64),
+                assets: round_cents(owned as f64),
+                liabilities: round_cents(owed as f64),
+                net_worth: round_cents(cash + investments as f64 + owned as f64 - owed as f64),
+            }
+        })
+        .collect()
+}
+
+/// An investment account's expected balance on `date`, from the latest
+/// projected month on or before it.
+fn expected_on(
+    start_on: NaiveDate,
+    starting_balance: f32,
+    points: &[GrowthPoint],
+    date: NaiveDate,
+) -> f32 {
+    if date < start_on {
+        return 0.;
+    }
+
+    points
+        .iter()
+        .take_while(|point| point.date <= date)
+        .last()
+        .map_or(starting_balance, |point| point.expected)
+}
+
+fn round_cents(value: f64) -> f32 {
+    ((value * 100.).round() / 100.) as f32
+}
@@ -0,0 +1,140 @@
+use super::*;
+use crate::models::{Asset, AssetValuation, DeltaKind, InvestmentAccount};
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn query() -> NetWorthQuery {
+    NetWorthQuery {
+        prediction_id: 1,
+        starting_balance: 1000.,
+        from: date(2026, 1, 1),
+        until: date(2026, 4, 1),
+    }
+}
+
+fn deltas() -> Vec<Delta> {
+    vec![Delta::new(
+        1,
+        1,
+        "paycheck".into(),
+        500.,
+        0.,
+        0.,
+        Repetition::Monthly {
+            from: date(2025, 1, 1),
+            to: date(2026, 12, 31),
+            repeat_on_day: MonthDay::new(1).unwrap(),
+        },
+    )]
+}
+
+#[test]
+fn test_cash_follows_deltas_after_start() {
+    let points = series(&query(), &deltas(), &[], &[]);
+
+    assert_eq!(points.len(), 4);
+    assert_eq!(points[0].date, date(2026, 1, 1));
+    assert_eq!(points[0].cash, 1000.);
+    assert_eq!(points[3].cash, 2500.);
+    assert_eq!(points[3].net_worth, 2500.);
+}
+
+#[test]
+fn test_cash_skips_deductions() {
+    let mut deltas = deltas();
+    deltas.push(
+        Delta::new(
+            2,
+            1,
+            "paycheck withholding".into(),
+            -100.,
+            0.,
+            0.,
+            Repetition::Monthly {
+                from: date(2025, 1, 1),
+                to: date(2026, 12, 31),
+                repeat_on_day: MonthDay::new(1).unwrap(),
+            },
+        )
+        .with_kind(DeltaKind::Withholding),
+    );
+
+    let points = series(&query(), &deltas, &[], &[]);
+    assert_eq!(points[3].cash, 2500.);
+}
+
+#[test]
+fn test_assets_and_liabilities() {
+    let valued = |is_liability, value| AssetWithValuations {
+        asset: Asset {
+            id: 1,
+            prediction_id: 1,
+            name: "".into(),
+            is_liability,
+            annual_rate: None,
+        },
+        valuations: vec![AssetValuation {
+            id: 1,
+            asset_id: 1,
+            valued_on: date(2026, 2, 1),
+            value,
+        }],
+    };
+    let assets = vec![valued(false, 20000.), valued(true, 15000.)];
+
+    let points = series(&query(), &[], &[], &assets);
+
+    assert_eq!(points[0].assets, 0.);
+    assert_eq!(points[1].assets, 20000.);
+    assert_eq!(points[1].liabilities, 15000.);
+    assert_eq!(points[1].net_worth, 6000.);
+}
+
+#[test]
+fn test_investments_count_toward_net_worth() {
+    let contribution = Delta::new(
+        2,
+        1,
+        "brokerage".into(),
+        -100.,
+        0.,
+        0.,
+        Repetition::Monthly {
+            from: date(2026, 1, 15),
+            to: date(2026, 12, 31),
+            repeat_on_day: MonthDay::new(15).unwrap(),
+        },
+    );
+    let account = InvestmentAccountWithContributions {
+        account: InvestmentAccount {
+            id: 3,
+            prediction_id: 1,
+            name: "brokerage".into(),
+            starting_balance: 500.,
+            start_on: date(2026, 1, 1),
+            expected_return: 0.,
+            volatility: 0.,
+        },
+        contribution_delta_ids: vec![2],
+    };
+
+    let points = series(&query(), &[contribution], &[account], &[]);
+
+    // money moved from cash into the account leaves net worth unchanged
+    assert_eq!(points[3].cash, 700.);
+    assert_eq!(points[3].investments, 800.);
+    assert_eq!(points[3].net_worth, 1500.);
+}
+
+#[test]
+fn test_query_validates() {
+    let query = |until| NetWorthQuery { until, ..query() };
+
+    assert!(query(date(2126, 12, 31)).validate(date(2026, 1, 1)).is_ok());
+    assert!(query(date(2127, 1, 1)).validate(date(2026, 1, 1)).is_err());
+    // an account opened long before the series still has to be projected
+    assert!(query(date(2100, 1, 1)).validate(date(1990, 1, 1)).is_err());
+    assert!(query(NaiveDate::MAX).validate(date(2026, 1, 1)).is_err());
+}
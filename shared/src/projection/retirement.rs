@@ -0,0 +1,241 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Delta;
+use crate::MoolahSharedError;
+
+/// The oldest age a retirement projection runs to.
+pub const MAX_AGE: u32 = 120;
+
+/// How much to take out of savings each year of retirement.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WithdrawalRule {
+    /// A set amount a year in today's money, rising with inflation
+    Fixed { annual: f32 },
+    /// A percentage of the balance at retirement in the first year, rising
+    /// with inflation after that (e.g. the 4% rule)
+    Percentage { rate: f32 },
+}
+
+impl WithdrawalRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WithdrawalRule::Fixed { .. } => "fixed amount",
+            WithdrawalRule::Percentage { .. } => "percentage",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetirementRequest {
+    pub prediction_id: i32,
+    pub born_on: NaiveDate,
+    /// Day the projection starts, with `starting_balance` saved by then
+    pub start_on: NaiveDate,
+    pub retire_on: NaiveDate,
+    /// Age the projection runs until
+    pub until_age: u32,
+    pub starting_balance: f32,
+    /// Average annual return as a percentage
+    pub expected_return: f32,
+    /// Annual inflation as a percentage
+    pub inflation: f32,
+    /// Deltas paying into savings until retirement
+    #[serde(default)]
+    pub contribution_delta_ids: Vec<i32>,
+    pub withdrawal: WithdrawalRule,
+}
+
+impl RetirementRequest {
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        if self.born_on >= self.start_on {
+            return Err(MoolahSharedError::RetirementError(
+                "birth date must be before the start of the projection".into(),
+            ));
+        }
+        if self.retire_on < self.start_on {
+            return Err(MoolahSharedError::RetirementError(
+                "retirement date cannot be before the start of the projection".into(),
+            ));
+        }
+        let end = add_years(self.born_on, self.until_age).ok_or_else(|| {
+            MoolahSharedError::RetirementError("projection ends too far in the future".into())
+        })?;
+        if self.until_age > MAX_AGE || end <= self.retire_on {
+            return Err(MoolahSharedError::RetirementError(format!(
+                "projection must run past retirement and end by age {}",
+                MAX_AGE
+            )));
+        }
+        if !self.starting_balance.is_finite() || self.starting_balance < 0. {
+            return Err(MoolahSharedError::RetirementError(
+                "starting balance cannot be negative".into(),
+            ));
+        }
+        if !self.expected_return.is_finite() || self.expected_return <= -100. {
+            return Err(MoolahSharedError::RetirementError(
+                "expected return must be greater than -100%".into(),
+            ));
+        }
+        if !self.inflation.is_finite() || self.inflation <= -100. {
+            return Err(MoolahSharedError::RetirementError(
+                "inflation must be greater than -100%".into(),
+            ));
+        }
+        match self.withdrawal {
+            WithdrawalRule::Fixed { annual } if !annual.is_finite() || annual < 0. => Err(
+                MoolahSharedError::RetirementError("withdrawals cannot be negative".into()),
+            ),
+            WithdrawalRule::Percentage { rate }
+                if !rate.is_finite() || !(0. ..=100.).contains(&rate) =>
+            {
+                Err(MoolahSharedError::RetirementError(
+                    "withdrawal rate must be between 0 and 100".into(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// One year of a retirement projection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetirementYear {
+    pub starts_on: NaiveDate,
+    /// Age at the start of the year
+    pub age: u32,
+    pub contributions: f32,
+    pub growth: f32,
+    pub withdrawals: f32,
+    /// Planned withdrawals that savings could not cover
+    pub shortfall: f32,
+    /// Balance at the end of the year
+    pub balance: f32,
+    /// `balance` in today's money
+    pub real_balance: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetirementProjection {
+    pub years: Vec<RetirementYear>,
+    pub balance_at_retirement: f32,
+    /// Age at which savings run out, if they do before the end of the
+    /// projection
+    pub depletion_age: Option<u32>,
+}
+
+/// Project savings a year at a time from `request.start_on` until
+/// `request.until_age`. The request should have passed
+/// [`RetirementRequest::validate`].
+///
+/// Until the retirement date, every occurrence of a contributing delta pays its
+/// amount into savings (a delta taking money out of the prediction pays in).
+/// Withdrawals start with the first year beginning on or after the retirement
+/// date. Each year the balance grows by the expected return, then takes in
+/// contributions and pays out withdrawals.
+pub fn project(request: &RetirementRequest, deltas: &[Delta]) -> RetirementProjection {
+    let contributions = deltas
+        .iter()
+        .filter(|delta| request.contribution_delta_ids.contains(&delta.id()))
+        .collect::<Vec<_>>();
+
+    let annual_return = request.expected_return as f64 / 100.;
+    let inflation = 1. + request.inflation as f64 / 100.;
+    let last_saving_day = request.retire_on.pred();
+    let end = add_years(request.born_on, request.until_age)
+        .expect("a validated request ends within the calendar");
+
+    let mut balance = request.starting_balance as f64;
+    // first year of retirement along with the balance going into it
+    let mut retirement = None;
+    let mut depletion_age = None;
+    let mut years = Vec::new();
+
+    for year in 0.. {
+        let starts_on = match add_years(request.start_on, year) {
+            Some(starts_on) if starts_on < end => starts_on,
+            _ => break,
+        };
+        let ends_on = add_years(request.start_on, year + 1)
+            .map_or(end.pred(), |next| next.pred().min(end.pred()));
+        let age = years_between(request.born_on, starts_on);
+        let price_level = inflation.powi(year as i32);
+
+        let contributed = if starts_on <= last_saving_day {
+            let until = ends_on.min(last_saving_day);
+            contributions
+                .iter()
+                .map(|delta| {
+                    -delta.value() as f64
+                        * delta.repetition().dates_between(starts_on, until).len() as f64
+                })
+                .sum()
+        } else {
+            0.
+        };
+
+        let planned = if starts_on >= request.retire_on {
+            let (retired_year, retired_balance) = *retirement.get_or_insert((year, balance));
+            match request.withdrawal {
+                WithdrawalRule::Fixed { annual } => annual as f64 * price_level,
+                WithdrawalRule::Percentage { rate } => {
+                    retired_balance * rate as f64 / 100.
+                        * inflation.powi((year - retired_year) as i32)
+                }
+            }
+        } else {
+            0.
+        };
+
+        let growth = balance * annual_return;
+        let available = (balance + growth + contributed).max(0.);
+        let withdrawn = planned.min(available);
+        balance = available - withdrawn;
+
+        if depletion_age.is_none() && planned > withdrawn {
+            depletion_age = Some(age);
+        }
+
+        years.push(RetirementYear {
+            starts_on,
+            age,
+            contributions: round_cents(contributed),
+            growth: round_cents(growth),
+            withdrawals: round_cents(withdrawn),
+            shortfall: round_cents(planned - withdrawn),
+            balance: round_cents(balance),
+            real_balance: round_cents(balance / (price_level * inflation)),
+        });
+    }
+
+    RetirementProjection {
+        years,
+        balance_at_retirement: round_cents(retirement.map_or(balance, |(_, balance)| balance)),
+        depletion_age,
+    }
+}
+
+/// The same day `years` years later, or the 28th for a 29th of February that
+/// lands outside a leap year. `None` if that is past the last date chrono can
+/// represent.
+fn add_years(date: NaiveDate, years: u32) -> Option<NaiveDate> {
+    let year = date.year().checked_add(i32::try_from(years).ok()?)?;
+    NaiveDate::from_ymd_opt(year, date.month(), date.day())
+        .or_else(|| NaiveDate::from_ymd_opt(year, date.month(), date.day() - 1))
+}
+
+/// Whole years from `from` to `to`.
+fn years_between(from: NaiveDate, to: NaiveDate) -> u32 {
+    let mut years = (to.year() - from.year()).max(0) as u32;
+    if years > 0 && !matches!(add_years(from, years), Some(anniversary) if anniversary <= to) {
+        years -= 1;
+    }
+    years
+}
+
+fn round_cents(value: f64) -> f32 {
+    ((value * 100.).round() / 100.) as f32
+}
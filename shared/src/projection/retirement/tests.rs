@@ -0,0 +1,146 @@
+use super::*;
+use crate::models::deltas::app::repetition::MonthDay;
+use crate::models::Repetition;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn request(withdrawal: WithdrawalRule) -> RetirementRequest {
+    RetirementRequest {
+        prediction_id: 1,
+        born_on: date(1990, 6, 15),
+        start_on: date(2026, 1, 1),
+        retire_on: date(2028, 1, 1),
+        until_age: 45,
+        starting_balance: 10000.,
+        expected_return: 0.,
+        inflation: 0.,
+        contribution_delta_ids: vec![1],
+        withdrawal,
+    }
+}
+
+fn deltas() -> Vec<Delta> {
+    vec![
+        Delta::new(
+            1,
+            1,
+            "401k".into(),
+            -500.,
+            0.,
+            0.,
+            Repetition::Monthly {
+                from: date(2026, 1, 1),
+                to: date(2060, 12, 31),
+                repeat_on_day: MonthDay::new(1).unwrap(),
+            },
+        ),
+        Delta::new(
+            2,
+            1,
+            "salary".into(),
+            4000.,
+            0.,
+            0.,
+            Repetition::Monthly {
+                from: date(2026, 1, 1),
+                to: date(2060, 12, 31),
+                repeat_on_day: MonthDay::new(1).unwrap(),
+            },
+        ),
+    ]
+}
+
+#[test]
+fn test_contributions_stop_at_retirement() {
+    let projection = project(&request(WithdrawalRule::Fixed { annual: 0. }), &deltas());
+
+    // yearly steps from the start until turning 45
+    assert_eq!(projection.years.len(), 10);
+    assert_eq!(projection.years[0].age, 35);
+    assert_eq!(projection.years[0].contributions, 6000.);
+    assert_eq!(projection.years[1].contributions, 6000.);
+    assert_eq!(projection.years[2].contributions, 0.);
+    assert_eq!(projection.balance_at_retirement, 22000.);
+    assert_eq!(projection.years.last().unwrap().balance, 22000.);
+    assert_eq!(projection.depletion_age, None);
+}
+
+#[test]
+fn test_fixed_withdrawals_deplete() {
+    let projection = project(&request(WithdrawalRule::Fixed { annual: 5000. }), &deltas());
+
+    assert_eq!(projection.years[2].withdrawals, 5000.);
+    assert_eq!(projection.years[2].balance, 17000.);
+    // 22000 covers four full years and part of a fifth
+    assert_eq!(projection.years[6].withdrawals, 2000.);
+    assert_eq!(projection.years[6].shortfall, 3000.);
+    assert_eq!(projection.depletion_age, Some(projection.years[6].age));
+}
+
+#[test]
+fn test_percentage_withdrawals_follow_balance_at_retirement() {
+    let mut request = request(WithdrawalRule::Percentage { rate: 10. });
+    request.inflation = 10.;
+
+    let projection = project(&request, &deltas());
+
+    assert_eq!(projection.years[2].withdrawals, 2200.);
+    assert_eq!(projection.years[3].withdrawals, 2420.);
+}
+
+#[test]
+fn test_growth_and_inflation() {
+    let mut request = request(WithdrawalRule::Fixed { annual: 1000. });
+    request.contribution_delta_ids.clear();
+    request.expected_return = 10.;
+    request.inflation = 10.;
+
+    let projection = project(&request, &deltas());
+
+    assert_eq!(projection.years[0].growth, 1000.);
+    assert_eq!(projection.years[0].balance, 11000.);
+    assert_eq!(projection.years[0].real_balance, 10000.);
+    // withdrawals keep pace with inflation
+    assert_eq!(projection.years[2].withdrawals, 1210.);
+}
+
+#[test]
+fn test_validate() {
+    assert!(request(WithdrawalRule::Fixed { annual: 1000. })
+        .validate()
+        .is_ok());
+    assert!(request(WithdrawalRule::Fixed { annual: -1. })
+        .validate()
+        .is_err());
+    assert!(request(WithdrawalRule::Percentage { rate: 101. })
+        .validate()
+        .is_err());
+
+    let mut too_short = request(WithdrawalRule::Fixed { annual: 0. });
+    too_short.until_age = 37;
+    assert!(too_short.validate().is_err());
+
+    let mut too_old = request(WithdrawalRule::Fixed { annual: 0. });
+    too_old.until_age = MAX_AGE + 1;
+    assert!(too_old.validate().is_err());
+}
+
+#[test]
+fn test_validate_dates_past_the_calendar() {
+    let mut far_future = request(WithdrawalRule::Fixed { annual: 0. });
+    far_future.born_on = date(262100, 1, 1);
+    far_future.start_on = date(262101, 1, 1);
+    far_future.retire_on = date(262102, 1, 1);
+
+    assert!(far_future.validate().is_err());
+}
+
+#[test]
+fn test_add_years() {
+    assert_eq!(Some(date(2025, 2, 28)), add_years(date(2024, 2, 29), 1));
+    assert_eq!(Some(date(2028, 2, 29)), add_years(date(2024, 2, 29), 4));
+    assert_eq!(None, add_years(date(262100, 1, 1), 100));
+    assert_eq!(None, add_years(date(2024, 1, 1), u32::MAX));
+}
@@ -0,0 +1,108 @@
+use super::*;
+use crate::models::deltas::app::repetition::MonthDay;
+use crate::models::{DeltaKind, Repetition};
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+#[test]
+fn test_schedule_running_balance() {
+    let deltas = vec![
+        Delta::new(
+            1,
+            1,
+            "paycheck".into(),
+            1000.,
+            0.,
+            0.,
+            Repetition::Monthly {
+                from: date(2022, 1, 1),
+                to: date(2022, 2, 28),
+                repeat_on_day: MonthDay::new(15).unwrap(),
+            },
+        ),
+        Delta::new(
+            2,
+            1,
+            "groceries".into(),
+            -100.,
+            20.,
+            50.,
+            Repetition::Weekly {
+                from: date(2022, 1, 10),
+                to: date(2022, 1, 24),
+                repeat_on_weekday: chrono::Weekday::Sat,
+            },
+        ),
+        Delta::new(
+            3,
+            1,
+            "gift".into(),
+            25.,
+            0.,
+            0.,
+            Repetition::Once {
+                on: date(2022, 1, 15),
+            },
+        ),
+    ];
+
+    let occurrences = schedule(&deltas, 500.);
+
+    assert_eq!(
+        occurrences
+            .iter()
+            .map(|occurrence| (occurrence.date, occurrence.delta_id, occurrence.balance))
+            .collect::<Vec<_>>(),
+        vec![
+            (date(2022, 1, 15), 1, 1500.),
+            (date(2022, 1, 15), 2, 1400.),
+            (date(2022, 1, 15), 3, 1425.),
+            (date(2022, 1, 22), 2, 1325.),
+            (date(2022, 2, 15), 1, 2325.),
+        ]
+    );
+
+    let last = occurrences.last().unwrap();
+    assert_eq!(last.balance_high, 2365.);
+    assert_eq!(last.balance_low, 2225.);
+}
+
+#[test]
+fn test_schedule_without_deltas() {
+    assert_eq!(schedule(&[], 100.), vec![]);
+}
+
+#[test]
+fn test_schedule_skips_deductions() {
+    let monthly = |id, value, kind| {
+        Delta::new(
+            id,
+            1,
+            "salary".into(),
+            value,
+            0.,
+            0.,
+            Repetition::Monthly {
+                from: date(2022, 1, 1),
+                to: date(2022, 2, 28),
+                repeat_on_day: MonthDay::new(15).unwrap(),
+            },
+        )
+        .with_kind(kind)
+    };
+    let deltas = vec![
+        monthly(1, 3800., DeltaKind::Income),
+        monthly(2, -500., DeltaKind::PreTaxDeduction),
+        monthly(3, -700., DeltaKind::Withholding),
+    ];
+
+    assert_eq!(
+        schedule(&deltas, 0.)
+            .iter()
+            .map(|occurrence| (occurrence.delta_id, occurrence.balance))
+            .collect::<Vec<_>>(),
+        vec![(1, 3800.), (1, 7600.)]
+    );
+}
@@ -0,0 +1,270 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::imports::ParsedTransaction;
+use crate::models::deltas::app::repetition::MonthDay;
+use crate::models::{NewDelta, Repetition};
+
+/// The fewest times a transaction has to show up before it is considered
+/// recurring.
+const MIN_OCCURRENCES: usize = 3;
+
+/// The window the proposed deltas should repeat over.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RecurringQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// A delta proposed from a group of similar transactions in a user's history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecurringDelta {
+    pub name: String,
+    /// The median of the observed amounts
+    pub value: f32,
+    /// How far above `value` the largest observed amount was
+    pub positive_uncertainty: f32,
+    /// How far below `value` the smallest observed amount was
+    pub negative_uncertainty: f32,
+    pub repetition: Repetition,
+    /// How many transactions the proposal is based on
+    pub occurrences: usize,
+}
+
+impl RecurringDelta {
+    pub fn into_new_delta(self, prediction_id: i32) -> NewDelta {
+        NewDelta::new(
+            prediction_id,
+            self.name,
+            self.value,
+            self.positive_uncertainty,
+            self.negative_uncertainty,
+            self.repetition,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cadence {
+    Daily,
+    Weekly,
+    Biweekly,
+    Monthly,
+}
+
+impl Cadence {
+    /// Classify the typical number of days between transactions.
+    fn from_gap(days: i64) -> Option<Self> {
+        match days {
+            1 => Some(Cadence::Daily),
+            6..=8 => Some(Cadence::Weekly),
+            13..=15 => Some(Cadence::Biweekly),
+            27..=34 => Some(Cadence::Monthly),
+            _ => None,
+        }
+    }
+
+    /// The gaps (in days) still consistent with the cadence, allowing for
+    /// weekends, holidays, and short months.
+    fn tolerance(&self) -> RangeInclusive<i64> {
+        match self {
+            Cadence::Daily => 0..=2,
+            Cadence::Weekly => 5..=9,
+            Cadence::Biweekly => 12..=16,
+            Cadence::Monthly => 25..=37,
+        }
+    }
+}
+
+/// Reduce a statement description to the part that stays the same from one
+/// occurrence to the next by dropping punctuation and any word containing a
+/// digit (check numbers, dates, reference ids, ...).
+pub fn normalize_description(description: &str) -> String {
+    let normalized = description
+        .to_uppercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !word.chars().any(|c| c.is_ascii_digit()))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if normalized.is_empty() {
+        description.trim().to_uppercase()
+    } else {
+        normalized
+    }
+}
+
+fn median(values: &mut [f32]) -> f32 {
+    values.sort_by(|a, b| a.partial_cmp(b).expect("amounts should not be NaN"));
+    (values[(values.len() - 1) / 2] + values[values.len() / 2]) / 2.
+}
+
+fn most_common_weekday(dates: &[NaiveDate]) -> Weekday {
+    let mut counts = [0usize; 7];
+    for date in dates {
+        counts[date.weekday().num_days_from_monday() as usize] += 1;
+    }
+
+    let (weekday, _) = counts
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|(_, count)| **count)
+        .expect("there are always 7 weekdays");
+
+    [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ][weekday]
+}
+
+/// The first `weekday` on or after `from` that falls in the same fortnightly
+/// cycle as `last`, so a biweekly delta lands on the weeks the payments do.
+fn next_in_phase(last: NaiveDate, weekday: Weekday, from: NaiveDate) -> NaiveDate {
+    let anchor = last - Duration::days(last.weekday().num_days_from_monday().into())
+        + Duration::days(weekday.num_days_from_monday().into());
+    let fortnights = ((from - anchor).num_days() + 13).div_euclid(14);
+    anchor + Duration::weeks(2 * fortnights)
+}
+
+fn propose(
+    transactions: &[&ParsedTransaction],
+    latest: NaiveDate,
+    query: &RecurringQuery,
+) -> Option<RecurringDelta> {
+    if transactions.len() < MIN_OCCURRENCES {
+        return None;
+    }
+
+    let dates = transactions
+        .iter()
+        .map(|transaction| transaction.posted_on)
+        .collect::<Vec<_>>();
+    let mut gaps = dates
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).num_days())
+        .collect::<Vec<_>>();
+    gaps.sort_unstable();
+
+    let cadence = Cadence::from_gap(gaps[gaps.len() / 2])?;
+    let tolerance = cadence.tolerance();
+
+    // most of the gaps have to fit the cadence, so a late payment (which throws
+    // off the gaps on both sides of it) doesn't hide a subscription but a few
+    // random purchases don't make one
+    let regular = gaps.iter().filter(|gap| tolerance.contains(gap)).count();
+    if regular * 2 <= gaps.len() {
+        return None;
+    }
+
+    // stopped showing up (e.g. a cancelled subscription)
+    let last = *dates.last().expect("checked there are enough transactions");
+    if (latest - last).num_days() > 2 * tolerance.end() {
+        return None;
+    }
+
+    let mut amounts = transactions
+        .iter()
+        .map(|transaction| transaction.amount)
+        .collect::<Vec<_>>();
+    let value = median(&mut amounts);
+    let (min, max) = (amounts[0], amounts[amounts.len() - 1]);
+
+    let repetition = match cadence {
+        Cadence::Daily => Repetition::Daily {
+            from: query.from,
+            to: query.to,
+        },
+        Cadence::Weekly => Repetition::Weekly {
+            from: query.from,
+            to: query.to,
+            repeat_on_weekday: most_common_weekday(&dates),
+        },
+        Cadence::Biweekly => {
+            let repeat_on_weekday = most_common_weekday(&dates);
+            let from = next_in_phase(last, repeat_on_weekday, query.from);
+            if from > query.to {
+                return None;
+            }
+            Repetition::Biweekly {
+                from,
+                to: query.to,
+                repeat_on_weekday,
+            }
+        }
+        Cadence::Monthly => {
+            let mut days = dates.iter().map(|date| date.day()).collect::<Vec<_>>();
+            days.sort_unstable();
+            Repetition::Monthly {
+                from: query.from,
+                to: query.to,
+                repeat_on_day: MonthDay::new(days[days.len() / 2] as i16)
+                    .expect("day of month is always 1-31"),
+            }
+        }
+    };
+
+    Some(RecurringDelta {
+        name: transactions
+            .last()
+            .expect("checked there are enough transactions")
+            .description
+            .trim()
+            .to_string(),
+        value,
+        positive_uncertainty: max - value,
+        negative_uncertainty: value - min,
+        repetition,
+        occurrences: transactions.len(),
+    })
+}
+
+/// Propose recurring deltas from a transaction history.
+///
+/// Transactions are grouped by their normalized description (and by whether
+/// money came in or went out), and each group that happens on a regular daily,
+/// weekly, biweekly or monthly schedule becomes a delta repeating over the
+/// query window.
+pub fn detect(transactions: &[ParsedTransaction], query: &RecurringQuery) -> Vec<RecurringDelta> {
+    let latest = match transactions
+        .iter()
+        .map(|transaction| transaction.posted_on)
+        .max()
+    {
+        Some(latest) => latest,
+        None => return Vec::new(),
+    };
+
+    let mut groups = HashMap::<(String, bool), Vec<&ParsedTransaction>>::new();
+    for transaction in transactions {
+        groups
+            .entry((
+                normalize_description(&transaction.description),
+                transaction.amount.is_sign_negative(),
+            ))
+            .or_default()
+            .push(transaction);
+    }
+
+    let mut proposals = groups
+        .into_values()
+        .filter_map(|mut group| {
+            group.sort_by_key(|transaction| transaction.posted_on);
+            propose(&group, latest, query)
+        })
+        .collect::<Vec<_>>();
+
+    proposals.sort_by(|a, b| a.name.cmp(&b.name));
+    proposals
+}
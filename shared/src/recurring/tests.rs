@@ -0,0 +1,171 @@
+use super::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+fn transaction(posted_on: NaiveDate, amount: f32, description: &str) -> ParsedTransaction {
+    ParsedTransaction {
+        posted_on,
+        amount,
+        description: description.into(),
+        fitid: None,
+        account: None,
+    }
+}
+
+fn query() -> RecurringQuery {
+    RecurringQuery {
+        from: date(2023, 1, 1),
+        to: date(2023, 12, 31),
+    }
+}
+
+#[test]
+fn test_normalize_description() {
+    assert_eq!(
+        normalize_description("Netflix.com 866-579-7172 CA"),
+        "NETFLIX COM CA"
+    );
+    assert_eq!(
+        normalize_description("CHECK #1043 rent"),
+        normalize_description("Check #1044 Rent")
+    );
+    assert_eq!(normalize_description("1234"), "1234");
+}
+
+#[test]
+fn test_monthly() {
+    let transactions = vec![
+        transaction(date(2022, 9, 1), -1450., "RENT PAYMENT #0901"),
+        transaction(date(2022, 10, 3), -1450., "RENT PAYMENT #1003"),
+        transaction(date(2022, 11, 1), -1450., "RENT PAYMENT #1101"),
+        transaction(date(2022, 12, 1), -1500., "RENT PAYMENT #1201"),
+        transaction(date(2022, 12, 14), -30., "HARDWARE STORE"),
+    ];
+
+    assert_eq!(
+        detect(&transactions, &query()),
+        vec![RecurringDelta {
+            name: "RENT PAYMENT #1201".into(),
+            value: -1450.,
+            positive_uncertainty: 0.,
+            negative_uncertainty: 50.,
+            repetition: Repetition::Monthly {
+                from: date(2023, 1, 1),
+                to: date(2023, 12, 31),
+                repeat_on_day: MonthDay::new(1).unwrap(),
+            },
+            occurrences: 4,
+        }]
+    );
+}
+
+#[test]
+fn test_weekly_with_a_late_payment() {
+    // fridays, except one that slipped to the following monday
+    let transactions = [
+        date(2022, 11, 4),
+        date(2022, 11, 11),
+        date(2022, 11, 18),
+        date(2022, 11, 28),
+        date(2022, 12, 2),
+        date(2022, 12, 9),
+    ]
+    .into_iter()
+    .map(|posted_on| transaction(posted_on, 20., "ALLOWANCE"))
+    .collect::<Vec<_>>();
+
+    let proposals = detect(&transactions, &query());
+
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(
+        proposals[0].repetition,
+        Repetition::Weekly {
+            from: date(2023, 1, 1),
+            to: date(2023, 12, 31),
+            repeat_on_weekday: Weekday::Fri,
+        }
+    );
+}
+
+#[test]
+fn test_biweekly() {
+    // every other friday, with one paid a day early
+    let transactions = [
+        date(2022, 11, 4),
+        date(2022, 11, 18),
+        date(2022, 12, 1),
+        date(2022, 12, 16),
+        date(2022, 12, 30),
+    ]
+    .into_iter()
+    .map(|posted_on| transaction(posted_on, 2100., "ACME CORP PAYROLL"))
+    .collect::<Vec<_>>();
+
+    let proposals = detect(&transactions, &query());
+
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(
+        proposals[0].repetition,
+        Repetition::Biweekly {
+            from: date(2023, 1, 13),
+            to: date(2023, 12, 31),
+            repeat_on_weekday: Weekday::Fri,
+        }
+    );
+    assert_eq!(
+        proposals[0].repetition.dates()[..2],
+        [date(2023, 1, 13), date(2023, 1, 27)]
+    );
+}
+
+#[test]
+fn test_income_and_spending_are_separate() {
+    let transactions = [1, 2, 3]
+        .into_iter()
+        .flat_map(|month| {
+            [
+                transaction(date(2022, month, 15), -9.99, "STREAMING SVC"),
+                transaction(date(2022, month, 20), 9.99, "STREAMING SVC"),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let values = detect(
+        &transactions,
+        &RecurringQuery {
+            from: date(2022, 4, 1),
+            to: date(2022, 12, 31),
+        },
+    )
+    .into_iter()
+    .map(|proposal| proposal.value)
+    .collect::<Vec<_>>();
+
+    assert_eq!(values.len(), 2);
+    assert!(values.contains(&-9.99));
+    assert!(values.contains(&9.99));
+}
+
+#[test]
+fn test_irregular_sparse_and_stopped_are_ignored() {
+    let transactions = vec![
+        // irregular
+        transaction(date(2022, 1, 3), -12., "COFFEE"),
+        transaction(date(2022, 1, 4), -12., "COFFEE"),
+        transaction(date(2022, 2, 20), -12., "COFFEE"),
+        transaction(date(2022, 6, 1), -12., "COFFEE"),
+        // too few
+        transaction(date(2022, 5, 1), -60., "INTERNET"),
+        transaction(date(2022, 6, 1), -60., "INTERNET"),
+        // cancelled in the spring
+        transaction(date(2022, 1, 10), -15., "GYM"),
+        transaction(date(2022, 2, 10), -15., "GYM"),
+        transaction(date(2022, 3, 10), -15., "GYM"),
+        transaction(date(2022, 12, 30), -5., "PARKING"),
+    ];
+
+    assert_eq!(detect(&transactions, &query()), vec![]);
+    assert_eq!(detect(&[], &query()), vec![]);
+}
@@ -1,6 +1,40 @@
 pub const LOGOUT: &str = "/api/logout";
-pub const LOGIN_REQUEST_PASSWORD: &str = "/api/login-pass";
 pub const LOGIN: &str = "/api/login";
+pub const LOGIN_TWO_FACTOR: &str = "/api/login/two-factor";
 pub const USER: &str = "api/user";
+pub const USER_PASSWORD: &str = "api/user/password";
+pub const USER_EMAIL: &str = "api/user/email";
+pub const USER_USERNAME: &str = "api/user/username";
+pub const TOTP: &str = "api/user/totp";
+pub const TOTP_RECOVERY_CODES: &str = "api/user/totp/recovery-codes";
+pub const PASSWORD_RESET_REQUEST: &str = "api/password-reset/request";
+pub const PASSWORD_RESET: &str = "api/password-reset";
+pub const EMAIL_VERIFICATION_REQUEST: &str = "api/email-verification/request";
+pub const EMAIL_VERIFICATION: &str = "api/email-verification";
+pub const SESSIONS: &str = "api/sessions/{username}";
+pub const API_TOKENS: &str = "api/tokens/{username}";
 pub const PREDICTIONS: &str = "api/predictions/{username}";
 pub const DELTAS: &str = "api/deltas/{username}";
+pub const ASSETS: &str = "api/assets/{username}";
+pub const ASSET_VALUATIONS: &str = "api/assets/{username}/valuations";
+pub const GOALS: &str = "api/goals/{username}";
+pub const GOALS_PROGRESS: &str = "api/goals/{username}/progress";
+pub const INVESTMENTS: &str = "api/investments/{username}";
+pub const INVESTMENTS_PROJECTION: &str = "api/investments/{username}/projection";
+pub const NET_WORTH: &str = "api/net-worth/{username}";
+pub const GENERATE_LOAN: &str = "api/generate/loan/{username}";
+pub const GENERATE_DEBT_PAYOFF: &str = "api/generate/debt-payoff/{username}";
+pub const GENERATE_PAYCHECK: &str = "api/generate/paycheck/{username}";
+pub const TAX_ESTIMATE: &str = "api/taxes/{username}/estimate";
+pub const RETIREMENT_PROJECTION: &str = "api/retirement/{username}/projection";
+pub const TRANSACTIONS: &str = "api/transactions/{username}";
+pub const IMPORT_CSV: &str = "api/import/csv/{username}";
+pub const IMPORT_OFX: &str = "api/import/ofx/{username}";
+pub const IMPORT_QIF: &str = "api/import/qif/{username}";
+pub const IMPORT_LEDGER: &str = "api/import/ledger/{username}";
+pub const RECURRING: &str = "api/recurring/{username}";
+pub const RULES: &str = "api/rules/{username}";
+pub const RULES_APPLY: &str = "api/rules/{username}/apply";
+pub const EXPORT_DELTAS: &str = "api/export/deltas/{username}";
+pub const EXPORT_SCHEDULE: &str = "api/export/schedule/{username}";
+pub const EXPORT_JOURNAL: &str = "api/export/journal/{username}";
@@ -1,3 +1,48 @@
+table! {
+    api_tokens (id) {
+        id -> Int4,
+        username -> Varchar,
+        name -> Varchar,
+        token_hash -> Varchar,
+        scopes -> Varchar,
+        created -> Timestamp,
+        last_used -> Nullable<Timestamp>,
+        expires -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    asset_valuations (id) {
+        id -> Int4,
+        asset_id -> Int4,
+        valued_on -> Date,
+        value -> Float4,
+    }
+}
+
+table! {
+    assets (id) {
+        id -> Int4,
+        prediction_id -> Int4,
+        name -> Varchar,
+        is_liability -> Bool,
+        annual_rate -> Nullable<Float4>,
+    }
+}
+
+table! {
+    categorization_rules (id) {
+        id -> Int4,
+        username -> Varchar,
+        category -> Varchar,
+        description_pattern -> Nullable<Varchar>,
+        min_amount -> Nullable<Float4>,
+        max_amount -> Nullable<Float4>,
+        account -> Nullable<Varchar>,
+        delta_id -> Nullable<Int4>,
+    }
+}
+
 table! {
     deltas (id) {
         id -> Int4,
@@ -11,6 +56,38 @@ table! {
         end_on -> Nullable<Date>,
         repeat_day -> Nullable<Int2>,
         repeat_weekday -> Nullable<Varchar>,
+        kind -> Varchar,
+    }
+}
+
+table! {
+    goals (id) {
+        id -> Int4,
+        prediction_id -> Int4,
+        name -> Varchar,
+        target_balance -> Float4,
+        target_date -> Nullable<Date>,
+        starting_balance -> Float4,
+        start_on -> Date,
+    }
+}
+
+table! {
+    investment_accounts (id) {
+        id -> Int4,
+        prediction_id -> Int4,
+        name -> Varchar,
+        starting_balance -> Float4,
+        start_on -> Date,
+        expected_return -> Float4,
+        volatility -> Float4,
+    }
+}
+
+table! {
+    investment_contributions (investment_account_id, delta_id) {
+        investment_account_id -> Int4,
+        delta_id -> Int4,
     }
 }
 
@@ -22,6 +99,63 @@ table! {
     }
 }
 
+table! {
+    recovery_codes (id) {
+        id -> Int4,
+        username -> Varchar,
+        code_hash -> Varchar,
+        used -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    sessions (id) {
+        id -> Int4,
+        token_hash -> Varchar,
+        username -> Varchar,
+        created -> Timestamp,
+        last_seen -> Timestamp,
+        user_agent -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    transactions (id) {
+        id -> Int4,
+        username -> Varchar,
+        posted_on -> Date,
+        amount -> Float4,
+        description -> Varchar,
+        imported -> Timestamp,
+        fitid -> Nullable<Varchar>,
+        account -> Nullable<Varchar>,
+        category -> Nullable<Varchar>,
+        delta_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    user_tokens (id) {
+        id -> Int4,
+        username -> Varchar,
+        purpose -> Varchar,
+        token_hash -> Varchar,
+        created -> Timestamp,
+        expires -> Timestamp,
+        used -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    user_totp (username) {
+        username -> Varchar,
+        secret -> Varchar,
+        created -> Timestamp,
+        enabled_at -> Nullable<Timestamp>,
+        last_step -> Nullable<Int8>,
+    }
+}
+
 table! {
     users (id) {
         id -> Int4,
@@ -30,13 +164,34 @@ table! {
         password -> Varchar,
         created -> Timestamp,
         last_login -> Timestamp,
+        verified_at -> Nullable<Timestamp>,
     }
 }
 
+joinable!(asset_valuations -> assets (asset_id));
+joinable!(assets -> predictions (prediction_id));
+joinable!(categorization_rules -> deltas (delta_id));
 joinable!(deltas -> predictions (prediction_id));
+joinable!(goals -> predictions (prediction_id));
+joinable!(investment_accounts -> predictions (prediction_id));
+joinable!(investment_contributions -> deltas (delta_id));
+joinable!(investment_contributions -> investment_accounts (investment_account_id));
+joinable!(transactions -> deltas (delta_id));
 
 allow_tables_to_appear_in_same_query!(
+    api_tokens,
+    asset_valuations,
+    assets,
+    categorization_rules,
     deltas,
+    goals,
+    investment_accounts,
+    investment_contributions,
     predictions,
+    recovery_codes,
+    sessions,
+    transactions,
+    user_tokens,
+    user_totp,
     users,
 );
@@ -0,0 +1,245 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Delta, DeltaKind};
+use crate::MoolahSharedError;
+
+lazy_static! {
+    /// The tax tables bundled with moolah, read from `taxes/tables.csv`.
+    pub static ref DEFAULT_TABLES: Vec<TaxTable> =
+        read_tables(include_str!("taxes/tables.csv")).expect("bundled tax tables are invalid");
+}
+
+/// A marginal rate applied to the part of income above `over`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxBracket {
+    /// Income at which this bracket starts
+    pub over: f32,
+    /// Rate as a percentage, e.g. `22`
+    pub rate: f32,
+}
+
+/// Check that brackets start at increasing incomes and have sensible rates.
+pub fn validate_brackets(brackets: &[TaxBracket]) -> Result<(), MoolahSharedError> {
+    if brackets.is_empty() {
+        return Err(MoolahSharedError::TaxError(
+            "a bracket table needs at least one bracket".into(),
+        ));
+    }
+
+    for (i, bracket) in brackets.iter().enumerate() {
+        if !bracket.over.is_finite() || bracket.over < 0. {
+            return Err(MoolahSharedError::TaxError(format!(
+                "bracket {} starts below 0",
+                i + 1
+            )));
+        }
+        if !bracket.rate.is_finite() || bracket.rate < 0. || bracket.rate > 100. {
+            return Err(MoolahSharedError::TaxError(format!(
+                "bracket {} rate must be between 0 and 100",
+                i + 1
+            )));
+        }
+        if i > 0 && bracket.over <= brackets[i - 1].over {
+            return Err(MoolahSharedError::TaxError(
+                "brackets must start at increasing incomes".into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Tax owed on `income` under marginal `brackets`, which must be sorted by
+/// where they start. Income below the first bracket is untaxed.
+pub fn tax_on(brackets: &[TaxBracket], income: f64) -> f64 {
+    brackets
+        .iter()
+        .enumerate()
+        .map(|(i, bracket)| {
+            let lower = bracket.over as f64;
+            let upper = brackets
+                .get(i + 1)
+                .map_or(f64::INFINITY, |next| next.over as f64);
+
+            (income.min(upper) - lower).max(0.) * bracket.rate as f64 / 100.
+        })
+        .sum()
+}
+
+/// Read brackets written as `over:rate` pairs separated by commas or new lines,
+/// e.g. `0:10, 11600:12, 47150:22`.
+pub fn parse_brackets(text: &str) -> Result<Vec<TaxBracket>, MoolahSharedError> {
+    let brackets = text
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (over, rate) = pair.split_once(':').ok_or_else(|| {
+                MoolahSharedError::TaxError(format!("expected `over:rate` but got `{}`", pair))
+            })?;
+
+            let parse = |value: &str| {
+                value
+                    .trim()
+                    .trim_start_matches('$')
+                    .trim_end_matches('%')
+                    .replace('_', "")
+                    .parse::<f32>()
+                    .map_err(|_| MoolahSharedError::TaxError(format!("invalid number `{}`", value)))
+            };
+
+            Ok(TaxBracket {
+                over: parse(over)?,
+                rate: parse(rate)?,
+            })
+        })
+        .collect::<Result<Vec<_>, MoolahSharedError>>()?;
+
+    validate_brackets(&brackets)?;
+    Ok(brackets)
+}
+
+/// The inverse of [`parse_brackets`].
+pub fn format_brackets(brackets: &[TaxBracket]) -> String {
+    brackets
+        .iter()
+        .map(|bracket| format!("{}:{}", bracket.over, bracket.rate))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A named set of annual brackets along with the deduction taken off income
+/// before they apply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxTable {
+    pub name: String,
+    pub standard_deduction: f32,
+    pub brackets: Vec<TaxBracket>,
+}
+
+impl TaxTable {
+    pub fn validate(&self) -> Result<(), MoolahSharedError> {
+        if !self.standard_deduction.is_finite() || self.standard_deduction < 0. {
+            return Err(MoolahSharedError::TaxError(
+                "standard deduction cannot be negative".into(),
+            ));
+        }
+        validate_brackets(&self.brackets)
+    }
+}
+
+#[derive(Deserialize)]
+struct TaxTableRow {
+    name: String,
+    standard_deduction: f32,
+    brackets: String,
+}
+
+/// Read tax tables from CSV with `name`, `standard_deduction` and `brackets`
+/// columns, the brackets written the way [`parse_brackets`] reads them.
+pub fn read_tables(contents: &str) -> Result<Vec<TaxTable>, MoolahSharedError> {
+    ::csv::ReaderBuilder::new()
+        .trim(::csv::Trim::All)
+        .from_reader(contents.as_bytes())
+        .deserialize::<TaxTableRow>()
+        .map(|row| {
+            let row = row.map_err(|err| MoolahSharedError::TaxError(err.to_string()))?;
+            let table = TaxTable {
+                name: row.name,
+                standard_deduction: row.standard_deduction,
+                brackets: parse_brackets(&row.brackets)?,
+            };
+            table.validate()?;
+            Ok(table)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxEstimateRequest {
+    pub prediction_id: i32,
+    pub year: i32,
+    pub table: TaxTable,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxEstimate {
+    pub year: i32,
+    /// Taxable income over the year
+    pub income: f32,
+    /// Pre-tax paycheck deductions, which are not taxed
+    pub pre_tax_deductions: f32,
+    pub standard_deduction: f32,
+    pub taxable_income: f32,
+    pub tax: f32,
+    /// Tax already paid through paycheck withholding
+    pub withheld: f32,
+    /// What comes back at tax time; negative when there is a balance due
+    pub refund: f32,
+}
+
+/// Estimate a year's income tax from a prediction's deltas and compare it with
+/// what is withheld along the way.
+///
+/// Deltas count by their kind over every occurrence in `year`. Income deltas
+/// are pay as it's paid, so the deductions and withholding taken out before
+/// it's paid are added back to get gross income; pre-tax deductions then come
+/// off it untaxed and withholding counts as tax paid. Deltas of any other kind
+/// are ignored, whichever way they go.
+pub fn estimate(
+    deltas: &[Delta],
+    year: i32,
+    table: &TaxTable,
+) -> Result<TaxEstimate, MoolahSharedError> {
+    let (first_day, last_day) = match (
+        NaiveDate::from_ymd_opt(year, 1, 1),
+        NaiveDate::from_ymd_opt(year, 12, 31),
+    ) {
+        (Some(first_day), Some(last_day)) => (first_day, last_day),
+        _ => {
+            return Err(MoolahSharedError::TaxError(format!(
+                "{} is not a year taxes can be estimated for",
+                year
+            )))
+        }
+    };
+
+    let mut income = 0.;
+    let mut pre_tax_deductions = 0.;
+    let mut withheld = 0.;
+
+    for delta in deltas {
+        let occurrences = delta.repetition().dates_between(first_day, last_day).len() as f64;
+        let total = delta.value() as f64 * occurrences;
+
+        match delta.kind() {
+            DeltaKind::Income => income += total,
+            DeltaKind::PreTaxDeduction => pre_tax_deductions -= total,
+            DeltaKind::Withholding => withheld -= total,
+            DeltaKind::Other => {}
+        }
+    }
+
+    // income deltas are what's left once deductions and withholding come out
+    income += pre_tax_deductions + withheld;
+    let taxable_income = (income - pre_tax_deductions - table.standard_deduction as f64).max(0.);
+    let tax = tax_on(&table.brackets, taxable_income);
+
+    Ok(TaxEstimate {
+        year,
+        income: round_cents(income),
+        pre_tax_deductions: round_cents(pre_tax_deductions),
+        standard_deduction: table.standard_deduction,
+        taxable_income: round_cents(taxable_income),
+        tax: round_cents(tax),
+        withheld: round_cents(withheld),
+        refund: round_cents(withheld - tax),
+    })
+}
+
+fn round_cents(value: f64) -> f32 {
+    ((value * 100.).round() / 100.) as f32
+}
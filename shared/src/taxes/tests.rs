@@ -0,0 +1,189 @@
+use super::*;
+
+fn brackets() -> Vec<TaxBracket> {
+    vec![
+        TaxBracket {
+            over: 0.,
+            rate: 10.,
+        },
+        TaxBracket {
+            over: 10000.,
+            rate: 20.,
+        },
+        TaxBracket {
+            over: 50000.,
+            rate: 30.,
+        },
+    ]
+}
+
+#[test]
+fn test_tax_on() {
+    assert_eq!(tax_on(&brackets(), 0.), 0.);
+    assert_eq!(tax_on(&brackets(), 5000.), 500.);
+    assert_eq!(tax_on(&brackets(), 10000.), 1000.);
+    assert_eq!(tax_on(&brackets(), 30000.), 5000.);
+    assert_eq!(tax_on(&brackets(), 60000.), 12000.);
+}
+
+#[test]
+fn test_income_below_first_bracket_untaxed() {
+    let brackets = vec![TaxBracket {
+        over: 12000.,
+        rate: 10.,
+    }];
+
+    assert_eq!(tax_on(&brackets, 10000.), 0.);
+    assert_eq!(tax_on(&brackets, 13000.), 100.);
+}
+
+#[test]
+fn test_parse_brackets() {
+    // thousands separators clash with the pair separator
+    assert!(parse_brackets("0:10, 10000:20\n$50,000:30%").is_err());
+    assert_eq!(
+        parse_brackets("0:10, 10_000:20\n$50000:30%").unwrap(),
+        brackets()
+    );
+    assert_eq!(
+        parse_brackets(&format_brackets(&brackets())).unwrap(),
+        brackets()
+    );
+}
+
+#[test]
+fn test_invalid_brackets() {
+    assert!(parse_brackets("").is_err());
+    assert!(parse_brackets("0:10, 0:20").is_err());
+    assert!(parse_brackets("0:10, 100:120").is_err());
+    assert!(parse_brackets("0-10").is_err());
+    assert!(parse_brackets("zero:10").is_err());
+}
+
+fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+    chrono::NaiveDate::from_ymd(y, m, d)
+}
+
+fn monthly(name: &str, value: f32, kind: DeltaKind) -> Delta {
+    use crate::models::{deltas::app::repetition::MonthDay, Repetition};
+
+    Delta::new(
+        1,
+        1,
+        name.into(),
+        value,
+        0.,
+        0.,
+        Repetition::Monthly {
+            from: date(2025, 7, 1),
+            to: date(2026, 12, 31),
+            repeat_on_day: MonthDay::new(1).unwrap(),
+        },
+    )
+    .with_kind(kind)
+}
+
+fn table() -> TaxTable {
+    TaxTable {
+        name: "test".into(),
+        standard_deduction: 12000.,
+        brackets: brackets(),
+    }
+}
+
+#[test]
+fn test_default_tables() {
+    assert!(DEFAULT_TABLES.len() >= 4);
+    let single = &DEFAULT_TABLES[0];
+    assert_eq!(single.name, "US federal 2024 single");
+    assert_eq!(single.standard_deduction, 14600.);
+    assert_eq!(single.brackets.len(), 7);
+    assert_eq!(
+        single.brackets[6],
+        TaxBracket {
+            over: 609350.,
+            rate: 37.
+        }
+    );
+}
+
+#[test]
+fn test_read_tables() {
+    let tables =
+        read_tables("name,standard_deduction,brackets\ntest,12000,\"0:10, 10000:20, 50000:30\"\n")
+            .unwrap();
+    assert_eq!(tables, vec![table()]);
+
+    assert!(read_tables("name,standard_deduction,brackets\ntest,-1,0:10\n").is_err());
+    assert!(read_tables("name,standard_deduction,brackets\ntest,0,10\n").is_err());
+}
+
+#[test]
+fn test_estimate_refund() {
+    let deltas = vec![
+        monthly("salary net pay", 3800., DeltaKind::Income),
+        monthly("salary 401k (pre-tax)", -500., DeltaKind::PreTaxDeduction),
+        monthly("salary federal withholding", -700., DeltaKind::Withholding),
+        monthly("rent", -1500., DeltaKind::Other),
+        monthly("side gig", 500., DeltaKind::Income),
+    ];
+
+    let estimate = estimate(&deltas, 2026, &table()).unwrap();
+
+    // 66000 of income less 6000 pre-tax and 12000 standard deduction
+    assert_eq!(
+        estimate,
+        TaxEstimate {
+            year: 2026,
+            income: 66000.,
+            pre_tax_deductions: 6000.,
+            standard_deduction: 12000.,
+            taxable_income: 48000.,
+            tax: 8600.,
+            withheld: 8400.,
+            refund: -200.,
+        }
+    );
+}
+
+#[test]
+fn test_estimate_goes_by_kind() {
+    let deltas = vec![
+        monthly("pay", 3800., DeltaKind::Income),
+        monthly("retirement", -500., DeltaKind::PreTaxDeduction),
+        monthly("federal taxes", -700., DeltaKind::Withholding),
+        monthly("transfer from savings", 1000., DeltaKind::Other),
+        monthly("old job gross pay", 2000., DeltaKind::Other),
+        monthly("loan (pre-tax)", -300., DeltaKind::Other),
+    ];
+
+    let estimate = estimate(&deltas, 2026, &table()).unwrap();
+    assert_eq!(estimate.income, 60000.);
+    assert_eq!(estimate.pre_tax_deductions, 6000.);
+    assert_eq!(estimate.withheld, 8400.);
+}
+
+#[test]
+fn test_estimate_only_counts_year() {
+    let deltas = vec![
+        monthly("salary net pay", 4000., DeltaKind::Income),
+        monthly("salary federal withholding", -1000., DeltaKind::Withholding),
+    ];
+
+    // only July to December fall in 2025
+    let estimate = estimate(&deltas, 2025, &table()).unwrap();
+    assert_eq!(estimate.income, 30000.);
+    assert_eq!(estimate.taxable_income, 18000.);
+    assert_eq!(estimate.tax, 2600.);
+    assert_eq!(estimate.refund, 3400.);
+
+    assert_eq!(super::estimate(&deltas, 2027, &table()).unwrap().income, 0.);
+}
+
+#[test]
+fn test_estimate_year_out_of_range() {
+    let deltas = vec![monthly("salary net pay", 5000., DeltaKind::Income)];
+
+    assert!(estimate(&deltas, i32::MAX, &table()).is_err());
+    assert!(estimate(&deltas, i32::MIN, &table()).is_err());
+}